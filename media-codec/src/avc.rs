@@ -0,0 +1,34 @@
+use media_core::{invalid_error, Result};
+
+const NAL_TYPE_SPS: u8 = 7;
+const NAL_TYPE_PPS: u8 = 8;
+
+#[cfg(feature = "openh264")]
+pub(crate) fn is_parameter_set(nal_unit: &[u8]) -> bool {
+    matches!(nal_unit.first().map(|header| header & 0x1f), Some(NAL_TYPE_SPS | NAL_TYPE_PPS))
+}
+
+// builds an avcC record for 4 byte NAL lengths from the SPS and PPS NAL units
+pub(crate) fn build_avcc(parameter_sets: &[&[u8]]) -> Result<Vec<u8>> {
+    let nal_type = |nal_unit: &&&[u8]| nal_unit.first().map(|header| header & 0x1f);
+    let sps = parameter_sets.iter().filter(|nal_unit| nal_type(nal_unit) == Some(NAL_TYPE_SPS)).collect::<Vec<_>>();
+    let pps = parameter_sets.iter().filter(|nal_unit| nal_type(nal_unit) == Some(NAL_TYPE_PPS)).collect::<Vec<_>>();
+    let first = sps.first().filter(|sps| sps.len() >= 4).ok_or_else(|| invalid_error!("missing SPS"))?;
+    if pps.is_empty() || sps.len() > 0x1f || pps.len() > 0xff {
+        return Err(invalid_error!("invalid parameter sets"));
+    }
+
+    // profile, constraint flags and level are copied from the first SPS
+    let mut avcc = vec![1, first[1], first[2], first[3], 0xfc | 3, 0xe0 | sps.len() as u8];
+    for (index, nal_units) in [sps, pps].into_iter().enumerate() {
+        if index == 1 {
+            avcc.push(nal_units.len() as u8);
+        }
+        for nal_unit in nal_units {
+            avcc.extend_from_slice(&(nal_unit.len() as u16).to_be_bytes());
+            avcc.extend_from_slice(nal_unit);
+        }
+    }
+
+    Ok(avcc)
+}
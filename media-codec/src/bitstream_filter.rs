@@ -0,0 +1,140 @@
+use std::collections::VecDeque;
+
+use media_codec_types::{
+    decoder::DecoderParameters,
+    packet::{Packet, PacketFlags},
+    CodecID,
+};
+use media_core::{error::Error, invalid_error, none_param_error, unsupported_error, Result};
+
+// Rewrites packets between a demuxer and a decoder, packets go in with
+// send_packet and come out of receive_packet, which returns Error::Again
+// until more input is needed
+pub trait BitstreamFilter: Send + Sync {
+    fn name(&self) -> &'static str;
+    fn send_packet(&mut self, packet: &Packet) -> Result<()>;
+    fn receive_packet(&mut self) -> Result<Packet<'static>>;
+    fn flush(&mut self);
+}
+
+const START_CODE: [u8; 4] = [0, 0, 0, 1];
+
+const NAL_TYPE_IDR: u8 = 5;
+const NAL_TYPE_SPS: u8 = 7;
+
+// Converts length-prefixed H.264 packets from MP4 to Annex-B start codes and
+// puts the parameter sets from the avcC extradata in front of keyframes
+pub struct Mp4ToAnnexB {
+    length_size: usize,
+    parameter_sets: Vec<u8>,
+    packets: VecDeque<Packet<'static>>,
+}
+
+impl Mp4ToAnnexB {
+    pub fn new(codec_id: CodecID, params: &DecoderParameters) -> Result<Self> {
+        if codec_id != CodecID::H264 {
+            return Err(unsupported_error!(codec_id));
+        }
+
+        let extra_data = params.extra_data.as_deref().ok_or_else(|| none_param_error!(extra_data))?;
+        let (length_size, nal_units) = parse_avcc(extra_data)?;
+        let parameter_sets = nal_units.iter().flat_map(|nal_unit| START_CODE.iter().chain(nal_unit.iter())).copied().collect();
+
+        Ok(Self {
+            length_size,
+            parameter_sets,
+            packets: VecDeque::new(),
+        })
+    }
+
+    fn convert(&self, packet: &Packet) -> Result<Packet<'static>> {
+        let mut payload = packet.payload();
+        let mut nal_units = Vec::new();
+        while !payload.is_empty() {
+            if payload.len() < self.length_size {
+                return Err(invalid_error!("truncated NAL length"));
+            }
+            let length = payload[..self.length_size].iter().fold(0usize, |length, byte| (length << 8) | *byte as usize);
+            let nal_unit = payload[self.length_size..].get(..length).ok_or_else(|| invalid_error!("truncated NAL unit"))?;
+            nal_units.push(nal_unit);
+            payload = &payload[self.length_size + length..];
+        }
+
+        let nal_type = |nal_unit: &&[u8]| nal_unit.first().map(|header| header & 0x1f);
+        let keyframe = packet.flags.contains(PacketFlags::Key) || nal_units.iter().any(|nal_unit| nal_type(nal_unit) == Some(NAL_TYPE_IDR));
+        // streams that repeat the parameter sets in band already carry them
+        let inject = keyframe && !nal_units.iter().any(|nal_unit| nal_type(nal_unit) == Some(NAL_TYPE_SPS));
+
+        let size = nal_units.iter().map(|nal_unit| START_CODE.len() + nal_unit.len()).sum::<usize>();
+        let mut data = Vec::with_capacity(size + self.parameter_sets.len());
+        if inject {
+            data.extend_from_slice(&self.parameter_sets);
+        }
+        for nal_unit in nal_units {
+            data.extend_from_slice(&START_CODE);
+            data.extend_from_slice(nal_unit);
+        }
+
+        let mut output = Packet::from_vec(data);
+        output.pts = packet.pts;
+        output.dts = packet.dts;
+        output.duration = packet.duration;
+        output.time_base = packet.time_base;
+        output.flags = packet.flags;
+        output.pos = packet.pos;
+        output.track_index = packet.track_index;
+
+        Ok(output)
+    }
+}
+
+// returns the NAL length size and the SPS and PPS NAL units
+pub(crate) fn parse_avcc(extra_data: &[u8]) -> Result<(usize, Vec<&[u8]>)> {
+    if extra_data.len() < 7 || extra_data[0] != 1 {
+        return Err(invalid_error!("not avcC extradata"));
+    }
+
+    let length_size = (extra_data[4] & 0x03) as usize + 1;
+    if length_size == 3 {
+        return Err(invalid_error!("invalid NAL length size"));
+    }
+
+    let mut parameter_sets = Vec::new();
+    let mut data = &extra_data[5..];
+    // SPS count in the low 5 bits, PPS count is a whole byte
+    for mask in [0x1f, 0xff] {
+        let (&count, rest) = data.split_first().ok_or_else(|| invalid_error!("truncated avcC"))?;
+        data = rest;
+        for _ in 0..count & mask {
+            if data.len() < 2 {
+                return Err(invalid_error!("truncated avcC"));
+            }
+            let length = u16::from_be_bytes([data[0], data[1]]) as usize;
+            let nal_unit = data[2..].get(..length).ok_or_else(|| invalid_error!("truncated avcC"))?;
+            parameter_sets.push(nal_unit);
+            data = &data[2 + length..];
+        }
+    }
+
+    Ok((length_size, parameter_sets))
+}
+
+impl BitstreamFilter for Mp4ToAnnexB {
+    fn name(&self) -> &'static str {
+        "h264_mp4toannexb"
+    }
+
+    fn send_packet(&mut self, packet: &Packet) -> Result<()> {
+        let packet = self.convert(packet)?;
+        self.packets.push_back(packet);
+        Ok(())
+    }
+
+    fn receive_packet(&mut self) -> Result<Packet<'static>> {
+        self.packets.pop_front().ok_or_else(|| Error::Again("no packet".into()))
+    }
+
+    fn flush(&mut self) {
+        self.packets.clear();
+    }
+}
@@ -158,4 +158,8 @@ impl<T: EncoderSpec> EncoderContext<T> {
     pub fn flush(&mut self) -> Result<()> {
         self.encoder.flush(&self.config)
     }
+
+    pub fn extra_data(&self) -> Option<&[u8]> {
+        self.encoder.extra_data()
+    }
 }
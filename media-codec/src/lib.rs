@@ -1,9 +1,19 @@
+#[cfg(all(feature = "video", feature = "encoder", any(feature = "openh264", target_os = "macos", target_os = "ios")))]
+mod avc;
+#[cfg(feature = "decoder")]
+pub mod bitstream_filter;
 #[cfg(any(feature = "decoder", feature = "encoder"))]
 pub mod codec;
 #[cfg(feature = "decoder")]
 pub mod decoder;
 #[cfg(feature = "encoder")]
 pub mod encoder;
+#[cfg(all(feature = "openh264", any(feature = "decoder", feature = "encoder")))]
+pub mod openh264;
+#[cfg(feature = "decoder")]
+pub mod reorder;
+#[cfg(all(any(target_os = "macos", target_os = "ios"), feature = "video", any(feature = "decoder", feature = "encoder")))]
+pub mod videotoolbox;
 
 #[cfg(any(feature = "decoder", feature = "encoder"))]
 pub use media_codec_types::codec::*;
@@ -0,0 +1,179 @@
+use std::{collections::VecDeque, sync::Arc};
+
+use media_codec_types::{
+    decoder::{Decoder, DecoderBuilder, VideoDecoder, VideoDecoderParameters},
+    define_codec_builder,
+    packet::Packet,
+    Codec, CodecID, CodecInformation, CodecParameters,
+};
+use media_core::{
+    error::Error,
+    frame::{Frame, SharedFrame},
+    frame_pool::FramePool,
+    invalid_error, unsupported_error,
+    variant::Variant,
+    video::{PixelFormat, VideoFrame, VideoFrameDescriptor},
+    Result,
+};
+use openh264::{decoder::Decoder as H264Decoder, formats::YUVSource};
+
+use crate::bitstream_filter::{BitstreamFilter, Mp4ToAnnexB};
+
+type VideoFramePool = FramePool<Frame<'static, VideoFrameDescriptor>>;
+
+// Decodes Annex-B H.264 packets, or MP4 packets when the extradata is avcC,
+// into I420 frames
+pub struct OpenH264Decoder {
+    decoder: H264Decoder,
+    filter: Option<Mp4ToAnnexB>,
+    frames: VecDeque<SharedFrame<Frame<'static, VideoFrameDescriptor>>>,
+}
+
+impl OpenH264Decoder {
+    pub fn new(id: CodecID, params: &CodecParameters) -> Result<Self> {
+        if id != CodecID::H264 {
+            return Err(unsupported_error!(id));
+        }
+
+        let params = VideoDecoderParameters::try_from(params)?;
+        let mut decoder = H264Decoder::new().map_err(|err| Error::CreationFailed(err.to_string().into()))?;
+
+        // avcC extradata starts with its version, Annex-B extradata holds the
+        // parameter sets with start codes and is decoded up front
+        let filter = match params.decoder.extra_data.as_deref() {
+            Some([1, ..]) => Some(Mp4ToAnnexB::new(id, &params.decoder)?),
+            Some(extra_data) if !extra_data.is_empty() => {
+                decoder.decode(extra_data).map_err(|err| invalid_error!(err.to_string()))?;
+                None
+            }
+            _ => None,
+        };
+
+        Ok(Self {
+            decoder,
+            filter,
+            frames: VecDeque::new(),
+        })
+    }
+
+    fn decode(&mut self, config: &VideoDecoder, pool: Option<&Arc<VideoFramePool>>, data: &[u8]) -> Result<()> {
+        let Some(yuv) = self.decoder.decode(data).map_err(|err| invalid_error!(err.to_string()))? else {
+            return Ok(());
+        };
+
+        let frame = copy_yuv(config, pool, &yuv)?;
+        self.frames.push_back(frame);
+
+        Ok(())
+    }
+}
+
+fn copy_yuv(
+    config: &VideoDecoder,
+    pool: Option<&Arc<VideoFramePool>>,
+    yuv: &impl YUVSource,
+) -> Result<SharedFrame<Frame<'static, VideoFrameDescriptor>>> {
+    let (width, height) = yuv.dimensions();
+    let mut desc = VideoFrameDescriptor::try_new(PixelFormat::I420, width as u32, height as u32)?;
+    if let Some(color_range) = config.video.color_range {
+        desc.color_range = color_range;
+    }
+    if let Some(color_matrix) = config.video.color_matrix {
+        desc.color_matrix = color_matrix;
+    }
+
+    let mut shared_frame = match pool {
+        Some(pool) => pool.get_frame_with_descriptor(desc)?,
+        None => SharedFrame::<Frame<'static, VideoFrameDescriptor>>::new(VideoFrame::new_with_descriptor(desc)?),
+    };
+    {
+        let frame = shared_frame.write().ok_or_else(|| invalid_error!("frame in use"))?;
+
+        let (y_stride, u_stride, v_stride) = yuv.strides();
+        let (chroma_width, chroma_height) = (width.div_ceil(2), height.div_ceil(2));
+        let mut guard = frame.map_mut()?;
+        let mut planes = guard.try_planes_mut()?;
+        for (plane_index, (src, src_stride, row_bytes, rows)) in
+            [(yuv.y(), y_stride, width, height), (yuv.u(), u_stride, chroma_width, chroma_height), (yuv.v(), v_stride, chroma_width, chroma_height)]
+                .into_iter()
+                .enumerate()
+        {
+            let dst_stride = planes.plane_stride(plane_index).ok_or_else(|| invalid_error!("plane stride"))?;
+            let dst = planes.plane_data_mut(plane_index).ok_or_else(|| invalid_error!("plane data"))?;
+            for (src_row, dst_row) in src.chunks(src_stride).zip(dst.chunks_mut(dst_stride)).take(rows) {
+                dst_row[..row_bytes].copy_from_slice(&src_row[..row_bytes]);
+            }
+        }
+    }
+
+    Ok(shared_frame)
+}
+
+impl CodecInformation for OpenH264Decoder {
+    fn id(&self) -> CodecID {
+        CodecID::H264
+    }
+
+    fn name(&self) -> &'static str {
+        "openh264"
+    }
+}
+
+impl Codec<VideoDecoder> for OpenH264Decoder {
+    fn configure(&mut self, _params: Option<&CodecParameters>, _options: Option<&Variant>) -> Result<()> {
+        Ok(())
+    }
+
+    fn set_option(&mut self, _key: &str, _value: &Variant) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl Decoder<VideoDecoder> for OpenH264Decoder {
+    fn send_packet(&mut self, config: &VideoDecoder, pool: Option<&Arc<VideoFramePool>>, packet: &Packet) -> Result<()> {
+        let Some(filter) = self.filter.as_mut() else {
+            return self.decode(config, pool, packet.payload());
+        };
+
+        filter.send_packet(packet)?;
+        loop {
+            let packet = match self.filter.as_mut().map(|filter| filter.receive_packet()) {
+                Some(Ok(packet)) => packet,
+                Some(Err(Error::Again(_))) | None => return Ok(()),
+                Some(Err(err)) => return Err(err),
+            };
+            self.decode(config, pool, packet.payload())?;
+        }
+    }
+
+    fn receive_frame(
+        &mut self,
+        _config: &VideoDecoder,
+        _pool: Option<&Arc<VideoFramePool>>,
+    ) -> Result<SharedFrame<Frame<'static, VideoFrameDescriptor>>> {
+        self.frames.pop_front().ok_or_else(|| Error::Again("no frame".into()))
+    }
+
+    // frames still held for reordering are output, then the decoder starts over
+    fn flush(&mut self, config: &VideoDecoder) -> Result<()> {
+        let remaining = self.decoder.flush_remaining().map_err(|err| invalid_error!(err.to_string()))?;
+        let frames = remaining.iter().map(|yuv| copy_yuv(config, None, yuv)).collect::<Result<Vec<_>>>()?;
+        self.frames.extend(frames);
+        if let Some(filter) = self.filter.as_mut() {
+            filter.flush();
+        }
+
+        Ok(())
+    }
+}
+
+define_codec_builder!(OpenH264DecoderBuilder<VideoDecoder> {
+    name: "openh264",
+    ids: [H264]
+});
+
+impl DecoderBuilder<VideoDecoder> for OpenH264DecoderBuilder {
+    fn new_decoder(&self, id: CodecID, params: &CodecParameters, _options: Option<&Variant>) -> Result<Box<dyn Decoder<VideoDecoder>>> {
+        Ok(Box::new(OpenH264Decoder::new(id, params)?))
+    }
+}
@@ -0,0 +1,195 @@
+use std::{collections::VecDeque, sync::Arc};
+
+use media_codec_types::{
+    define_codec_builder,
+    encoder::{Encoder, EncoderBuilder, VideoEncoder, VideoEncoderParameters},
+    packet::{Packet, PacketFlags},
+    Codec, CodecID, CodecInformation, CodecParameters,
+};
+use media_core::{
+    buffer::BufferPool,
+    error::Error,
+    failed_error,
+    frame::{Frame, SharedFrame},
+    invalid_error, unsupported_error,
+    variant::Variant,
+    video::{PixelFormat, VideoFrameDescriptor},
+    Result,
+};
+use openh264::{
+    encoder::{BitRate, Encoder as H264Encoder, EncoderConfig, FrameRate, FrameType},
+    formats::YUVSlices,
+    nal_units, OpenH264API,
+};
+
+use crate::avc::{build_avcc, is_parameter_set};
+
+const DEFAULT_BIT_RATE: u32 = 2_000_000;
+
+// Encodes I420 and NV12 frames into H.264 packets with 4 byte NAL lengths,
+// the SPS and PPS go to an avcC record returned by extra_data
+pub struct OpenH264Encoder {
+    encoder: H264Encoder,
+    extra_data: Option<Vec<u8>>,
+    packets: VecDeque<Packet<'static>>,
+}
+
+impl OpenH264Encoder {
+    pub fn new(id: CodecID, params: &CodecParameters) -> Result<Self> {
+        if id != CodecID::H264 {
+            return Err(unsupported_error!(id));
+        }
+
+        let params = VideoEncoderParameters::try_from(params)?;
+        let bit_rate = params.encoder.bit_rate.map_or(DEFAULT_BIT_RATE, |bit_rate| bit_rate.min(u32::MAX as u64) as u32);
+        let mut config = EncoderConfig::new().bitrate(BitRate::from_bps(bit_rate)).skip_frames(false);
+        if let Some(frame_rate) = params.video.frame_rate.filter(|frame_rate| *frame_rate.numer() > 0 && *frame_rate.denom() > 0) {
+            config = config.max_frame_rate(FrameRate::from_hz(*frame_rate.numer() as f32 / *frame_rate.denom() as f32));
+        }
+
+        let encoder =
+            H264Encoder::with_api_config(OpenH264API::from_source(), config).map_err(|err| Error::CreationFailed(err.to_string().into()))?;
+
+        Ok(Self {
+            encoder,
+            extra_data: None,
+            packets: VecDeque::new(),
+        })
+    }
+
+    fn encode(&mut self, frame: &Frame<'static, VideoFrameDescriptor>) -> Result<()> {
+        let desc = frame.descriptor();
+        let (width, height) = (desc.width().get() as usize, desc.height().get() as usize);
+        let (chroma_width, chroma_height) = (width.div_ceil(2), height.div_ceil(2));
+
+        let guard = frame.map()?;
+        let planes = guard.try_planes()?;
+        let plane = |index: usize| -> Result<(&[u8], usize)> {
+            let data = planes.plane_data(index).ok_or_else(|| invalid_error!("plane data"))?;
+            let stride = planes.plane_stride(index).ok_or_else(|| invalid_error!("plane stride"))?;
+            Ok((data, stride))
+        };
+
+        let (y, y_stride) = plane(0)?;
+        let chroma;
+        let (u, v, u_stride, v_stride) = match desc.format {
+            PixelFormat::I420 => {
+                let ((u, u_stride), (v, v_stride)) = (plane(1)?, plane(2)?);
+                (u, v, u_stride, v_stride)
+            }
+            // the encoder only reads planar chroma
+            PixelFormat::NV12 => {
+                let (uv, uv_stride) = plane(1)?;
+                let mut u = Vec::with_capacity(chroma_width * chroma_height);
+                let mut v = Vec::with_capacity(chroma_width * chroma_height);
+                for row in uv.chunks(uv_stride).take(chroma_height) {
+                    for pair in row[..chroma_width * 2].chunks_exact(2) {
+                        u.push(pair[0]);
+                        v.push(pair[1]);
+                    }
+                }
+                chroma = (u, v);
+                (chroma.0.as_slice(), chroma.1.as_slice(), chroma_width, chroma_width)
+            }
+            format => return Err(unsupported_error!(format)),
+        };
+
+        let yuv = YUVSlices::new((y, u, v), (width, height), (y_stride, u_stride, v_stride));
+        let bit_stream = self.encoder.encode(&yuv).map_err(|err| failed_error!(err))?;
+
+        let keyframe = matches!(bit_stream.frame_type(), FrameType::IDR | FrameType::I);
+        let stream = bit_stream.to_vec();
+        let mut data = Vec::new();
+        let mut parameter_sets = Vec::new();
+        // NAL units come with Annex-B start codes
+        for nal_unit in nal_units(&stream).map(strip_start_code) {
+            if is_parameter_set(nal_unit) {
+                parameter_sets.push(nal_unit);
+            } else {
+                data.extend_from_slice(&(nal_unit.len() as u32).to_be_bytes());
+                data.extend_from_slice(nal_unit);
+            }
+        }
+
+        if !parameter_sets.is_empty() {
+            self.extra_data = Some(build_avcc(&parameter_sets)?);
+        }
+
+        // skipped frames produce no output
+        if data.is_empty() {
+            return Ok(());
+        }
+
+        // no B-frames, so decode order is presentation order
+        let mut packet = Packet::from_vec(data);
+        packet.pts = frame.pts;
+        packet.dts = frame.pts;
+        packet.duration = frame.duration;
+        packet.time_base = frame.time_base;
+        if keyframe {
+            packet.flags = PacketFlags::Key;
+        }
+        self.packets.push_back(packet);
+
+        Ok(())
+    }
+}
+
+fn strip_start_code(nal_unit: &[u8]) -> &[u8] {
+    nal_unit.strip_prefix(&[0, 0, 0, 1]).or_else(|| nal_unit.strip_prefix(&[0, 0, 1])).unwrap_or(nal_unit)
+}
+
+impl CodecInformation for OpenH264Encoder {
+    fn id(&self) -> CodecID {
+        CodecID::H264
+    }
+
+    fn name(&self) -> &'static str {
+        "openh264"
+    }
+}
+
+impl Codec<VideoEncoder> for OpenH264Encoder {
+    fn configure(&mut self, _params: Option<&CodecParameters>, _options: Option<&Variant>) -> Result<()> {
+        Ok(())
+    }
+
+    fn set_option(&mut self, _key: &str, _value: &Variant) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl Encoder<VideoEncoder> for OpenH264Encoder {
+    fn send_frame(
+        &mut self,
+        _config: &VideoEncoder,
+        _pool: Option<&Arc<BufferPool>>,
+        frame: SharedFrame<Frame<'static, VideoFrameDescriptor>>,
+    ) -> Result<()> {
+        self.encode(frame.read())
+    }
+
+    fn receive_packet(&mut self, _config: &VideoEncoder, _pool: Option<&Arc<BufferPool>>) -> Result<Packet<'static>> {
+        self.packets.pop_front().ok_or_else(|| Error::Again("no packet".into()))
+    }
+
+    // every frame is output as soon as it is encoded
+    fn flush(&mut self, _config: &VideoEncoder) -> Result<()> {
+        Ok(())
+    }
+
+    fn extra_data(&self) -> Option<&[u8]> {
+        self.extra_data.as_deref()
+    }
+}
+
+define_codec_builder!(OpenH264EncoderBuilder<VideoEncoder> {
+    name: "openh264",
+    ids: [H264]
+});
+
+impl EncoderBuilder<VideoEncoder> for OpenH264EncoderBuilder {
+    fn new_encoder(&self, id: CodecID, params: &CodecParameters, _options: Option<&Variant>) -> Result<Box<dyn Encoder<VideoEncoder>>> {
+        Ok(Box::new(OpenH264Encoder::new(id, params)?))
+    }
+}
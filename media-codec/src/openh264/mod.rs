@@ -0,0 +1,5 @@
+// H.264 through the OpenH264 software codec, built from source
+#[cfg(feature = "decoder")]
+pub mod decoder;
+#[cfg(feature = "encoder")]
+pub mod encoder;
@@ -0,0 +1,70 @@
+use std::collections::VecDeque;
+
+#[cfg(feature = "video")]
+use media_codec_types::VideoParameters;
+use media_core::{
+    frame::{Frame, SharedFrame},
+    FrameDescriptorSpec,
+};
+
+// H.264 and HEVC never hold more than 16 frames in the DPB
+pub const MAX_REORDER_DEPTH: usize = 16;
+
+// Holds decoded frames until `depth` later frames have arrived, then releases
+// them in presentation order
+pub struct ReorderBuffer<D: FrameDescriptorSpec> {
+    depth: usize,
+    frames: VecDeque<(Option<i64>, SharedFrame<Frame<'static, D>>)>,
+}
+
+impl<D: FrameDescriptorSpec> ReorderBuffer<D> {
+    pub fn new(depth: usize) -> Self {
+        Self {
+            depth,
+            frames: VecDeque::with_capacity(depth + 1),
+        }
+    }
+
+    // falls back to the maximum depth when the stream does not signal it
+    #[cfg(feature = "video")]
+    pub fn from_video_parameters(params: &VideoParameters) -> Self {
+        Self::new(params.max_num_reorder_frames.map_or(MAX_REORDER_DEPTH, |depth| (depth as usize).min(MAX_REORDER_DEPTH)))
+    }
+
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    pub fn len(&self) -> usize {
+        self.frames.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+
+    // returns the next frame in presentation order once more than `depth`
+    // frames are buffered, frames without pts are ordered by dts
+    pub fn push(&mut self, frame: SharedFrame<Frame<'static, D>>) -> Option<SharedFrame<Frame<'static, D>>> {
+        let pts = frame.read().pts.or(frame.read().dts);
+        // equal timestamps keep their decode order
+        let index = self.frames.partition_point(|(frame_pts, _)| *frame_pts <= pts);
+        self.frames.insert(index, (pts, frame));
+
+        if self.frames.len() > self.depth {
+            self.frames.pop_front().map(|(_, frame)| frame)
+        } else {
+            None
+        }
+    }
+
+    // releases the remaining frames in presentation order, at end of stream
+    // or before a decoder flush
+    pub fn drain(&mut self) -> impl Iterator<Item = SharedFrame<Frame<'static, D>>> + '_ {
+        self.frames.drain(..).map(|(_, frame)| frame)
+    }
+
+    pub fn clear(&mut self) {
+        self.frames.clear();
+    }
+}
@@ -0,0 +1,243 @@
+use std::{
+    collections::VecDeque,
+    sync::{Arc, Mutex},
+};
+
+use core_foundation::{
+    base::{CFType, TCFType},
+    dictionary::CFDictionary,
+    number::CFNumber,
+    string::CFString,
+};
+use core_media::{
+    block_buffer::{kCMBlockBufferAssureMemoryNowFlag, CMBlockBuffer},
+    format_description::{CMVideoFormatDescription, TCMFormatDescription},
+    sample_buffer::{CMSampleBuffer, CMSampleTimingInfo},
+    time::{kCMTimeFlags_Valid, kCMTimeInvalid, CMTime},
+};
+use core_video::pixel_buffer::{
+    kCVPixelFormatType_420YpCbCr8BiPlanarFullRange, kCVPixelFormatType_420YpCbCr8BiPlanarVideoRange, CVPixelBuffer, CVPixelBufferKeys,
+};
+use media_codec_types::{
+    decoder::{Decoder, DecoderBuilder, VideoDecoder, VideoDecoderParameters},
+    define_codec_builder,
+    packet::Packet,
+    Codec, CodecID, CodecInformation, CodecParameters,
+};
+use media_core::{
+    error::Error,
+    failed_error,
+    frame::{Frame, SharedFrame},
+    frame_pool::FramePool,
+    invalid_error, none_param_error, unsupported_error,
+    variant::Variant,
+    video::{ColorRange, VideoFrame, VideoFrameDescriptor},
+    Result,
+};
+use video_toolbox::{
+    decompression_session::VTDecompressionSession,
+    errors::{VTDecodeFrameFlags, VTDecodeInfoFlags},
+};
+
+use super::os_status_error;
+use crate::bitstream_filter::parse_avcc;
+
+type VideoFramePool = FramePool<Frame<'static, VideoFrameDescriptor>>;
+type DecodedFrames = Arc<Mutex<VecDeque<Result<SharedFrame<Frame<'static, VideoFrameDescriptor>>>>>>;
+
+// Decodes length-prefixed H.264 and HEVC packets described by avcC or hvcC
+// extradata into NV12 frames backed by the decoder's pixel buffers
+pub struct VideoToolboxDecoder {
+    id: CodecID,
+    format_description: CMVideoFormatDescription,
+    session: VTDecompressionSession,
+    frames: DecodedFrames,
+}
+
+// the session and format description are immutable CoreFoundation objects
+// that VideoToolbox allows to be used from any thread
+unsafe impl Send for VideoToolboxDecoder {}
+unsafe impl Sync for VideoToolboxDecoder {}
+
+impl VideoToolboxDecoder {
+    pub fn new(id: CodecID, params: &CodecParameters) -> Result<Self> {
+        let params = VideoDecoderParameters::try_from(params)?;
+        let extra_data = params.decoder.extra_data.as_deref().ok_or_else(|| none_param_error!(extra_data))?;
+
+        let format_description = match id {
+            CodecID::H264 => {
+                let (length_size, parameter_sets) = parse_avcc(extra_data)?;
+                CMVideoFormatDescription::from_h264_parameter_sets(&parameter_sets, length_size as i32)
+            }
+            CodecID::HEVC => {
+                let (length_size, parameter_sets) = parse_hvcc(extra_data)?;
+                CMVideoFormatDescription::from_hevc_parameter_sets(&parameter_sets, length_size as i32, None)
+            }
+            _ => return Err(unsupported_error!(id)),
+        }
+        .map_err(|status| os_status_error("CMVideoFormatDescription", status))?;
+
+        let pixel_format = match params.video.color_range {
+            Some(ColorRange::Full) => kCVPixelFormatType_420YpCbCr8BiPlanarFullRange,
+            _ => kCVPixelFormatType_420YpCbCr8BiPlanarVideoRange,
+        };
+        let attributes = CFDictionary::<CFString, CFType>::from_CFType_pairs(&[
+            (CVPixelBufferKeys::PixelFormatType.into(), CFNumber::from(pixel_format as i64).as_CFType()),
+            (CVPixelBufferKeys::IOSurfaceProperties.into(), CFDictionary::<CFString, CFType>::from_CFType_pairs(&[]).as_CFType()),
+        ]);
+
+        let session = VTDecompressionSession::new(format_description.clone(), None, Some(attributes))
+            .map_err(|status| Error::CreationFailed(format!("VTDecompressionSession: {status}").into()))?;
+
+        Ok(Self {
+            id,
+            format_description,
+            session,
+            frames: Arc::new(Mutex::new(VecDeque::new())),
+        })
+    }
+
+    fn sample_buffer(&self, packet: &Packet) -> Result<CMSampleBuffer> {
+        let data = packet.payload();
+        if data.is_empty() {
+            return Err(invalid_error!("empty packet"));
+        }
+
+        let block_buffer = unsafe { CMBlockBuffer::new_with_memory_block(None, data.len(), None, 0, data.len(), kCMBlockBufferAssureMemoryNowFlag) }
+            .map_err(|status| os_status_error("CMBlockBuffer", status))?;
+        block_buffer.replace_data_bytes(data, 0).map_err(|status| os_status_error("CMBlockBuffer", status))?;
+
+        // timestamps only order the output, they are carried through in the
+        // time base of the packet
+        let time = |value: Option<i64>| value.map_or(unsafe { kCMTimeInvalid }, |value| CMTime::make(value, 1));
+        let timing = CMSampleTimingInfo {
+            duration: time(packet.duration),
+            presentationTimeStamp: time(packet.pts),
+            decodeTimeStamp: time(packet.dts),
+        };
+
+        CMSampleBuffer::new_ready(&block_buffer, Some(&self.format_description.as_format_description()), 1, Some(&[timing]), Some(&[data.len()]))
+            .map_err(|status| os_status_error("CMSampleBuffer", status))
+    }
+}
+
+// returns the NAL length size and the VPS, SPS and PPS NAL units
+fn parse_hvcc(extra_data: &[u8]) -> Result<(usize, Vec<&[u8]>)> {
+    if extra_data.len() < 23 || extra_data[0] != 1 {
+        return Err(invalid_error!("not hvcC extradata"));
+    }
+
+    let length_size = (extra_data[21] & 0x03) as usize + 1;
+    if length_size == 3 {
+        return Err(invalid_error!("invalid NAL length size"));
+    }
+
+    let mut parameter_sets = Vec::new();
+    let mut data = &extra_data[23..];
+    for _ in 0..extra_data[22] {
+        if data.len() < 3 {
+            return Err(invalid_error!("truncated hvcC"));
+        }
+        let count = u16::from_be_bytes([data[1], data[2]]);
+        data = &data[3..];
+        for _ in 0..count {
+            if data.len() < 2 {
+                return Err(invalid_error!("truncated hvcC"));
+            }
+            let length = u16::from_be_bytes([data[0], data[1]]) as usize;
+            let nal_unit = data[2..].get(..length).ok_or_else(|| invalid_error!("truncated hvcC"))?;
+            parameter_sets.push(nal_unit);
+            data = &data[2 + length..];
+        }
+    }
+
+    Ok((length_size, parameter_sets))
+}
+
+impl CodecInformation for VideoToolboxDecoder {
+    fn id(&self) -> CodecID {
+        self.id
+    }
+
+    fn name(&self) -> &'static str {
+        "videotoolbox"
+    }
+}
+
+impl Codec<VideoDecoder> for VideoToolboxDecoder {
+    fn configure(&mut self, _params: Option<&CodecParameters>, _options: Option<&Variant>) -> Result<()> {
+        Ok(())
+    }
+
+    fn set_option(&mut self, _key: &str, _value: &Variant) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl Decoder<VideoDecoder> for VideoToolboxDecoder {
+    // frames wrap the pixel buffers VideoToolbox allocates, so the pool is not used
+    fn send_packet(&mut self, _config: &VideoDecoder, _pool: Option<&Arc<VideoFramePool>>, packet: &Packet) -> Result<()> {
+        let sample_buffer = self.sample_buffer(packet)?;
+        let frames = self.frames.clone();
+
+        // temporal processing lets the decoder hold frames back and output them
+        // in presentation order
+        self.session
+            .decode_frame_with_closure(
+                sample_buffer,
+                VTDecodeFrameFlags::Frame_EnableTemporalProcessing,
+                move |status, info_flags, image_buffer, pts, duration| {
+                    if info_flags.contains(VTDecodeInfoFlags::FrameDropped) {
+                        return;
+                    }
+
+                    let frame = if status != 0 {
+                        Err(os_status_error("VTDecompressionSession", status))
+                    } else {
+                        image_buffer
+                            .downcast::<CVPixelBuffer>()
+                            .ok_or_else(|| invalid_error!("not a pixel buffer"))
+                            .and_then(|pixel_buffer| VideoFrame::from_pixel_buffer(&pixel_buffer))
+                            .map(|mut frame| {
+                                frame.pts = (pts.flags & kCMTimeFlags_Valid != 0).then_some(pts.value);
+                                frame.duration = (duration.flags & kCMTimeFlags_Valid != 0).then_some(duration.value);
+                                SharedFrame::<Frame<'static, VideoFrameDescriptor>>::new(frame)
+                            })
+                    };
+
+                    if let Ok(mut frames) = frames.lock() {
+                        frames.push_back(frame);
+                    }
+                },
+            )
+            .map_err(|status| os_status_error("VTDecompressionSession", status))?;
+
+        Ok(())
+    }
+
+    fn receive_frame(
+        &mut self,
+        _config: &VideoDecoder,
+        _pool: Option<&Arc<VideoFramePool>>,
+    ) -> Result<SharedFrame<Frame<'static, VideoFrameDescriptor>>> {
+        let mut frames = self.frames.lock().map_err(|_| failed_error!("lock frames"))?;
+        frames.pop_front().unwrap_or_else(|| Err(Error::Again("no frame".into())))
+    }
+
+    // frames held back for reordering are output
+    fn flush(&mut self, _config: &VideoDecoder) -> Result<()> {
+        self.session.finish_delayed_frames().map_err(|status| os_status_error("VTDecompressionSession", status))?;
+        self.session.wait_for_asynchronous_frames().map_err(|status| os_status_error("VTDecompressionSession", status))
+    }
+}
+
+define_codec_builder!(VideoToolboxDecoderBuilder<VideoDecoder> {
+    name: "videotoolbox",
+    ids: [H264, HEVC]
+});
+
+impl DecoderBuilder<VideoDecoder> for VideoToolboxDecoderBuilder {
+    fn new_decoder(&self, id: CodecID, params: &CodecParameters, _options: Option<&Variant>) -> Result<Box<dyn Decoder<VideoDecoder>>> {
+        Ok(Box::new(VideoToolboxDecoder::new(id, params)?))
+    }
+}
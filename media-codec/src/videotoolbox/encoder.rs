@@ -0,0 +1,313 @@
+use std::{
+    collections::VecDeque,
+    sync::{Arc, Mutex},
+};
+
+use core_foundation::{
+    base::{CFAllocator, CFAllocatorGetDefault, TCFType},
+    boolean::CFBoolean,
+    dictionary::CFDictionary,
+    number::CFNumber,
+    string::CFString,
+};
+use core_media::{
+    format_description::{kCMVideoCodecType_H264, CMVideoFormatDescription},
+    sample_buffer::{kCMSampleAttachmentKey_NotSync, CMSampleBuffer},
+    time::{kCMTimeFlags_Valid, kCMTimeInvalid, CMTime},
+};
+use core_video::image_buffer::TCVImageBuffer;
+use media_codec_types::{
+    define_codec_builder,
+    encoder::{Encoder, EncoderBuilder, VideoEncoder, VideoEncoderParameters},
+    packet::{Packet, PacketFlags},
+    Codec, CodecID, CodecInformation, CodecParameters,
+};
+use media_core::{
+    buffer::BufferPool,
+    error::Error,
+    failed_error,
+    frame::{Frame, SharedFrame},
+    invalid_error, none_param_error,
+    rational::Rational64,
+    unsupported_error,
+    variant::Variant,
+    video::{PixelFormat, VideoFrame, VideoFrameDescriptor},
+    Result,
+};
+use video_toolbox::{
+    compression_properties::{CompressionPropertyKey, ProfileLevel},
+    compression_session::VTCompressionSession,
+    session::TVTSession,
+};
+
+use super::os_status_error;
+use crate::avc::build_avcc;
+
+#[derive(Default)]
+struct EncodedOutput {
+    packets: VecDeque<Result<Packet<'static>>>,
+    extra_data: Option<Vec<u8>>,
+}
+
+// Encodes NV12 and I420 frames into H.264 packets with 4 byte NAL lengths,
+// the SPS and PPS go to an avcC record returned by extra_data
+pub struct VideoToolboxEncoder {
+    session: VTCompressionSession,
+    output: Arc<Mutex<EncodedOutput>>,
+    extra_data: Option<Vec<u8>>,
+}
+
+// the session is a CoreFoundation object that VideoToolbox allows to be used
+// from any thread
+unsafe impl Send for VideoToolboxEncoder {}
+unsafe impl Sync for VideoToolboxEncoder {}
+
+impl VideoToolboxEncoder {
+    pub fn new(id: CodecID, params: &CodecParameters) -> Result<Self> {
+        if id != CodecID::H264 {
+            return Err(unsupported_error!(id));
+        }
+
+        let params = VideoEncoderParameters::try_from(params)?;
+        let width = params.video.width.ok_or_else(|| none_param_error!(width))?;
+        let height = params.video.height.ok_or_else(|| none_param_error!(height))?;
+
+        let allocator = unsafe { CFAllocator::wrap_under_get_rule(CFAllocatorGetDefault()) };
+        let session = VTCompressionSession::new(
+            width.get() as i32,
+            height.get() as i32,
+            kCMVideoCodecType_H264,
+            CFDictionary::from_CFType_pairs(&[]),
+            CFDictionary::from_CFType_pairs(&[]),
+            allocator,
+        )
+        .map_err(|status| Error::CreationFailed(format!("VTCompressionSession: {status}").into()))?;
+
+        // without frame reordering decode order is presentation order
+        let mut properties = vec![
+            (CompressionPropertyKey::RealTime, CFBoolean::true_value().as_CFType()),
+            (CompressionPropertyKey::AllowFrameReordering, CFBoolean::false_value().as_CFType()),
+            (CompressionPropertyKey::ProfileLevel, CFString::from(ProfileLevel::H264MainAutoLevel).as_CFType()),
+        ];
+        if let Some(bit_rate) = params.encoder.bit_rate {
+            properties.push((CompressionPropertyKey::AverageBitRate, CFNumber::from(bit_rate as i64).as_CFType()));
+        }
+        if let Some(frame_rate) = params.video.frame_rate.filter(|frame_rate| *frame_rate.denom() > 0) {
+            let frame_rate = *frame_rate.numer() as f64 / *frame_rate.denom() as f64;
+            properties.push((CompressionPropertyKey::ExpectedFrameRate, CFNumber::from(frame_rate).as_CFType()));
+        }
+        for (key, value) in properties {
+            session.as_session().set_property(key.into(), value).map_err(|status| os_status_error("VTSessionSetProperty", status))?;
+        }
+        session.prepare_to_encode_frames().map_err(|status| os_status_error("VTCompressionSession", status))?;
+
+        Ok(Self {
+            session,
+            output: Arc::new(Mutex::new(EncodedOutput::default())),
+            extra_data: None,
+        })
+    }
+
+    fn encode(&mut self, frame: &Frame<'static, VideoFrameDescriptor>) -> Result<()> {
+        let pts = frame.pts.ok_or_else(|| none_param_error!(frame.pts))?;
+        // captured frames carry millisecond timestamps
+        let time_base = frame.time_base.unwrap_or(Rational64::new(1, 1000));
+
+        let pixel_buffer = match frame.pixel_buffer() {
+            Some(pixel_buffer) => pixel_buffer.clone(),
+            None => {
+                let copy = copy_to_pixel_buffer(frame)?;
+                copy.pixel_buffer().cloned().ok_or_else(|| invalid_error!("not a pixel buffer"))?
+            }
+        };
+
+        let output = self.output.clone();
+        self.session
+            .encode_frame_with_closure(
+                pixel_buffer.as_image_buffer(),
+                into_cm_time(Some(pts), time_base),
+                into_cm_time(frame.duration, time_base),
+                CFDictionary::from_CFType_pairs(&[]),
+                move |status, _info_flags, sample_buffer| {
+                    let Ok(mut output) = output.lock() else {
+                        return;
+                    };
+
+                    if status != 0 {
+                        output.packets.push_back(Err(os_status_error("VTCompressionSession", status)));
+                        return;
+                    }
+
+                    // dropped frames have no sample buffer
+                    if sample_buffer.is_null() {
+                        return;
+                    }
+
+                    let sample_buffer = unsafe { CMSampleBuffer::wrap_under_get_rule(sample_buffer) };
+                    let packet = into_packet(&sample_buffer, time_base);
+                    if let Ok(packet) = &packet {
+                        if packet.flags.contains(PacketFlags::Key) {
+                            if let Some(extra_data) = avcc_from_sample_buffer(&sample_buffer) {
+                                output.extra_data = Some(extra_data);
+                            }
+                        }
+                    }
+                    output.packets.push_back(packet);
+                },
+            )
+            .map_err(|status| os_status_error("VTCompressionSession", status))?;
+
+        Ok(())
+    }
+}
+
+impl Drop for VideoToolboxEncoder {
+    fn drop(&mut self) {
+        self.session.invalidate();
+    }
+}
+
+// VideoToolbox only encodes from pixel buffers
+fn copy_to_pixel_buffer(frame: &Frame<'static, VideoFrameDescriptor>) -> Result<VideoFrame<'static>> {
+    let desc = frame.descriptor();
+    let (width, height) = (desc.width().get() as usize, desc.height().get() as usize);
+    let (chroma_width, chroma_height) = (width.div_ceil(2), height.div_ceil(2));
+    let plane_sizes = match desc.format {
+        PixelFormat::NV12 => vec![(width, height), (chroma_width * 2, chroma_height)],
+        PixelFormat::I420 => vec![(width, height), (chroma_width, chroma_height), (chroma_width, chroma_height)],
+        format => return Err(unsupported_error!(format)),
+    };
+
+    let mut copy = VideoFrame::new_pixel_buffer_with_descriptor(desc.clone())?;
+    {
+        let src_guard = frame.map()?;
+        let src_planes = src_guard.try_planes()?;
+        let mut dst_guard = copy.map_mut()?;
+        let mut dst_planes = dst_guard.try_planes_mut()?;
+        for (index, (row_bytes, rows)) in plane_sizes.into_iter().enumerate() {
+            let src_stride = src_planes.plane_stride(index).ok_or_else(|| invalid_error!("plane stride"))?;
+            let dst_stride = dst_planes.plane_stride(index).ok_or_else(|| invalid_error!("plane stride"))?;
+            let src = src_planes.plane_data(index).ok_or_else(|| invalid_error!("plane data"))?;
+            let dst = dst_planes.plane_data_mut(index).ok_or_else(|| invalid_error!("plane data"))?;
+            for (src_row, dst_row) in src.chunks(src_stride).zip(dst.chunks_mut(dst_stride)).take(rows) {
+                dst_row[..row_bytes].copy_from_slice(&src_row[..row_bytes]);
+            }
+        }
+    }
+
+    Ok(copy)
+}
+
+fn into_cm_time(value: Option<i64>, time_base: Rational64) -> CMTime {
+    match (value, i32::try_from(*time_base.denom())) {
+        (Some(value), Ok(timescale)) => CMTime::make(value * time_base.numer(), timescale),
+        _ => unsafe { kCMTimeInvalid },
+    }
+}
+
+fn from_cm_time(time: CMTime, time_base: Rational64) -> Option<i64> {
+    if time.flags & kCMTimeFlags_Valid == 0 || time.timescale == 0 || *time_base.numer() == 0 {
+        return None;
+    }
+
+    let value = time.value as i128 * *time_base.denom() as i128 / (time.timescale as i128 * *time_base.numer() as i128);
+    Some(value as i64)
+}
+
+fn into_packet(sample_buffer: &CMSampleBuffer, time_base: Rational64) -> Result<Packet<'static>> {
+    let block_buffer = sample_buffer.get_data_buffer().ok_or_else(|| failed_error!("no data buffer"))?;
+    let mut data = vec![0; block_buffer.get_data_length()];
+    block_buffer.copy_data_bytes(0, &mut data).map_err(|status| os_status_error("CMBlockBuffer", status))?;
+
+    // samples without the NotSync attachment are sync samples
+    let not_sync_key = unsafe { CFString::wrap_under_get_rule(kCMSampleAttachmentKey_NotSync) };
+    let not_sync = sample_buffer
+        .get_sample_attachments_array(false)
+        .and_then(|attachments| {
+            let attachment = attachments.get(0)?;
+            let not_sync = attachment.find(&not_sync_key)?;
+            not_sync.downcast::<CFBoolean>().map(bool::from)
+        })
+        .unwrap_or(false);
+
+    let mut packet = Packet::from_vec(data);
+    packet.pts = from_cm_time(sample_buffer.get_presentation_time_stamp(), time_base);
+    packet.dts = from_cm_time(sample_buffer.get_decode_time_stamp(), time_base).or(packet.pts);
+    packet.duration = from_cm_time(sample_buffer.get_duration(), time_base);
+    packet.time_base = Some(time_base);
+    if !not_sync {
+        packet.flags = PacketFlags::Key;
+    }
+
+    Ok(packet)
+}
+
+fn avcc_from_sample_buffer(sample_buffer: &CMSampleBuffer) -> Option<Vec<u8>> {
+    let format_description = sample_buffer.get_format_description()?.downcast::<CMVideoFormatDescription>()?;
+    let (_, count, _) = format_description.get_h264_parameter_set_at_index(0).ok()?;
+    let parameter_sets = (0..count)
+        .map(|index| format_description.get_h264_parameter_set_at_index(index).map(|(parameter_set, _, _)| parameter_set))
+        .collect::<std::result::Result<Vec<_>, _>>()
+        .ok()?;
+
+    build_avcc(&parameter_sets).ok()
+}
+
+impl CodecInformation for VideoToolboxEncoder {
+    fn id(&self) -> CodecID {
+        CodecID::H264
+    }
+
+    fn name(&self) -> &'static str {
+        "videotoolbox"
+    }
+}
+
+impl Codec<VideoEncoder> for VideoToolboxEncoder {
+    fn configure(&mut self, _params: Option<&CodecParameters>, _options: Option<&Variant>) -> Result<()> {
+        Ok(())
+    }
+
+    fn set_option(&mut self, _key: &str, _value: &Variant) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl Encoder<VideoEncoder> for VideoToolboxEncoder {
+    fn send_frame(
+        &mut self,
+        _config: &VideoEncoder,
+        _pool: Option<&Arc<BufferPool>>,
+        frame: SharedFrame<Frame<'static, VideoFrameDescriptor>>,
+    ) -> Result<()> {
+        self.encode(frame.read())
+    }
+
+    fn receive_packet(&mut self, _config: &VideoEncoder, _pool: Option<&Arc<BufferPool>>) -> Result<Packet<'static>> {
+        let mut output = self.output.lock().map_err(|_| failed_error!("lock output"))?;
+        if let Some(extra_data) = output.extra_data.take() {
+            self.extra_data = Some(extra_data);
+        }
+        output.packets.pop_front().unwrap_or_else(|| Err(Error::Again("no packet".into())))
+    }
+
+    // waits for the frames still being encoded
+    fn flush(&mut self, _config: &VideoEncoder) -> Result<()> {
+        self.session.complete_frames(unsafe { kCMTimeInvalid }).map_err(|status| os_status_error("VTCompressionSession", status))
+    }
+
+    fn extra_data(&self) -> Option<&[u8]> {
+        self.extra_data.as_deref()
+    }
+}
+
+define_codec_builder!(VideoToolboxEncoderBuilder<VideoEncoder> {
+    name: "videotoolbox",
+    ids: [H264]
+});
+
+impl EncoderBuilder<VideoEncoder> for VideoToolboxEncoderBuilder {
+    fn new_encoder(&self, id: CodecID, params: &CodecParameters, _options: Option<&Variant>) -> Result<Box<dyn Encoder<VideoEncoder>>> {
+        Ok(Box::new(VideoToolboxEncoder::new(id, params)?))
+    }
+}
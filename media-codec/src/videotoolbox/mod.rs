@@ -0,0 +1,12 @@
+// Hardware codecs through VideoToolbox on macOS and iOS
+#[cfg(feature = "decoder")]
+pub mod decoder;
+#[cfg(feature = "encoder")]
+pub mod encoder;
+
+use core_foundation::base::OSStatus;
+use media_core::error::Error;
+
+fn os_status_error(name: &'static str, status: OSStatus) -> Error {
+    Error::Failed(format!("{name}: {status}").into())
+}
@@ -0,0 +1,106 @@
+#![cfg(feature = "decoder")]
+
+use media_codec::{
+    bitstream_filter::{BitstreamFilter, Mp4ToAnnexB},
+    decoder::DecoderParameters,
+    packet::{Packet, PacketFlags},
+    CodecID,
+};
+use media_core::error::Error;
+
+const SPS: &[u8] = &[0x67, 0x64, 0x00, 0x1f];
+const PPS: &[u8] = &[0x68, 0xee, 0x3c];
+
+fn avcc(length_size: u8) -> Vec<u8> {
+    let mut extra_data = vec![1, 0x64, 0x00, 0x1f, 0xfc | (length_size - 1), 0xe1, 0, SPS.len() as u8];
+    extra_data.extend_from_slice(SPS);
+    extra_data.extend_from_slice(&[1, 0, PPS.len() as u8]);
+    extra_data.extend_from_slice(PPS);
+    extra_data
+}
+
+fn length_prefixed(nal_units: &[&[u8]], length_size: usize) -> Vec<u8> {
+    let mut data = Vec::new();
+    for nal_unit in nal_units {
+        data.extend_from_slice(&(nal_unit.len() as u32).to_be_bytes()[4 - length_size..]);
+        data.extend_from_slice(nal_unit);
+    }
+    data
+}
+
+fn annexb(nal_units: &[&[u8]]) -> Vec<u8> {
+    nal_units.iter().flat_map(|nal_unit| [0, 0, 0, 1].iter().chain(nal_unit.iter()).copied()).collect()
+}
+
+fn filter(length_size: u8) -> Mp4ToAnnexB {
+    let params = DecoderParameters {
+        extra_data: Some(avcc(length_size)),
+        ..Default::default()
+    };
+    Mp4ToAnnexB::new(CodecID::H264, &params).unwrap()
+}
+
+#[test]
+fn test_mp4_to_annexb() {
+    let sei: &[u8] = &[0x06, 0x05, 0x01, 0x80];
+    let idr: &[u8] = &[0x65, 0x88, 0x84, 0x00, 0x33];
+    let slice: &[u8] = &[0x41, 0x9a, 0x02];
+
+    let mut filter = filter(4);
+    let sequence = [(vec![sei, idr], true), (vec![slice], false), (vec![slice], false), (vec![idr], true)];
+    for (pts, (nal_units, key)) in sequence.iter().enumerate() {
+        let data = length_prefixed(nal_units, 4);
+        let mut packet = Packet::from_slice(&data);
+        packet.pts = Some(pts as i64);
+        if *key {
+            packet.flags = PacketFlags::Key;
+        }
+        filter.send_packet(&packet).unwrap();
+    }
+
+    for (pts, (nal_units, key)) in sequence.iter().enumerate() {
+        let packet = filter.receive_packet().unwrap();
+        assert_eq!(packet.pts, Some(pts as i64));
+        let expected = if *key {
+            annexb(&[SPS, PPS].into_iter().chain(nal_units.iter().copied()).collect::<Vec<_>>())
+        } else {
+            annexb(nal_units)
+        };
+        assert_eq!(packet.payload(), expected);
+    }
+    assert!(matches!(filter.receive_packet(), Err(Error::Again(_))));
+
+    // in-band parameter sets are not repeated, IDR marks keyframes without the flag
+    let data = length_prefixed(&[SPS, PPS, idr], 4);
+    filter.send_packet(&Packet::from_slice(&data)).unwrap();
+    assert_eq!(filter.receive_packet().unwrap().payload(), annexb(&[SPS, PPS, idr]));
+    let data = length_prefixed(&[idr], 4);
+    filter.send_packet(&Packet::from_slice(&data)).unwrap();
+    assert_eq!(filter.receive_packet().unwrap().payload(), annexb(&[SPS, PPS, idr]));
+
+    // two byte lengths
+    let mut filter = self::filter(2);
+    let data = length_prefixed(&[slice, slice], 2);
+    filter.send_packet(&Packet::from_slice(&data)).unwrap();
+    assert_eq!(filter.receive_packet().unwrap().payload(), annexb(&[slice, slice]));
+
+    // a length running past the packet
+    let mut data = length_prefixed(&[slice], 2);
+    data[1] += 1;
+    assert!(filter.send_packet(&Packet::from_slice(&data)).is_err());
+
+    let params = DecoderParameters {
+        extra_data: Some(annexb(&[SPS, PPS])),
+        ..Default::default()
+    };
+    assert!(Mp4ToAnnexB::new(CodecID::H264, &params).is_err());
+    assert!(Mp4ToAnnexB::new(CodecID::H264, &DecoderParameters::default()).is_err());
+    assert!(Mp4ToAnnexB::new(
+        CodecID::HEVC,
+        &DecoderParameters {
+            extra_data: Some(avcc(4)),
+            ..Default::default()
+        }
+    )
+    .is_err());
+}
@@ -0,0 +1,140 @@
+#![cfg(all(feature = "openh264", feature = "decoder", feature = "encoder"))]
+
+use std::sync::Arc;
+
+use media_codec::{
+    decoder::{register_decoder, DecoderContext, DecoderParameters, VideoDecoder},
+    encoder::{register_encoder, EncoderContext, EncoderParameters, VideoEncoder},
+    openh264::{decoder::OpenH264DecoderBuilder, encoder::OpenH264EncoderBuilder},
+    packet::{Packet, PacketFlags},
+    CodecID, CodecParameters, VideoParameters,
+};
+use media_core::{
+    error::Error,
+    frame::{Frame, SharedFrame},
+    rational::Rational64,
+    video::{PixelFormat, VideoFrame, VideoFrameDescriptor},
+};
+
+// 16x16 IDR with SPS/PPS of a flat Y=81 U=90 V=240 picture
+const IDR: &[u8] = &[
+    0x00, 0x00, 0x00, 0x01, 0x67, 0x42, 0xD0, 0x0B, 0x8C, 0x69, 0xC8, 0x07, 0x84, 0x42, 0x35, 0x00, 0x00, 0x00, 0x01, 0x68, 0xCE, 0x3C, 0x80, 0x00,
+    0x00, 0x00, 0x01, 0x65, 0xB8, 0x00, 0x04, 0x09, 0xFF, 0xFF, 0xF8, 0x22, 0x8A, 0x00, 0x02, 0x01, 0xBE, 0x38, 0x00, 0x08, 0x15, 0xC7, 0x00, 0x01,
+    0x0B, 0xEC,
+];
+
+#[test]
+fn test_openh264_decode() {
+    register_decoder::<VideoDecoder>(Arc::new(OpenH264DecoderBuilder), false).unwrap();
+
+    let params = CodecParameters::new(
+        VideoParameters::default(),
+        DecoderParameters {
+            use_pool: Some(true),
+            ..Default::default()
+        },
+    );
+    let mut decoder = DecoderContext::<VideoDecoder>::new(CodecID::H264, Some("openh264"), &params, None).unwrap();
+    assert_eq!(decoder.codec_name(), "openh264");
+
+    let mut packet = Packet::from_slice(IDR);
+    packet.pts = Some(0);
+    packet.flags = PacketFlags::Key;
+    decoder.send_packet(&packet).unwrap();
+    decoder.flush().unwrap();
+
+    let shared_frame = decoder.receive_frame().unwrap();
+    let frame = shared_frame.read();
+    assert_eq!(frame.pts, Some(0));
+    let desc = frame.descriptor();
+    assert_eq!(desc.format, PixelFormat::I420);
+    assert_eq!((desc.width().get(), desc.height().get()), (16, 16));
+
+    {
+        let guard = frame.map().unwrap();
+        let planes = guard.try_planes().unwrap();
+        let stride = planes.plane_stride(0).unwrap();
+        assert!(planes.plane_data(0).unwrap()[8 * stride + 8].abs_diff(81) <= 2);
+        assert_eq!(planes.plane_data(1).unwrap()[0], 90);
+        assert_eq!(planes.plane_data(2).unwrap()[0], 240);
+    }
+
+    assert!(matches!(decoder.receive_frame(), Err(Error::Again(_))));
+}
+
+fn flat_frame(format: PixelFormat, pts: i64) -> SharedFrame<Frame<'static, VideoFrameDescriptor>> {
+    let mut frame = VideoFrame::new(format, 32, 32).unwrap();
+    frame.pts = Some(pts);
+    frame.time_base = Some(Rational64::new(1, 30));
+    {
+        let mut guard = frame.map_mut().unwrap();
+        let mut planes = guard.try_planes_mut().unwrap();
+        planes.plane_data_mut(0).unwrap().fill(81);
+        match format {
+            PixelFormat::NV12 => planes.plane_data_mut(1).unwrap().chunks_exact_mut(2).for_each(|uv| uv.copy_from_slice(&[90, 240])),
+            _ => {
+                planes.plane_data_mut(1).unwrap().fill(90);
+                planes.plane_data_mut(2).unwrap().fill(240);
+            }
+        }
+    }
+    SharedFrame::<Frame<'static, VideoFrameDescriptor>>::new(frame)
+}
+
+#[test]
+fn test_openh264_encode() {
+    register_encoder::<VideoEncoder>(Arc::new(OpenH264EncoderBuilder), false).unwrap();
+    register_decoder::<VideoDecoder>(Arc::new(OpenH264DecoderBuilder), false).unwrap();
+
+    let params = CodecParameters::new(VideoParameters::default(), EncoderParameters::default());
+    let mut encoder = EncoderContext::<VideoEncoder>::new(CodecID::H264, Some("openh264"), &params, None).unwrap();
+    assert!(encoder.extra_data().is_none());
+
+    let mut packets = Vec::new();
+    for (pts, format) in [PixelFormat::NV12, PixelFormat::I420, PixelFormat::NV12].into_iter().enumerate() {
+        encoder.send_frame(flat_frame(format, pts as i64)).unwrap();
+        while let Ok(packet) = encoder.receive_packet() {
+            packets.push(packet);
+        }
+    }
+    encoder.flush().unwrap();
+    while let Ok(packet) = encoder.receive_packet() {
+        packets.push(packet);
+    }
+
+    assert_eq!(packets.len(), 3);
+    assert!(packets[0].flags.contains(PacketFlags::Key));
+    assert!(!packets[1].flags.contains(PacketFlags::Key));
+    for (pts, packet) in packets.iter().enumerate() {
+        assert_eq!((packet.pts, packet.dts), (Some(pts as i64), Some(pts as i64)));
+        assert_eq!(packet.time_base, Some(Rational64::new(1, 30)));
+    }
+
+    // avcC extradata with 4 byte NAL lengths, the parameter sets stay out of band
+    let extra_data = encoder.extra_data().unwrap().to_vec();
+    assert_eq!((extra_data[0], extra_data[4] & 0x03), (1, 3));
+    let payload = packets[0].payload();
+    assert_eq!(payload[4] & 0x1f, 5);
+    assert_eq!(u32::from_be_bytes(payload[..4].try_into().unwrap()) as usize, payload.len() - 4);
+
+    let params = CodecParameters::new(
+        VideoParameters::default(),
+        DecoderParameters {
+            extra_data: Some(extra_data),
+            ..Default::default()
+        },
+    );
+    let mut decoder = DecoderContext::<VideoDecoder>::new(CodecID::H264, Some("openh264"), &params, None).unwrap();
+    decoder.send_packet(&packets[0]).unwrap();
+    decoder.flush().unwrap();
+
+    let shared_frame = decoder.receive_frame().unwrap();
+    let frame = shared_frame.read();
+    let desc = frame.descriptor();
+    assert_eq!((desc.width().get(), desc.height().get()), (32, 32));
+    let guard = frame.map().unwrap();
+    let planes = guard.try_planes().unwrap();
+    assert!(planes.plane_data(0).unwrap()[0].abs_diff(81) <= 2);
+    assert!(planes.plane_data(1).unwrap()[0].abs_diff(90) <= 2);
+    assert!(planes.plane_data(2).unwrap()[0].abs_diff(240) <= 2);
+}
@@ -0,0 +1,44 @@
+#![cfg(all(feature = "video", feature = "decoder"))]
+
+use media_codec::{reorder::ReorderBuffer, VideoParameters};
+use media_core::{
+    frame::{Frame, SharedFrame},
+    video::{PixelFormat, VideoFrame, VideoFrameDescriptor},
+};
+
+fn decoded_frame(pts: i64, dts: i64) -> SharedFrame<Frame<'static, VideoFrameDescriptor>> {
+    let desc = VideoFrameDescriptor::try_new(PixelFormat::I420, 16, 16).unwrap();
+    let mut frame = VideoFrame::new_with_descriptor(desc).unwrap();
+    frame.pts = Some(pts);
+    frame.dts = Some(dts);
+    SharedFrame::<Frame<'static, VideoFrameDescriptor>>::new(frame)
+}
+
+#[test]
+fn test_reorder_buffer() {
+    // I P B B P B B in decode order, two B-frames between references
+    let decode_order = [(0, 0), (3, 1), (1, 2), (2, 3), (6, 4), (4, 5), (5, 6)];
+
+    let params = VideoParameters {
+        max_num_reorder_frames: Some(2),
+        ..Default::default()
+    };
+    let mut buffer = ReorderBuffer::from_video_parameters(&params);
+    assert_eq!(buffer.depth(), 2);
+
+    let mut output = Vec::new();
+    for (pts, dts) in decode_order {
+        output.extend(buffer.push(decoded_frame(pts, dts)).map(|frame| frame.read().pts.unwrap()));
+    }
+    assert_eq!(buffer.len(), 2);
+    output.extend(buffer.drain().map(|frame| frame.read().pts.unwrap()));
+    assert!(buffer.is_empty());
+    assert_eq!(output, [0, 1, 2, 3, 4, 5, 6]);
+
+    // without reordering frames pass straight through
+    let mut buffer = ReorderBuffer::new(0);
+    assert_eq!(buffer.push(decoded_frame(5, 5)).unwrap().read().pts, Some(5));
+    assert!(buffer.is_empty());
+
+    assert_eq!(ReorderBuffer::<VideoFrameDescriptor>::from_video_parameters(&VideoParameters::default()).depth(), 16);
+}
@@ -0,0 +1,110 @@
+#![cfg(all(target_os = "macos", feature = "decoder", feature = "encoder"))]
+
+use std::{num::NonZeroU32, sync::Arc};
+
+use media_codec::{
+    decoder::{register_decoder, DecoderContext, DecoderParameters, VideoDecoder},
+    encoder::{register_encoder, EncoderContext, EncoderParameters, VideoEncoder},
+    packet::{Packet, PacketFlags},
+    videotoolbox::{decoder::VideoToolboxDecoderBuilder, encoder::VideoToolboxEncoderBuilder},
+    CodecID, CodecParameters, VideoParameters,
+};
+use media_core::{
+    error::Error,
+    frame::{Frame, SharedFrame},
+    rational::Rational64,
+    video::{PixelFormat, VideoFrame, VideoFrameDescriptor},
+};
+
+// parameter sets and IDR of a flat 16x16 Y=81 U=90 V=240 picture
+const SPS: &[u8] = &[0x67, 0x42, 0xD0, 0x0B, 0x8C, 0x69, 0xC8, 0x07, 0x84, 0x42, 0x35];
+const PPS: &[u8] = &[0x68, 0xCE, 0x3C, 0x80];
+const IDR: &[u8] =
+    &[0x65, 0xB8, 0x00, 0x04, 0x09, 0xFF, 0xFF, 0xF8, 0x22, 0x8A, 0x00, 0x02, 0x01, 0xBE, 0x38, 0x00, 0x08, 0x15, 0xC7, 0x00, 0x01, 0x0B, 0xEC];
+
+fn avcc() -> Vec<u8> {
+    let mut extra_data = vec![1, SPS[1], SPS[2], SPS[3], 0xff, 0xe1, 0, SPS.len() as u8];
+    extra_data.extend_from_slice(SPS);
+    extra_data.extend_from_slice(&[1, 0, PPS.len() as u8]);
+    extra_data.extend_from_slice(PPS);
+    extra_data
+}
+
+#[test]
+fn test_videotoolbox_decode() {
+    register_decoder::<VideoDecoder>(Arc::new(VideoToolboxDecoderBuilder), false).unwrap();
+
+    let params = CodecParameters::new(
+        VideoParameters::default(),
+        DecoderParameters {
+            extra_data: Some(avcc()),
+            ..Default::default()
+        },
+    );
+    let mut decoder = DecoderContext::<VideoDecoder>::new(CodecID::H264, Some("videotoolbox"), &params, None).unwrap();
+
+    let mut data = (IDR.len() as u32).to_be_bytes().to_vec();
+    data.extend_from_slice(IDR);
+    let mut packet = Packet::from_vec(data);
+    packet.pts = Some(0);
+    packet.dts = Some(0);
+    packet.flags = PacketFlags::Key;
+    decoder.send_packet(&packet).unwrap();
+    decoder.flush().unwrap();
+
+    let shared_frame = decoder.receive_frame().unwrap();
+    let frame = shared_frame.read();
+    assert_eq!(frame.pts, Some(0));
+    let desc = frame.descriptor();
+    assert_eq!(desc.format, PixelFormat::NV12);
+    assert_eq!((desc.width().get(), desc.height().get()), (16, 16));
+
+    {
+        let guard = frame.map().unwrap();
+        let planes = guard.try_planes().unwrap();
+        let stride = planes.plane_stride(0).unwrap();
+        assert!(planes.plane_data(0).unwrap()[8 * stride + 8].abs_diff(81) <= 2);
+        assert_eq!(&planes.plane_data(1).unwrap()[..2], &[90, 240]);
+    }
+
+    assert!(matches!(decoder.receive_frame(), Err(Error::Again(_))));
+}
+
+#[test]
+fn test_videotoolbox_encode() {
+    register_encoder::<VideoEncoder>(Arc::new(VideoToolboxEncoderBuilder), false).unwrap();
+
+    let video = VideoParameters {
+        width: NonZeroU32::new(64),
+        height: NonZeroU32::new(64),
+        frame_rate: Some(Rational64::new(30, 1)),
+        ..Default::default()
+    };
+    let params = CodecParameters::new(video, EncoderParameters::default());
+    let mut encoder = EncoderContext::<VideoEncoder>::new(CodecID::H264, Some("videotoolbox"), &params, None).unwrap();
+
+    // one memory frame and one pixel buffer frame
+    let mut packets = Vec::new();
+    for pts in 0..2 {
+        let mut frame = if pts == 0 {
+            VideoFrame::new(PixelFormat::NV12, 64, 64).unwrap()
+        } else {
+            VideoFrame::new_pixel_buffer(PixelFormat::NV12, 64, 64).unwrap()
+        };
+        frame.pts = Some(pts);
+        frame.time_base = Some(Rational64::new(1, 30));
+        encoder.send_frame(SharedFrame::<Frame<'static, VideoFrameDescriptor>>::new(frame)).unwrap();
+    }
+    encoder.flush().unwrap();
+    while let Ok(packet) = encoder.receive_packet() {
+        packets.push(packet);
+    }
+
+    assert_eq!(packets.len(), 2);
+    assert!(packets[0].flags.contains(PacketFlags::Key));
+    for (pts, packet) in packets.iter().enumerate() {
+        assert_eq!((packet.pts, packet.dts), (Some(pts as i64), Some(pts as i64)));
+    }
+    let extra_data = encoder.extra_data().unwrap();
+    assert_eq!((extra_data[0], extra_data[4] & 0x03), (1, 3));
+}
@@ -181,6 +181,43 @@ impl CodecParameters {
     }
 }
 
+#[cfg(feature = "decoder")]
+impl CodecParameters {
+    // Whether a decoder configured for one set of parameters can decode the other
+    pub fn compatible_with(&self, other: &CodecParameters) -> bool {
+        let media_compatible = match (&self.media, &other.media) {
+            #[cfg(feature = "audio")]
+            (MediaParametersType::Audio(params), MediaParametersType::Audio(other)) => params.compatible_with(other),
+            #[cfg(feature = "video")]
+            (MediaParametersType::Video(params), MediaParametersType::Video(other)) => params.compatible_with(other),
+            #[allow(unreachable_patterns)]
+            _ => false,
+        };
+
+        let codec_compatible = match (&self.codec, &other.codec) {
+            (CodecParametersType::Decoder(params), CodecParametersType::Decoder(other)) => params.compatible_with(other),
+            #[allow(unreachable_patterns)]
+            _ => false,
+        };
+
+        media_compatible && codec_compatible
+    }
+}
+
+// Writes the value or "?" if it is unknown
+#[cfg(any(feature = "audio", feature = "video"))]
+struct OptionDisplay<'a, T>(&'a Option<T>);
+
+#[cfg(any(feature = "audio", feature = "video"))]
+impl<T: Display> Display for OptionDisplay<'_, T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self.0 {
+            Some(value) => write!(f, "{}", value),
+            None => f.write_str("?"),
+        }
+    }
+}
+
 pub trait CodecSpec: Clone + Send + Sync + 'static {
     type FrameDescriptor: FrameDescriptorSpec;
 
@@ -220,6 +257,27 @@ impl AudioParameters {
             _ => {}
         }
     }
+
+    pub fn compatible_with(&self, other: &AudioParameters) -> bool {
+        self.format == other.format && self.sample_rate == other.sample_rate && self.channel_layout == other.channel_layout
+    }
+}
+
+#[cfg(feature = "audio")]
+impl Display for AudioParameters {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match &self.format {
+            Some(format) => write!(f, "{:?}", format)?,
+            None => f.write_str("?")?,
+        }
+        write!(f, ", {} Hz", OptionDisplay(&self.sample_rate))?;
+        write!(f, ", {} channels", OptionDisplay(&self.channel_layout.as_ref().map(|channel_layout| channel_layout.channels)))?;
+        if let Some(samples) = self.samples {
+            write!(f, ", {} samples", samples)?;
+        }
+
+        Ok(())
+    }
 }
 
 #[cfg(feature = "audio")]
@@ -247,6 +305,7 @@ pub struct VideoParameters {
     pub color_transfer_characteristics: Option<ColorTransferCharacteristics>,
     pub chroma_location: Option<ChromaLocation>,
     pub frame_rate: Option<Rational64>,
+    pub max_num_reorder_frames: Option<u32>,
 }
 
 #[cfg(feature = "video")]
@@ -261,6 +320,7 @@ impl VideoParameters {
         self.color_transfer_characteristics = other.color_transfer_characteristics.or(self.color_transfer_characteristics);
         self.chroma_location = other.chroma_location.or(self.chroma_location);
         self.frame_rate = other.frame_rate.or(self.frame_rate);
+        self.max_num_reorder_frames = other.max_num_reorder_frames.or(self.max_num_reorder_frames);
     }
 
     pub(crate) fn update_with_option(&mut self, key: &str, value: &Variant) {
@@ -275,9 +335,42 @@ impl VideoParameters {
                 self.color_transfer_characteristics = value.get_uint32().and_then(|v| ColorTransferCharacteristics::try_from(v as usize).ok())
             }
             "chroma_location" => self.chroma_location = value.get_uint32().map(|v| ChromaLocation::from(v as usize)),
+            "max_num_reorder_frames" => self.max_num_reorder_frames = value.get_uint32(),
             _ => {}
         }
     }
+
+    pub fn compatible_with(&self, other: &VideoParameters) -> bool {
+        self.format == other.format && self.width == other.width && self.height == other.height
+    }
+}
+
+#[cfg(feature = "video")]
+impl Display for VideoParameters {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match &self.format {
+            Some(format) => write!(f, "{:?}", format)?,
+            None => f.write_str("?")?,
+        }
+        write!(f, ", {}x{}", OptionDisplay(&self.width), OptionDisplay(&self.height))?;
+        if let Some(frame_rate) = self.frame_rate {
+            write!(f, ", {} fps", frame_rate)?;
+        }
+        if let Some(color_range) = self.color_range {
+            write!(f, ", range: {:?}", color_range)?;
+        }
+        if let Some(color_matrix) = self.color_matrix {
+            write!(f, ", matrix: {:?}", color_matrix)?;
+        }
+        if let Some(color_primaries) = self.color_primaries {
+            write!(f, ", primaries: {:?}", color_primaries)?;
+        }
+        if let Some(color_transfer_characteristics) = self.color_transfer_characteristics {
+            write!(f, ", transfer: {:?}", color_transfer_characteristics)?;
+        }
+
+        Ok(())
+    }
 }
 
 #[cfg(feature = "video")]
@@ -40,6 +40,10 @@ impl DecoderParameters {
             _ => {}
         }
     }
+
+    pub fn compatible_with(&self, other: &DecoderParameters) -> bool {
+        self.extra_data == other.extra_data
+    }
 }
 
 impl TryFrom<&CodecParametersType> for DecoderParameters {
@@ -197,6 +201,9 @@ impl CodecSpec for VideoDecoder {
 }
 
 pub trait Decoder<T: CodecSpec>: Codec<T> + Send + Sync {
+    fn codec_id(&self) -> CodecID {
+        self.id()
+    }
     fn init(&mut self, _config: &T) -> Result<()> {
         Ok(())
     }
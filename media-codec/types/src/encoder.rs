@@ -218,12 +218,20 @@ impl CodecSpec for VideoEncoder {
 }
 
 pub trait Encoder<T: CodecSpec>: Codec<T> + Send + Sync {
+    fn codec_id(&self) -> CodecID {
+        self.id()
+    }
     fn init(&mut self, _config: &T) -> Result<()> {
         Ok(())
     }
     fn send_frame(&mut self, config: &T, pool: Option<&Arc<BufferPool>>, frame: SharedFrame<Frame<'static, T::FrameDescriptor>>) -> Result<()>;
     fn receive_packet(&mut self, config: &T, pool: Option<&Arc<BufferPool>>) -> Result<Packet<'static>>;
     fn flush(&mut self, config: &T) -> Result<()>;
+    // out-of-band codec configuration such as the avcC record, available once
+    // the encoder has produced it
+    fn extra_data(&self) -> Option<&[u8]> {
+        None
+    }
 }
 
 pub trait EncoderBuilder<T: CodecSpec>: CodecBuilder<T> {
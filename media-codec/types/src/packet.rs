@@ -125,6 +125,10 @@ impl<'a> Packet<'a> {
         Self::from_data(PacketData::from_buffer(buffer))
     }
 
+    pub fn from_vec(data: Vec<u8>) -> Self {
+        Self::from_data(PacketData::from_vec(data))
+    }
+
     pub fn into_owned(self) -> Packet<'static> {
         Packet {
             pts: self.pts,
@@ -158,6 +162,28 @@ impl<'a> Packet<'a> {
         self.data.as_mut_slice()
     }
 
+    pub fn payload(&self) -> &[u8] {
+        self.data.as_slice()
+    }
+
+    pub fn with_prefix(&self, prefix: &[u8]) -> Packet<'static> {
+        let payload = self.payload();
+        let mut data = Vec::with_capacity(prefix.len() + payload.len());
+        data.extend_from_slice(prefix);
+        data.extend_from_slice(payload);
+
+        Packet {
+            pts: self.pts,
+            dts: self.dts,
+            duration: self.duration,
+            time_base: self.time_base,
+            flags: self.flags,
+            pos: self.pos,
+            track_index: self.track_index,
+            data: PacketData::from_vec(data),
+        }
+    }
+
     pub fn truncate(&mut self, len: usize) -> Result<()> {
         let current_len = self.data.len();
         if len > current_len {
@@ -0,0 +1,40 @@
+#![cfg(all(feature = "video", feature = "decoder"))]
+
+use std::num::NonZeroU32;
+
+use media_codec_types::{decoder::DecoderParameters, CodecParameters, VideoParameters};
+use media_core::{rational::Rational64, video::PixelFormat};
+
+fn video_parameters(width: u32, height: u32) -> VideoParameters {
+    VideoParameters {
+        format: Some(PixelFormat::I420),
+        width: NonZeroU32::new(width),
+        height: NonZeroU32::new(height),
+        frame_rate: Some(Rational64::new(30, 1)),
+        ..Default::default()
+    }
+}
+
+fn decoder_parameters() -> DecoderParameters {
+    DecoderParameters {
+        extra_data: Some(vec![0x01, 0x64, 0x00, 0x1f]),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_video_parameters_compatible_with() {
+    let params = video_parameters(1920, 1080);
+    assert!(params.compatible_with(&video_parameters(1920, 1080)));
+    assert!(!params.compatible_with(&video_parameters(1280, 720)));
+
+    let codec_params = CodecParameters::new(params.clone(), decoder_parameters());
+    assert!(codec_params.compatible_with(&CodecParameters::new(params.clone(), decoder_parameters())));
+    assert!(!codec_params.compatible_with(&CodecParameters::new(params, DecoderParameters::default())));
+}
+
+#[test]
+fn test_video_parameters_display() {
+    assert_eq!(video_parameters(1920, 1080).to_string(), "I420, 1920x1080, 30 fps");
+    assert_eq!(VideoParameters::default().to_string(), "?, ?x?");
+}
@@ -0,0 +1,103 @@
+#![cfg(all(feature = "video", feature = "decoder"))]
+
+use std::sync::Arc;
+
+use media_codec_types::{
+    decoder::{Decoder, VideoDecoder},
+    packet::Packet,
+    Codec, CodecID, CodecInformation, CodecParameters,
+};
+use media_core::{
+    error::Error,
+    frame::{Frame, SharedFrame},
+    frame_pool::FramePool,
+    variant::Variant,
+    video::{PixelFormat, VideoFrame, VideoFrameDescriptor},
+    Result,
+};
+
+struct MockDecoder {
+    id: CodecID,
+    width: u32,
+    pending: usize,
+}
+
+impl CodecInformation for MockDecoder {
+    fn id(&self) -> CodecID {
+        self.id
+    }
+
+    fn name(&self) -> &'static str {
+        "mock"
+    }
+}
+
+impl Codec<VideoDecoder> for MockDecoder {
+    fn configure(&mut self, _params: Option<&CodecParameters>, _options: Option<&Variant>) -> Result<()> {
+        Ok(())
+    }
+
+    fn set_option(&mut self, _key: &str, _value: &Variant) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl Decoder<VideoDecoder> for MockDecoder {
+    fn send_packet(
+        &mut self,
+        _config: &VideoDecoder,
+        _pool: Option<&Arc<FramePool<Frame<'static, VideoFrameDescriptor>>>>,
+        _packet: &Packet,
+    ) -> Result<()> {
+        self.pending += 1;
+        Ok(())
+    }
+
+    fn receive_frame(
+        &mut self,
+        _config: &VideoDecoder,
+        _pool: Option<&Arc<FramePool<Frame<'static, VideoFrameDescriptor>>>>,
+    ) -> Result<SharedFrame<Frame<'static, VideoFrameDescriptor>>> {
+        if self.pending == 0 {
+            return Err(Error::Again("no frame".into()));
+        }
+        self.pending -= 1;
+        let desc = VideoFrameDescriptor::try_new(PixelFormat::I420, self.width, 16)?;
+        Ok(SharedFrame::<Frame<'static, VideoFrameDescriptor>>::new(VideoFrame::new_with_descriptor(desc)?))
+    }
+
+    fn flush(&mut self, _config: &VideoDecoder) -> Result<()> {
+        self.pending = 0;
+        Ok(())
+    }
+}
+
+#[test]
+fn test_dyn_decoders() {
+    let config = VideoDecoder {
+        video: Default::default(),
+        decoder: Default::default(),
+    };
+    let mut decoders: Vec<Box<dyn Decoder<VideoDecoder>>> = vec![
+        Box::new(MockDecoder {
+            id: CodecID::H264,
+            width: 32,
+            pending: 0,
+        }),
+        Box::new(MockDecoder {
+            id: CodecID::HEVC,
+            width: 64,
+            pending: 0,
+        }),
+    ];
+
+    assert_eq!(decoders.iter().map(|decoder| decoder.codec_id()).collect::<Vec<_>>(), [CodecID::H264, CodecID::HEVC]);
+
+    let packet = Packet::from_slice(&[0, 0, 0, 1]);
+    for (decoder, width) in decoders.iter_mut().zip([32, 64]) {
+        decoder.send_packet(&config, None, &packet).unwrap();
+        let frame = decoder.receive_frame(&config, None).unwrap();
+        assert_eq!(frame.read().descriptor().width().get(), width);
+        assert!(decoder.receive_frame(&config, None).is_err());
+    }
+}
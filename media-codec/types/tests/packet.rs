@@ -0,0 +1,23 @@
+use media_codec_types::packet::{Packet, PacketFlags};
+
+#[test]
+fn test_packet_with_prefix() {
+    let extra_data = [0, 0, 0, 1, 0x67, 0x42, 0, 0, 0, 1, 0x68, 0xce];
+    let idr = [0, 0, 0, 1, 0x65, 0x88, 0x84];
+
+    let mut packet = Packet::from_slice(&idr);
+    packet.pts = Some(100);
+    packet.dts = Some(90);
+    packet.flags = PacketFlags::Key;
+
+    let prefixed = packet.with_prefix(&extra_data);
+    assert_eq!(prefixed.len(), extra_data.len() + idr.len());
+    assert_eq!(&prefixed.payload()[..extra_data.len()], &extra_data);
+    assert_eq!(&prefixed.payload()[extra_data.len()..], &idr);
+    assert_eq!(prefixed.pts, Some(100));
+    assert_eq!(prefixed.dts, Some(90));
+    assert_eq!(prefixed.flags, PacketFlags::Key);
+
+    // the source packet is left untouched
+    assert_eq!(packet.payload(), &idr);
+}
@@ -95,7 +95,7 @@ impl AudioCircularBuffer {
         }
 
         let guard = frame.map()?;
-        let planes = guard.planes().unwrap();
+        let planes = guard.try_planes()?;
 
         if planes.len() != self.buffers.len() {
             return Err(invalid_error!("plane count mismatch"));
@@ -103,7 +103,7 @@ impl AudioCircularBuffer {
 
         planes.iter().enumerate().try_for_each::<_, Result<()>>(|(i, plane)| {
             let buffer = &mut self.buffers[i];
-            buffer.write(plane.data().unwrap())?;
+            buffer.write(plane.data().ok_or_else(|| invalid_error!("plane data"))?)?;
             Ok(())
         })?;
 
@@ -119,7 +119,7 @@ impl AudioCircularBuffer {
         let samples = self.validate_frame(frame)?.min(self.len);
 
         let mut guard = frame.map_mut()?;
-        let mut planes = guard.planes_mut().unwrap();
+        let mut planes = guard.try_planes_mut()?;
 
         if planes.len() != self.buffers.len() {
             return Err(invalid_error!("plane count mismatch"));
@@ -127,7 +127,7 @@ impl AudioCircularBuffer {
 
         planes.iter_mut().enumerate().try_for_each::<_, Result<()>>(|(i, plane)| {
             let buffer = &mut self.buffers[i];
-            buffer.read(plane.data_mut().unwrap())?;
+            buffer.read(plane.data_mut().ok_or_else(|| invalid_error!("plane data"))?)?;
             Ok(())
         })?;
 
@@ -134,13 +134,143 @@ fn data_convert(
     Ok(())
 }
 
+// Triangular PDF dither with first-order error feedback, used when converting
+// float samples to integer samples. Keep one instance per stream so the error
+// state carries over between frames.
+pub struct AudioDither {
+    seed: u32,
+    errors: Vec<f64>,
+}
+
+impl Default for AudioDither {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl AudioDither {
+    pub fn new() -> Self {
+        Self {
+            seed: 0x9E37_79B9,
+            errors: Vec::new(),
+        }
+    }
+
+    pub fn reset(&mut self) {
+        self.errors.clear();
+    }
+
+    // xorshift32, uniform in [0, 1)
+    #[inline]
+    fn next_random(&mut self) -> f64 {
+        self.seed ^= self.seed << 13;
+        self.seed ^= self.seed >> 17;
+        self.seed ^= self.seed << 5;
+        self.seed as f64 / (u32::MAX as f64 + 1.0)
+    }
+
+    // difference of two uniform values, triangular in (-1, 1) LSB
+    #[inline]
+    fn tpdf(&mut self) -> f64 {
+        self.next_random() - self.next_random()
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn dither_samples<S: Pod, D: Pod>(
+    src_buffer: &[u8],
+    dst_buffer: &mut [u8],
+    src_data_step: usize,
+    dst_data_step: usize,
+    samples: u32,
+    dither: &mut AudioDither,
+    channel: usize,
+    load: impl Fn(S) -> f64,
+    store: impl Fn(f64) -> D,
+) {
+    let src_data: &[S] = bytemuck::cast_slice(src_buffer);
+    let dst_data: &mut [D] = bytemuck::cast_slice_mut(dst_buffer);
+    // interleaved buffers hold all channels, starting at the channel index
+    let src_offset = (src_data_step > 1) as usize * channel;
+    let dst_offset = (dst_data_step > 1) as usize * channel;
+
+    let scale = (1u64 << (size_of::<D>() * 8 - 1)) as f64;
+    let mut error = dither.errors[channel];
+
+    for i in 0..samples as usize {
+        let shaped = load(src_data[i * src_data_step + src_offset]) * scale - error;
+        let value = (shaped + dither.tpdf()).round().clamp(-scale, scale - 1.0);
+        error = value - shaped;
+        dst_data[i * dst_data_step + dst_offset] = store(value);
+    }
+
+    dither.errors[channel] = error;
+}
+
+fn data_dither(
+    src_planes: &MappedPlanes,
+    dst_planes: &mut MappedPlanes,
+    src_format: SampleFormat,
+    dst_format: SampleFormat,
+    channels: u8,
+    samples: u32,
+    dither: &mut AudioDither,
+) -> Result<()> {
+    let (src_plane_index_step, src_data_step) = if src_format.is_planar() {
+        (1, 1)
+    } else {
+        (0, channels as usize)
+    };
+
+    let (dst_plane_index_step, dst_data_step) = if dst_format.is_planar() {
+        (1, 1)
+    } else {
+        (0, channels as usize)
+    };
+
+    dither.errors.resize(channels as usize, 0.0);
+
+    for ch in 0..channels as usize {
+        let src_i = ch * src_plane_index_step;
+        let dst_i = ch * dst_plane_index_step;
+        let src_data = src_planes.plane_data(src_i).ok_or_else(|| invalid_error!(format!("out of range: src index {}", src_i)))?;
+        let dst_data = dst_planes.plane_data_mut(dst_i).ok_or_else(|| invalid_error!(format!("out of range: dst index {}", dst_i)))?;
+
+        macro_rules! dither {
+            ($load:expr, $store:expr) => {
+                dither_samples(src_data, dst_data, src_data_step, dst_data_step, samples, dither, ch, $load, $store)
+            };
+        }
+
+        match (src_format.packed_sample_format(), dst_format.packed_sample_format()) {
+            (SampleFormat::F32, SampleFormat::U8) => dither!(|x: f32| x as f64, |x| (x as i32 + 0x80) as u8),
+            (SampleFormat::F32, SampleFormat::S16) => dither!(|x: f32| x as f64, |x| x as i16),
+            (SampleFormat::F32, SampleFormat::S32) => dither!(|x: f32| x as f64, |x| x as i32),
+            (SampleFormat::F64, SampleFormat::U8) => dither!(|x: f64| x, |x| (x as i32 + 0x80) as u8),
+            (SampleFormat::F64, SampleFormat::S16) => dither!(|x: f64| x, |x| x as i16),
+            (SampleFormat::F64, SampleFormat::S32) => dither!(|x: f64| x, |x| x as i32),
+            _ => return Err(unsupported_error!(dst_format)),
+        }
+    }
+
+    Ok(())
+}
+
 impl Frame<'_> {
     pub fn convert_audio_to(&self, dst: &mut Frame) -> Result<()> {
         let (FrameDescriptor::Audio(src_desc), FrameDescriptor::Audio(dst_desc)) = (&self.desc, &dst.desc) else {
             return Err(invalid_error!("not audio frame"));
         };
 
-        AudioFrame::convert_audio_to_internal(src_desc, &self.data, dst_desc, &mut dst.data)
+        AudioFrame::convert_audio_to_internal(src_desc, &self.data, dst_desc, &mut dst.data, None)
+    }
+
+    pub fn convert_audio_to_with_dither(&self, dst: &mut Frame, dither: &mut AudioDither) -> Result<()> {
+        let (FrameDescriptor::Audio(src_desc), FrameDescriptor::Audio(dst_desc)) = (&self.desc, &dst.desc) else {
+            return Err(invalid_error!("not audio frame"));
+        };
+
+        AudioFrame::convert_audio_to_internal(src_desc, &self.data, dst_desc, &mut dst.data, Some(dither))
     }
 }
 
@@ -150,6 +280,7 @@ impl AudioFrame<'_> {
         src_data: &FrameData,
         dst_desc: &AudioFrameDescriptor,
         dst_data: &mut FrameData,
+        dither: Option<&mut AudioDither>,
     ) -> Result<()> {
         if src_desc.samples != dst_desc.samples {
             return Err(unsupported_error!("samples mismatch"));
@@ -164,8 +295,8 @@ impl AudioFrame<'_> {
 
         let guard = src_data.map()?;
         let mut dst_guard = dst_data.map_mut()?;
-        let src_planes = guard.planes().unwrap();
-        let mut dst_planes = dst_guard.planes_mut().unwrap();
+        let src_planes = guard.try_planes()?;
+        let mut dst_planes = dst_guard.try_planes_mut()?;
 
         let (src_format, dst_format) = if src_channels == 1 {
             (src_desc.format.planar_sample_format(), dst_desc.format.planar_sample_format())
@@ -173,14 +304,23 @@ impl AudioFrame<'_> {
             (src_desc.format, dst_desc.format)
         };
 
+        // dither only applies when reducing float samples to integers
+        let dither = dither.filter(|_| src_format.is_float() && !dst_format.is_float() && dst_format.bits() < 64);
+
         if src_format == dst_format {
             data_copy(&src_planes, &mut dst_planes)
+        } else if let Some(dither) = dither {
+            data_dither(&src_planes, &mut dst_planes, src_format, dst_format, src_channels, src_desc.samples.get(), dither)
         } else {
             data_convert(&src_planes, &mut dst_planes, src_format, dst_format, src_channels, src_desc.samples.get())
         }
     }
 
     pub fn convert_to(&self, dst: &mut AudioFrame) -> Result<()> {
-        Self::convert_audio_to_internal(&self.desc, &self.data, &dst.desc, &mut dst.data)
+        Self::convert_audio_to_internal(&self.desc, &self.data, &dst.desc, &mut dst.data, None)
+    }
+
+    pub fn convert_to_with_dither(&self, dst: &mut AudioFrame, dither: &mut AudioDither) -> Result<()> {
+        Self::convert_audio_to_internal(&self.desc, &self.data, &dst.desc, &mut dst.data, Some(dither))
     }
 }
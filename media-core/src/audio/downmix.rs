@@ -0,0 +1,186 @@
+use std::f32::consts::FRAC_1_SQRT_2;
+
+use super::{
+    audio::{AudioFrameDescriptor, SampleFormat},
+    channel_layout::{Channel, ChannelLayout},
+    frame::AudioFrame,
+};
+use crate::{
+    frame::{DataMappable, Frame, FrameData},
+    invalid_error, invalid_param_error, unsupported_error, FrameDescriptor, Result,
+};
+
+// where a source channel goes when the destination does not have it, the first
+// alternative the destination supports wins
+type DownmixTargets = &'static [(Channel, f32)];
+
+const DOWNMIX_RULES: &[(Channel, &[DownmixTargets])] = &[
+    (Channel::FrontCenter, &[&[(Channel::FrontLeft, FRAC_1_SQRT_2), (Channel::FrontRight, FRAC_1_SQRT_2)]]),
+    (Channel::FrontLeftOfCenter, &[&[(Channel::FrontLeft, 1.0)]]),
+    (Channel::FrontRightOfCenter, &[&[(Channel::FrontRight, 1.0)]]),
+    (Channel::BackLeft, &[&[(Channel::SideLeft, 1.0)], &[(Channel::FrontLeft, FRAC_1_SQRT_2)]]),
+    (Channel::BackRight, &[&[(Channel::SideRight, 1.0)], &[(Channel::FrontRight, FRAC_1_SQRT_2)]]),
+    (Channel::SideLeft, &[&[(Channel::BackLeft, 1.0)], &[(Channel::FrontLeft, FRAC_1_SQRT_2)]]),
+    (Channel::SideRight, &[&[(Channel::BackRight, 1.0)], &[(Channel::FrontRight, FRAC_1_SQRT_2)]]),
+    (
+        Channel::BackCenter,
+        &[
+            &[(Channel::BackLeft, FRAC_1_SQRT_2), (Channel::BackRight, FRAC_1_SQRT_2)],
+            &[(Channel::SideLeft, FRAC_1_SQRT_2), (Channel::SideRight, FRAC_1_SQRT_2)],
+            &[(Channel::FrontLeft, 0.5), (Channel::FrontRight, 0.5)],
+        ],
+    ),
+];
+
+impl ChannelLayout {
+    // dst_channels x src_channels matrix, LFE is dropped unless the destination has
+    // it
+    pub fn downmix_coefficients(&self, dst: &ChannelLayout) -> Vec<Vec<f32>> {
+        let src_channels = self.channels.get() as usize;
+        let dst_channels = dst.channels.get() as usize;
+        let mut coefficients = vec![vec![0.0; src_channels]; dst_channels];
+
+        let mono_dst = dst_channels == 1;
+        for src_index in 0..src_channels {
+            let Some(channel) = self.get_channel_from_index(src_index) else {
+                // unknown layouts map by index
+                if let Some(row) = coefficients.get_mut(src_index) {
+                    row[src_index] = 1.0;
+                }
+                continue;
+            };
+
+            if let Some(dst_index) = dst.get_index_from_channel(channel) {
+                coefficients[dst_index][src_index] = 1.0;
+                continue;
+            }
+
+            if mono_dst {
+                if channel != Channel::LowFrequency {
+                    coefficients[0][src_index] = FRAC_1_SQRT_2;
+                }
+                continue;
+            }
+
+            let alternatives =
+                DOWNMIX_RULES.iter().find(|(src_channel, _)| *src_channel == channel).map_or(&[][..], |(_, alternatives)| alternatives);
+            let targets = alternatives.iter().find(|targets| targets.iter().all(|(target, _)| dst.get_index_from_channel(*target).is_some()));
+            for &(target, weight) in targets.copied().unwrap_or_default() {
+                if let Some(dst_index) = dst.get_index_from_channel(target) {
+                    coefficients[dst_index][src_index] = weight;
+                }
+            }
+        }
+
+        coefficients
+    }
+}
+
+impl Frame<'_> {
+    pub fn downmix_to(&self, dst: &mut Frame, coefficients: Option<&[Vec<f32>]>) -> Result<()> {
+        let (FrameDescriptor::Audio(src_desc), FrameDescriptor::Audio(dst_desc)) = (&self.desc, &dst.desc) else {
+            return Err(invalid_error!("not audio frame"));
+        };
+
+        AudioFrame::downmix_to_internal(src_desc, &self.data, dst_desc, &mut dst.data, coefficients)
+    }
+}
+
+impl AudioFrame<'_> {
+    fn downmix_to_internal(
+        src_desc: &AudioFrameDescriptor,
+        src_data: &FrameData,
+        dst_desc: &AudioFrameDescriptor,
+        dst_data: &mut FrameData,
+        coefficients: Option<&[Vec<f32>]>,
+    ) -> Result<()> {
+        if src_desc.samples != dst_desc.samples {
+            return Err(unsupported_error!("samples mismatch"));
+        }
+
+        if !matches!(src_desc.format, SampleFormat::F32 | SampleFormat::F32P) {
+            return Err(unsupported_error!(src_desc.format));
+        }
+        if !matches!(dst_desc.format, SampleFormat::F32 | SampleFormat::F32P) {
+            return Err(unsupported_error!(dst_desc.format));
+        }
+
+        let src_channels = src_desc.channels().get() as usize;
+        let dst_channels = dst_desc.channels().get() as usize;
+
+        let default_coefficients;
+        let coefficients = match coefficients {
+            Some(coefficients) => {
+                if coefficients.len() != dst_channels || coefficients.iter().any(|row| row.len() != src_channels) {
+                    return Err(invalid_param_error!(coefficients));
+                }
+                coefficients
+            }
+            None => {
+                default_coefficients = src_desc.channel_layout.downmix_coefficients(&dst_desc.channel_layout);
+                &default_coefficients
+            }
+        };
+
+        let samples = src_desc.samples.get() as usize;
+        let guard = src_data.map()?;
+        let mut dst_guard = dst_data.map_mut()?;
+        let src_planes = guard.try_planes()?;
+        let mut dst_planes = dst_guard.try_planes_mut()?;
+
+        // packed samples share plane 0
+        let src_planar = src_desc.format.is_planar() || src_channels == 1;
+        let dst_planar = dst_desc.format.is_planar() || dst_channels == 1;
+
+        let src_data = (0..if src_planar {
+            src_channels
+        } else {
+            1
+        })
+            .map(|index| {
+                let data = src_planes.plane_data(index).ok_or_else(|| invalid_error!("plane data"))?;
+                bytemuck::try_cast_slice::<u8, f32>(data).map_err(|_| invalid_error!("plane alignment"))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let src_sample = |channel: usize, index: usize| {
+            if src_planar {
+                src_data[channel][index]
+            } else {
+                src_data[0][index * src_channels + channel]
+            }
+        };
+
+        let mut mixed = vec![0.0f32; dst_channels * samples];
+        for (dst_channel, row) in coefficients.iter().enumerate() {
+            for (src_channel, &weight) in row.iter().enumerate().filter(|(_, weight)| **weight != 0.0) {
+                for index in 0..samples {
+                    mixed[dst_channel * samples + index] += src_sample(src_channel, index) * weight;
+                }
+            }
+        }
+
+        for plane_index in 0..if dst_planar {
+            dst_channels
+        } else {
+            1
+        } {
+            let data = dst_planes.plane_data_mut(plane_index).ok_or_else(|| invalid_error!("plane data"))?;
+            let data = bytemuck::try_cast_slice_mut::<u8, f32>(data).map_err(|_| invalid_error!("plane alignment"))?;
+            if dst_planar {
+                data[..samples].copy_from_slice(&mixed[plane_index * samples..(plane_index + 1) * samples]);
+            } else {
+                for (index, frame) in data[..samples * dst_channels].chunks_exact_mut(dst_channels).enumerate() {
+                    for (dst_channel, value) in frame.iter_mut().enumerate() {
+                        *value = mixed[dst_channel * samples + index];
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn downmix_to(&self, dst: &mut AudioFrame, coefficients: Option<&[Vec<f32>]>) -> Result<()> {
+        Self::downmix_to_internal(&self.desc, &self.data, &dst.desc, &mut dst.data, coefficients)
+    }
+}
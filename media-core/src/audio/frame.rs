@@ -7,7 +7,9 @@ use super::audio::{AudioFrameDescriptor, SampleFormat};
 use crate::{
     error::Error,
     frame::{Data, Frame, FrameData, FrameSpec, MemoryData},
-    invalid_error, invalid_param_error, unsupported_error, FrameDescriptor, MediaType, Result, DEFAULT_ALIGNMENT,
+    invalid_error, invalid_param_error,
+    rational::Rational64,
+    unsupported_error, FrameDescriptor, MediaType, Result, DEFAULT_ALIGNMENT,
 };
 
 pub type AudioFrame<'a> = Frame<'a, AudioFrameDescriptor>;
@@ -110,6 +112,11 @@ impl Frame<'_> {
         self.desc.is_audio()
     }
 
+    // in seconds, derived from the sample count rather than the timestamps
+    pub fn audio_duration(&self) -> Option<Rational64> {
+        self.audio_descriptor().map(AudioFrame::calc_duration)
+    }
+
     pub fn truncate(&mut self, samples: u32) -> Result<()> {
         let FrameDescriptor::Audio(desc) = &mut self.desc else {
             return Err(unsupported_error!("truncate for non-audio frame"));
@@ -162,6 +169,14 @@ impl AudioFrame<'_> {
         Ok(Frame::from_data_with_generic_descriptor(desc, data))
     }
 
+    fn calc_duration(desc: &AudioFrameDescriptor) -> Rational64 {
+        Rational64::new(desc.samples.get() as i64, desc.sample_rate.get() as i64)
+    }
+
+    pub fn audio_duration(&self) -> Rational64 {
+        Self::calc_duration(&self.desc)
+    }
+
     fn truncate_internal(desc: &mut AudioFrameDescriptor, data: &mut FrameData, samples: u32) -> Result<()> {
         if desc.samples.get() < samples || samples == 0 {
             return Err(invalid_param_error!(samples));
@@ -189,6 +204,7 @@ impl<'a> From<AudioFrame<'a>> for Frame<'a> {
             dts: frame.dts,
             duration: frame.duration,
             time_base: frame.time_base,
+            sequence: frame.sequence,
             metadata: frame.metadata,
             data: frame.data,
         }
@@ -207,6 +223,7 @@ impl<'a> TryFrom<Frame<'a>> for AudioFrame<'a> {
                 dts: frame.dts,
                 duration: frame.duration,
                 time_base: frame.time_base,
+                sequence: frame.sequence,
                 metadata: frame.metadata,
                 data: frame.data,
             })
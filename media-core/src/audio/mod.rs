@@ -1,10 +1,12 @@
 #[allow(clippy::module_inception)]
 mod audio;
 mod convert;
+mod downmix;
 mod frame;
 
 pub mod channel_layout;
 pub mod circular_buffer;
 
 pub use audio::*;
+pub use convert::AudioDither;
 pub use frame::*;
@@ -61,6 +61,15 @@ impl Frame<'_> {
     pub fn is_data(&self) -> bool {
         self.desc.is_data()
     }
+
+    // An empty data frame marks the end of a stream
+    pub fn eos() -> Frame<'static> {
+        Frame::from_data(FrameDescriptor::Data(DataFrameDescriptor::new(DataFormat::Variant)), FrameData::Empty)
+    }
+
+    pub fn is_eos(&self) -> bool {
+        self.is_data() && matches!(self.data, FrameData::Empty)
+    }
 }
 
 impl DataFrame<'_> {
@@ -82,6 +91,7 @@ impl<'a> From<DataFrame<'a>> for Frame<'a> {
             dts: frame.dts,
             duration: frame.duration,
             time_base: frame.time_base,
+            sequence: frame.sequence,
             metadata: frame.metadata,
             data: frame.data,
         }
@@ -101,6 +111,7 @@ impl<'a> TryFrom<Frame<'a>> for DataFrame<'a> {
                 dts: frame.dts,
                 duration: frame.duration,
                 time_base: frame.time_base,
+                sequence: frame.sequence,
                 metadata: frame.metadata,
                 data: frame.data,
             })
@@ -1,99 +1,237 @@
-use std::borrow::Cow;
+use std::{backtrace::Backtrace, borrow::Cow, fmt, ops::Deref, sync::Arc};
 
 use thiserror::Error;
 
-#[derive(Clone, Debug, Error)]
+// The message of an error, along with where the error macros constructed it
+// when the backtrace feature is enabled
+#[derive(Clone)]
+pub struct ErrorMessage {
+    message: Cow<'static, str>,
+    backtrace: Option<Arc<Backtrace>>,
+}
+
+impl ErrorMessage {
+    // called by the error macros
+    #[doc(hidden)]
+    #[inline]
+    pub fn capture(message: impl Into<Cow<'static, str>>) -> Self {
+        Self {
+            message: message.into(),
+            #[cfg(feature = "backtrace")]
+            backtrace: Some(Arc::new(Backtrace::force_capture())),
+            #[cfg(not(feature = "backtrace"))]
+            backtrace: None,
+        }
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.message
+    }
+
+    pub fn backtrace(&self) -> Option<&Backtrace> {
+        self.backtrace.as_deref()
+    }
+}
+
+impl From<&'static str> for ErrorMessage {
+    fn from(message: &'static str) -> Self {
+        Cow::Borrowed(message).into()
+    }
+}
+
+impl From<String> for ErrorMessage {
+    fn from(message: String) -> Self {
+        Cow::<'static, str>::Owned(message).into()
+    }
+}
+
+impl From<Cow<'static, str>> for ErrorMessage {
+    fn from(message: Cow<'static, str>) -> Self {
+        Self {
+            message,
+            backtrace: None,
+        }
+    }
+}
+
+impl Deref for ErrorMessage {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        &self.message
+    }
+}
+
+impl PartialEq<str> for ErrorMessage {
+    fn eq(&self, other: &str) -> bool {
+        self.message == other
+    }
+}
+
+impl PartialEq<&str> for ErrorMessage {
+    fn eq(&self, other: &&str) -> bool {
+        self.message == *other
+    }
+}
+
+impl fmt::Display for ErrorMessage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl fmt::Debug for ErrorMessage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&self.message, f)
+    }
+}
+
+#[derive(Clone, Error)]
 pub enum Error {
     #[error("Failed: {0}")]
-    Failed(Cow<'static, str>),
+    Failed(ErrorMessage),
     #[error("Invalid: {0}")]
-    Invalid(Cow<'static, str>),
+    Invalid(ErrorMessage),
     #[error("Again: {0}")]
-    Again(Cow<'static, str>),
+    Again(ErrorMessage),
     #[error("Canceled: {0}")]
-    Canceled(Cow<'static, str>),
+    Canceled(ErrorMessage),
     #[error("Creation failed: {0}")]
-    CreationFailed(Cow<'static, str>),
+    CreationFailed(ErrorMessage),
     #[error("Invalid parameter: {0} {1}")]
-    InvalidParameter(Cow<'static, str>, Cow<'static, str>),
+    InvalidParameter(ErrorMessage, ErrorMessage),
     #[error("Not implemented")]
     NotImplemented,
     #[error("Not found: {0}")]
-    NotFound(Cow<'static, str>),
+    NotFound(ErrorMessage),
     #[error("Unsupported: {0}")]
-    Unsupported(Cow<'static, str>),
+    Unsupported(ErrorMessage),
     #[error("Initialization failed: {0}")]
-    InitializationFailed(Cow<'static, str>),
+    InitializationFailed(ErrorMessage),
     #[error("Open failed: {0}")]
-    OpenFailed(Cow<'static, str>),
+    OpenFailed(ErrorMessage),
     #[error("Close failed: {0}")]
-    CloseFailed(Cow<'static, str>),
+    CloseFailed(ErrorMessage),
     #[error("Start failed: {0}")]
-    StartFailed(Cow<'static, str>),
+    StartFailed(ErrorMessage),
     #[error("Stop failed: {0}")]
-    StopFailed(Cow<'static, str>),
+    StopFailed(ErrorMessage),
     #[error("Not running: {0}")]
-    NotRunning(Cow<'static, str>),
+    NotRunning(ErrorMessage),
     #[error("Get failed: {0}")]
-    GetFailed(Cow<'static, str>),
+    GetFailed(ErrorMessage),
     #[error("Set failed: {0}")]
-    SetFailed(Cow<'static, str>),
+    SetFailed(ErrorMessage),
     #[error("Read failed: {0}")]
-    ReadFailed(Cow<'static, str>),
+    ReadFailed(ErrorMessage),
     #[error("Write failed: {0}")]
-    WriteFailed(Cow<'static, str>),
+    WriteFailed(ErrorMessage),
+}
+
+impl Error {
+    // where the error macros constructed this error, None without the
+    // backtrace feature or for errors built directly
+    pub fn backtrace(&self) -> Option<&Backtrace> {
+        self.parts().1.and_then(ErrorMessage::backtrace)
+    }
+
+    fn parts(&self) -> (&'static str, Option<&ErrorMessage>, Option<&ErrorMessage>) {
+        match self {
+            Error::Failed(message) => ("Failed", Some(message), None),
+            Error::Invalid(message) => ("Invalid", Some(message), None),
+            Error::Again(message) => ("Again", Some(message), None),
+            Error::Canceled(message) => ("Canceled", Some(message), None),
+            Error::CreationFailed(message) => ("CreationFailed", Some(message), None),
+            Error::InvalidParameter(name, value) => ("InvalidParameter", Some(name), Some(value)),
+            Error::NotImplemented => ("NotImplemented", None, None),
+            Error::NotFound(message) => ("NotFound", Some(message), None),
+            Error::Unsupported(message) => ("Unsupported", Some(message), None),
+            Error::InitializationFailed(message) => ("InitializationFailed", Some(message), None),
+            Error::OpenFailed(message) => ("OpenFailed", Some(message), None),
+            Error::CloseFailed(message) => ("CloseFailed", Some(message), None),
+            Error::StartFailed(message) => ("StartFailed", Some(message), None),
+            Error::StopFailed(message) => ("StopFailed", Some(message), None),
+            Error::NotRunning(message) => ("NotRunning", Some(message), None),
+            Error::GetFailed(message) => ("GetFailed", Some(message), None),
+            Error::SetFailed(message) => ("SetFailed", Some(message), None),
+            Error::ReadFailed(message) => ("ReadFailed", Some(message), None),
+            Error::WriteFailed(message) => ("WriteFailed", Some(message), None),
+        }
+    }
+}
+
+// formatted like the derived output, followed by the backtrace when captured
+impl fmt::Debug for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (name, first, second) = self.parts();
+        if first.is_none() {
+            f.write_str(name)?;
+        } else {
+            let mut tuple = f.debug_tuple(name);
+            first.into_iter().chain(second).for_each(|message| {
+                tuple.field(message);
+            });
+            tuple.finish()?;
+        }
+
+        if let Some(backtrace) = self.backtrace() {
+            write!(f, "\n\nBacktrace:\n{}", backtrace)?;
+        }
+
+        Ok(())
+    }
 }
 
 #[macro_export]
 macro_rules! invalid_error {
     ($param:literal) => {
-        $crate::error::Error::Invalid($param.into())
+        $crate::error::Error::Invalid($crate::error::ErrorMessage::capture($param))
     };
     ($param:expr) => {
-        $crate::error::Error::Invalid(format!("{:?}", $param).into())
+        $crate::error::Error::Invalid($crate::error::ErrorMessage::capture(format!("{:?}", $param)))
     };
 }
 
 #[macro_export]
 macro_rules! failed_error {
     ($param:literal) => {
-        $crate::error::Error::Failed($param.into())
+        $crate::error::Error::Failed($crate::error::ErrorMessage::capture($param))
     };
     ($param:expr) => {
-        $crate::error::Error::Failed(format!("{:?}", $param).into())
+        $crate::error::Error::Failed($crate::error::ErrorMessage::capture(format!("{:?}", $param)))
     };
 }
 
 #[macro_export]
 macro_rules! invalid_param_error {
     ($param:expr) => {
-        $crate::error::Error::InvalidParameter(stringify!($param).into(), format!("{:?}", $param).into())
+        $crate::error::Error::InvalidParameter($crate::error::ErrorMessage::capture(stringify!($param)), format!("{:?}", $param).into())
     };
 }
 
 #[macro_export]
 macro_rules! none_param_error {
     ($param:expr) => {
-        $crate::error::Error::InvalidParameter(stringify!($param).into(), stringify!(None).into())
+        $crate::error::Error::InvalidParameter($crate::error::ErrorMessage::capture(stringify!($param)), stringify!(None).into())
     };
 }
 
 #[macro_export]
 macro_rules! not_found_error {
     ($param:literal) => {
-        $crate::error::Error::NotFound($param.into())
+        $crate::error::Error::NotFound($crate::error::ErrorMessage::capture($param))
     };
     ($param:expr) => {
-        $crate::error::Error::NotFound(format!("{:?}", $param).into())
+        $crate::error::Error::NotFound($crate::error::ErrorMessage::capture(format!("{:?}", $param)))
     };
 }
 
 #[macro_export]
 macro_rules! unsupported_error {
     ($param:literal) => {
-        $crate::error::Error::Unsupported($param.into())
+        $crate::error::Error::Unsupported($crate::error::ErrorMessage::capture($param))
     };
     ($param:expr) => {
-        $crate::error::Error::Unsupported(format!("{:?}", $param).into())
+        $crate::error::Error::Unsupported($crate::error::ErrorMessage::capture(format!("{:?}", $param)))
     };
 }
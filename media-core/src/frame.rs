@@ -1,3 +1,5 @@
+#[cfg(feature = "video")]
+use std::num::NonZeroU32;
 use std::{
     borrow::Cow,
     sync::{Arc, LockResult, RwLock, RwLockReadGuard, RwLockWriteGuard, Weak},
@@ -15,6 +17,8 @@ use smallvec::SmallVec;
 
 #[cfg(all(feature = "video", any(target_os = "macos", target_os = "ios")))]
 use crate::video::pixel_buffer::frame::PixelBuffer;
+#[cfg(feature = "video")]
+use crate::video::{PixelFormat, VideoFrameDescriptor};
 #[cfg(any(feature = "audio", feature = "video"))]
 use crate::{buffer::Buffer, unsupported_error};
 use crate::{
@@ -121,9 +125,38 @@ pub(crate) enum DataRef<'a> {
     Mutable(&'a mut dyn DataMappable),
 }
 
+// A read-only view of one video plane
+#[cfg(feature = "video")]
+pub struct Plane<'a> {
+    pub data: &'a [u8],
+    pub stride: usize,
+    pub height: u32,
+    pub row_bytes: usize, // bytes of pixel data in each row, without padding
+}
+
+// The subsampled chroma of an 8-bit YUV frame
+#[cfg(feature = "video")]
+pub enum ChromaPlanes<'a> {
+    Interleaved(Plane<'a>),         // UV pairs for NV12/NV16/NV24, VU pairs for NV21/NV61/NV42
+    Separate(Plane<'a>, Plane<'a>), // U and V planes
+}
+
+#[cfg(feature = "video")]
+impl ChromaPlanes<'_> {
+    // chroma samples per row and number of rows
+    pub fn dimensions(&self) -> (u32, u32) {
+        match self {
+            ChromaPlanes::Interleaved(plane) => ((plane.row_bytes / 2) as u32, plane.height),
+            ChromaPlanes::Separate(u, _) => (u.row_bytes as u32, u.height),
+        }
+    }
+}
+
 #[cfg(any(feature = "audio", feature = "video"))]
 pub struct MappedGuard<'a> {
     pub(crate) data_ref: DataRef<'a>,
+    #[cfg(feature = "video")]
+    pub(crate) video_format: Option<(PixelFormat, NonZeroU32)>, // format and width, set when mapped through a video frame
 }
 
 #[cfg(any(feature = "audio", feature = "video"))]
@@ -141,7 +174,61 @@ impl Drop for MappedGuard<'_> {
 }
 
 #[cfg(any(feature = "audio", feature = "video"))]
-impl MappedGuard<'_> {
+impl<'a> MappedGuard<'a> {
+    pub(crate) fn new(data_ref: DataRef<'a>) -> Self {
+        Self {
+            data_ref,
+            #[cfg(feature = "video")]
+            video_format: None,
+        }
+    }
+
+    #[cfg(feature = "video")]
+    pub(crate) fn with_video_format(mut self, desc: Option<&VideoFrameDescriptor>) -> Self {
+        self.video_format = desc.map(|desc| (desc.format, desc.width()));
+        self
+    }
+
+    #[cfg(feature = "video")]
+    #[allow(unreachable_patterns)]
+    pub fn plane(&self, index: usize) -> Option<Plane<'_>> {
+        let (format, width) = self.video_format?;
+        let (data, stride, height) = match self.planes()?.into_iter().nth(index)? {
+            MappedPlane::Video {
+                data,
+                stride,
+                height,
+            } => (data, stride, height),
+            _ => return None,
+        };
+        let data = match data {
+            MappedData::Ref(data) => data,
+            MappedData::RefMut(data) => data,
+        };
+
+        Some(Plane {
+            data,
+            stride,
+            height,
+            row_bytes: format.calc_plane_row_bytes(index, width.get()) as usize,
+        })
+    }
+
+    #[cfg(feature = "video")]
+    pub fn chroma_planes(&self) -> Option<ChromaPlanes<'_>> {
+        let (format, _) = self.video_format?;
+        match format {
+            PixelFormat::NV12 | PixelFormat::NV21 | PixelFormat::NV16 | PixelFormat::NV61 | PixelFormat::NV24 | PixelFormat::NV42 => {
+                Some(ChromaPlanes::Interleaved(self.plane(1)?))
+            }
+            PixelFormat::I420 | PixelFormat::I422 | PixelFormat::I444 | PixelFormat::I440 => {
+                Some(ChromaPlanes::Separate(self.plane(1)?, self.plane(2)?))
+            }
+            PixelFormat::YV12 | PixelFormat::YV16 | PixelFormat::YV24 => Some(ChromaPlanes::Separate(self.plane(2)?, self.plane(1)?)),
+            _ => None,
+        }
+    }
+
     pub fn planes(&self) -> Option<MappedPlanes<'_>> {
         match &self.data_ref {
             DataRef::Immutable(data) => data.planes(),
@@ -155,6 +242,15 @@ impl MappedGuard<'_> {
             DataRef::Mutable(data) => data.planes_mut(),
         }
     }
+
+    // like planes, but an unsupported error instead of None
+    pub fn try_planes(&self) -> Result<MappedPlanes<'_>> {
+        self.planes().ok_or_else(|| unsupported_error!("planes"))
+    }
+
+    pub fn try_planes_mut(&mut self) -> Result<MappedPlanes<'_>> {
+        self.planes_mut().ok_or_else(|| unsupported_error!("planes"))
+    }
 }
 
 #[cfg(any(feature = "audio", feature = "video"))]
@@ -392,6 +488,14 @@ pub(crate) struct BufferData {
     pub(crate) planes: PlaneVec<(usize, PlaneDescriptor)>,
 }
 
+// refcounted read-only memory, such as frames received from the network
+#[cfg(feature = "bytes")]
+#[derive(Clone)]
+pub(crate) struct BytesData {
+    pub(crate) data: bytes::Bytes,
+    pub(crate) planes: PlaneVec<(usize, PlaneDescriptor)>,
+}
+
 #[derive(Clone)]
 pub(crate) enum FrameData<'a> {
     #[allow(dead_code)]
@@ -401,6 +505,8 @@ pub(crate) enum FrameData<'a> {
     #[cfg(any(feature = "audio", feature = "video"))]
     #[allow(dead_code)]
     Buffer(BufferData),
+    #[cfg(feature = "bytes")]
+    Bytes(BytesData),
     #[cfg(all(feature = "video", any(target_os = "macos", target_os = "ios")))]
     PixelBuffer(PixelBuffer),
     Variant(Variant),
@@ -416,6 +522,8 @@ impl FrameData<'_> {
             FrameData::SeparateMemory(data) => FrameData::Memory(data.into_owned()),
             #[cfg(any(feature = "audio", feature = "video"))]
             FrameData::Buffer(data) => FrameData::Buffer(data),
+            #[cfg(feature = "bytes")]
+            FrameData::Bytes(data) => FrameData::Bytes(data),
             #[cfg(all(feature = "video", any(target_os = "macos", target_os = "ios")))]
             FrameData::PixelBuffer(pixel_buffer) => FrameData::PixelBuffer(pixel_buffer),
             FrameData::Variant(variant) => FrameData::Variant(variant),
@@ -446,15 +554,11 @@ pub trait DataMappable: Send + Sync {
 #[cfg(any(feature = "audio", feature = "video"))]
 impl DataMappable for MemoryData<'_> {
     fn map(&self) -> Result<MappedGuard<'_>> {
-        Ok(MappedGuard {
-            data_ref: DataRef::Immutable(self),
-        })
+        Ok(MappedGuard::new(DataRef::Immutable(self)))
     }
 
     fn map_mut(&mut self) -> Result<MappedGuard<'_>> {
-        Ok(MappedGuard {
-            data_ref: DataRef::Mutable(self),
-        })
+        Ok(MappedGuard::new(DataRef::Mutable(self)))
     }
 
     fn unmap(&self) -> Result<()> {
@@ -551,9 +655,7 @@ impl DataMappable for MemoryData<'_> {
 #[cfg(feature = "video")]
 impl DataMappable for SeparateMemoryData<'_> {
     fn map(&self) -> Result<MappedGuard<'_>> {
-        Ok(MappedGuard {
-            data_ref: DataRef::Immutable(self),
-        })
+        Ok(MappedGuard::new(DataRef::Immutable(self)))
     }
 
     fn map_mut(&mut self) -> Result<MappedGuard<'_>> {
@@ -590,12 +692,52 @@ impl DataMappable for SeparateMemoryData<'_> {
     }
 }
 
+// planes at offsets into one shared allocation
+#[cfg(any(feature = "audio", feature = "video"))]
+fn offset_planes<'a>(data: &'a [u8], descriptors: &[(usize, PlaneDescriptor)]) -> Option<MappedPlanes<'a>> {
+    let mut planes = SmallVec::with_capacity(DEFAULT_MAX_PLANES);
+
+    for plane in descriptors {
+        let (offset, plane_size) = match plane.1 {
+            #[cfg(feature = "audio")]
+            PlaneDescriptor::Audio(plane_size, _) => (plane.0, plane_size),
+            #[cfg(feature = "video")]
+            PlaneDescriptor::Video(stride, height) => (plane.0, stride * (height as usize)),
+        };
+
+        if plane_size > data.len() || plane_size == 0 {
+            return None;
+        }
+
+        #[allow(unused_variables)]
+        let plane_data = &data[offset..offset + plane_size];
+
+        let mapped_plane = match plane.1 {
+            #[cfg(feature = "audio")]
+            PlaneDescriptor::Audio(_, actual_bytes) => MappedPlane::Audio {
+                data: MappedData::Ref(&plane_data[..actual_bytes]),
+                actual_bytes,
+            },
+            #[cfg(feature = "video")]
+            PlaneDescriptor::Video(stride, height) => MappedPlane::Video {
+                data: MappedData::Ref(plane_data),
+                stride,
+                height,
+            },
+        };
+
+        planes.push(mapped_plane);
+    }
+
+    Some(MappedPlanes {
+        planes,
+    })
+}
+
 #[cfg(any(feature = "audio", feature = "video"))]
 impl DataMappable for BufferData {
     fn map(&self) -> Result<MappedGuard<'_>> {
-        Ok(MappedGuard {
-            data_ref: DataRef::Immutable(self),
-        })
+        Ok(MappedGuard::new(DataRef::Immutable(self)))
     }
 
     fn map_mut(&mut self) -> Result<MappedGuard<'_>> {
@@ -611,44 +753,34 @@ impl DataMappable for BufferData {
     }
 
     fn planes(&self) -> Option<MappedPlanes<'_>> {
-        let data = self.data.data();
-        let mut planes = SmallVec::with_capacity(DEFAULT_MAX_PLANES);
+        offset_planes(self.data.data(), &self.planes)
+    }
 
-        for plane in &self.planes {
-            let (offset, plane_size) = match plane.1 {
-                #[cfg(feature = "audio")]
-                PlaneDescriptor::Audio(plane_size, _) => (plane.0, plane_size),
-                #[cfg(feature = "video")]
-                PlaneDescriptor::Video(stride, height) => (plane.0, stride * (height as usize)),
-            };
+    fn planes_mut(&mut self) -> Option<MappedPlanes<'_>> {
+        None
+    }
+}
 
-            if plane_size > data.len() || plane_size == 0 {
-                return None;
-            }
+#[cfg(feature = "bytes")]
+impl DataMappable for BytesData {
+    fn map(&self) -> Result<MappedGuard<'_>> {
+        Ok(MappedGuard::new(DataRef::Immutable(self)))
+    }
 
-            #[allow(unused_variables)]
-            let plane_data = &data[offset..offset + plane_size];
+    fn map_mut(&mut self) -> Result<MappedGuard<'_>> {
+        Err(unsupported_error!("map"))
+    }
 
-            let mapped_plane = match plane.1 {
-                #[cfg(feature = "audio")]
-                PlaneDescriptor::Audio(_, actual_bytes) => MappedPlane::Audio {
-                    data: MappedData::Ref(&plane_data[..actual_bytes]),
-                    actual_bytes,
-                },
-                #[cfg(feature = "video")]
-                PlaneDescriptor::Video(stride, height) => MappedPlane::Video {
-                    data: MappedData::Ref(plane_data),
-                    stride,
-                    height,
-                },
-            };
+    fn unmap(&self) -> Result<()> {
+        Ok(())
+    }
 
-            planes.push(mapped_plane);
-        }
+    fn unmap_mut(&mut self) -> Result<()> {
+        Err(unsupported_error!("unmap"))
+    }
 
-        Some(MappedPlanes {
-            planes,
-        })
+    fn planes(&self) -> Option<MappedPlanes<'_>> {
+        offset_planes(&self.data, &self.planes)
     }
 
     fn planes_mut(&mut self) -> Option<MappedPlanes<'_>> {
@@ -664,6 +796,8 @@ impl DataMappable for FrameData<'_> {
             #[cfg(feature = "video")]
             FrameData::SeparateMemory(data) => data.map(),
             FrameData::Buffer(data) => data.map(),
+            #[cfg(feature = "bytes")]
+            FrameData::Bytes(data) => data.map(),
             #[cfg(all(feature = "video", any(target_os = "macos", target_os = "ios")))]
             FrameData::PixelBuffer(data) => data.map(),
             _ => Err(unsupported_error!("frame data")),
@@ -676,6 +810,8 @@ impl DataMappable for FrameData<'_> {
             #[cfg(feature = "video")]
             FrameData::SeparateMemory(data) => data.map_mut(),
             FrameData::Buffer(data) => data.map_mut(),
+            #[cfg(feature = "bytes")]
+            FrameData::Bytes(data) => data.map_mut(),
             #[cfg(all(feature = "video", any(target_os = "macos", target_os = "ios")))]
             FrameData::PixelBuffer(data) => data.map_mut(),
             _ => Err(unsupported_error!("frame data")),
@@ -688,6 +824,8 @@ impl DataMappable for FrameData<'_> {
             #[cfg(feature = "video")]
             FrameData::SeparateMemory(data) => data.unmap(),
             FrameData::Buffer(data) => data.unmap(),
+            #[cfg(feature = "bytes")]
+            FrameData::Bytes(data) => data.unmap(),
             #[cfg(all(feature = "video", any(target_os = "macos", target_os = "ios")))]
             FrameData::PixelBuffer(data) => data.unmap(),
             _ => Err(unsupported_error!("frame data")),
@@ -700,6 +838,8 @@ impl DataMappable for FrameData<'_> {
             #[cfg(feature = "video")]
             FrameData::SeparateMemory(data) => data.unmap_mut(),
             FrameData::Buffer(data) => data.unmap_mut(),
+            #[cfg(feature = "bytes")]
+            FrameData::Bytes(data) => data.unmap_mut(),
             #[cfg(all(feature = "video", any(target_os = "macos", target_os = "ios")))]
             FrameData::PixelBuffer(data) => data.unmap_mut(),
             _ => Err(unsupported_error!("frame data")),
@@ -712,6 +852,8 @@ impl DataMappable for FrameData<'_> {
             #[cfg(feature = "video")]
             FrameData::SeparateMemory(data) => data.planes(),
             FrameData::Buffer(data) => data.planes(),
+            #[cfg(feature = "bytes")]
+            FrameData::Bytes(data) => data.planes(),
             #[cfg(all(feature = "video", any(target_os = "macos", target_os = "ios")))]
             FrameData::PixelBuffer(data) => data.planes(),
             _ => None,
@@ -724,6 +866,8 @@ impl DataMappable for FrameData<'_> {
             #[cfg(feature = "video")]
             FrameData::SeparateMemory(_) => None,
             FrameData::Buffer(_) => None,
+            #[cfg(feature = "bytes")]
+            FrameData::Bytes(_) => None,
             #[cfg(all(feature = "video", any(target_os = "macos", target_os = "ios")))]
             FrameData::PixelBuffer(data) => data.planes_mut(),
             _ => None,
@@ -744,6 +888,7 @@ pub struct Frame<'a, D: FrameDescriptorSpec = FrameDescriptor> {
     pub dts: Option<i64>,
     pub duration: Option<i64>,
     pub time_base: Option<Rational64>,
+    pub sequence: Option<u64>, // delivery order of captured frames, per device
     pub metadata: Option<Variant>,
     pub(crate) data: FrameData<'a>,
 }
@@ -771,6 +916,7 @@ impl Frame<'_, FrameDescriptor> {
             dts: None,
             duration: None,
             time_base: None,
+            sequence: None,
             metadata: None,
             data,
         }
@@ -782,6 +928,9 @@ impl Frame<'_, FrameDescriptor> {
 
     #[cfg(any(feature = "audio", feature = "video"))]
     pub fn convert_to(&self, dst: &mut Frame) -> Result<()> {
+        #[cfg(feature = "video")]
+        self.check_uncompressed()?;
+
         match self.media_type() {
             #[cfg(feature = "audio")]
             MediaType::Audio => self.convert_audio_to(dst),
@@ -801,6 +950,7 @@ impl<D: FrameDescriptorSpec> Frame<'_, D> {
             dts: None,
             duration: None,
             time_base: None,
+            sequence: None,
             metadata: None,
             data,
         }
@@ -810,6 +960,18 @@ impl<D: FrameDescriptorSpec> Frame<'_, D> {
         &self.desc
     }
 
+    // timing, source and metadata for a frame derived from `src`, the
+    // descriptor and data are left as they are
+    pub fn copy_props_from<S: FrameDescriptorSpec>(&mut self, src: &Frame<'_, S>) {
+        self.source = src.source.clone();
+        self.pts = src.pts;
+        self.dts = src.dts;
+        self.duration = src.duration;
+        self.time_base = src.time_base;
+        self.sequence = src.sequence;
+        self.metadata = src.metadata.clone();
+    }
+
     pub fn into_owned(self) -> Frame<'static, D> {
         Frame {
             desc: self.desc,
@@ -818,6 +980,7 @@ impl<D: FrameDescriptorSpec> Frame<'_, D> {
             dts: self.dts,
             duration: self.duration,
             time_base: self.time_base,
+            sequence: self.sequence,
             metadata: self.metadata,
             data: self.data.into_owned(),
         }
@@ -825,12 +988,18 @@ impl<D: FrameDescriptorSpec> Frame<'_, D> {
 
     #[cfg(any(feature = "audio", feature = "video"))]
     pub fn map(&self) -> Result<MappedGuard<'_>> {
-        self.data.map()
+        let guard = self.data.map()?;
+        #[cfg(feature = "video")]
+        let guard = guard.with_video_format(self.desc.as_video());
+        Ok(guard)
     }
 
     #[cfg(any(feature = "audio", feature = "video"))]
     pub fn map_mut(&mut self) -> Result<MappedGuard<'_>> {
-        self.data.map_mut()
+        let guard = self.data.map_mut()?;
+        #[cfg(feature = "video")]
+        let guard = guard.with_video_format(self.desc.as_video());
+        Ok(guard)
     }
 }
 
@@ -856,12 +1025,21 @@ impl<D: FrameDescriptorSpec> SharedFrameInner for Frame<'_, D> {
     type Descriptor = D;
 }
 
-#[derive(Clone)]
 pub struct SharedFrame<F: SharedFrameInner = RwLock<Frame<'static>>> {
     inner: Arc<F>,
     pub(crate) pool: Option<Weak<FramePool<F>>>,
 }
 
+// clones share the frame, whatever the inner type
+impl<F: SharedFrameInner> Clone for SharedFrame<F> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: Arc::clone(&self.inner),
+            pool: self.pool.clone(),
+        }
+    }
+}
+
 impl<D: FrameDescriptorSpec> SharedFrame<RwLock<Frame<'static, D>>> {
     pub fn new(frame: Frame<'_, D>) -> Self {
         Self {
@@ -898,8 +1076,15 @@ impl<D: FrameDescriptorSpec> SharedFrame<Frame<'static, D>> {
 
 impl<F: SharedFrameInner> Drop for SharedFrame<F> {
     fn drop(&mut self) {
+        // clones share the frame, it goes back to the pool with the last one
+        if Arc::strong_count(&self.inner) > 1 {
+            return;
+        }
+
         if let Some(pool) = &self.pool {
             if let Some(pool) = pool.upgrade() {
+                #[cfg(debug_assertions)]
+                pool.frame_released();
                 let cloned = SharedFrame {
                     inner: Arc::clone(&self.inner),
                     pool: None,
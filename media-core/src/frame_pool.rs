@@ -1,3 +1,5 @@
+#[cfg(debug_assertions)]
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::{
     marker::PhantomData,
     sync::{Arc, RwLock},
@@ -51,6 +53,42 @@ pub struct FramePoolConfig<D: FrameDescriptorSpec> {
 pub struct FramePool<F: SharedFrameInner = RwLock<Frame<'static>>> {
     queue: SegQueue<SharedFrame<F>>,
     config: Arc<RwLock<FramePoolConfig<F::Descriptor>>>,
+    #[cfg(debug_assertions)]
+    leak_tracker: LeakTracker,
+}
+
+// Counts frames handed out and not yet returned
+#[cfg(debug_assertions)]
+struct LeakTracker {
+    outstanding: AtomicUsize,
+    threshold: AtomicUsize,
+    warn_at: AtomicUsize,
+}
+
+#[cfg(debug_assertions)]
+impl LeakTracker {
+    fn new() -> Self {
+        Self {
+            outstanding: AtomicUsize::new(0),
+            threshold: AtomicUsize::new(usize::MAX),
+            warn_at: AtomicUsize::new(usize::MAX),
+        }
+    }
+
+    fn acquired(&self) {
+        let outstanding = self.outstanding.fetch_add(1, Ordering::Relaxed) + 1;
+        let warn_at = self.warn_at.load(Ordering::Relaxed);
+        // warn again only after the count doubles
+        if outstanding > warn_at &&
+            self.warn_at.compare_exchange(warn_at, outstanding.saturating_mul(2), Ordering::Relaxed, Ordering::Relaxed).is_ok()
+        {
+            log::warn!("frame pool has {} outstanding frames, frames may be leaking", outstanding);
+        }
+    }
+
+    fn released(&self) {
+        let _ = self.outstanding.fetch_update(Ordering::Relaxed, Ordering::Relaxed, |outstanding| outstanding.checked_sub(1));
+    }
 }
 
 impl<F: SharedFrameInner> FramePool<F> {
@@ -64,6 +102,8 @@ impl<F: SharedFrameInner> FramePool<F> {
                 desc: None,
                 creator: DefaultFrameCreator::<F::Descriptor>::default().into(),
             })),
+            #[cfg(debug_assertions)]
+            leak_tracker: LeakTracker::new(),
         })
     }
 
@@ -74,6 +114,8 @@ impl<F: SharedFrameInner> FramePool<F> {
                 desc: Some(desc),
                 creator: Arc::from(creator),
             })),
+            #[cfg(debug_assertions)]
+            leak_tracker: LeakTracker::new(),
         })
     }
 
@@ -107,6 +149,31 @@ impl<F: SharedFrameInner> FramePool<F> {
         }
     }
 
+    // frames acquired from the pool and not dropped yet
+    #[cfg(debug_assertions)]
+    pub fn outstanding(&self) -> usize {
+        self.leak_tracker.outstanding.load(Ordering::Relaxed)
+    }
+
+    // warn when more than `threshold` frames are outstanding, None disables it
+    #[cfg(debug_assertions)]
+    pub fn set_leak_threshold(&self, threshold: Option<usize>) {
+        let threshold = threshold.unwrap_or(usize::MAX);
+        self.leak_tracker.threshold.store(threshold, Ordering::Relaxed);
+        self.leak_tracker.warn_at.store(threshold, Ordering::Relaxed);
+    }
+
+    // outstanding frames beyond the leak threshold
+    #[cfg(debug_assertions)]
+    pub fn leaked_count(&self) -> usize {
+        self.outstanding().saturating_sub(self.leak_tracker.threshold.load(Ordering::Relaxed))
+    }
+
+    #[cfg(debug_assertions)]
+    pub(crate) fn frame_released(&self) {
+        self.leak_tracker.released();
+    }
+
     pub fn recycle_frame(&self, frame: SharedFrame<F>) {
         self.queue.push(frame);
     }
@@ -133,6 +200,8 @@ impl<F: SharedFrameInner> FramePool<F> {
         if let Some(mut frame) = self.queue.pop() {
             if get_frame_desc(&frame) == desc {
                 frame.pool = Some(Arc::downgrade(self));
+                #[cfg(debug_assertions)]
+                self.leak_tracker.acquired();
                 return Ok(frame);
             }
         }
@@ -140,6 +209,8 @@ impl<F: SharedFrameInner> FramePool<F> {
         let frame = creator.create_frame(desc)?;
         let mut shared_frame = new_shared_frame(frame);
         shared_frame.pool = Some(Arc::downgrade(self));
+        #[cfg(debug_assertions)]
+        self.leak_tracker.acquired();
 
         Ok(shared_frame)
     }
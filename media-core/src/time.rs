@@ -1,3 +1,7 @@
+use num_traits::{ToPrimitive, Zero};
+
+use crate::rational::Rational64;
+
 pub const NSEC_PER_USEC: u64 = 1_000;
 pub const NSEC_PER_MSEC: u64 = 1_000_000;
 pub const NSEC_PER_SEC: u64 = 1_000_000_000;
@@ -5,6 +9,16 @@ pub const USEC_PER_MSEC: u64 = 1_000;
 pub const USEC_PER_SEC: u64 = 1_000_000;
 pub const MSEC_PER_SEC: u64 = 1_000;
 
+// common frame rates, NTSC rates are exact multiples of 1000/1001
+pub const FPS_23_976: Rational64 = Rational64::new_raw(24000, 1001);
+pub const FPS_24: Rational64 = Rational64::new_raw(24, 1);
+pub const FPS_25: Rational64 = Rational64::new_raw(25, 1);
+pub const FPS_29_97: Rational64 = Rational64::new_raw(30000, 1001);
+pub const FPS_30: Rational64 = Rational64::new_raw(30, 1);
+pub const FPS_50: Rational64 = Rational64::new_raw(50, 1);
+pub const FPS_59_94: Rational64 = Rational64::new_raw(60000, 1001);
+pub const FPS_60: Rational64 = Rational64::new_raw(60, 1);
+
 pub fn timestamp_usec() -> u64 {
     let now = std::time::Instant::now();
     let duration = now.elapsed();
@@ -22,3 +36,44 @@ pub fn timestamp_sec() -> u64 {
 pub fn tick_count() -> u64 {
     timestamp_msec()
 }
+
+// snaps rates within rounding distance of an integer or an NTSC rate to the
+// exact rational, anything else is approximated, so rates below one frame per
+// second are never snapped to zero
+pub fn fps_to_rational(fps: f64) -> Rational64 {
+    if !fps.is_finite() || fps <= 0.0 {
+        return Rational64::zero();
+    }
+
+    const EPSILON: f64 = 0.005;
+
+    let integer = fps.round();
+    if integer >= 1.0 && (fps - integer).abs() < EPSILON {
+        return Rational64::from_integer(integer as i64);
+    }
+
+    let ntsc = (fps * 1.001).round();
+    if ntsc >= 1.0 && (fps - ntsc / 1.001).abs() < EPSILON {
+        return Rational64::new(ntsc as i64 * 1000, 1001);
+    }
+
+    Rational64::approximate_float(fps).unwrap_or_else(Rational64::zero)
+}
+
+// frame duration in seconds, zero for invalid rates
+pub fn fps_to_duration(fps: f64) -> Rational64 {
+    let rate = fps_to_rational(fps);
+    if rate.is_zero() {
+        return rate;
+    }
+
+    rate.recip()
+}
+
+pub fn duration_to_fps(duration: Rational64) -> f64 {
+    if duration.is_zero() {
+        return 0.0;
+    }
+
+    duration.recip().to_f64().unwrap_or_default()
+}
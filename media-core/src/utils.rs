@@ -31,3 +31,16 @@ where
 {
     (value + (T::one() << shift) - T::one()) >> shift
 }
+
+// CRC-32 (IEEE 802.3), continuing from `crc`, start with 0
+#[allow(dead_code)]
+pub fn crc32(crc: u32, data: &[u8]) -> u32 {
+    let mut crc = !crc;
+    for &byte in data {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            crc = (crc >> 1) ^ (0xEDB8_8320 & (crc & 1).wrapping_neg());
+        }
+    }
+    !crc
+}
@@ -0,0 +1,118 @@
+use super::{
+    frame::VideoFrame,
+    video::{ColorRange, PixelFormat, VideoFrameDescriptor},
+};
+use crate::{
+    frame::{DataMappable, Frame, FrameData},
+    invalid_error, unsupported_error, FrameDescriptor, Result,
+};
+
+// (plane index, bytes per pixel, alpha offset)
+fn alpha_layout(format: PixelFormat) -> Result<(usize, usize, usize)> {
+    if !format.has_alpha() {
+        return Err(invalid_error!("pixel format has no alpha"));
+    }
+
+    match format {
+        PixelFormat::ARGB32 | PixelFormat::ABGR32 | PixelFormat::AYUV => Ok((0, 4, 0)),
+        PixelFormat::BGRA32 | PixelFormat::RGBA32 => Ok((0, 4, 3)),
+        PixelFormat::YA8 => Ok((1, 1, 0)),
+        _ => Err(unsupported_error!(format)),
+    }
+}
+
+impl Frame<'_> {
+    pub fn extract_alpha(&self) -> Result<Frame<'static>> {
+        let FrameDescriptor::Video(src_desc) = &self.desc else {
+            return Err(invalid_error!("not video frame"));
+        };
+
+        let mut dst_desc = VideoFrameDescriptor::new(PixelFormat::Y8, src_desc.width(), src_desc.height());
+        dst_desc.color_range = ColorRange::Full;
+
+        let mut dst = Frame::video_creator().create_with_descriptor(dst_desc.clone())?;
+        VideoFrame::extract_alpha_internal(src_desc, &self.data, &dst_desc, &mut dst.data)?;
+
+        dst.copy_props_from(self);
+
+        Ok(dst)
+    }
+
+    pub fn set_alpha_from_luma(&mut self, mask: &Frame) -> Result<()> {
+        let (FrameDescriptor::Video(dst_desc), FrameDescriptor::Video(mask_desc)) = (&self.desc, &mask.desc) else {
+            return Err(invalid_error!("not video frame"));
+        };
+
+        VideoFrame::set_alpha_from_luma_internal(dst_desc, &mut self.data, mask_desc, &mask.data)
+    }
+}
+
+impl VideoFrame<'_> {
+    fn extract_alpha_internal(
+        src_desc: &VideoFrameDescriptor,
+        src_data: &FrameData,
+        dst_desc: &VideoFrameDescriptor,
+        dst_data: &mut FrameData,
+    ) -> Result<()> {
+        let (plane_index, bytes_per_pixel, offset) = alpha_layout(src_desc.format)?;
+
+        let width = dst_desc.width().get() as usize;
+        let height = dst_desc.height().get() as usize;
+
+        let guard = src_data.map()?;
+        let mut dst_guard = dst_data.map_mut()?;
+        let src_planes = guard.try_planes()?;
+        let mut dst_planes = dst_guard.try_planes_mut()?;
+
+        let src_stride = src_planes.plane_stride(plane_index).ok_or_else(|| invalid_error!("plane stride"))?;
+        let dst_stride = dst_planes.plane_stride(0).ok_or_else(|| invalid_error!("plane stride"))?;
+        let src = src_planes.plane_data(plane_index).ok_or_else(|| invalid_error!("plane data"))?;
+        let dst = dst_planes.plane_data_mut(0).ok_or_else(|| invalid_error!("plane data"))?;
+
+        for (src_row, dst_row) in src.chunks(src_stride).zip(dst.chunks_mut(dst_stride)).take(height) {
+            for (pixel, alpha) in src_row[..width * bytes_per_pixel].chunks_exact(bytes_per_pixel).zip(&mut dst_row[..width]) {
+                *alpha = pixel[offset];
+            }
+        }
+
+        Ok(())
+    }
+
+    fn set_alpha_from_luma_internal(
+        dst_desc: &VideoFrameDescriptor,
+        dst_data: &mut FrameData,
+        mask_desc: &VideoFrameDescriptor,
+        mask_data: &FrameData,
+    ) -> Result<()> {
+        if mask_desc.format != PixelFormat::Y8 {
+            return Err(unsupported_error!(mask_desc.format));
+        }
+
+        if mask_desc.width() != dst_desc.width() || mask_desc.height() != dst_desc.height() {
+            return Err(invalid_error!("dimensions mismatch"));
+        }
+
+        let (plane_index, bytes_per_pixel, offset) = alpha_layout(dst_desc.format)?;
+
+        let width = dst_desc.width().get() as usize;
+        let height = dst_desc.height().get() as usize;
+
+        let mask_guard = mask_data.map()?;
+        let mut dst_guard = dst_data.map_mut()?;
+        let mask_planes = mask_guard.try_planes()?;
+        let mut dst_planes = dst_guard.try_planes_mut()?;
+
+        let mask_stride = mask_planes.plane_stride(0).ok_or_else(|| invalid_error!("plane stride"))?;
+        let dst_stride = dst_planes.plane_stride(plane_index).ok_or_else(|| invalid_error!("plane stride"))?;
+        let mask = mask_planes.plane_data(0).ok_or_else(|| invalid_error!("plane data"))?;
+        let dst = dst_planes.plane_data_mut(plane_index).ok_or_else(|| invalid_error!("plane data"))?;
+
+        for (mask_row, dst_row) in mask.chunks(mask_stride).zip(dst.chunks_mut(dst_stride)).take(height) {
+            for (luma, pixel) in mask_row[..width].iter().zip(dst_row[..width * bytes_per_pixel].chunks_exact_mut(bytes_per_pixel)) {
+                pixel[offset] = *luma;
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,109 @@
+use ndarray::{Array3, ArrayView3, ArrayViewMut3, CowArray, Ix3, ShapeBuilder};
+
+use super::video::PixelFormat;
+use crate::{
+    frame::{DataMappable, Frame, FrameData, MappedData, MappedPlane},
+    invalid_error, unsupported_error, FrameDescriptor, Result,
+};
+
+// bytes per pixel of the packed 8-bit formats that map onto a height x width x
+// channels array
+fn hwc_channels(format: PixelFormat) -> Result<usize> {
+    match format {
+        PixelFormat::ARGB32 | PixelFormat::BGRA32 | PixelFormat::ABGR32 | PixelFormat::RGBA32 => Ok(4),
+        PixelFormat::RGB24 | PixelFormat::BGR24 => Ok(3),
+        PixelFormat::Y8 => Ok(1),
+        _ => Err(unsupported_error!(format)),
+    }
+}
+
+impl Frame<'_> {
+    fn hwc_shape(&self) -> Result<(usize, usize, usize)> {
+        let FrameDescriptor::Video(desc) = &self.desc else {
+            return Err(invalid_error!("not video frame"));
+        };
+
+        Ok((desc.height().get() as usize, desc.width().get() as usize, hwc_channels(desc.format)?))
+    }
+
+    // a view of frames in memory, rows step over any stride padding; frames
+    // that have to be mapped, such as pixel buffers, are copied
+    pub fn as_ndarray_hwc(&self) -> Result<CowArray<'_, u8, Ix3>> {
+        let (height, width, channels) = self.hwc_shape()?;
+
+        // memory that stays valid without mapping can be viewed in place
+        let in_memory = match &self.data {
+            FrameData::Memory(_) | FrameData::SeparateMemory(_) | FrameData::Buffer(_) => true,
+            #[cfg(feature = "bytes")]
+            FrameData::Bytes(_) => true,
+            _ => false,
+        };
+
+        if in_memory {
+            let plane = self.data.planes().and_then(|planes| planes.into_iter().next());
+            let Some(MappedPlane::Video {
+                data: MappedData::Ref(data),
+                stride,
+                ..
+            }) = plane
+            else {
+                return Err(invalid_error!("plane data"));
+            };
+
+            let view = ArrayView3::from_shape((height, width, channels).strides((stride, channels, 1)), data)
+                .map_err(|err| invalid_error!(err.to_string()))?;
+            return Ok(CowArray::from(view));
+        }
+
+        let guard = self.map()?;
+        let plane = guard.plane(0).ok_or_else(|| invalid_error!("plane data"))?;
+        let mut array = Array3::zeros((height, width, channels));
+        for (src_row, mut dst_row) in plane.data.chunks(plane.stride).zip(array.outer_iter_mut()) {
+            dst_row.iter_mut().zip(&src_row[..plane.row_bytes]).for_each(|(dst, src)| *dst = *src);
+        }
+        Ok(CowArray::from(array))
+    }
+
+    // writes go straight to the frame, so only frames that own or mutably
+    // borrow their memory are supported
+    pub fn as_ndarray_hwc_mut(&mut self) -> Result<ArrayViewMut3<'_, u8>> {
+        let (height, width, channels) = self.hwc_shape()?;
+
+        let FrameData::Memory(_) = &self.data else {
+            return Err(unsupported_error!("frame data"));
+        };
+
+        let plane = self.data.planes_mut().and_then(|planes| planes.into_iter().next());
+        let Some(MappedPlane::Video {
+            data: MappedData::RefMut(data),
+            stride,
+            ..
+        }) = plane
+        else {
+            return Err(unsupported_error!("read-only frame data"));
+        };
+
+        ArrayViewMut3::from_shape((height, width, channels).strides((stride, channels, 1)), data).map_err(|err| invalid_error!(err.to_string()))
+    }
+
+    // copies a height x width x channels array of any layout into a new frame
+    pub fn from_ndarray_hwc(array: ArrayView3<'_, u8>, format: PixelFormat) -> Result<Frame<'static>> {
+        let (height, width, channels) = array.dim();
+        if channels != hwc_channels(format)? {
+            return Err(invalid_error!("channel count mismatch"));
+        }
+
+        let mut frame = Frame::video_creator().create(format, width as u32, height as u32)?;
+        {
+            let mut guard = frame.map_mut()?;
+            let mut planes = guard.try_planes_mut()?;
+            let stride = planes.plane_stride(0).ok_or_else(|| invalid_error!("plane stride"))?;
+            let data = planes.plane_data_mut(0).ok_or_else(|| invalid_error!("plane data"))?;
+            for (dst_row, src_row) in data.chunks_mut(stride).zip(array.outer_iter()) {
+                dst_row.iter_mut().zip(src_row.iter()).for_each(|(dst, src)| *dst = *src);
+            }
+        }
+
+        Ok(frame)
+    }
+}
@@ -0,0 +1,101 @@
+use super::{
+    frame::VideoFrame,
+    video::{PixelFormat, VideoFrameDescriptor},
+};
+use crate::{
+    frame::{DataMappable, Frame, FrameData},
+    invalid_error, unsupported_error, FrameDescriptor, Result,
+};
+
+// Running sum over a window of 2 * radius + 1 samples, replicating the edges.
+// Sums are 64-bit, a vertical pass over large radii overflows 32 bits
+fn box_sum(src: impl Fn(usize) -> u64, len: usize, radius: usize, mut dst: impl FnMut(usize, u64)) {
+    let last = len - 1;
+    // the window beyond either edge repeats the edge sample
+    let inner = radius.min(last);
+    let mut sum = src(0) * (radius as u64 + 1) + (1..=inner).map(&src).sum::<u64>() + src(last) * (radius - inner) as u64;
+
+    for i in 0..len {
+        dst(i, sum);
+        sum += src((i + radius + 1).min(last));
+        sum -= src(i.saturating_sub(radius));
+    }
+}
+
+impl Frame<'_> {
+    pub fn box_blur_luma(&self, radius: u32) -> Result<Frame<'static>> {
+        let FrameDescriptor::Video(src_desc) = &self.desc else {
+            return Err(invalid_error!("not video frame"));
+        };
+
+        let mut dst = Frame::video_creator().create_with_descriptor(src_desc.clone())?;
+        // chroma is kept as is
+        self.convert_video_to(&mut dst)?;
+        VideoFrame::box_blur_luma_internal(src_desc, &self.data, &mut dst.data, radius)?;
+
+        dst.copy_props_from(self);
+
+        Ok(dst)
+    }
+}
+
+impl VideoFrame<'_> {
+    fn box_blur_luma_internal(desc: &VideoFrameDescriptor, src_data: &FrameData, dst_data: &mut FrameData, radius: u32) -> Result<()> {
+        if !matches!(
+            desc.format,
+            PixelFormat::I420 |
+                PixelFormat::I422 |
+                PixelFormat::I444 |
+                PixelFormat::I440 |
+                PixelFormat::NV12 |
+                PixelFormat::NV21 |
+                PixelFormat::NV16 |
+                PixelFormat::NV61 |
+                PixelFormat::NV24 |
+                PixelFormat::NV42 |
+                PixelFormat::YV12 |
+                PixelFormat::YV16 |
+                PixelFormat::YV24 |
+                PixelFormat::Y8
+        ) {
+            return Err(unsupported_error!(desc.format));
+        }
+
+        let width = desc.width().get() as usize;
+        let height = desc.height().get() as usize;
+        let radius = radius as usize;
+        let area = ((2 * radius + 1) as u64).pow(2);
+
+        let guard = src_data.map()?;
+        let mut dst_guard = dst_data.map_mut()?;
+        let src_planes = guard.try_planes()?;
+        let mut dst_planes = dst_guard.try_planes_mut()?;
+
+        let src_stride = src_planes.plane_stride(0).ok_or_else(|| invalid_error!("plane stride"))?;
+        let src = src_planes.plane_data(0).ok_or_else(|| invalid_error!("plane data"))?;
+        let dst_stride = dst_planes.plane_stride(0).ok_or_else(|| invalid_error!("plane stride"))?;
+        let dst = dst_planes.plane_data_mut(0).ok_or_else(|| invalid_error!("plane data"))?;
+
+        // horizontal pass into row sums, then a vertical pass over them
+        let mut sums = vec![0u64; width * height];
+        for y in 0..height {
+            let row = &src[y * src_stride..];
+            box_sum(|x| row[x] as u64, width, radius, |x, sum| sums[y * width + x] = sum);
+        }
+
+        for x in 0..width {
+            box_sum(|y| sums[y * width + x], height, radius, |y, sum| dst[y * dst_stride + x] = ((sum + area / 2) / area) as u8);
+        }
+
+        Ok(())
+    }
+
+    pub fn box_blur_luma(&self, radius: u32) -> Result<VideoFrame<'static>> {
+        let mut dst = VideoFrame::new_with_descriptor(self.desc.clone())?;
+        self.convert_to(&mut dst)?;
+        Self::box_blur_luma_internal(&self.desc, &self.data, &mut dst.data, radius)?;
+        dst.copy_props_from(self);
+
+        Ok(dst)
+    }
+}
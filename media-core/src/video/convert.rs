@@ -3,17 +3,17 @@ use std::{fmt::Debug, num::NonZeroU32, sync::LazyLock};
 use bytemuck::{self, Pod};
 use strum::EnumCount;
 use yuv::{
-    self, BufferStoreMut, Rgb30ByteOrder::Network, YuvBiPlanarImage, YuvBiPlanarImageMut, YuvConversionMode, YuvConversionMode::Fast, YuvPackedImage,
-    YuvPackedImageMut, YuvPlanarImage, YuvPlanarImageMut, YuvRange, YuvStandardMatrix,
+    self, BufferStoreMut, Rgb30ByteOrder::Network, YuvBiPlanarImage, YuvBiPlanarImageMut, YuvChromaSubsampling, YuvConversionMode, YuvGrayAlphaImage,
+    YuvGrayImage, YuvPackedImage, YuvPackedImageMut, YuvPlanarImage, YuvPlanarImageMut, YuvRange, YuvStandardMatrix,
 };
 
 use super::{
     frame::VideoFrame,
-    video::{ColorMatrix, ColorRange, PixelFormat, VideoFrameDescriptor},
+    video::{ChromaLocation, ColorMatrix, ColorRange, PixelFormat, VideoFrameDescriptor},
 };
 use crate::{
-    frame::{DataMappable, Frame, FrameData, MappedPlanes},
-    invalid_error, unsupported_error, FrameDescriptor, Result,
+    frame::{DataMappable, Frame, FrameData, MappedData, MappedPlane, MappedPlanes, PlaneDescriptor},
+    invalid_error, invalid_param_error, unsupported_error, FrameDescriptor, Result,
 };
 
 fn into_yuv_planar_image<'a, T>(src: &'a MappedPlanes, width: NonZeroU32, height: NonZeroU32) -> Result<YuvPlanarImage<'a, T>>
@@ -156,6 +156,46 @@ where
     })
 }
 
+fn into_yuv_gray_image<'a, T>(src: &'a MappedPlanes, width: NonZeroU32, height: NonZeroU32) -> Result<YuvGrayImage<'a, T>>
+where
+    T: Debug + Pod,
+{
+    if src.planes.is_empty() {
+        return Err(invalid_error!("invalid plane count"));
+    }
+
+    let planes = &src.planes;
+    let size = size_of::<T>();
+
+    Ok(YuvGrayImage::<T> {
+        y_plane: bytemuck::cast_slice(planes[0].data().unwrap()),
+        y_stride: (planes[0].stride().unwrap() / size) as u32,
+        width: width.get(),
+        height: height.get(),
+    })
+}
+
+fn into_yuv_gray_alpha_image<'a, T>(src: &'a MappedPlanes, width: NonZeroU32, height: NonZeroU32) -> Result<YuvGrayAlphaImage<'a, T>>
+where
+    T: Debug + Pod,
+{
+    if src.planes.len() != 2 {
+        return Err(invalid_error!("invalid plane count"));
+    }
+
+    let planes = &src.planes;
+    let size = size_of::<T>();
+
+    Ok(YuvGrayAlphaImage::<T> {
+        y_plane: bytemuck::cast_slice(planes[0].data().unwrap()),
+        y_stride: (planes[0].stride().unwrap() / size) as u32,
+        a_plane: bytemuck::cast_slice(planes[1].data().unwrap()),
+        a_stride: (planes[1].stride().unwrap() / size) as u32,
+        width: width.get(),
+        height: height.get(),
+    })
+}
+
 impl From<ColorRange> for YuvRange {
     fn from(range: ColorRange) -> Self {
         match range {
@@ -180,6 +220,25 @@ impl From<ColorMatrix> for YuvStandardMatrix {
     }
 }
 
+// Precision of the RGB and YUV conversions, fast matches libyuv
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum ConversionMode {
+    #[default]
+    Fast,
+    Balanced,
+    Professional,
+}
+
+impl From<ConversionMode> for YuvConversionMode {
+    fn from(mode: ConversionMode) -> Self {
+        match mode {
+            ConversionMode::Fast => YuvConversionMode::Fast,
+            ConversionMode::Balanced => YuvConversionMode::Balanced,
+            ConversionMode::Professional => YuvConversionMode::Professional,
+        }
+    }
+}
+
 macro_rules! impl_rgb_to_rgb {
     ($func_name:ident, $convert_func:ident) => {
         fn $func_name(
@@ -187,6 +246,7 @@ macro_rules! impl_rgb_to_rgb {
             dst: &mut MappedPlanes,
             _color_range: ColorRange,
             _color_matrix: ColorMatrix,
+            _mode: ConversionMode,
             width: NonZeroU32,
             height: NonZeroU32,
         ) -> Result<()> {
@@ -214,6 +274,7 @@ macro_rules! impl_rgb_to_yuv {
             dst: &mut MappedPlanes,
             color_range: ColorRange,
             color_matrix: ColorMatrix,
+            mode: ConversionMode,
             width: NonZeroU32,
             height: NonZeroU32,
         ) -> Result<()> {
@@ -225,7 +286,7 @@ macro_rules! impl_rgb_to_yuv {
                 src.plane_stride(0).unwrap() as u32,
                 color_range.into(),
                 color_matrix.into(),
-                YuvConversionMode::Fast,
+                mode.into(),
             )
             .map_err(|e| invalid_error!(e.to_string()))?;
 
@@ -234,6 +295,40 @@ macro_rules! impl_rgb_to_yuv {
     };
 }
 
+macro_rules! impl_rgb_to_yuv_packed {
+    ($func_name:ident, $convert_func:ident, $pack_func:ident) => {
+        fn $func_name(
+            src: &MappedPlanes,
+            dst: &mut MappedPlanes,
+            color_range: ColorRange,
+            color_matrix: ColorMatrix,
+            mode: ConversionMode,
+            width: NonZeroU32,
+            height: NonZeroU32,
+        ) -> Result<()> {
+            // convert to planar 4:2:2 first, there is no direct RGB to packed YUV
+            // conversion
+            let mut planar_image = YuvPlanarImageMut::<u8>::alloc(width.get(), height.get(), YuvChromaSubsampling::Yuv422);
+
+            yuv::$convert_func(
+                &mut planar_image,
+                src.plane_data(0).unwrap(),
+                src.plane_stride(0).unwrap() as u32,
+                color_range.into(),
+                color_matrix.into(),
+                mode.into(),
+            )
+            .map_err(|e| invalid_error!(e.to_string()))?;
+
+            let mut packed_image = into_yuv_packed_image_mut(dst, width, height)?;
+
+            yuv::$pack_func(&mut packed_image, &planar_image.to_fixed()).map_err(|e| invalid_error!(e.to_string()))?;
+
+            Ok(())
+        }
+    };
+}
+
 macro_rules! impl_yuv_to_rgb {
     ($func_name:ident, $convert_func:ident, $into_image_func:ident) => {
         fn $func_name(
@@ -241,9 +336,15 @@ macro_rules! impl_yuv_to_rgb {
             dst: &mut MappedPlanes,
             color_range: ColorRange,
             color_matrix: ColorMatrix,
+            mode: ConversionMode,
             width: NonZeroU32,
             height: NonZeroU32,
         ) -> Result<()> {
+            // the planar and packed 8-bit conversions only come in the fast precision
+            if mode != ConversionMode::default() {
+                return Err(unsupported_error!(mode));
+            }
+
             let yuv_image = $into_image_func(src, width, height)?;
             let dst_stride = dst.plane_stride(0).unwrap() as u32;
 
@@ -256,19 +357,20 @@ macro_rules! impl_yuv_to_rgb {
 }
 
 macro_rules! impl_yuv_to_rgb_with_conversion_mode {
-    ($func_name:ident, $convert_func:ident, $into_image_func:ident, $conversion_mode:ident) => {
+    ($func_name:ident, $convert_func:ident, $into_image_func:ident) => {
         fn $func_name(
             src: &MappedPlanes,
             dst: &mut MappedPlanes,
             color_range: ColorRange,
             color_matrix: ColorMatrix,
+            mode: ConversionMode,
             width: NonZeroU32,
             height: NonZeroU32,
         ) -> Result<()> {
             let yuv_image = $into_image_func(src, width, height)?;
             let dst_stride = dst.plane_stride(0).unwrap() as u32;
 
-            yuv::$convert_func(&yuv_image, dst.plane_data_mut(0).unwrap(), dst_stride, color_range.into(), color_matrix.into(), $conversion_mode)
+            yuv::$convert_func(&yuv_image, dst.plane_data_mut(0).unwrap(), dst_stride, color_range.into(), color_matrix.into(), mode.into())
                 .map_err(|e| invalid_error!(e.to_string()))?;
 
             Ok(())
@@ -283,6 +385,7 @@ macro_rules! impl_yuv_to_rgb_with_byte_order {
             dst: &mut MappedPlanes,
             color_range: ColorRange,
             color_matrix: ColorMatrix,
+            _mode: ConversionMode,
             width: NonZeroU32,
             height: NonZeroU32,
         ) -> Result<()> {
@@ -304,6 +407,7 @@ macro_rules! impl_yuv_to_yuv {
             dst: &mut MappedPlanes,
             _color_range: ColorRange,
             _color_matrix: ColorMatrix,
+            _mode: ConversionMode,
             width: NonZeroU32,
             height: NonZeroU32,
         ) -> Result<()> {
@@ -317,6 +421,34 @@ macro_rules! impl_yuv_to_yuv {
     };
 }
 
+macro_rules! impl_gray_to_rgb {
+    ($func_name:ident, $convert_func:ident, $into_image_func:ident) => {
+        fn $func_name(
+            src: &MappedPlanes,
+            dst: &mut MappedPlanes,
+            color_range: ColorRange,
+            color_matrix: ColorMatrix,
+            _mode: ConversionMode,
+            width: NonZeroU32,
+            height: NonZeroU32,
+        ) -> Result<()> {
+            let gray_image = $into_image_func(src, width, height)?;
+            let dst_stride = dst.plane_stride(0).unwrap() as u32;
+            // grayscale is full range unless explicitly tagged otherwise, so luma is
+            // replicated as is
+            let range = match color_range {
+                ColorRange::Video => YuvRange::Limited,
+                _ => YuvRange::Full,
+            };
+
+            yuv::$convert_func(&gray_image, dst.plane_data_mut(0).unwrap(), dst_stride, range, color_matrix.into())
+                .map_err(|e| invalid_error!(e.to_string()))?;
+
+            Ok(())
+        }
+    };
+}
+
 impl_rgb_to_rgb!(bgra32_to_rgba32, bgra_to_rgba);
 
 impl_rgb_to_yuv!(bgra32_to_i420, bgra_to_yuv420, into_yuv_planar_image_mut);
@@ -329,8 +461,83 @@ impl_rgb_to_yuv!(bgra32_to_nv21, bgra_to_yuv_nv21, into_yuv_bi_planar_image_mut)
 impl_rgb_to_yuv!(bgra32_to_nv61, bgra_to_yuv_nv61, into_yuv_bi_planar_image_mut);
 impl_rgb_to_yuv!(bgra32_to_nv42, bgra_to_yuv_nv42, into_yuv_bi_planar_image_mut);
 
+impl_rgb_to_yuv_packed!(bgra32_to_yuyv, bgra_to_yuv422, yuv422_to_yuyv422);
+impl_rgb_to_yuv_packed!(bgra32_to_yvyu, bgra_to_yuv422, yuv422_to_yvyu422);
+impl_rgb_to_yuv_packed!(bgra32_to_uyvy, bgra_to_yuv422, yuv422_to_uyvy422);
+impl_rgb_to_yuv_packed!(bgra32_to_vyuy, bgra_to_yuv422, yuv422_to_vyuy422);
+
 impl_rgb_to_rgb!(rgba32_to_bgra32, rgba_to_bgra);
 
+// reorders the components of packed RGB formats, alpha is dropped when the
+// destination has none
+fn rgb_swizzle(
+    src: &MappedPlanes,
+    dst: &mut MappedPlanes,
+    src_format: PixelFormat,
+    dst_format: PixelFormat,
+    width: NonZeroU32,
+    height: NonZeroU32,
+) -> Result<()> {
+    let (src_plane, src_order) = swizzle_layout(src_format).ok_or_else(|| unsupported_error!(src_format))?;
+    let (dst_plane, dst_order) = swizzle_layout(dst_format).ok_or_else(|| unsupported_error!(dst_format))?;
+    let map = component_map(src_order, dst_order).filter(|_| src_plane == 0 && dst_plane == 0).ok_or_else(|| unsupported_error!(dst_format))?;
+    let src_row_bytes = width.get() as usize * src_order.len();
+    let dst_row_bytes = width.get() as usize * dst_order.len();
+
+    let src_stride = src.plane_stride(0).ok_or_else(|| invalid_error!("plane stride"))?;
+    let src_data = src.plane_data(0).ok_or_else(|| invalid_error!("plane data"))?;
+    let dst_stride = dst.plane_stride(0).ok_or_else(|| invalid_error!("plane stride"))?;
+    let dst_data = dst.plane_data_mut(0).ok_or_else(|| invalid_error!("plane data"))?;
+
+    for (src_row, dst_row) in src_data.chunks(src_stride).zip(dst_data.chunks_mut(dst_stride)).take(height.get() as usize) {
+        for (src_pixel, dst_pixel) in
+            src_row[..src_row_bytes].chunks_exact(src_order.len()).zip(dst_row[..dst_row_bytes].chunks_exact_mut(dst_order.len()))
+        {
+            for (value, &src_index) in dst_pixel.iter_mut().zip(&map) {
+                *value = src_pixel[src_index];
+            }
+        }
+    }
+
+    Ok(())
+}
+
+macro_rules! impl_rgb_swizzle {
+    ($func_name:ident, $src_format:ident, $dst_format:ident) => {
+        fn $func_name(
+            src: &MappedPlanes,
+            dst: &mut MappedPlanes,
+            _color_range: ColorRange,
+            _color_matrix: ColorMatrix,
+            _mode: ConversionMode,
+            width: NonZeroU32,
+            height: NonZeroU32,
+        ) -> Result<()> {
+            rgb_swizzle(src, dst, PixelFormat::$src_format, PixelFormat::$dst_format, width, height)
+        }
+    };
+}
+
+impl_rgb_swizzle!(argb32_to_bgra32, ARGB32, BGRA32);
+impl_rgb_swizzle!(argb32_to_rgba32, ARGB32, RGBA32);
+impl_rgb_swizzle!(abgr32_to_bgra32, ABGR32, BGRA32);
+impl_rgb_swizzle!(abgr32_to_rgba32, ABGR32, RGBA32);
+impl_rgb_swizzle!(bgra32_to_argb32, BGRA32, ARGB32);
+impl_rgb_swizzle!(bgra32_to_abgr32, BGRA32, ABGR32);
+impl_rgb_swizzle!(rgba32_to_argb32, RGBA32, ARGB32);
+impl_rgb_swizzle!(rgba32_to_abgr32, RGBA32, ABGR32);
+impl_rgb_swizzle!(argb32_to_rgb24, ARGB32, RGB24);
+impl_rgb_swizzle!(argb32_to_bgr24, ARGB32, BGR24);
+impl_rgb_swizzle!(bgra32_to_rgb24, BGRA32, RGB24);
+impl_rgb_swizzle!(bgra32_to_bgr24, BGRA32, BGR24);
+impl_rgb_swizzle!(abgr32_to_rgb24, ABGR32, RGB24);
+impl_rgb_swizzle!(abgr32_to_bgr24, ABGR32, BGR24);
+impl_rgb_swizzle!(rgba32_to_rgb24, RGBA32, RGB24);
+impl_rgb_swizzle!(rgba32_to_bgr24, RGBA32, BGR24);
+
+impl_rgb_to_rgb!(rgb24_to_bgr24, rgb_to_bgr);
+impl_rgb_to_rgb!(bgr24_to_rgb24, bgr_to_rgb);
+
 impl_rgb_to_yuv!(rgba32_to_i420, rgba_to_yuv420, into_yuv_planar_image_mut);
 impl_rgb_to_yuv!(rgba32_to_i422, rgba_to_yuv422, into_yuv_planar_image_mut);
 impl_rgb_to_yuv!(rgba32_to_i444, rgba_to_yuv444, into_yuv_planar_image_mut);
@@ -341,6 +548,41 @@ impl_rgb_to_yuv!(rgba32_to_nv21, rgba_to_yuv_nv21, into_yuv_bi_planar_image_mut)
 impl_rgb_to_yuv!(rgba32_to_nv61, rgba_to_yuv_nv61, into_yuv_bi_planar_image_mut);
 impl_rgb_to_yuv!(rgba32_to_nv42, rgba_to_yuv_nv42, into_yuv_bi_planar_image_mut);
 
+impl_rgb_to_yuv_packed!(rgba32_to_yuyv, rgba_to_yuv422, yuv422_to_yuyv422);
+impl_rgb_to_yuv_packed!(rgba32_to_yvyu, rgba_to_yuv422, yuv422_to_yvyu422);
+impl_rgb_to_yuv_packed!(rgba32_to_uyvy, rgba_to_yuv422, yuv422_to_uyvy422);
+impl_rgb_to_yuv_packed!(rgba32_to_vyuy, rgba_to_yuv422, yuv422_to_vyuy422);
+
+impl_rgb_to_yuv!(rgb24_to_i420, rgb_to_yuv420, into_yuv_planar_image_mut);
+impl_rgb_to_yuv!(rgb24_to_i422, rgb_to_yuv422, into_yuv_planar_image_mut);
+impl_rgb_to_yuv!(rgb24_to_i444, rgb_to_yuv444, into_yuv_planar_image_mut);
+impl_rgb_to_yuv!(rgb24_to_nv12, rgb_to_yuv_nv12, into_yuv_bi_planar_image_mut);
+impl_rgb_to_yuv!(rgb24_to_nv16, rgb_to_yuv_nv16, into_yuv_bi_planar_image_mut);
+impl_rgb_to_yuv!(rgb24_to_nv24, rgb_to_yuv_nv24, into_yuv_bi_planar_image_mut);
+impl_rgb_to_yuv!(rgb24_to_nv21, rgb_to_yuv_nv21, into_yuv_bi_planar_image_mut);
+impl_rgb_to_yuv!(rgb24_to_nv61, rgb_to_yuv_nv61, into_yuv_bi_planar_image_mut);
+impl_rgb_to_yuv!(rgb24_to_nv42, rgb_to_yuv_nv42, into_yuv_bi_planar_image_mut);
+
+impl_rgb_to_yuv_packed!(rgb24_to_yuyv, rgb_to_yuv422, yuv422_to_yuyv422);
+impl_rgb_to_yuv_packed!(rgb24_to_yvyu, rgb_to_yuv422, yuv422_to_yvyu422);
+impl_rgb_to_yuv_packed!(rgb24_to_uyvy, rgb_to_yuv422, yuv422_to_uyvy422);
+impl_rgb_to_yuv_packed!(rgb24_to_vyuy, rgb_to_yuv422, yuv422_to_vyuy422);
+
+impl_rgb_to_yuv!(bgr24_to_i420, bgr_to_yuv420, into_yuv_planar_image_mut);
+impl_rgb_to_yuv!(bgr24_to_i422, bgr_to_yuv422, into_yuv_planar_image_mut);
+impl_rgb_to_yuv!(bgr24_to_i444, bgr_to_yuv444, into_yuv_planar_image_mut);
+impl_rgb_to_yuv!(bgr24_to_nv12, bgr_to_yuv_nv12, into_yuv_bi_planar_image_mut);
+impl_rgb_to_yuv!(bgr24_to_nv16, bgr_to_yuv_nv16, into_yuv_bi_planar_image_mut);
+impl_rgb_to_yuv!(bgr24_to_nv24, bgr_to_yuv_nv24, into_yuv_bi_planar_image_mut);
+impl_rgb_to_yuv!(bgr24_to_nv21, bgr_to_yuv_nv21, into_yuv_bi_planar_image_mut);
+impl_rgb_to_yuv!(bgr24_to_nv61, bgr_to_yuv_nv61, into_yuv_bi_planar_image_mut);
+impl_rgb_to_yuv!(bgr24_to_nv42, bgr_to_yuv_nv42, into_yuv_bi_planar_image_mut);
+
+impl_rgb_to_yuv_packed!(bgr24_to_yuyv, bgr_to_yuv422, yuv422_to_yuyv422);
+impl_rgb_to_yuv_packed!(bgr24_to_yvyu, bgr_to_yuv422, yuv422_to_yvyu422);
+impl_rgb_to_yuv_packed!(bgr24_to_uyvy, bgr_to_yuv422, yuv422_to_uyvy422);
+impl_rgb_to_yuv_packed!(bgr24_to_vyuy, bgr_to_yuv422, yuv422_to_vyuy422);
+
 impl_yuv_to_rgb!(i420_to_bgra32, yuv420_to_bgra, into_yuv_planar_image);
 impl_yuv_to_rgb!(i420_to_rgba32, yuv420_to_rgba, into_yuv_planar_image);
 impl_yuv_to_rgb!(i420_to_bgr24, yuv420_to_bgr, into_yuv_planar_image);
@@ -371,35 +613,35 @@ impl_yuv_to_yuv!(i444_to_yvyu, yuv444_to_yvyu422, into_yuv_planar_image, into_yu
 impl_yuv_to_yuv!(i444_to_uyvy, yuv444_to_uyvy422, into_yuv_planar_image, into_yuv_packed_image_mut);
 impl_yuv_to_yuv!(i444_to_vyuy, yuv444_to_vyuy422, into_yuv_planar_image, into_yuv_packed_image_mut);
 
-impl_yuv_to_rgb_with_conversion_mode!(nv12_to_bgra32, yuv_nv12_to_bgra, into_yuv_bi_planar_image, Fast);
-impl_yuv_to_rgb_with_conversion_mode!(nv12_to_rgba32, yuv_nv12_to_rgba, into_yuv_bi_planar_image, Fast);
-impl_yuv_to_rgb_with_conversion_mode!(nv12_to_bgr24, yuv_nv12_to_bgr, into_yuv_bi_planar_image, Fast);
-impl_yuv_to_rgb_with_conversion_mode!(nv12_to_rgb24, yuv_nv12_to_rgb, into_yuv_bi_planar_image, Fast);
+impl_yuv_to_rgb_with_conversion_mode!(nv12_to_bgra32, yuv_nv12_to_bgra, into_yuv_bi_planar_image);
+impl_yuv_to_rgb_with_conversion_mode!(nv12_to_rgba32, yuv_nv12_to_rgba, into_yuv_bi_planar_image);
+impl_yuv_to_rgb_with_conversion_mode!(nv12_to_bgr24, yuv_nv12_to_bgr, into_yuv_bi_planar_image);
+impl_yuv_to_rgb_with_conversion_mode!(nv12_to_rgb24, yuv_nv12_to_rgb, into_yuv_bi_planar_image);
 
-impl_yuv_to_rgb_with_conversion_mode!(nv16_to_bgra32, yuv_nv16_to_bgra, into_yuv_bi_planar_image, Fast);
-impl_yuv_to_rgb_with_conversion_mode!(nv16_to_rgba32, yuv_nv16_to_rgba, into_yuv_bi_planar_image, Fast);
-impl_yuv_to_rgb_with_conversion_mode!(nv16_to_bgr24, yuv_nv16_to_bgr, into_yuv_bi_planar_image, Fast);
-impl_yuv_to_rgb_with_conversion_mode!(nv16_to_rgb24, yuv_nv16_to_rgb, into_yuv_bi_planar_image, Fast);
+impl_yuv_to_rgb_with_conversion_mode!(nv16_to_bgra32, yuv_nv16_to_bgra, into_yuv_bi_planar_image);
+impl_yuv_to_rgb_with_conversion_mode!(nv16_to_rgba32, yuv_nv16_to_rgba, into_yuv_bi_planar_image);
+impl_yuv_to_rgb_with_conversion_mode!(nv16_to_bgr24, yuv_nv16_to_bgr, into_yuv_bi_planar_image);
+impl_yuv_to_rgb_with_conversion_mode!(nv16_to_rgb24, yuv_nv16_to_rgb, into_yuv_bi_planar_image);
 
-impl_yuv_to_rgb_with_conversion_mode!(nv24_to_bgra32, yuv_nv24_to_bgra, into_yuv_bi_planar_image, Fast);
-impl_yuv_to_rgb_with_conversion_mode!(nv24_to_rgba32, yuv_nv24_to_rgba, into_yuv_bi_planar_image, Fast);
-impl_yuv_to_rgb_with_conversion_mode!(nv24_to_bgr24, yuv_nv24_to_bgr, into_yuv_bi_planar_image, Fast);
-impl_yuv_to_rgb_with_conversion_mode!(nv24_to_rgb24, yuv_nv24_to_rgb, into_yuv_bi_planar_image, Fast);
+impl_yuv_to_rgb_with_conversion_mode!(nv24_to_bgra32, yuv_nv24_to_bgra, into_yuv_bi_planar_image);
+impl_yuv_to_rgb_with_conversion_mode!(nv24_to_rgba32, yuv_nv24_to_rgba, into_yuv_bi_planar_image);
+impl_yuv_to_rgb_with_conversion_mode!(nv24_to_bgr24, yuv_nv24_to_bgr, into_yuv_bi_planar_image);
+impl_yuv_to_rgb_with_conversion_mode!(nv24_to_rgb24, yuv_nv24_to_rgb, into_yuv_bi_planar_image);
 
-impl_yuv_to_rgb_with_conversion_mode!(nv21_to_bgra32, yuv_nv21_to_bgra, into_yuv_bi_planar_image, Fast);
-impl_yuv_to_rgb_with_conversion_mode!(nv21_to_rgba32, yuv_nv21_to_rgba, into_yuv_bi_planar_image, Fast);
-impl_yuv_to_rgb_with_conversion_mode!(nv21_to_bgr24, yuv_nv21_to_bgr, into_yuv_bi_planar_image, Fast);
-impl_yuv_to_rgb_with_conversion_mode!(nv21_to_rgb24, yuv_nv21_to_rgb, into_yuv_bi_planar_image, Fast);
+impl_yuv_to_rgb_with_conversion_mode!(nv21_to_bgra32, yuv_nv21_to_bgra, into_yuv_bi_planar_image);
+impl_yuv_to_rgb_with_conversion_mode!(nv21_to_rgba32, yuv_nv21_to_rgba, into_yuv_bi_planar_image);
+impl_yuv_to_rgb_with_conversion_mode!(nv21_to_bgr24, yuv_nv21_to_bgr, into_yuv_bi_planar_image);
+impl_yuv_to_rgb_with_conversion_mode!(nv21_to_rgb24, yuv_nv21_to_rgb, into_yuv_bi_planar_image);
 
-impl_yuv_to_rgb_with_conversion_mode!(nv61_to_bgra32, yuv_nv61_to_bgra, into_yuv_bi_planar_image, Fast);
-impl_yuv_to_rgb_with_conversion_mode!(nv61_to_rgba32, yuv_nv61_to_rgba, into_yuv_bi_planar_image, Fast);
-impl_yuv_to_rgb_with_conversion_mode!(nv61_to_bgr24, yuv_nv61_to_bgr, into_yuv_bi_planar_image, Fast);
-impl_yuv_to_rgb_with_conversion_mode!(nv61_to_rgb24, yuv_nv61_to_rgb, into_yuv_bi_planar_image, Fast);
+impl_yuv_to_rgb_with_conversion_mode!(nv61_to_bgra32, yuv_nv61_to_bgra, into_yuv_bi_planar_image);
+impl_yuv_to_rgb_with_conversion_mode!(nv61_to_rgba32, yuv_nv61_to_rgba, into_yuv_bi_planar_image);
+impl_yuv_to_rgb_with_conversion_mode!(nv61_to_bgr24, yuv_nv61_to_bgr, into_yuv_bi_planar_image);
+impl_yuv_to_rgb_with_conversion_mode!(nv61_to_rgb24, yuv_nv61_to_rgb, into_yuv_bi_planar_image);
 
-impl_yuv_to_rgb_with_conversion_mode!(nv42_to_bgra32, yuv_nv42_to_bgra, into_yuv_bi_planar_image, Fast);
-impl_yuv_to_rgb_with_conversion_mode!(nv42_to_rgba32, yuv_nv42_to_rgba, into_yuv_bi_planar_image, Fast);
-impl_yuv_to_rgb_with_conversion_mode!(nv42_to_bgr24, yuv_nv42_to_bgr, into_yuv_bi_planar_image, Fast);
-impl_yuv_to_rgb_with_conversion_mode!(nv42_to_rgb24, yuv_nv42_to_rgb, into_yuv_bi_planar_image, Fast);
+impl_yuv_to_rgb_with_conversion_mode!(nv42_to_bgra32, yuv_nv42_to_bgra, into_yuv_bi_planar_image);
+impl_yuv_to_rgb_with_conversion_mode!(nv42_to_rgba32, yuv_nv42_to_rgba, into_yuv_bi_planar_image);
+impl_yuv_to_rgb_with_conversion_mode!(nv42_to_bgr24, yuv_nv42_to_bgr, into_yuv_bi_planar_image);
+impl_yuv_to_rgb_with_conversion_mode!(nv42_to_rgb24, yuv_nv42_to_rgb, into_yuv_bi_planar_image);
 
 impl_yuv_to_rgb!(yuyv_to_bgra32, yuyv422_to_bgra, into_yuv_packed_image);
 impl_yuv_to_rgb!(yuyv_to_rgba32, yuyv422_to_rgba, into_yuv_packed_image);
@@ -444,7 +686,417 @@ impl_yuv_to_rgb_with_byte_order!(i410_to_rgb30, i410_to_ra30, into_yuv_planar_im
 impl_yuv_to_rgb_with_byte_order!(p010_to_rgb30, p010_to_ra30, into_yuv_bi_planar_image, Network);
 impl_yuv_to_rgb_with_byte_order!(p210_to_rgb30, p210_to_ra30, into_yuv_bi_planar_image, Network);
 
-type VideoFormatConvertFunc = fn(&MappedPlanes, &mut MappedPlanes, ColorRange, ColorMatrix, NonZeroU32, NonZeroU32) -> Result<()>;
+// BGR30 is RGB30 with red and blue swapped, both are big-endian words
+fn rgb30_swap_red_blue(dst: &mut MappedPlanes, width: NonZeroU32, height: NonZeroU32) -> Result<()> {
+    let stride = dst.plane_stride(0).ok_or_else(|| invalid_error!("plane stride"))?;
+    let data = dst.plane_data_mut(0).ok_or_else(|| invalid_error!("plane data"))?;
+
+    for row in data.chunks_mut(stride).take(height.get() as usize) {
+        for pixel in row[..width.get() as usize * 4].chunks_exact_mut(4) {
+            let value = u32::from_be_bytes([pixel[0], pixel[1], pixel[2], pixel[3]]);
+            let value = (value & 0x003f_f003) | ((value >> 22) & 0x3ff) << 2 | ((value >> 2) & 0x3ff) << 22;
+            pixel.copy_from_slice(&value.to_be_bytes());
+        }
+    }
+
+    Ok(())
+}
+
+fn i410_to_bgr30(
+    src: &MappedPlanes,
+    dst: &mut MappedPlanes,
+    color_range: ColorRange,
+    color_matrix: ColorMatrix,
+    mode: ConversionMode,
+    width: NonZeroU32,
+    height: NonZeroU32,
+) -> Result<()> {
+    i410_to_rgb30(src, dst, color_range, color_matrix, mode, width, height)?;
+    rgb30_swap_red_blue(dst, width, height)
+}
+
+// the yuv crate has no 4:4:4 biplanar to RGB30 conversion, so P410 goes
+// through I410, P410 samples are MSB aligned and I410 samples LSB aligned
+fn p410_to_rgb30(
+    src: &MappedPlanes,
+    dst: &mut MappedPlanes,
+    color_range: ColorRange,
+    color_matrix: ColorMatrix,
+    _mode: ConversionMode,
+    width: NonZeroU32,
+    height: NonZeroU32,
+) -> Result<()> {
+    let bi_planar_image = into_yuv_bi_planar_image::<u16>(src, width, height)?;
+    let plane_width = width.get() as usize;
+    let plane_height = height.get() as usize;
+
+    let mut y_plane = Vec::with_capacity(plane_width * plane_height);
+    let mut u_plane = Vec::with_capacity(plane_width * plane_height);
+    let mut v_plane = Vec::with_capacity(plane_width * plane_height);
+    for (y_row, uv_row) in bi_planar_image
+        .y_plane
+        .chunks(bi_planar_image.y_stride as usize)
+        .zip(bi_planar_image.uv_plane.chunks(bi_planar_image.uv_stride as usize))
+        .take(plane_height)
+    {
+        y_plane.extend(y_row[..plane_width].iter().map(|value| value >> 6));
+        for uv in uv_row[..plane_width * 2].chunks_exact(2) {
+            u_plane.push(uv[0] >> 6);
+            v_plane.push(uv[1] >> 6);
+        }
+    }
+
+    let planar_image = YuvPlanarImage::<u16> {
+        y_plane: &y_plane,
+        y_stride: width.get(),
+        u_plane: &u_plane,
+        u_stride: width.get(),
+        v_plane: &v_plane,
+        v_stride: width.get(),
+        width: width.get(),
+        height: height.get(),
+    };
+    let dst_stride = dst.plane_stride(0).unwrap() as u32;
+
+    yuv::i410_to_ra30(&planar_image, dst.plane_data_mut(0).unwrap(), dst_stride, Network, color_range.into(), color_matrix.into())
+        .map_err(|e| invalid_error!(e.to_string()))?;
+
+    Ok(())
+}
+
+fn copy_plane(src: &MappedPlanes, src_index: usize, dst: &mut MappedPlanes, dst_index: usize, row_bytes: usize, height: usize) -> Result<()> {
+    let src_stride = src.plane_stride(src_index).ok_or_else(|| invalid_error!("plane stride"))?;
+    let src_data = src.plane_data(src_index).ok_or_else(|| invalid_error!("plane data"))?;
+    let dst_stride = dst.plane_stride(dst_index).ok_or_else(|| invalid_error!("plane stride"))?;
+    let dst_data = dst.plane_data_mut(dst_index).ok_or_else(|| invalid_error!("plane data"))?;
+
+    for (src_row, dst_row) in src_data.chunks(src_stride).zip(dst_data.chunks_mut(dst_stride)).take(height) {
+        dst_row[..row_bytes].copy_from_slice(&src_row[..row_bytes]);
+    }
+
+    Ok(())
+}
+
+// `format` is the biplanar format, `swap` is set when it stores V before U
+fn nv_to_planar(src: &MappedPlanes, dst: &mut MappedPlanes, format: PixelFormat, swap: bool, width: NonZeroU32, height: NonZeroU32) -> Result<()> {
+    copy_plane(src, 0, dst, 0, width.get() as usize, height.get() as usize)?;
+
+    let (chroma_width, chroma_height) = format.calc_chroma_dimensions(width.get(), height.get());
+    let (chroma_width, chroma_height) = (chroma_width as usize, chroma_height as usize);
+    let (u_index, v_index) = if swap {
+        (1, 0)
+    } else {
+        (0, 1)
+    };
+
+    let src_stride = src.plane_stride(1).ok_or_else(|| invalid_error!("plane stride"))?;
+    let src_data = src.plane_data(1).ok_or_else(|| invalid_error!("plane data"))?;
+    for dst_plane in [1, 2] {
+        let offset = if dst_plane == 1 {
+            u_index
+        } else {
+            v_index
+        };
+        let dst_stride = dst.plane_stride(dst_plane).ok_or_else(|| invalid_error!("plane stride"))?;
+        let dst_data = dst.plane_data_mut(dst_plane).ok_or_else(|| invalid_error!("plane data"))?;
+        for (src_row, dst_row) in src_data.chunks(src_stride).zip(dst_data.chunks_mut(dst_stride)).take(chroma_height) {
+            for (value, uv) in dst_row[..chroma_width].iter_mut().zip(src_row[..chroma_width * 2].chunks_exact(2)) {
+                *value = uv[offset];
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn planar_to_nv(src: &MappedPlanes, dst: &mut MappedPlanes, format: PixelFormat, swap: bool, width: NonZeroU32, height: NonZeroU32) -> Result<()> {
+    copy_plane(src, 0, dst, 0, width.get() as usize, height.get() as usize)?;
+
+    let (chroma_width, chroma_height) = format.calc_chroma_dimensions(width.get(), height.get());
+    let (chroma_width, chroma_height) = (chroma_width as usize, chroma_height as usize);
+    let (first_plane, second_plane) = if swap {
+        (2, 1)
+    } else {
+        (1, 2)
+    };
+
+    let first_stride = src.plane_stride(first_plane).ok_or_else(|| invalid_error!("plane stride"))?;
+    let first_data = src.plane_data(first_plane).ok_or_else(|| invalid_error!("plane data"))?;
+    let second_stride = src.plane_stride(second_plane).ok_or_else(|| invalid_error!("plane stride"))?;
+    let second_data = src.plane_data(second_plane).ok_or_else(|| invalid_error!("plane data"))?;
+    let dst_stride = dst.plane_stride(1).ok_or_else(|| invalid_error!("plane stride"))?;
+    let dst_data = dst.plane_data_mut(1).ok_or_else(|| invalid_error!("plane data"))?;
+
+    for ((first_row, second_row), dst_row) in
+        first_data.chunks(first_stride).zip(second_data.chunks(second_stride)).zip(dst_data.chunks_mut(dst_stride)).take(chroma_height)
+    {
+        for ((uv, first), second) in dst_row[..chroma_width * 2].chunks_exact_mut(2).zip(&first_row[..chroma_width]).zip(&second_row[..chroma_width])
+        {
+            uv.copy_from_slice(&[*first, *second]);
+        }
+    }
+
+    Ok(())
+}
+
+macro_rules! impl_nv_planar_convert {
+    ($func_name:ident, $convert_func:ident, $format:ident, $swap:expr) => {
+        fn $func_name(
+            src: &MappedPlanes,
+            dst: &mut MappedPlanes,
+            _color_range: ColorRange,
+            _color_matrix: ColorMatrix,
+            _mode: ConversionMode,
+            width: NonZeroU32,
+            height: NonZeroU32,
+        ) -> Result<()> {
+            $convert_func(src, dst, PixelFormat::$format, $swap, width, height)
+        }
+    };
+}
+
+impl_nv_planar_convert!(nv12_to_i420, nv_to_planar, NV12, false);
+impl_nv_planar_convert!(nv21_to_i420, nv_to_planar, NV21, true);
+impl_nv_planar_convert!(nv16_to_i422, nv_to_planar, NV16, false);
+impl_nv_planar_convert!(nv61_to_i422, nv_to_planar, NV61, true);
+impl_nv_planar_convert!(nv24_to_i444, nv_to_planar, NV24, false);
+impl_nv_planar_convert!(nv42_to_i444, nv_to_planar, NV42, true);
+
+impl_nv_planar_convert!(i420_to_nv12, planar_to_nv, NV12, false);
+impl_nv_planar_convert!(i420_to_nv21, planar_to_nv, NV21, true);
+impl_nv_planar_convert!(i422_to_nv16, planar_to_nv, NV16, false);
+impl_nv_planar_convert!(i422_to_nv61, planar_to_nv, NV61, true);
+impl_nv_planar_convert!(i444_to_nv24, planar_to_nv, NV24, false);
+impl_nv_planar_convert!(i444_to_nv42, planar_to_nv, NV42, true);
+
+// byte offsets of Y0, U, Y1 and V within a packed 4:2:2 macropixel
+const YUYV_LAYOUT: [usize; 4] = [0, 1, 2, 3];
+const YVYU_LAYOUT: [usize; 4] = [0, 3, 2, 1];
+const UYVY_LAYOUT: [usize; 4] = [1, 0, 3, 2];
+const VYUY_LAYOUT: [usize; 4] = [1, 2, 3, 0];
+
+// the yuv crate has no packed to biplanar conversion, chroma is taken from
+// the even rows like its packed to I420 conversion does
+fn packed_to_nv12(src: &MappedPlanes, dst: &mut MappedPlanes, layout: [usize; 4], width: NonZeroU32, height: NonZeroU32) -> Result<()> {
+    let [y0, u, y1, v] = layout;
+    let width = width.get() as usize;
+    let height = height.get() as usize;
+    let packed_row_bytes = width.div_ceil(2) * 4;
+
+    let src_stride = src.plane_stride(0).ok_or_else(|| invalid_error!("plane stride"))?;
+    let src_data = src.plane_data(0).ok_or_else(|| invalid_error!("plane data"))?;
+
+    let y_stride = dst.plane_stride(0).ok_or_else(|| invalid_error!("plane stride"))?;
+    let y_data = dst.plane_data_mut(0).ok_or_else(|| invalid_error!("plane data"))?;
+    for (src_row, dst_row) in src_data.chunks(src_stride).zip(y_data.chunks_mut(y_stride)).take(height) {
+        for (luma, macropixel) in dst_row[..width].chunks_mut(2).zip(src_row[..packed_row_bytes].chunks_exact(4)) {
+            luma[0] = macropixel[y0];
+            if let Some(luma) = luma.get_mut(1) {
+                *luma = macropixel[y1];
+            }
+        }
+    }
+
+    let uv_stride = dst.plane_stride(1).ok_or_else(|| invalid_error!("plane stride"))?;
+    let uv_data = dst.plane_data_mut(1).ok_or_else(|| invalid_error!("plane data"))?;
+    for (src_row, dst_row) in src_data.chunks(src_stride).step_by(2).zip(uv_data.chunks_mut(uv_stride)).take(height.div_ceil(2)) {
+        for (uv, macropixel) in dst_row[..width.div_ceil(2) * 2].chunks_exact_mut(2).zip(src_row[..packed_row_bytes].chunks_exact(4)) {
+            uv.copy_from_slice(&[macropixel[u], macropixel[v]]);
+        }
+    }
+
+    Ok(())
+}
+
+fn nv12_to_packed(src: &MappedPlanes, dst: &mut MappedPlanes, layout: [usize; 4], width: NonZeroU32, height: NonZeroU32) -> Result<()> {
+    let [y0, u, y1, v] = layout;
+    let width = width.get() as usize;
+    let height = height.get() as usize;
+    let chroma_width = width.div_ceil(2);
+
+    let y_stride = src.plane_stride(0).ok_or_else(|| invalid_error!("plane stride"))?;
+    let y_data = src.plane_data(0).ok_or_else(|| invalid_error!("plane data"))?;
+    let uv_stride = src.plane_stride(1).ok_or_else(|| invalid_error!("plane stride"))?;
+    let uv_data = src.plane_data(1).ok_or_else(|| invalid_error!("plane data"))?;
+    let dst_stride = dst.plane_stride(0).ok_or_else(|| invalid_error!("plane stride"))?;
+    let dst_data = dst.plane_data_mut(0).ok_or_else(|| invalid_error!("plane data"))?;
+
+    for (row, dst_row) in dst_data.chunks_mut(dst_stride).take(height).enumerate() {
+        let y_row = &y_data[row * y_stride..][..width];
+        let uv_row = &uv_data[row / 2 * uv_stride..][..chroma_width * 2];
+        for ((macropixel, luma), uv) in dst_row[..chroma_width * 4].chunks_exact_mut(4).zip(y_row.chunks(2)).zip(uv_row.chunks_exact(2)) {
+            // an odd width repeats the last luma sample
+            macropixel[y0] = luma[0];
+            macropixel[y1] = luma[luma.len() - 1];
+            macropixel[u] = uv[0];
+            macropixel[v] = uv[1];
+        }
+    }
+
+    Ok(())
+}
+
+macro_rules! impl_packed_nv_convert {
+    ($func_name:ident, $convert_func:ident, $layout:ident) => {
+        fn $func_name(
+            src: &MappedPlanes,
+            dst: &mut MappedPlanes,
+            _color_range: ColorRange,
+            _color_matrix: ColorMatrix,
+            _mode: ConversionMode,
+            width: NonZeroU32,
+            height: NonZeroU32,
+        ) -> Result<()> {
+            $convert_func(src, dst, $layout, width, height)
+        }
+    };
+}
+
+impl_packed_nv_convert!(yuyv_to_nv12, packed_to_nv12, YUYV_LAYOUT);
+impl_packed_nv_convert!(yvyu_to_nv12, packed_to_nv12, YVYU_LAYOUT);
+impl_packed_nv_convert!(uyvy_to_nv12, packed_to_nv12, UYVY_LAYOUT);
+impl_packed_nv_convert!(vyuy_to_nv12, packed_to_nv12, VYUY_LAYOUT);
+
+impl_packed_nv_convert!(nv12_to_yuyv, nv12_to_packed, YUYV_LAYOUT);
+impl_packed_nv_convert!(nv12_to_yvyu, nv12_to_packed, YVYU_LAYOUT);
+impl_packed_nv_convert!(nv12_to_uyvy, nv12_to_packed, UYVY_LAYOUT);
+impl_packed_nv_convert!(nv12_to_vyuy, nv12_to_packed, VYUY_LAYOUT);
+
+impl_gray_to_rgb!(y8_to_bgra32, yuv400_to_bgra, into_yuv_gray_image);
+impl_gray_to_rgb!(y8_to_rgba32, yuv400_to_rgba, into_yuv_gray_image);
+impl_gray_to_rgb!(y8_to_bgr24, yuv400_to_bgr, into_yuv_gray_image);
+impl_gray_to_rgb!(y8_to_rgb24, yuv400_to_rgb, into_yuv_gray_image);
+
+impl_gray_to_rgb!(ya8_to_bgra32, yuv400_alpha_to_bgra, into_yuv_gray_alpha_image);
+impl_gray_to_rgb!(ya8_to_rgba32, yuv400_alpha_to_rgba, into_yuv_gray_alpha_image);
+
+// Y16 holds native-endian samples, `range` is mapped linearly to 0..=255
+fn y16_to_gray(
+    src: &MappedPlanes,
+    dst: &mut MappedPlanes,
+    width: NonZeroU32,
+    height: NonZeroU32,
+    range: (u16, u16),
+    bytes_per_pixel: usize,
+    alpha: Option<usize>,
+) -> Result<()> {
+    let (low, high) = range;
+    if low >= high {
+        return Err(invalid_param_error!(range));
+    }
+
+    let src_stride = src.plane_stride(0).ok_or_else(|| invalid_error!("plane stride"))?;
+    let src_data = src.plane_data(0).ok_or_else(|| invalid_error!("plane data"))?;
+    let dst_stride = dst.plane_stride(0).ok_or_else(|| invalid_error!("plane stride"))?;
+    let dst_data = dst.plane_data_mut(0).ok_or_else(|| invalid_error!("plane data"))?;
+
+    let width = width.get() as usize;
+    let span = (high - low) as u32;
+
+    for (src_row, dst_row) in src_data.chunks(src_stride).zip(dst_data.chunks_mut(dst_stride)).take(height.get() as usize) {
+        for (sample, pixel) in src_row[..width * 2].chunks_exact(2).zip(dst_row[..width * bytes_per_pixel].chunks_exact_mut(bytes_per_pixel)) {
+            // Y16 samples are little-endian, like the V4L2 format
+            let value = u16::from_le_bytes([sample[0], sample[1]]).clamp(low, high) - low;
+            pixel.fill(((value as u32 * 255 + span / 2) / span) as u8);
+            if let Some(alpha) = alpha {
+                pixel[alpha] = u8::MAX;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+macro_rules! impl_y16_to_gray {
+    ($func_name:ident, $bytes_per_pixel:expr, $alpha:expr) => {
+        fn $func_name(
+            src: &MappedPlanes,
+            dst: &mut MappedPlanes,
+            _color_range: ColorRange,
+            _color_matrix: ColorMatrix,
+            _mode: ConversionMode,
+            width: NonZeroU32,
+            height: NonZeroU32,
+        ) -> Result<()> {
+            y16_to_gray(src, dst, width, height, (u16::MIN, u16::MAX), $bytes_per_pixel, $alpha)
+        }
+    };
+}
+
+impl_y16_to_gray!(y16_to_y8, 1, None);
+impl_y16_to_gray!(y16_to_gray_rgb24, 3, None);
+impl_y16_to_gray!(y16_to_gray_rgba32, 4, Some(3));
+impl_y16_to_gray!(y16_to_gray_argb32, 4, Some(0));
+
+// 16-bit channels are big-endian, `order` picks the source channel for each
+// destination channel
+fn rgb64_to_rgb32(src: &MappedPlanes, dst: &mut MappedPlanes, width: NonZeroU32, height: NonZeroU32, order: [usize; 4]) -> Result<()> {
+    let src_stride = src.plane_stride(0).ok_or_else(|| invalid_error!("plane stride"))?;
+    let src_data = src.plane_data(0).ok_or_else(|| invalid_error!("plane data"))?;
+    let dst_stride = dst.plane_stride(0).ok_or_else(|| invalid_error!("plane stride"))?;
+    let dst_data = dst.plane_data_mut(0).ok_or_else(|| invalid_error!("plane data"))?;
+
+    let width = width.get() as usize;
+
+    for (src_row, dst_row) in src_data.chunks(src_stride).zip(dst_data.chunks_mut(dst_stride)).take(height.get() as usize) {
+        for (src_pixel, dst_pixel) in src_row[..width * 8].chunks_exact(8).zip(dst_row[..width * 4].chunks_exact_mut(4)) {
+            // keep the high byte of each channel
+            for (value, &channel) in dst_pixel.iter_mut().zip(&order) {
+                *value = src_pixel[channel * 2];
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn rgb32_to_rgb64(src: &MappedPlanes, dst: &mut MappedPlanes, width: NonZeroU32, height: NonZeroU32, order: [usize; 4]) -> Result<()> {
+    let src_stride = src.plane_stride(0).ok_or_else(|| invalid_error!("plane stride"))?;
+    let src_data = src.plane_data(0).ok_or_else(|| invalid_error!("plane data"))?;
+    let dst_stride = dst.plane_stride(0).ok_or_else(|| invalid_error!("plane stride"))?;
+    let dst_data = dst.plane_data_mut(0).ok_or_else(|| invalid_error!("plane data"))?;
+
+    let width = width.get() as usize;
+
+    for (src_row, dst_row) in src_data.chunks(src_stride).zip(dst_data.chunks_mut(dst_stride)).take(height.get() as usize) {
+        for (src_pixel, dst_pixel) in src_row[..width * 4].chunks_exact(4).zip(dst_row[..width * 8].chunks_exact_mut(8)) {
+            // replicate the byte so that 0xFF expands to 0xFFFF
+            for (value, &channel) in dst_pixel.chunks_exact_mut(2).zip(&order) {
+                value.fill(src_pixel[channel]);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+macro_rules! impl_rgb_depth_convert {
+    ($func_name:ident, $convert_func:ident, $order:expr) => {
+        fn $func_name(
+            src: &MappedPlanes,
+            dst: &mut MappedPlanes,
+            _color_range: ColorRange,
+            _color_matrix: ColorMatrix,
+            _mode: ConversionMode,
+            width: NonZeroU32,
+            height: NonZeroU32,
+        ) -> Result<()> {
+            $convert_func(src, dst, width, height, $order)
+        }
+    };
+}
+
+impl_rgb_depth_convert!(argb64_to_bgra32, rgb64_to_rgb32, [3, 2, 1, 0]);
+impl_rgb_depth_convert!(argb64_to_rgba32, rgb64_to_rgb32, [1, 2, 3, 0]);
+impl_rgb_depth_convert!(rgba64_to_rgba32, rgb64_to_rgb32, [0, 1, 2, 3]);
+impl_rgb_depth_convert!(rgba64_to_bgra32, rgb64_to_rgb32, [2, 1, 0, 3]);
+
+impl_rgb_depth_convert!(bgra32_to_argb64, rgb32_to_rgb64, [3, 2, 1, 0]);
+impl_rgb_depth_convert!(rgba32_to_argb64, rgb32_to_rgb64, [3, 0, 1, 2]);
+impl_rgb_depth_convert!(rgba32_to_rgba64, rgb32_to_rgb64, [0, 1, 2, 3]);
+impl_rgb_depth_convert!(bgra32_to_rgba64, rgb32_to_rgb64, [2, 1, 0, 3]);
+
+type VideoFormatConvertFunc = fn(&MappedPlanes, &mut MappedPlanes, ColorRange, ColorMatrix, ConversionMode, NonZeroU32, NonZeroU32) -> Result<()>;
 
 const PIXEL_FORMAT_MAX: usize = PixelFormat::COUNT;
 
@@ -460,6 +1112,10 @@ static VIDEO_FORMAT_CONVERT_FUNCS: LazyLock<[[Option<VideoFormatConvertFunc>; PI
     funcs[PixelFormat::BGRA32 as usize][PixelFormat::NV21 as usize] = Some(bgra32_to_nv21);
     funcs[PixelFormat::BGRA32 as usize][PixelFormat::NV61 as usize] = Some(bgra32_to_nv61);
     funcs[PixelFormat::BGRA32 as usize][PixelFormat::NV42 as usize] = Some(bgra32_to_nv42);
+    funcs[PixelFormat::BGRA32 as usize][PixelFormat::YUYV as usize] = Some(bgra32_to_yuyv);
+    funcs[PixelFormat::BGRA32 as usize][PixelFormat::YVYU as usize] = Some(bgra32_to_yvyu);
+    funcs[PixelFormat::BGRA32 as usize][PixelFormat::UYVY as usize] = Some(bgra32_to_uyvy);
+    funcs[PixelFormat::BGRA32 as usize][PixelFormat::VYUY as usize] = Some(bgra32_to_vyuy);
     funcs[PixelFormat::RGBA32 as usize][PixelFormat::BGRA32 as usize] = Some(rgba32_to_bgra32);
     funcs[PixelFormat::RGBA32 as usize][PixelFormat::I420 as usize] = Some(rgba32_to_i420);
     funcs[PixelFormat::RGBA32 as usize][PixelFormat::I422 as usize] = Some(rgba32_to_i422);
@@ -470,6 +1126,54 @@ static VIDEO_FORMAT_CONVERT_FUNCS: LazyLock<[[Option<VideoFormatConvertFunc>; PI
     funcs[PixelFormat::RGBA32 as usize][PixelFormat::NV21 as usize] = Some(rgba32_to_nv21);
     funcs[PixelFormat::RGBA32 as usize][PixelFormat::NV61 as usize] = Some(rgba32_to_nv61);
     funcs[PixelFormat::RGBA32 as usize][PixelFormat::NV42 as usize] = Some(rgba32_to_nv42);
+    funcs[PixelFormat::RGBA32 as usize][PixelFormat::YUYV as usize] = Some(rgba32_to_yuyv);
+    funcs[PixelFormat::RGBA32 as usize][PixelFormat::YVYU as usize] = Some(rgba32_to_yvyu);
+    funcs[PixelFormat::RGBA32 as usize][PixelFormat::UYVY as usize] = Some(rgba32_to_uyvy);
+    funcs[PixelFormat::RGBA32 as usize][PixelFormat::VYUY as usize] = Some(rgba32_to_vyuy);
+    funcs[PixelFormat::BGRA32 as usize][PixelFormat::ARGB32 as usize] = Some(bgra32_to_argb32);
+    funcs[PixelFormat::BGRA32 as usize][PixelFormat::ABGR32 as usize] = Some(bgra32_to_abgr32);
+    funcs[PixelFormat::RGBA32 as usize][PixelFormat::ARGB32 as usize] = Some(rgba32_to_argb32);
+    funcs[PixelFormat::RGBA32 as usize][PixelFormat::ABGR32 as usize] = Some(rgba32_to_abgr32);
+    funcs[PixelFormat::ARGB32 as usize][PixelFormat::BGRA32 as usize] = Some(argb32_to_bgra32);
+    funcs[PixelFormat::ARGB32 as usize][PixelFormat::RGBA32 as usize] = Some(argb32_to_rgba32);
+    funcs[PixelFormat::ABGR32 as usize][PixelFormat::BGRA32 as usize] = Some(abgr32_to_bgra32);
+    funcs[PixelFormat::ABGR32 as usize][PixelFormat::RGBA32 as usize] = Some(abgr32_to_rgba32);
+    funcs[PixelFormat::ARGB32 as usize][PixelFormat::RGB24 as usize] = Some(argb32_to_rgb24);
+    funcs[PixelFormat::ARGB32 as usize][PixelFormat::BGR24 as usize] = Some(argb32_to_bgr24);
+    funcs[PixelFormat::BGRA32 as usize][PixelFormat::RGB24 as usize] = Some(bgra32_to_rgb24);
+    funcs[PixelFormat::BGRA32 as usize][PixelFormat::BGR24 as usize] = Some(bgra32_to_bgr24);
+    funcs[PixelFormat::ABGR32 as usize][PixelFormat::RGB24 as usize] = Some(abgr32_to_rgb24);
+    funcs[PixelFormat::ABGR32 as usize][PixelFormat::BGR24 as usize] = Some(abgr32_to_bgr24);
+    funcs[PixelFormat::RGBA32 as usize][PixelFormat::RGB24 as usize] = Some(rgba32_to_rgb24);
+    funcs[PixelFormat::RGBA32 as usize][PixelFormat::BGR24 as usize] = Some(rgba32_to_bgr24);
+    funcs[PixelFormat::RGB24 as usize][PixelFormat::BGR24 as usize] = Some(rgb24_to_bgr24);
+    funcs[PixelFormat::BGR24 as usize][PixelFormat::RGB24 as usize] = Some(bgr24_to_rgb24);
+    funcs[PixelFormat::RGB24 as usize][PixelFormat::I420 as usize] = Some(rgb24_to_i420);
+    funcs[PixelFormat::RGB24 as usize][PixelFormat::I422 as usize] = Some(rgb24_to_i422);
+    funcs[PixelFormat::RGB24 as usize][PixelFormat::I444 as usize] = Some(rgb24_to_i444);
+    funcs[PixelFormat::RGB24 as usize][PixelFormat::NV12 as usize] = Some(rgb24_to_nv12);
+    funcs[PixelFormat::RGB24 as usize][PixelFormat::NV16 as usize] = Some(rgb24_to_nv16);
+    funcs[PixelFormat::RGB24 as usize][PixelFormat::NV24 as usize] = Some(rgb24_to_nv24);
+    funcs[PixelFormat::RGB24 as usize][PixelFormat::NV21 as usize] = Some(rgb24_to_nv21);
+    funcs[PixelFormat::RGB24 as usize][PixelFormat::NV61 as usize] = Some(rgb24_to_nv61);
+    funcs[PixelFormat::RGB24 as usize][PixelFormat::NV42 as usize] = Some(rgb24_to_nv42);
+    funcs[PixelFormat::RGB24 as usize][PixelFormat::YUYV as usize] = Some(rgb24_to_yuyv);
+    funcs[PixelFormat::RGB24 as usize][PixelFormat::YVYU as usize] = Some(rgb24_to_yvyu);
+    funcs[PixelFormat::RGB24 as usize][PixelFormat::UYVY as usize] = Some(rgb24_to_uyvy);
+    funcs[PixelFormat::RGB24 as usize][PixelFormat::VYUY as usize] = Some(rgb24_to_vyuy);
+    funcs[PixelFormat::BGR24 as usize][PixelFormat::I420 as usize] = Some(bgr24_to_i420);
+    funcs[PixelFormat::BGR24 as usize][PixelFormat::I422 as usize] = Some(bgr24_to_i422);
+    funcs[PixelFormat::BGR24 as usize][PixelFormat::I444 as usize] = Some(bgr24_to_i444);
+    funcs[PixelFormat::BGR24 as usize][PixelFormat::NV12 as usize] = Some(bgr24_to_nv12);
+    funcs[PixelFormat::BGR24 as usize][PixelFormat::NV16 as usize] = Some(bgr24_to_nv16);
+    funcs[PixelFormat::BGR24 as usize][PixelFormat::NV24 as usize] = Some(bgr24_to_nv24);
+    funcs[PixelFormat::BGR24 as usize][PixelFormat::NV21 as usize] = Some(bgr24_to_nv21);
+    funcs[PixelFormat::BGR24 as usize][PixelFormat::NV61 as usize] = Some(bgr24_to_nv61);
+    funcs[PixelFormat::BGR24 as usize][PixelFormat::NV42 as usize] = Some(bgr24_to_nv42);
+    funcs[PixelFormat::BGR24 as usize][PixelFormat::YUYV as usize] = Some(bgr24_to_yuyv);
+    funcs[PixelFormat::BGR24 as usize][PixelFormat::YVYU as usize] = Some(bgr24_to_yvyu);
+    funcs[PixelFormat::BGR24 as usize][PixelFormat::UYVY as usize] = Some(bgr24_to_uyvy);
+    funcs[PixelFormat::BGR24 as usize][PixelFormat::VYUY as usize] = Some(bgr24_to_vyuy);
     funcs[PixelFormat::I420 as usize][PixelFormat::BGRA32 as usize] = Some(i420_to_bgra32);
     funcs[PixelFormat::I420 as usize][PixelFormat::RGBA32 as usize] = Some(i420_to_rgba32);
     funcs[PixelFormat::I420 as usize][PixelFormat::BGR24 as usize] = Some(i420_to_bgr24);
@@ -478,6 +1182,8 @@ static VIDEO_FORMAT_CONVERT_FUNCS: LazyLock<[[Option<VideoFormatConvertFunc>; PI
     funcs[PixelFormat::I420 as usize][PixelFormat::YVYU as usize] = Some(i420_to_yvyu);
     funcs[PixelFormat::I420 as usize][PixelFormat::UYVY as usize] = Some(i420_to_uyvy);
     funcs[PixelFormat::I420 as usize][PixelFormat::VYUY as usize] = Some(i420_to_vyuy);
+    funcs[PixelFormat::I420 as usize][PixelFormat::NV12 as usize] = Some(i420_to_nv12);
+    funcs[PixelFormat::I420 as usize][PixelFormat::NV21 as usize] = Some(i420_to_nv21);
     funcs[PixelFormat::I422 as usize][PixelFormat::BGRA32 as usize] = Some(i422_to_bgra32);
     funcs[PixelFormat::I422 as usize][PixelFormat::RGBA32 as usize] = Some(i422_to_rgba32);
     funcs[PixelFormat::I422 as usize][PixelFormat::BGR24 as usize] = Some(i422_to_bgr24);
@@ -486,6 +1192,8 @@ static VIDEO_FORMAT_CONVERT_FUNCS: LazyLock<[[Option<VideoFormatConvertFunc>; PI
     funcs[PixelFormat::I422 as usize][PixelFormat::YVYU as usize] = Some(i422_to_yvyu);
     funcs[PixelFormat::I422 as usize][PixelFormat::UYVY as usize] = Some(i422_to_uyvy);
     funcs[PixelFormat::I422 as usize][PixelFormat::VYUY as usize] = Some(i422_to_vyuy);
+    funcs[PixelFormat::I422 as usize][PixelFormat::NV16 as usize] = Some(i422_to_nv16);
+    funcs[PixelFormat::I422 as usize][PixelFormat::NV61 as usize] = Some(i422_to_nv61);
     funcs[PixelFormat::I444 as usize][PixelFormat::BGRA32 as usize] = Some(i444_to_bgra32);
     funcs[PixelFormat::I444 as usize][PixelFormat::RGBA32 as usize] = Some(i444_to_rgba32);
     funcs[PixelFormat::I444 as usize][PixelFormat::BGR24 as usize] = Some(i444_to_bgr24);
@@ -494,30 +1202,42 @@ static VIDEO_FORMAT_CONVERT_FUNCS: LazyLock<[[Option<VideoFormatConvertFunc>; PI
     funcs[PixelFormat::I444 as usize][PixelFormat::YVYU as usize] = Some(i444_to_yvyu);
     funcs[PixelFormat::I444 as usize][PixelFormat::UYVY as usize] = Some(i444_to_uyvy);
     funcs[PixelFormat::I444 as usize][PixelFormat::VYUY as usize] = Some(i444_to_vyuy);
+    funcs[PixelFormat::I444 as usize][PixelFormat::NV24 as usize] = Some(i444_to_nv24);
+    funcs[PixelFormat::I444 as usize][PixelFormat::NV42 as usize] = Some(i444_to_nv42);
     funcs[PixelFormat::NV12 as usize][PixelFormat::BGRA32 as usize] = Some(nv12_to_bgra32);
     funcs[PixelFormat::NV12 as usize][PixelFormat::RGBA32 as usize] = Some(nv12_to_rgba32);
     funcs[PixelFormat::NV12 as usize][PixelFormat::BGR24 as usize] = Some(nv12_to_bgr24);
     funcs[PixelFormat::NV12 as usize][PixelFormat::RGB24 as usize] = Some(nv12_to_rgb24);
+    funcs[PixelFormat::NV12 as usize][PixelFormat::I420 as usize] = Some(nv12_to_i420);
+    funcs[PixelFormat::NV12 as usize][PixelFormat::YUYV as usize] = Some(nv12_to_yuyv);
+    funcs[PixelFormat::NV12 as usize][PixelFormat::YVYU as usize] = Some(nv12_to_yvyu);
+    funcs[PixelFormat::NV12 as usize][PixelFormat::UYVY as usize] = Some(nv12_to_uyvy);
+    funcs[PixelFormat::NV12 as usize][PixelFormat::VYUY as usize] = Some(nv12_to_vyuy);
     funcs[PixelFormat::NV16 as usize][PixelFormat::BGRA32 as usize] = Some(nv16_to_bgra32);
     funcs[PixelFormat::NV16 as usize][PixelFormat::RGBA32 as usize] = Some(nv16_to_rgba32);
     funcs[PixelFormat::NV16 as usize][PixelFormat::BGR24 as usize] = Some(nv16_to_bgr24);
     funcs[PixelFormat::NV16 as usize][PixelFormat::RGB24 as usize] = Some(nv16_to_rgb24);
+    funcs[PixelFormat::NV16 as usize][PixelFormat::I422 as usize] = Some(nv16_to_i422);
     funcs[PixelFormat::NV24 as usize][PixelFormat::BGRA32 as usize] = Some(nv24_to_bgra32);
     funcs[PixelFormat::NV24 as usize][PixelFormat::RGBA32 as usize] = Some(nv24_to_rgba32);
     funcs[PixelFormat::NV24 as usize][PixelFormat::BGR24 as usize] = Some(nv24_to_bgr24);
     funcs[PixelFormat::NV24 as usize][PixelFormat::RGB24 as usize] = Some(nv24_to_rgb24);
+    funcs[PixelFormat::NV24 as usize][PixelFormat::I444 as usize] = Some(nv24_to_i444);
     funcs[PixelFormat::NV21 as usize][PixelFormat::BGRA32 as usize] = Some(nv21_to_bgra32);
     funcs[PixelFormat::NV21 as usize][PixelFormat::RGBA32 as usize] = Some(nv21_to_rgba32);
     funcs[PixelFormat::NV21 as usize][PixelFormat::BGR24 as usize] = Some(nv21_to_bgr24);
     funcs[PixelFormat::NV21 as usize][PixelFormat::RGB24 as usize] = Some(nv21_to_rgb24);
+    funcs[PixelFormat::NV21 as usize][PixelFormat::I420 as usize] = Some(nv21_to_i420);
     funcs[PixelFormat::NV61 as usize][PixelFormat::BGRA32 as usize] = Some(nv61_to_bgra32);
     funcs[PixelFormat::NV61 as usize][PixelFormat::RGBA32 as usize] = Some(nv61_to_rgba32);
     funcs[PixelFormat::NV61 as usize][PixelFormat::BGR24 as usize] = Some(nv61_to_bgr24);
     funcs[PixelFormat::NV61 as usize][PixelFormat::RGB24 as usize] = Some(nv61_to_rgb24);
+    funcs[PixelFormat::NV61 as usize][PixelFormat::I422 as usize] = Some(nv61_to_i422);
     funcs[PixelFormat::NV42 as usize][PixelFormat::BGRA32 as usize] = Some(nv42_to_bgra32);
     funcs[PixelFormat::NV42 as usize][PixelFormat::RGBA32 as usize] = Some(nv42_to_rgba32);
     funcs[PixelFormat::NV42 as usize][PixelFormat::BGR24 as usize] = Some(nv42_to_bgr24);
     funcs[PixelFormat::NV42 as usize][PixelFormat::RGB24 as usize] = Some(nv42_to_rgb24);
+    funcs[PixelFormat::NV42 as usize][PixelFormat::I444 as usize] = Some(nv42_to_i444);
     funcs[PixelFormat::YUYV as usize][PixelFormat::BGRA32 as usize] = Some(yuyv_to_bgra32);
     funcs[PixelFormat::YUYV as usize][PixelFormat::RGBA32 as usize] = Some(yuyv_to_rgba32);
     funcs[PixelFormat::YUYV as usize][PixelFormat::BGR24 as usize] = Some(yuyv_to_bgr24);
@@ -525,6 +1245,7 @@ static VIDEO_FORMAT_CONVERT_FUNCS: LazyLock<[[Option<VideoFormatConvertFunc>; PI
     funcs[PixelFormat::YUYV as usize][PixelFormat::I420 as usize] = Some(yuyv_to_i420);
     funcs[PixelFormat::YUYV as usize][PixelFormat::I422 as usize] = Some(yuyv_to_i422);
     funcs[PixelFormat::YUYV as usize][PixelFormat::I444 as usize] = Some(yuyv_to_i444);
+    funcs[PixelFormat::YUYV as usize][PixelFormat::NV12 as usize] = Some(yuyv_to_nv12);
     funcs[PixelFormat::YVYU as usize][PixelFormat::BGRA32 as usize] = Some(yvyu_to_bgra32);
     funcs[PixelFormat::YVYU as usize][PixelFormat::RGBA32 as usize] = Some(yvyu_to_rgba32);
     funcs[PixelFormat::YVYU as usize][PixelFormat::BGR24 as usize] = Some(yvyu_to_bgr24);
@@ -532,6 +1253,7 @@ static VIDEO_FORMAT_CONVERT_FUNCS: LazyLock<[[Option<VideoFormatConvertFunc>; PI
     funcs[PixelFormat::YVYU as usize][PixelFormat::I420 as usize] = Some(yvyu_to_i420);
     funcs[PixelFormat::YVYU as usize][PixelFormat::I422 as usize] = Some(yvyu_to_i422);
     funcs[PixelFormat::YVYU as usize][PixelFormat::I444 as usize] = Some(yvyu_to_i444);
+    funcs[PixelFormat::YVYU as usize][PixelFormat::NV12 as usize] = Some(yvyu_to_nv12);
     funcs[PixelFormat::UYVY as usize][PixelFormat::BGRA32 as usize] = Some(uyvy_to_bgra32);
     funcs[PixelFormat::UYVY as usize][PixelFormat::RGBA32 as usize] = Some(uyvy_to_rgba32);
     funcs[PixelFormat::UYVY as usize][PixelFormat::BGR24 as usize] = Some(uyvy_to_bgr24);
@@ -539,6 +1261,7 @@ static VIDEO_FORMAT_CONVERT_FUNCS: LazyLock<[[Option<VideoFormatConvertFunc>; PI
     funcs[PixelFormat::UYVY as usize][PixelFormat::I420 as usize] = Some(uyvy_to_i420);
     funcs[PixelFormat::UYVY as usize][PixelFormat::I422 as usize] = Some(uyvy_to_i422);
     funcs[PixelFormat::UYVY as usize][PixelFormat::I444 as usize] = Some(uyvy_to_i444);
+    funcs[PixelFormat::UYVY as usize][PixelFormat::NV12 as usize] = Some(uyvy_to_nv12);
     funcs[PixelFormat::VYUY as usize][PixelFormat::BGRA32 as usize] = Some(vyuy_to_bgra32);
     funcs[PixelFormat::VYUY as usize][PixelFormat::RGBA32 as usize] = Some(vyuy_to_rgba32);
     funcs[PixelFormat::VYUY as usize][PixelFormat::BGR24 as usize] = Some(vyuy_to_bgr24);
@@ -546,29 +1269,587 @@ static VIDEO_FORMAT_CONVERT_FUNCS: LazyLock<[[Option<VideoFormatConvertFunc>; PI
     funcs[PixelFormat::VYUY as usize][PixelFormat::I420 as usize] = Some(vyuy_to_i420);
     funcs[PixelFormat::VYUY as usize][PixelFormat::I422 as usize] = Some(vyuy_to_i422);
     funcs[PixelFormat::VYUY as usize][PixelFormat::I444 as usize] = Some(vyuy_to_i444);
+    funcs[PixelFormat::VYUY as usize][PixelFormat::NV12 as usize] = Some(vyuy_to_nv12);
+    funcs[PixelFormat::Y8 as usize][PixelFormat::BGRA32 as usize] = Some(y8_to_bgra32);
+    funcs[PixelFormat::Y8 as usize][PixelFormat::RGBA32 as usize] = Some(y8_to_rgba32);
+    funcs[PixelFormat::Y8 as usize][PixelFormat::BGR24 as usize] = Some(y8_to_bgr24);
+    funcs[PixelFormat::Y8 as usize][PixelFormat::RGB24 as usize] = Some(y8_to_rgb24);
+    funcs[PixelFormat::YA8 as usize][PixelFormat::BGRA32 as usize] = Some(ya8_to_bgra32);
+    funcs[PixelFormat::YA8 as usize][PixelFormat::RGBA32 as usize] = Some(ya8_to_rgba32);
+    funcs[PixelFormat::Y16 as usize][PixelFormat::Y8 as usize] = Some(y16_to_y8);
+    funcs[PixelFormat::Y16 as usize][PixelFormat::RGB24 as usize] = Some(y16_to_gray_rgb24);
+    funcs[PixelFormat::Y16 as usize][PixelFormat::BGR24 as usize] = Some(y16_to_gray_rgb24);
+    funcs[PixelFormat::Y16 as usize][PixelFormat::RGBA32 as usize] = Some(y16_to_gray_rgba32);
+    funcs[PixelFormat::Y16 as usize][PixelFormat::BGRA32 as usize] = Some(y16_to_gray_rgba32);
+    funcs[PixelFormat::Y16 as usize][PixelFormat::ARGB32 as usize] = Some(y16_to_gray_argb32);
+    funcs[PixelFormat::Y16 as usize][PixelFormat::ABGR32 as usize] = Some(y16_to_gray_argb32);
     funcs[PixelFormat::I010 as usize][PixelFormat::RGB30 as usize] = Some(i010_to_rgb30);
     funcs[PixelFormat::I210 as usize][PixelFormat::RGB30 as usize] = Some(i210_to_rgb30);
     funcs[PixelFormat::I410 as usize][PixelFormat::RGB30 as usize] = Some(i410_to_rgb30);
+    funcs[PixelFormat::I410 as usize][PixelFormat::BGR30 as usize] = Some(i410_to_bgr30);
     funcs[PixelFormat::P010 as usize][PixelFormat::RGB30 as usize] = Some(p010_to_rgb30);
     funcs[PixelFormat::P210 as usize][PixelFormat::RGB30 as usize] = Some(p210_to_rgb30);
+    funcs[PixelFormat::P410 as usize][PixelFormat::RGB30 as usize] = Some(p410_to_rgb30);
+    funcs[PixelFormat::ARGB64 as usize][PixelFormat::BGRA32 as usize] = Some(argb64_to_bgra32);
+    funcs[PixelFormat::ARGB64 as usize][PixelFormat::RGBA32 as usize] = Some(argb64_to_rgba32);
+    funcs[PixelFormat::RGBA64 as usize][PixelFormat::RGBA32 as usize] = Some(rgba64_to_rgba32);
+    funcs[PixelFormat::RGBA64 as usize][PixelFormat::BGRA32 as usize] = Some(rgba64_to_bgra32);
+    funcs[PixelFormat::BGRA32 as usize][PixelFormat::ARGB64 as usize] = Some(bgra32_to_argb64);
+    funcs[PixelFormat::RGBA32 as usize][PixelFormat::ARGB64 as usize] = Some(rgba32_to_argb64);
+    funcs[PixelFormat::RGBA32 as usize][PixelFormat::RGBA64 as usize] = Some(rgba32_to_rgba64);
+    funcs[PixelFormat::BGRA32 as usize][PixelFormat::RGBA64 as usize] = Some(bgra32_to_rgba64);
     funcs
 });
 
+#[inline]
+fn is_contiguous(stride: usize, row_bytes: usize) -> bool {
+    stride == row_bytes
+}
+
 fn data_copy(src: &MappedPlanes, dst: &mut MappedPlanes, format: PixelFormat, width: NonZeroU32, height: NonZeroU32) -> Result<()> {
     if src.planes.len() != dst.planes.len() {
         return Err(invalid_error!("planes size mismatch"));
     }
 
     for (plane_index, (src_plane, dst_plane)) in src.planes.iter().zip(&mut dst.planes).enumerate() {
-        let plane_row_bytes = format.calc_plane_row_bytes(plane_index, width.get()) as usize;
-        let plane_height = format.calc_plane_height(plane_index, height.get());
-        if let (Some(src_stride), Some(dst_stride)) = (src_plane.stride(), dst_plane.stride()) {
-            if let (Some(src_data), Some(dst_data)) = (src_plane.data(), dst_plane.data_mut()) {
-                for row in 0..plane_height {
-                    let src_start = row as usize * src_stride;
-                    let dst_start = row as usize * dst_stride;
-                    dst_data[dst_start..dst_start + plane_row_bytes].copy_from_slice(&src_data[src_start..src_start + plane_row_bytes]);
-                }
+        let row_bytes = format.calc_plane_row_bytes(plane_index, width.get()) as usize;
+        let rows = format.calc_plane_height(plane_index, height.get()) as usize;
+        let (Some(src_stride), Some(dst_stride)) = (src_plane.stride(), dst_plane.stride()) else {
+            continue;
+        };
+        let (Some(src_data), Some(dst_data)) = (src_plane.data(), dst_plane.data_mut()) else {
+            continue;
+        };
+
+        if rows == 0 || row_bytes == 0 {
+            continue;
+        }
+
+        // check the extents once so that the row loop is free of bounds checks
+        let src_size = (rows - 1) * src_stride + row_bytes;
+        let dst_size = (rows - 1) * dst_stride + row_bytes;
+        if src_stride < row_bytes || src_data.len() < src_size {
+            return Err(invalid_error!("source plane size"));
+        }
+        if dst_stride < row_bytes || dst_data.len() < dst_size {
+            return Err(invalid_error!("destination plane size"));
+        }
+        let src_data = &src_data[..src_size];
+        let dst_data = &mut dst_data[..dst_size];
+
+        if is_contiguous(src_stride, row_bytes) && is_contiguous(dst_stride, row_bytes) {
+            dst_data.copy_from_slice(src_data);
+            continue;
+        }
+
+        for (src_row, dst_row) in src_data.chunks(src_stride).zip(dst_data.chunks_mut(dst_stride)) {
+            dst_row[..row_bytes].copy_from_slice(&src_row[..row_bytes]);
+        }
+    }
+
+    Ok(())
+}
+
+// Make sure every plane can hold `width` x `height` of `format` before the
+// conversion functions index into it
+fn validate_planes(planes: &MappedPlanes, format: PixelFormat, width: NonZeroU32, height: NonZeroU32) -> Result<()> {
+    let (_, plane_descs) = format.calc_data_size(width.get(), height.get(), 1);
+    if planes.planes.len() < plane_descs.len() {
+        return Err(invalid_error!("planes size mismatch"));
+    }
+
+    for (plane_index, plane) in planes.planes.iter().take(plane_descs.len()).enumerate() {
+        let (Some(stride), Some(data)) = (plane.stride(), plane.data()) else {
+            continue;
+        };
+        let row_bytes = format.calc_plane_row_bytes(plane_index, width.get()) as usize;
+        let rows = format.calc_plane_height(plane_index, height.get()) as usize;
+
+        if stride < row_bytes || data.len() < (rows - 1) * stride + row_bytes {
+            return Err(invalid_error!("plane size"));
+        }
+    }
+
+    Ok(())
+}
+
+// Chroma sample offsets from the co-sited luma sample, in half luma pixels
+fn chroma_siting(location: ChromaLocation) -> Option<(isize, isize)> {
+    match location {
+        ChromaLocation::Left => Some((0, 1)),
+        ChromaLocation::Center => Some((1, 1)),
+        ChromaLocation::TopLeft => Some((0, 0)),
+        ChromaLocation::Top => Some((1, 0)),
+        ChromaLocation::BottomLeft => Some((0, 2)),
+        ChromaLocation::Bottom => Some((1, 2)),
+        ChromaLocation::Unspecified => None,
+    }
+}
+
+// Linear interpolation of a 2x subsampled position, as (index, weight of
+// index + 1 in quarters)
+#[inline]
+fn sited_tap(pos: usize, siting: isize) -> (isize, u32) {
+    let quarters = 2 * pos as isize - siting;
+    (quarters.div_euclid(4), quarters.rem_euclid(4) as u32)
+}
+
+// Upsample 4:2:0 chroma to full resolution, honoring the chroma siting
+#[allow(clippy::too_many_arguments)]
+fn upsample_chroma_sited(
+    src: &[u8],
+    src_stride: usize,
+    step: usize,
+    chroma_width: usize,
+    chroma_height: usize,
+    dst: &mut [u8],
+    dst_stride: usize,
+    width: usize,
+    height: usize,
+    (h_siting, v_siting): (isize, isize),
+) {
+    let sample = |x: isize, y: isize| -> u32 {
+        let x = x.clamp(0, chroma_width as isize - 1) as usize;
+        let y = y.clamp(0, chroma_height as isize - 1) as usize;
+        src[y * src_stride + x * step] as u32
+    };
+
+    let h_taps: Vec<(isize, u32)> = (0..width).map(|x| sited_tap(x, h_siting)).collect();
+    for y in 0..height {
+        let (cy, wy) = sited_tap(y, v_siting);
+        let row = &mut dst[y * dst_stride..y * dst_stride + width];
+        for (value, &(cx, wx)) in row.iter_mut().zip(&h_taps) {
+            let top = sample(cx, cy) * (4 - wx) + sample(cx + 1, cy) * wx;
+            let bottom = sample(cx, cy + 1) * (4 - wx) + sample(cx + 1, cy + 1) * wx;
+            *value = ((top * (4 - wy) + bottom * wy + 8) / 16) as u8;
+        }
+    }
+}
+
+// 4:2:0 to RGB with a known chroma location goes through I444 so that the
+// chroma is aligned with luma, None if the default path applies
+fn convert_chroma_sited(
+    src_desc: &VideoFrameDescriptor,
+    src_planes: &MappedPlanes,
+    dst_format: PixelFormat,
+    dst_planes: &mut MappedPlanes,
+    mode: ConversionMode,
+) -> Option<Result<()>> {
+    if !dst_format.is_rgb() {
+        return None;
+    }
+
+    let siting = chroma_siting(src_desc.chroma_location)?;
+    // (U plane, U offset, V plane, V offset, chroma sample step)
+    let (u_plane, u_offset, v_plane, v_offset, step) = match src_desc.format {
+        PixelFormat::I420 => (1, 0, 2, 0, 1),
+        PixelFormat::YV12 => (2, 0, 1, 0, 1),
+        PixelFormat::NV12 => (1, 0, 1, 1, 2),
+        PixelFormat::NV21 => (1, 1, 1, 0, 2),
+        _ => return None,
+    };
+    let convert = VIDEO_FORMAT_CONVERT_FUNCS[PixelFormat::I444 as usize][dst_format as usize]?;
+
+    let result = (|| {
+        let width = src_desc.width();
+        let height = src_desc.height();
+        let (chroma_width, chroma_height) = src_desc.format.calc_chroma_dimensions(width.get(), height.get());
+
+        let mut i444_frame = Frame::video_creator().create(PixelFormat::I444, width.get(), height.get())?;
+        let mut i444_guard = i444_frame.map_mut()?;
+        let mut i444_planes = i444_guard.try_planes_mut()?;
+
+        let src_y_stride = src_planes.plane_stride(0).ok_or_else(|| invalid_error!("plane stride"))?;
+        let src_y = src_planes.plane_data(0).ok_or_else(|| invalid_error!("plane data"))?;
+        let dst_y_stride = i444_planes.plane_stride(0).ok_or_else(|| invalid_error!("plane stride"))?;
+        let dst_y = i444_planes.plane_data_mut(0).ok_or_else(|| invalid_error!("plane data"))?;
+        for (src_row, dst_row) in src_y.chunks(src_y_stride).zip(dst_y.chunks_mut(dst_y_stride)).take(height.get() as usize) {
+            dst_row[..width.get() as usize].copy_from_slice(&src_row[..width.get() as usize]);
+        }
+
+        for (dst_plane, src_plane, offset) in [(1, u_plane, u_offset), (2, v_plane, v_offset)] {
+            let src_stride = src_planes.plane_stride(src_plane).ok_or_else(|| invalid_error!("plane stride"))?;
+            let src = src_planes.plane_data(src_plane).ok_or_else(|| invalid_error!("plane data"))?;
+            let dst_stride = i444_planes.plane_stride(dst_plane).ok_or_else(|| invalid_error!("plane stride"))?;
+            let dst = i444_planes.plane_data_mut(dst_plane).ok_or_else(|| invalid_error!("plane data"))?;
+            upsample_chroma_sited(
+                &src[offset..],
+                src_stride,
+                step,
+                chroma_width as usize,
+                chroma_height as usize,
+                dst,
+                dst_stride,
+                width.get() as usize,
+                height.get() as usize,
+                siting,
+            );
+        }
+
+        convert(&i444_planes, dst_planes, src_desc.color_range, src_desc.color_matrix, mode, width, height)
+    })();
+
+    Some(result)
+}
+
+// alpha byte of a premultiplied source that has to be divided out before
+// converting to a format without alpha, YUV or RGB
+fn premultiplied_alpha_index(src_desc: &VideoFrameDescriptor, dst_format: PixelFormat) -> Option<usize> {
+    if !src_desc.premultiplied || dst_format.has_alpha() {
+        return None;
+    }
+
+    match src_desc.format {
+        PixelFormat::ARGB32 | PixelFormat::ABGR32 => Some(0),
+        PixelFormat::BGRA32 | PixelFormat::RGBA32 => Some(3),
+        _ => None,
+    }
+}
+
+// packed copy of plane 0 with straight alpha
+fn unpremultiply(src: &MappedPlanes, alpha_index: usize, width: NonZeroU32, height: NonZeroU32) -> Result<Vec<u8>> {
+    let src_stride = src.plane_stride(0).ok_or_else(|| invalid_error!("plane stride"))?;
+    let src_data = src.plane_data(0).ok_or_else(|| invalid_error!("plane data"))?;
+    let row_bytes = width.get() as usize * 4;
+    let mut data = Vec::with_capacity(row_bytes * height.get() as usize);
+
+    for src_row in src_data.chunks(src_stride).take(height.get() as usize) {
+        for pixel in src_row[..row_bytes].chunks_exact(4) {
+            let alpha = pixel[alpha_index] as u32;
+            data.extend(pixel.iter().enumerate().map(|(index, &value)| match (index == alpha_index, alpha) {
+                (true, _) => value,
+                (false, 0) => 0,
+                (false, _) => ((value as u32 * 255 + alpha / 2) / alpha).min(255) as u8,
+            }));
+        }
+    }
+
+    Ok(data)
+}
+
+fn packed_planes(data: &[u8], width: NonZeroU32, height: NonZeroU32) -> MappedPlanes<'_> {
+    let mut planes = MappedPlanes {
+        planes: Default::default(),
+    };
+    planes.planes.push(MappedPlane::Video {
+        data: MappedData::Ref(data),
+        stride: width.get() as usize * 4,
+        height: height.get(),
+    });
+    planes
+}
+
+fn convert_planes(
+    src_desc: &VideoFrameDescriptor,
+    src_planes: &MappedPlanes,
+    dst_format: PixelFormat,
+    dst_planes: &mut MappedPlanes,
+    mode: ConversionMode,
+) -> Result<()> {
+    validate_planes(src_planes, src_desc.format, src_desc.width(), src_desc.height())?;
+    validate_planes(dst_planes, dst_format, src_desc.width(), src_desc.height())?;
+
+    if let Some(alpha_index) = premultiplied_alpha_index(src_desc, dst_format) {
+        let unpremultiplied = unpremultiply(src_planes, alpha_index, src_desc.width(), src_desc.height())?;
+        return convert_validated_planes(
+            src_desc,
+            &packed_planes(&unpremultiplied, src_desc.width(), src_desc.height()),
+            dst_format,
+            dst_planes,
+            mode,
+        );
+    }
+
+    convert_validated_planes(src_desc, src_planes, dst_format, dst_planes, mode)
+}
+
+fn convert_validated_planes(
+    src_desc: &VideoFrameDescriptor,
+    src_planes: &MappedPlanes,
+    dst_format: PixelFormat,
+    dst_planes: &mut MappedPlanes,
+    mode: ConversionMode,
+) -> Result<()> {
+    if src_desc.format == dst_format {
+        return data_copy(src_planes, dst_planes, src_desc.format, src_desc.width(), src_desc.height());
+    }
+
+    if let Some(result) = convert_chroma_sited(src_desc, src_planes, dst_format, dst_planes, mode) {
+        return result;
+    }
+
+    let convert =
+        VIDEO_FORMAT_CONVERT_FUNCS[src_desc.format as usize][dst_format as usize].ok_or_else(|| unsupported_error!("video format conversion"))?;
+
+    convert(src_planes, dst_planes, src_desc.color_range, src_desc.color_matrix, mode, src_desc.width(), src_desc.height())
+}
+
+// (first row, rows) of each plane in the bands starting at the given luma rows
+#[cfg(feature = "rayon")]
+fn band_rows(format: PixelFormat, plane_index: usize, band_tops: &[u32], height: u32) -> impl Iterator<Item = (usize, u32)> + '_ {
+    let plane_height = format.calc_plane_height(plane_index, height);
+    band_tops.iter().enumerate().map(move |(band_index, &top)| {
+        let top = format.calc_plane_height(plane_index, top);
+        let bottom = band_tops.get(band_index + 1).map_or(plane_height, |&next| format.calc_plane_height(plane_index, next));
+        (top as usize, bottom - top)
+    })
+}
+
+// converts horizontal bands on the rayon pool, band boundaries fall on whole
+// chroma rows so every band converts exactly like the whole frame would
+#[cfg(feature = "rayon")]
+fn convert_planes_parallel(
+    src_desc: &VideoFrameDescriptor,
+    src_planes: &MappedPlanes,
+    dst_format: PixelFormat,
+    dst_planes: &mut MappedPlanes,
+    mode: ConversionMode,
+    threads: usize,
+) -> Result<()> {
+    use rayon::prelude::*;
+
+    if threads == 0 {
+        return Err(invalid_param_error!(threads));
+    }
+
+    // chroma siting interpolates between neighbouring chroma rows
+    if threads == 1 || chroma_siting(src_desc.chroma_location).is_some() {
+        return convert_planes(src_desc, src_planes, dst_format, dst_planes, mode);
+    }
+
+    let src_format = src_desc.format;
+    validate_planes(src_planes, src_format, src_desc.width(), src_desc.height())?;
+    validate_planes(dst_planes, dst_format, src_desc.width(), src_desc.height())?;
+
+    let height = src_desc.height().get();
+    let align = [src_format, dst_format].iter().map(|format| 2 / format.calc_chroma_dimensions(2, 2).1).max().unwrap_or(1);
+    let band_height = height.div_ceil(threads as u32).next_multiple_of(align);
+    let band_tops: Vec<u32> = (0..height).step_by(band_height as usize).collect();
+
+    let new_bands = || -> Vec<MappedPlanes> {
+        band_tops
+            .iter()
+            .map(|_| MappedPlanes {
+                planes: Default::default(),
+            })
+            .collect()
+    };
+
+    let mut src_bands = new_bands();
+    let plane_count = src_format.calc_data_size(1, 1, 1).1.len();
+    for (plane_index, plane) in src_planes.planes.iter().take(plane_count).enumerate() {
+        let stride = plane.stride().ok_or_else(|| invalid_error!("plane stride"))?;
+        let data = plane.data().ok_or_else(|| invalid_error!("plane data"))?;
+        for (band, (top, rows)) in src_bands.iter_mut().zip(band_rows(src_format, plane_index, &band_tops, height)) {
+            band.planes.push(MappedPlane::Video {
+                data: MappedData::Ref(&data[top * stride..]),
+                stride,
+                height: rows,
+            });
+        }
+    }
+
+    let mut dst_bands = new_bands();
+    let plane_count = dst_format.calc_data_size(1, 1, 1).1.len();
+    for (plane_index, plane) in dst_planes.planes.iter_mut().take(plane_count).enumerate() {
+        let stride = plane.stride().ok_or_else(|| invalid_error!("plane stride"))?;
+        let mut remaining = plane.data_mut().ok_or_else(|| invalid_error!("plane data"))?;
+        for (band, (_, rows)) in dst_bands.iter_mut().zip(band_rows(dst_format, plane_index, &band_tops, height)) {
+            // the last row of a tightly packed buffer may be shorter than the stride
+            let size = (rows as usize * stride).min(remaining.len());
+            let (data, rest) = std::mem::take(&mut remaining).split_at_mut(size);
+            remaining = rest;
+            band.planes.push(MappedPlane::Video {
+                data: MappedData::RefMut(data),
+                stride,
+                height: rows,
+            });
+        }
+    }
+
+    src_bands.into_par_iter().zip(dst_bands).enumerate().try_for_each(|(band_index, (src_band, mut dst_band))| {
+        let rows = band_tops.get(band_index + 1).unwrap_or(&height) - band_tops[band_index];
+        let mut band_desc = src_desc.clone();
+        band_desc.dimensions.height = NonZeroU32::new(rows).ok_or_else(|| invalid_error!("band height"))?;
+        convert_planes(&band_desc, &src_band, dst_format, &mut dst_band, mode)
+    })
+}
+
+// Overrides the color range and matrix the descriptors carry. RGB is always
+// full range, so src_range only applies to YUV input and dst_range to YUV
+// output. Unset fields fall back to the frame descriptors
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ConvertOptions {
+    pub src_range: Option<ColorRange>,
+    pub dst_range: Option<ColorRange>,
+    pub matrix: Option<ColorMatrix>,
+    pub mode: ConversionMode,
+}
+
+impl ConvertOptions {
+    // the source descriptor with the range and matrix of the YUV side. YUV to
+    // YUV conversions copy samples as they are, so a different dst_range is
+    // unsupported rather than silently ignored
+    fn resolve(&self, src_desc: &VideoFrameDescriptor, dst_desc: &VideoFrameDescriptor) -> Result<VideoFrameDescriptor> {
+        let mut desc = src_desc.clone();
+        if dst_desc.format.is_yuv() && !src_desc.format.is_yuv() {
+            desc.color_range = self.dst_range.unwrap_or(dst_desc.color_range);
+            desc.color_matrix = self.matrix.unwrap_or(dst_desc.color_matrix);
+        } else {
+            desc.color_range = self.src_range.unwrap_or(src_desc.color_range);
+            desc.color_matrix = self.matrix.unwrap_or(src_desc.color_matrix);
+        }
+
+        if src_desc.format.is_yuv() && dst_desc.format.is_yuv() && self.dst_range.is_some_and(|dst_range| dst_range != desc.color_range) {
+            return Err(unsupported_error!("YUV range conversion"));
+        }
+
+        Ok(desc)
+    }
+}
+
+// Resolves the conversion function once for a fixed pair of formats
+pub struct Converter {
+    src_format: PixelFormat,
+    dst_format: PixelFormat,
+    convert: Option<VideoFormatConvertFunc>, // None when the formats are the same
+}
+
+impl Converter {
+    pub fn new(src_format: PixelFormat, dst_format: PixelFormat) -> Result<Self> {
+        let convert = if src_format == dst_format {
+            None
+        } else {
+            Some(VIDEO_FORMAT_CONVERT_FUNCS[src_format as usize][dst_format as usize].ok_or_else(|| unsupported_error!("video format conversion"))?)
+        };
+
+        Ok(Self {
+            src_format,
+            dst_format,
+            convert,
+        })
+    }
+
+    pub fn src_format(&self) -> PixelFormat {
+        self.src_format
+    }
+
+    pub fn dst_format(&self) -> PixelFormat {
+        self.dst_format
+    }
+
+    pub fn convert(&self, src: &Frame, dst: &mut Frame) -> Result<()> {
+        let (FrameDescriptor::Video(src_desc), FrameDescriptor::Video(dst_desc)) = (&src.desc, &dst.desc) else {
+            return Err(invalid_error!("not video frame"));
+        };
+
+        if src_desc.format != self.src_format {
+            return Err(invalid_param_error!(src_desc.format));
+        }
+
+        if dst_desc.format != self.dst_format {
+            return Err(invalid_param_error!(dst_desc.format));
+        }
+
+        if src_desc.dimensions != dst_desc.dimensions {
+            return Err(invalid_error!("video frame dimensions mismatch"));
+        }
+
+        let guard = src.data.map()?;
+        let mut dst_guard = dst.data.map_mut()?;
+        let src_planes = guard.try_planes()?;
+        let mut dst_planes = dst_guard.try_planes_mut()?;
+
+        validate_planes(&src_planes, self.src_format, src_desc.width(), src_desc.height())?;
+        validate_planes(&dst_planes, self.dst_format, dst_desc.width(), dst_desc.height())?;
+
+        if let Some(alpha_index) = premultiplied_alpha_index(src_desc, self.dst_format) {
+            let unpremultiplied = unpremultiply(&src_planes, alpha_index, src_desc.width(), src_desc.height())?;
+            return self.convert_validated(src_desc, &packed_planes(&unpremultiplied, src_desc.width(), src_desc.height()), &mut dst_planes);
+        }
+
+        self.convert_validated(src_desc, &src_planes, &mut dst_planes)
+    }
+
+    fn convert_validated(&self, src_desc: &VideoFrameDescriptor, src_planes: &MappedPlanes, dst_planes: &mut MappedPlanes) -> Result<()> {
+        if let Some(result) = convert_chroma_sited(src_desc, src_planes, self.dst_format, dst_planes, ConversionMode::default()) {
+            return result;
+        }
+
+        match self.convert {
+            Some(convert) => convert(
+                src_planes,
+                dst_planes,
+                src_desc.color_range,
+                src_desc.color_matrix,
+                ConversionMode::default(),
+                src_desc.width(),
+                src_desc.height(),
+            ),
+            None => data_copy(src_planes, dst_planes, src_desc.format, src_desc.width(), src_desc.height()),
+        }
+    }
+}
+
+// byte order of the components sharing a plane, R G B A as 0 1 2 3 or U V as
+// 0 1, formats with the same plane and width can be swizzled in place
+const SWIZZLE_LAYOUTS: &[(PixelFormat, usize, &[usize])] = &[
+    (PixelFormat::ARGB32, 0, &[3, 0, 1, 2]),
+    (PixelFormat::BGRA32, 0, &[2, 1, 0, 3]),
+    (PixelFormat::ABGR32, 0, &[3, 2, 1, 0]),
+    (PixelFormat::RGBA32, 0, &[0, 1, 2, 3]),
+    (PixelFormat::RGB24, 0, &[0, 1, 2]),
+    (PixelFormat::BGR24, 0, &[2, 1, 0]),
+    (PixelFormat::NV12, 1, &[0, 1]),
+    (PixelFormat::NV21, 1, &[1, 0]),
+    (PixelFormat::NV16, 1, &[0, 1]),
+    (PixelFormat::NV61, 1, &[1, 0]),
+    (PixelFormat::NV24, 1, &[0, 1]),
+    (PixelFormat::NV42, 1, &[1, 0]),
+];
+
+fn swizzle_layout(format: PixelFormat) -> Option<(usize, &'static [usize])> {
+    SWIZZLE_LAYOUTS.iter().find(|(layout_format, _, _)| *layout_format == format).map(|(_, plane, order)| (*plane, *order))
+}
+
+// dst byte -> src byte, None if the destination has a component the source
+// lacks
+fn component_map(src_order: &[usize], dst_order: &[usize]) -> Option<Vec<usize>> {
+    dst_order.iter().map(|component| src_order.iter().position(|src_component| src_component == component)).collect()
+}
+
+// (plane index, dst byte -> src byte) for an in-place conversion
+fn swizzle_map(src_format: PixelFormat, dst_format: PixelFormat) -> Option<(usize, Vec<usize>)> {
+    let (src_plane, src_order) = swizzle_layout(src_format)?;
+    let (dst_plane, dst_order) = swizzle_layout(dst_format)?;
+
+    if src_plane != dst_plane || src_order.len() != dst_order.len() || src_format.chroma_subsampling() != dst_format.chroma_subsampling() {
+        return None;
+    }
+
+    component_map(src_order, dst_order).map(|map| (src_plane, map))
+}
+
+fn swizzle_in_place(desc: &VideoFrameDescriptor, data: &mut FrameData, target: PixelFormat) -> Result<()> {
+    let (plane_index, map) = swizzle_map(desc.format, target).ok_or_else(|| unsupported_error!(target))?;
+    let row_bytes = desc.format.calc_plane_row_bytes(plane_index, desc.width().get()) as usize;
+    let height = desc.format.calc_plane_height(plane_index, desc.height().get()) as usize;
+
+    let mut guard = data.map_mut()?;
+    let mut planes = guard.try_planes_mut()?;
+    let stride = planes.plane_stride(plane_index).ok_or_else(|| invalid_error!("plane stride"))?;
+    let plane = planes.plane_data_mut(plane_index).ok_or_else(|| invalid_error!("plane data"))?;
+
+    if stride < row_bytes || plane.len() < stride * (height - 1) + row_bytes {
+        return Err(invalid_error!("plane size"));
+    }
+
+    let mut pixel = [0u8; 4];
+    for row in plane.chunks_mut(stride).take(height) {
+        for unit in row[..row_bytes].chunks_exact_mut(map.len()) {
+            pixel[..map.len()].copy_from_slice(unit);
+            for (value, &src_index) in unit.iter_mut().zip(&map) {
+                *value = pixel[src_index];
             }
         }
     }
@@ -577,21 +1858,92 @@ fn data_copy(src: &MappedPlanes, dst: &mut MappedPlanes, format: PixelFormat, wi
 }
 
 impl Frame<'_> {
+    // data frames have no planes to convert, so they are unsupported
     pub fn convert_video_to(&self, dst: &mut Frame) -> Result<()> {
+        self.check_uncompressed()?;
+        let (FrameDescriptor::Video(src_desc), FrameDescriptor::Video(dst_desc)) = (&self.desc, &dst.desc) else {
+            return Err(unsupported_error!("not video frame"));
+        };
+
+        VideoFrame::convert_video_to_internal(src_desc, &self.data, dst_desc, &mut dst.data, ConversionMode::default())
+    }
+
+    // updates the range and matrix of a YUV destination to the ones used
+    pub fn convert_to_with(&self, dst: &mut Frame, options: &ConvertOptions) -> Result<()> {
+        self.check_uncompressed()?;
+        let (FrameDescriptor::Video(src_desc), FrameDescriptor::Video(dst_desc)) = (&self.desc, &mut dst.desc) else {
+            return Err(unsupported_error!("not video frame"));
+        };
+
+        VideoFrame::convert_video_to_with_internal(src_desc, &self.data, dst_desc, &mut dst.data, options)
+    }
+
+    // splits the frame into `threads` horizontal bands converted in parallel
+    #[cfg(feature = "rayon")]
+    pub fn convert_to_parallel(&self, dst: &mut Frame, threads: usize) -> Result<()> {
+        self.check_uncompressed()?;
+        let (FrameDescriptor::Video(src_desc), FrameDescriptor::Video(dst_desc)) = (&self.desc, &dst.desc) else {
+            return Err(unsupported_error!("not video frame"));
+        };
+
+        VideoFrame::convert_to_parallel_internal(src_desc, &self.data, dst_desc, &mut dst.data, threads)
+    }
+
+    // stretch the Y16 `range` to Y8, the full range if None
+    pub fn convert_y16_to_y8(&self, dst: &mut Frame, range: Option<(u16, u16)>) -> Result<()> {
         let (FrameDescriptor::Video(src_desc), FrameDescriptor::Video(dst_desc)) = (&self.desc, &dst.desc) else {
             return Err(invalid_error!("not video frame"));
         };
 
-        VideoFrame::convert_video_to_internal(src_desc, &self.data, dst_desc, &mut dst.data)
+        VideoFrame::convert_y16_to_y8_internal(src_desc, &self.data, dst_desc, &mut dst.data, range)
+    }
+
+    pub fn convert_into_slice(&self, target: PixelFormat, out: &mut [u8], stride: usize) -> Result<()> {
+        let FrameDescriptor::Video(src_desc) = &self.desc else {
+            return Err(invalid_error!("not video frame"));
+        };
+
+        VideoFrame::convert_into_slice_internal(src_desc, &self.data, target, out, stride)
+    }
+
+    pub fn convert_into_new(&self, target: PixelFormat) -> Result<Frame<'static>> {
+        let FrameDescriptor::Video(src_desc) = &self.desc else {
+            return Err(invalid_error!("not video frame"));
+        };
+
+        let mut dst_desc = src_desc.clone();
+        dst_desc.format = target;
+        let mut dst = Frame::video_creator().create_with_descriptor(dst_desc.clone())?;
+        VideoFrame::convert_video_to_internal(src_desc, &self.data, &dst_desc, &mut dst.data, ConversionMode::default())?;
+
+        dst.copy_props_from(self);
+
+        Ok(dst)
+    }
+
+    // reorders the components within the existing buffer, only for formats that
+    // differ in component order
+    pub fn convert_in_place(&mut self, target: PixelFormat) -> Result<()> {
+        let FrameDescriptor::Video(desc) = &mut self.desc else {
+            return Err(invalid_error!("not video frame"));
+        };
+
+        if desc.format != target {
+            swizzle_in_place(desc, &mut self.data, target)?;
+            desc.format = target;
+        }
+
+        Ok(())
     }
 }
 
 impl VideoFrame<'_> {
-    fn convert_video_to_internal(
+    pub(super) fn convert_video_to_internal(
         src_desc: &VideoFrameDescriptor,
         src_data: &FrameData,
         dst_desc: &VideoFrameDescriptor,
         dst_data: &mut FrameData,
+        mode: ConversionMode,
     ) -> Result<()> {
         if src_desc.dimensions != dst_desc.dimensions {
             return Err(invalid_error!("video frame dimensions mismatch"));
@@ -599,20 +1951,159 @@ impl VideoFrame<'_> {
 
         let guard = src_data.map()?;
         let mut dst_guard = dst_data.map_mut()?;
-        let src_planes = guard.planes().unwrap();
-        let mut dst_planes = dst_guard.planes_mut().unwrap();
+        let src_planes = guard.try_planes()?;
+        let mut dst_planes = dst_guard.try_planes_mut()?;
 
-        if src_desc.format == dst_desc.format {
-            return data_copy(&src_planes, &mut dst_planes, src_desc.format, src_desc.width(), src_desc.height());
+        convert_planes(src_desc, &src_planes, dst_desc.format, &mut dst_planes, mode)
+    }
+
+    fn convert_video_to_with_internal(
+        src_desc: &VideoFrameDescriptor,
+        src_data: &FrameData,
+        dst_desc: &mut VideoFrameDescriptor,
+        dst_data: &mut FrameData,
+        options: &ConvertOptions,
+    ) -> Result<()> {
+        let resolved_desc = options.resolve(src_desc, dst_desc)?;
+        Self::convert_video_to_internal(&resolved_desc, src_data, dst_desc, dst_data, options.mode)?;
+
+        if dst_desc.format.is_yuv() {
+            dst_desc.color_range = resolved_desc.color_range;
+            dst_desc.color_matrix = resolved_desc.color_matrix;
         }
 
-        let convert = VIDEO_FORMAT_CONVERT_FUNCS[src_desc.format as usize][dst_desc.format as usize]
-            .ok_or_else(|| unsupported_error!("video format conversion"))?;
+        Ok(())
+    }
 
-        convert(&src_planes, &mut dst_planes, src_desc.color_range, src_desc.color_matrix, src_desc.width(), src_desc.height())
+    #[cfg(feature = "rayon")]
+    fn convert_to_parallel_internal(
+        src_desc: &VideoFrameDescriptor,
+        src_data: &FrameData,
+        dst_desc: &VideoFrameDescriptor,
+        dst_data: &mut FrameData,
+        threads: usize,
+    ) -> Result<()> {
+        if src_desc.dimensions != dst_desc.dimensions {
+            return Err(invalid_error!("video frame dimensions mismatch"));
+        }
+
+        let guard = src_data.map()?;
+        let mut dst_guard = dst_data.map_mut()?;
+        let src_planes = guard.try_planes()?;
+        let mut dst_planes = dst_guard.try_planes_mut()?;
+
+        convert_planes_parallel(src_desc, &src_planes, dst_desc.format, &mut dst_planes, ConversionMode::default(), threads)
+    }
+
+    fn convert_y16_to_y8_internal(
+        src_desc: &VideoFrameDescriptor,
+        src_data: &FrameData,
+        dst_desc: &VideoFrameDescriptor,
+        dst_data: &mut FrameData,
+        range: Option<(u16, u16)>,
+    ) -> Result<()> {
+        if src_desc.format != PixelFormat::Y16 {
+            return Err(unsupported_error!(src_desc.format));
+        }
+
+        if dst_desc.format != PixelFormat::Y8 {
+            return Err(unsupported_error!(dst_desc.format));
+        }
+
+        if src_desc.dimensions != dst_desc.dimensions {
+            return Err(invalid_error!("video frame dimensions mismatch"));
+        }
+
+        let guard = src_data.map()?;
+        let mut dst_guard = dst_data.map_mut()?;
+        let src_planes = guard.try_planes()?;
+        let mut dst_planes = dst_guard.try_planes_mut()?;
+
+        y16_to_gray(&src_planes, &mut dst_planes, src_desc.width(), src_desc.height(), range.unwrap_or((u16::MIN, u16::MAX)), 1, None)
+    }
+
+    // lay out the planes of `target` back to back in `out`, like an aligned buffer
+    fn convert_into_slice_internal(
+        src_desc: &VideoFrameDescriptor,
+        src_data: &FrameData,
+        target: PixelFormat,
+        out: &mut [u8],
+        stride: usize,
+    ) -> Result<()> {
+        if stride < target.calc_plane_row_bytes(0, src_desc.width().get()) as usize {
+            return Err(invalid_param_error!(stride));
+        }
+
+        let stride = u32::try_from(stride).map_err(|_| invalid_param_error!(stride))?;
+        let (size, plane_descs) = target.calc_data_size_with_stride(src_desc.height().get(), stride);
+
+        if out.len() < size {
+            return Err(invalid_error!("buffer size"));
+        }
+
+        let mut dst_planes = MappedPlanes {
+            planes: Default::default(),
+        };
+        let mut remaining = &mut out[..size];
+        for plane_desc in plane_descs {
+            #[allow(unreachable_patterns)]
+            let (stride, height) = match plane_desc {
+                PlaneDescriptor::Video(stride, height) => (stride, height),
+                _ => return Err(invalid_error!("not video plane")),
+            };
+            let (data, rest) = remaining.split_at_mut(stride * height as usize);
+            remaining = rest;
+            dst_planes.planes.push(MappedPlane::Video {
+                data: MappedData::RefMut(data),
+                stride,
+                height,
+            });
+        }
+
+        let guard = src_data.map()?;
+        let src_planes = guard.try_planes()?;
+
+        convert_planes(src_desc, &src_planes, target, &mut dst_planes, ConversionMode::default())
     }
 
     pub fn convert_to(&self, dst: &mut VideoFrame) -> Result<()> {
-        Self::convert_video_to_internal(&self.desc, &self.data, &dst.desc, &mut dst.data)
+        Self::convert_video_to_internal(&self.desc, &self.data, &dst.desc, &mut dst.data, ConversionMode::default())
+    }
+
+    pub fn convert_to_with(&self, dst: &mut VideoFrame, options: &ConvertOptions) -> Result<()> {
+        Self::convert_video_to_with_internal(&self.desc, &self.data, &mut dst.desc, &mut dst.data, options)
+    }
+
+    #[cfg(feature = "rayon")]
+    pub fn convert_to_parallel(&self, dst: &mut VideoFrame, threads: usize) -> Result<()> {
+        Self::convert_to_parallel_internal(&self.desc, &self.data, &dst.desc, &mut dst.data, threads)
+    }
+
+    pub fn convert_y16_to_y8(&self, dst: &mut VideoFrame, range: Option<(u16, u16)>) -> Result<()> {
+        Self::convert_y16_to_y8_internal(&self.desc, &self.data, &dst.desc, &mut dst.data, range)
+    }
+
+    pub fn convert_into_slice(&self, target: PixelFormat, out: &mut [u8], stride: usize) -> Result<()> {
+        Self::convert_into_slice_internal(&self.desc, &self.data, target, out, stride)
+    }
+
+    pub fn convert_into_new(&self, target: PixelFormat) -> Result<VideoFrame<'static>> {
+        let mut dst_desc = self.desc.clone();
+        dst_desc.format = target;
+        let mut dst = VideoFrame::new_with_descriptor(dst_desc.clone())?;
+        Self::convert_video_to_internal(&self.desc, &self.data, &dst_desc, &mut dst.data, ConversionMode::default())?;
+
+        dst.copy_props_from(self);
+
+        Ok(dst)
+    }
+
+    pub fn convert_in_place(&mut self, target: PixelFormat) -> Result<()> {
+        if self.desc.format != target {
+            swizzle_in_place(&self.desc, &mut self.data, target)?;
+            self.desc.format = target;
+        }
+
+        Ok(())
     }
 }
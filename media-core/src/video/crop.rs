@@ -0,0 +1,136 @@
+use super::{
+    frame::VideoFrame,
+    video::{Dimensions, VideoFrameDescriptor},
+};
+use crate::{
+    frame::{DataMappable, Frame, FrameData},
+    invalid_error, invalid_param_error, unsupported_error,
+    variant::Variant,
+    FrameDescriptor, Result,
+};
+
+// well-known metadata keys describing a region of interest in pixels
+pub const ROI_X: &str = "roi_x";
+pub const ROI_Y: &str = "roi_y";
+pub const ROI_W: &str = "roi_w";
+pub const ROI_H: &str = "roi_h";
+
+impl Frame<'_> {
+    pub fn crop(&self, left: u32, top: u32, width: u32, height: u32) -> Result<Frame<'static>> {
+        let FrameDescriptor::Video(src_desc) = &self.desc else {
+            return Err(invalid_error!("not video frame"));
+        };
+
+        let mut dst_desc = src_desc.clone();
+        dst_desc.dimensions = Dimensions::new(width, height)?;
+        dst_desc.crop_left = 0;
+        dst_desc.crop_top = 0;
+        dst_desc.crop_right = 0;
+        dst_desc.crop_bottom = 0;
+
+        let mut dst = Frame::video_creator().create_with_descriptor(dst_desc.clone())?;
+        VideoFrame::crop_internal(src_desc, &self.data, &dst_desc, &mut dst.data, left, top)?;
+
+        dst.copy_props_from(self);
+
+        Ok(dst)
+    }
+
+    pub fn set_roi(&mut self, x: u32, y: u32, width: u32, height: u32) {
+        let metadata = self.metadata.get_or_insert_with(Variant::new_dict);
+        metadata.dict_set_uint32(ROI_X, x);
+        metadata.dict_set_uint32(ROI_Y, y);
+        metadata.dict_set_uint32(ROI_W, width);
+        metadata.dict_set_uint32(ROI_H, height);
+    }
+
+    pub fn roi(&self) -> Option<(u32, u32, u32, u32)> {
+        let metadata = self.metadata.as_ref()?;
+
+        Some((metadata.dict_get_uint32(ROI_X)?, metadata.dict_get_uint32(ROI_Y)?, metadata.dict_get_uint32(ROI_W)?, metadata.dict_get_uint32(ROI_H)?))
+    }
+
+    pub fn crop_to_roi(&self) -> Result<Frame<'static>> {
+        let (x, y, width, height) = self.roi().ok_or_else(|| invalid_error!("no region of interest"))?;
+
+        let mut dst = self.crop(x, y, width, height)?;
+        // the cropped frame covers exactly the region of interest
+        if let Some(metadata) = dst.metadata.as_mut() {
+            for key in [ROI_X, ROI_Y, ROI_W, ROI_H] {
+                metadata.dict_remove(key);
+            }
+        }
+
+        Ok(dst)
+    }
+}
+
+impl VideoFrame<'_> {
+    fn crop_internal(
+        src_desc: &VideoFrameDescriptor,
+        src_data: &FrameData,
+        dst_desc: &VideoFrameDescriptor,
+        dst_data: &mut FrameData,
+        left: u32,
+        top: u32,
+    ) -> Result<()> {
+        let format = src_desc.format;
+
+        if format != dst_desc.format {
+            return Err(invalid_error!("pixel format mismatch"));
+        }
+
+        if !format.is_planar() && !format.is_biplanar() && !format.is_packed() {
+            return Err(unsupported_error!(format));
+        }
+
+        let width = dst_desc.width().get();
+        let height = dst_desc.height().get();
+
+        if left.checked_add(width).is_none_or(|right| right > src_desc.width().get()) {
+            return Err(invalid_param_error!(left));
+        }
+
+        if top.checked_add(height).is_none_or(|bottom| bottom > src_desc.height().get()) {
+            return Err(invalid_param_error!(top));
+        }
+
+        // the origin must not split subsampled chroma samples
+        let (chroma_width, chroma_height) = format.calc_chroma_dimensions(2, 2);
+        if left % (2 / chroma_width) != 0 {
+            return Err(invalid_param_error!(left));
+        }
+        if top % (2 / chroma_height) != 0 {
+            return Err(invalid_param_error!(top));
+        }
+
+        let guard = src_data.map()?;
+        let mut dst_guard = dst_data.map_mut()?;
+        let src_planes = guard.try_planes()?;
+        let mut dst_planes = dst_guard.try_planes_mut()?;
+
+        if src_planes.len() != dst_planes.len() {
+            return Err(invalid_error!("planes size mismatch"));
+        }
+
+        for (plane_index, (src_plane, dst_plane)) in src_planes.iter().zip(dst_planes.iter_mut()).enumerate() {
+            let x_offset = format.calc_plane_row_bytes(plane_index, left) as usize;
+            let y_offset = format.calc_plane_height(plane_index, top) as usize;
+            let row_bytes = format.calc_plane_row_bytes(plane_index, width) as usize;
+            let rows = format.calc_plane_height(plane_index, height) as usize;
+
+            let src_stride = src_plane.stride().ok_or_else(|| invalid_error!("plane stride"))?;
+            let dst_stride = dst_plane.stride().ok_or_else(|| invalid_error!("plane stride"))?;
+            let src = src_plane.data().ok_or_else(|| invalid_error!("plane data"))?;
+            let dst = dst_plane.data_mut().ok_or_else(|| invalid_error!("plane data"))?;
+
+            for row in 0..rows {
+                let src_start = (y_offset + row) * src_stride + x_offset;
+                let dst_start = row * dst_stride;
+                dst[dst_start..dst_start + row_bytes].copy_from_slice(&src[src_start..src_start + row_bytes]);
+            }
+        }
+
+        Ok(())
+    }
+}
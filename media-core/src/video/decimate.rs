@@ -0,0 +1,105 @@
+use super::{
+    frame::VideoFrame,
+    video::{Dimensions, VideoFrameDescriptor},
+};
+use crate::{
+    frame::{DataMappable, Frame, FrameData},
+    invalid_error, invalid_param_error, unsupported_error, FrameDescriptor, Result,
+};
+
+// drop crop and size the descriptor down by `factor`
+fn decimated_descriptor(desc: &VideoFrameDescriptor, factor: u32) -> Result<VideoFrameDescriptor> {
+    if factor == 0 {
+        return Err(invalid_param_error!(factor));
+    }
+
+    let mut dst_desc = desc.clone();
+    dst_desc.dimensions = Dimensions::new(desc.width().get() / factor, desc.height().get() / factor)?;
+    dst_desc.crop_left = 0;
+    dst_desc.crop_top = 0;
+    dst_desc.crop_right = 0;
+    dst_desc.crop_bottom = 0;
+
+    Ok(dst_desc)
+}
+
+impl Frame<'_> {
+    pub fn decimate(&self, factor: u32) -> Result<Frame<'static>> {
+        let FrameDescriptor::Video(desc) = &self.desc else {
+            return Err(invalid_error!("not video frame"));
+        };
+
+        let dst_desc = decimated_descriptor(desc, factor)?;
+        let mut dst = Frame::video_creator().create_with_descriptor(dst_desc.clone())?;
+        VideoFrame::decimate_internal(desc, &self.data, &dst_desc, &mut dst.data, factor)?;
+
+        dst.copy_props_from(self);
+
+        Ok(dst)
+    }
+}
+
+impl VideoFrame<'_> {
+    // keeps every `factor`th sample of every `factor`th row in each plane
+    fn decimate_internal(
+        src_desc: &VideoFrameDescriptor,
+        src_data: &FrameData,
+        dst_desc: &VideoFrameDescriptor,
+        dst_data: &mut FrameData,
+        factor: u32,
+    ) -> Result<()> {
+        let format = src_desc.format;
+
+        // packed YUV samples come in macropixels that cannot be split
+        if !(format.is_planar() || format.is_biplanar() || (format.is_packed() && !format.is_yuv())) {
+            return Err(unsupported_error!(format));
+        }
+
+        let factor = factor as usize;
+        let src_width = src_desc.width().get();
+        let src_height = src_desc.height().get();
+        let dst_width = dst_desc.width().get();
+        let dst_height = dst_desc.height().get();
+
+        let guard = src_data.map()?;
+        let mut dst_guard = dst_data.map_mut()?;
+        let src_planes = guard.try_planes()?;
+        let mut dst_planes = dst_guard.try_planes_mut()?;
+
+        if src_planes.len() != dst_planes.len() {
+            return Err(invalid_error!("planes size mismatch"));
+        }
+
+        for plane_index in 0..src_planes.len() {
+            let unit = format.calc_plane_row_bytes(plane_index, 1) as usize;
+            let src_samples = format.calc_plane_row_bytes(plane_index, src_width) as usize / unit;
+            let src_rows = format.calc_plane_height(plane_index, src_height) as usize;
+            let dst_samples = format.calc_plane_row_bytes(plane_index, dst_width) as usize / unit;
+            let dst_rows = format.calc_plane_height(plane_index, dst_height) as usize;
+
+            let src_stride = src_planes.plane_stride(plane_index).ok_or_else(|| invalid_error!("plane stride"))?;
+            let src = src_planes.plane_data(plane_index).ok_or_else(|| invalid_error!("plane data"))?;
+            let dst_stride = dst_planes.plane_stride(plane_index).ok_or_else(|| invalid_error!("plane stride"))?;
+            let dst = dst_planes.plane_data_mut(plane_index).ok_or_else(|| invalid_error!("plane data"))?;
+
+            for (y, dst_row) in dst.chunks_mut(dst_stride).take(dst_rows).enumerate() {
+                let src_row = &src[(y * factor).min(src_rows - 1) * src_stride..];
+                for (x, dst_sample) in dst_row[..dst_samples * unit].chunks_exact_mut(unit).enumerate() {
+                    let src_x = (x * factor).min(src_samples - 1) * unit;
+                    dst_sample.copy_from_slice(&src_row[src_x..src_x + unit]);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn decimate(&self, factor: u32) -> Result<VideoFrame<'static>> {
+        let dst_desc = decimated_descriptor(&self.desc, factor)?;
+        let mut dst = VideoFrame::new_with_descriptor(dst_desc.clone())?;
+        Self::decimate_internal(&self.desc, &self.data, &dst_desc, &mut dst.data, factor)?;
+        dst.copy_props_from(self);
+
+        Ok(dst)
+    }
+}
@@ -0,0 +1,100 @@
+use super::{
+    frame::VideoFrame,
+    video::{PixelFormat, VideoFrameDescriptor},
+};
+use crate::{
+    frame::{DataMappable, Frame, FrameData},
+    invalid_error, unsupported_error, FrameDescriptor, Result,
+};
+
+impl Frame<'_> {
+    pub fn fill_from_fn<F>(&mut self, f: F) -> Result<()>
+    where
+        F: Fn(u32, u32) -> [u8; 4],
+    {
+        let FrameDescriptor::Video(desc) = &self.desc else {
+            return Err(invalid_error!("not video frame"));
+        };
+
+        VideoFrame::fill_from_fn_internal(desc, &mut self.data, f)
+    }
+
+    // zeroes the bytes past each row and after the last row of every plane, so
+    // that whole buffers hash and serialize deterministically
+    pub fn zero_padding(&mut self) -> Result<()> {
+        let FrameDescriptor::Video(desc) = &self.desc else {
+            return Err(invalid_error!("not video frame"));
+        };
+
+        VideoFrame::zero_padding_internal(desc, &mut self.data)
+    }
+}
+
+impl VideoFrame<'_> {
+    // the returned bytes are written in the memory order of the pixel format
+    fn fill_from_fn_internal<F>(desc: &VideoFrameDescriptor, data: &mut FrameData, f: F) -> Result<()>
+    where
+        F: Fn(u32, u32) -> [u8; 4],
+    {
+        if !matches!(desc.format, PixelFormat::ARGB32 | PixelFormat::BGRA32 | PixelFormat::ABGR32 | PixelFormat::RGBA32) {
+            return Err(unsupported_error!(desc.format));
+        }
+
+        let width = desc.width().get();
+        let height = desc.height().get();
+
+        let mut guard = data.map_mut()?;
+        let mut planes = guard.try_planes_mut()?;
+        let stride = planes.plane_stride(0).ok_or_else(|| invalid_error!("plane stride"))?;
+        let plane = planes.plane_data_mut(0).ok_or_else(|| invalid_error!("plane data"))?;
+
+        for (y, row) in plane.chunks_mut(stride).take(height as usize).enumerate() {
+            for (x, pixel) in row[..width as usize * 4].chunks_exact_mut(4).enumerate() {
+                pixel.copy_from_slice(&f(x as u32, y as u32));
+            }
+        }
+
+        Ok(())
+    }
+
+    fn zero_padding_internal(desc: &VideoFrameDescriptor, data: &mut FrameData) -> Result<()> {
+        let width = desc.width().get();
+        let height = desc.height().get();
+
+        let mut guard = data.map_mut()?;
+        let mut planes = guard.try_planes_mut()?;
+
+        for plane_index in 0..planes.planes.len() {
+            let row_bytes = desc.format.calc_plane_row_bytes(plane_index, width) as usize;
+            let plane_height = desc.format.calc_plane_height(plane_index, height) as usize;
+            let stride = planes.plane_stride(plane_index).ok_or_else(|| invalid_error!("plane stride"))?;
+            let plane = planes.plane_data_mut(plane_index).ok_or_else(|| invalid_error!("plane data"))?;
+
+            if stride < row_bytes || plane.len() < stride * (plane_height - 1) + row_bytes {
+                return Err(invalid_error!("plane size"));
+            }
+
+            for (y, row) in plane.chunks_mut(stride).enumerate() {
+                let padding_start = if y < plane_height {
+                    row_bytes
+                } else {
+                    0
+                };
+                row[padding_start..].fill(0);
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn fill_from_fn<F>(&mut self, f: F) -> Result<()>
+    where
+        F: Fn(u32, u32) -> [u8; 4],
+    {
+        Self::fill_from_fn_internal(&self.desc, &mut self.data, f)
+    }
+
+    pub fn zero_padding(&mut self) -> Result<()> {
+        Self::zero_padding_internal(&self.desc, &mut self.data)
+    }
+}
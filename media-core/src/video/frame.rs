@@ -1,6 +1,11 @@
 use std::{borrow::Cow, num::NonZeroU32, sync::Arc};
 
-use super::video::{PixelFormat, VideoFrameDescriptor};
+#[cfg(feature = "bytes")]
+use bytes::Bytes;
+
+use super::video::{PixelFormat, VideoFormat, VideoFrameDescriptor};
+#[cfg(feature = "bytes")]
+use crate::frame::BytesData;
 use crate::{
     buffer::Buffer,
     error::Error,
@@ -105,6 +110,31 @@ impl VideoDataCreator {
             planes,
         })
     }
+
+    #[cfg(feature = "bytes")]
+    fn create_from_bytes(
+        format: PixelFormat,
+        height: NonZeroU32,
+        bytes: Bytes,
+        bytes_planes: &[(usize, u32)], // (offset, stride), offset from the start of the Bytes
+    ) -> Result<BytesData> {
+        let mut planes = PlaneVec::with_capacity(bytes_planes.len());
+
+        for (i, (offset, stride)) in bytes_planes.iter().enumerate() {
+            let height = format.calc_plane_height(i, height.get());
+
+            if *offset + (*stride as usize * height as usize) > bytes.len() {
+                return Err(invalid_error!("bytes length"));
+            }
+
+            planes.push((*offset, PlaneDescriptor::Video(*stride as usize, height)));
+        }
+
+        Ok(BytesData {
+            data: bytes,
+            planes,
+        })
+    }
 }
 
 impl BufferData {
@@ -144,6 +174,7 @@ impl VideoFrameCreator {
     }
 
     pub fn create_with_descriptor(&self, desc: VideoFrameDescriptor) -> Result<Frame<'static>> {
+        desc.validate()?;
         let data = VideoDataCreator::create(desc.format, desc.width(), desc.height())?;
 
         Ok(Self::create_from_data(desc, data))
@@ -162,6 +193,7 @@ impl VideoFrameCreator {
     where
         T: Into<Cow<'a, [u8]>>,
     {
+        desc.validate()?;
         let data = VideoDataCreator::create_from_buffer(desc.format, desc.width(), desc.height(), buffer)?;
 
         Ok(Self::create_from_data(desc, data))
@@ -181,6 +213,7 @@ impl VideoFrameCreator {
     where
         T: Into<Cow<'a, [u8]>>,
     {
+        desc.validate()?;
         let data = VideoDataCreator::create_from_aligned_buffer(desc.format, desc.height(), stride, buffer)?;
 
         Ok(Self::create_from_data(desc, data))
@@ -200,6 +233,7 @@ impl VideoFrameCreator {
     where
         T: Into<Cow<'a, [u8]>>,
     {
+        desc.validate()?;
         let data = VideoDataCreator::create_from_packed_buffer(desc.format, desc.height(), stride, buffer)?;
 
         Ok(Self::create_from_data(desc, data))
@@ -212,11 +246,19 @@ impl VideoFrameCreator {
     }
 
     pub fn create_from_buffers_with_descriptor<'a>(&self, desc: VideoFrameDescriptor, buffers: &[(&'a [u8], u32)]) -> Result<Frame<'a>> {
+        desc.validate()?;
         let data = SeparateMemoryData::from_buffers(desc.format, desc.height(), buffers)?;
 
         Ok(Frame::from_data(FrameDescriptor::Video(desc), FrameData::SeparateMemory(data)))
     }
 
+    pub fn create_from_separate_planes<'a>(&self, desc: VideoFrameDescriptor, planes: &[(&'a [u8], u32, u32)]) -> Result<Frame<'a>> {
+        desc.validate()?;
+        let data = SeparateMemoryData::from_planes(&desc, planes)?;
+
+        Ok(Frame::from_data(FrameDescriptor::Video(desc), FrameData::SeparateMemory(data)))
+    }
+
     pub fn create_from_shared_buffer(
         &self,
         format: PixelFormat,
@@ -236,11 +278,40 @@ impl VideoFrameCreator {
         buffer: Arc<Buffer>,
         planes: &[(usize, u32)], // (offset, stride), offset from the start of the Buffer
     ) -> Result<Frame<'static>> {
+        desc.validate()?;
         let data = VideoDataCreator::create_from_shared_buffer(desc.format, desc.height(), buffer, planes)?;
 
         Ok(Frame::from_data(FrameDescriptor::Video(desc), FrameData::Buffer(data)))
     }
 
+    // read-only frame sharing the Bytes, the data is not copied
+    #[cfg(feature = "bytes")]
+    pub fn create_from_bytes(
+        &self,
+        format: PixelFormat,
+        width: u32,
+        height: u32,
+        bytes: Bytes,
+        planes: &[(usize, u32)], // (offset, stride), offset from the start of the Bytes
+    ) -> Result<Frame<'static>> {
+        let desc = VideoFrameDescriptor::try_new(format, width, height)?;
+
+        self.create_from_bytes_with_descriptor(desc, bytes, planes)
+    }
+
+    #[cfg(feature = "bytes")]
+    pub fn create_from_bytes_with_descriptor(
+        &self,
+        desc: VideoFrameDescriptor,
+        bytes: Bytes,
+        planes: &[(usize, u32)], // (offset, stride), offset from the start of the Bytes
+    ) -> Result<Frame<'static>> {
+        desc.validate()?;
+        let data = VideoDataCreator::create_from_bytes(desc.format, desc.height(), bytes, planes)?;
+
+        Ok(Frame::from_data(FrameDescriptor::Video(desc), FrameData::Bytes(data)))
+    }
+
     pub fn create_empty(&self, format: PixelFormat, width: u32, height: u32) -> Result<Frame<'static>> {
         let desc = VideoFrameDescriptor::try_new(format, width, height)?;
 
@@ -248,6 +319,7 @@ impl VideoFrameCreator {
     }
 
     pub fn create_empty_with_descriptor(&self, desc: VideoFrameDescriptor) -> Result<Frame<'static>> {
+        desc.validate()?;
         let data = FrameData::Empty;
 
         Ok(Frame::from_data(FrameDescriptor::Video(desc), data))
@@ -276,6 +348,38 @@ impl<'a> SeparateMemoryData<'a> {
             planes: data_vec,
         })
     }
+
+    // (data, stride, height) per plane, e.g. planes living in separate DMA buffers
+    fn from_planes(desc: &VideoFrameDescriptor, planes: &[(&'a [u8], u32, u32)]) -> Result<Self> {
+        let format = desc.format;
+        let (_, expected_planes) = format.calc_data_size(desc.width().get(), desc.height().get(), 1);
+
+        if planes.len() != expected_planes.len() {
+            return Err(invalid_error!("planes count"));
+        }
+
+        let mut data_vec = PlaneVec::with_capacity(planes.len());
+
+        for (i, (data, stride, height)) in planes.iter().enumerate() {
+            if *stride < format.calc_plane_row_bytes(i, desc.width().get()) {
+                return Err(invalid_error!("plane stride"));
+            }
+
+            if *height < format.calc_plane_height(i, desc.height().get()) {
+                return Err(invalid_error!("plane height"));
+            }
+
+            if data.len() < *stride as usize * *height as usize {
+                return Err(invalid_error!("plane size"));
+            }
+
+            data_vec.push((&data[..*stride as usize * *height as usize], *stride as usize, *height));
+        }
+
+        Ok(Self {
+            planes: data_vec,
+        })
+    }
 }
 
 impl Frame<'_> {
@@ -295,6 +399,23 @@ impl Frame<'_> {
         self.desc.is_video()
     }
 
+    // a compressed camera frame, delivered as a data frame holding {format, data}
+    pub fn is_compressed_video(&self) -> bool {
+        let (FrameDescriptor::Data(_), FrameData::Variant(variant)) = (&self.desc, &self.data) else {
+            return false;
+        };
+
+        variant["format"].get_uint32().and_then(|format| VideoFormat::try_from(format).ok()).is_some_and(|format| format.is_compressed())
+    }
+
+    pub(crate) fn check_uncompressed(&self) -> Result<()> {
+        if self.is_compressed_video() {
+            return Err(unsupported_error!("decode compressed frame first"));
+        }
+
+        Ok(())
+    }
+
     pub fn attach_video_shared_buffer(
         &mut self,
         format: PixelFormat,
@@ -341,6 +462,7 @@ impl VideoFrame<'_> {
     }
 
     pub fn new_with_descriptor(desc: VideoFrameDescriptor) -> Result<Self> {
+        desc.validate()?;
         let data = VideoDataCreator::create(desc.format, desc.width(), desc.height())?;
 
         Ok(Frame::from_data_with_generic_descriptor(desc, FrameData::Memory(data)))
@@ -359,6 +481,7 @@ impl VideoFrame<'_> {
     where
         T: Into<Cow<'a, [u8]>>,
     {
+        desc.validate()?;
         let data = VideoDataCreator::create_from_buffer(desc.format, desc.width(), desc.height(), buffer)?;
 
         Ok(Frame::from_data_with_generic_descriptor(desc, FrameData::Memory(data)))
@@ -378,6 +501,7 @@ impl VideoFrame<'_> {
     where
         T: Into<Cow<'a, [u8]>>,
     {
+        desc.validate()?;
         let data = VideoDataCreator::create_from_aligned_buffer(desc.format, desc.height(), stride, buffer)?;
 
         Ok(Frame::from_data_with_generic_descriptor(desc, FrameData::Memory(data)))
@@ -397,6 +521,7 @@ impl VideoFrame<'_> {
     where
         T: Into<Cow<'a, [u8]>>,
     {
+        desc.validate()?;
         let data = VideoDataCreator::create_from_packed_buffer(desc.format, desc.height(), stride, buffer)?;
 
         Ok(Frame::from_data_with_generic_descriptor(desc, FrameData::Memory(data)))
@@ -409,6 +534,7 @@ impl VideoFrame<'_> {
     }
 
     pub fn from_buffers_with_descriptor<'a>(desc: VideoFrameDescriptor, buffers: &[(&'a [u8], u32)]) -> Result<VideoFrame<'a>> {
+        desc.validate()?;
         let data = SeparateMemoryData::from_buffers(desc.format, desc.height(), buffers)?;
 
         Ok(Frame::from_data_with_generic_descriptor(desc, FrameData::SeparateMemory(data)))
@@ -431,11 +557,37 @@ impl VideoFrame<'_> {
         buffer: Arc<Buffer>,
         planes: &[(usize, u32)], // (offset, stride), offset from the start of the Buffer
     ) -> Result<VideoFrame<'static>> {
+        desc.validate()?;
         let data = VideoDataCreator::create_from_shared_buffer(desc.format, desc.height(), buffer, planes)?;
 
         Ok(Frame::from_data_with_generic_descriptor(desc, FrameData::Buffer(data)))
     }
 
+    #[cfg(feature = "bytes")]
+    pub fn from_bytes(
+        format: PixelFormat,
+        width: u32,
+        height: u32,
+        bytes: Bytes,
+        planes: &[(usize, u32)], // (offset, stride), offset from the start of the Bytes
+    ) -> Result<VideoFrame<'static>> {
+        let desc = VideoFrameDescriptor::try_new(format, width, height)?;
+
+        Self::from_bytes_with_descriptor(desc, bytes, planes)
+    }
+
+    #[cfg(feature = "bytes")]
+    pub fn from_bytes_with_descriptor(
+        desc: VideoFrameDescriptor,
+        bytes: Bytes,
+        planes: &[(usize, u32)], // (offset, stride), offset from the start of the Bytes
+    ) -> Result<VideoFrame<'static>> {
+        desc.validate()?;
+        let data = VideoDataCreator::create_from_bytes(desc.format, desc.height(), bytes, planes)?;
+
+        Ok(Frame::from_data_with_generic_descriptor(desc, FrameData::Bytes(data)))
+    }
+
     pub fn new_empty(format: PixelFormat, width: u32, height: u32) -> Result<VideoFrame<'static>> {
         let desc = VideoFrameDescriptor::try_new(format, width, height)?;
 
@@ -443,6 +595,7 @@ impl VideoFrame<'_> {
     }
 
     pub fn new_empty_with_descriptor(desc: VideoFrameDescriptor) -> Result<VideoFrame<'static>> {
+        desc.validate()?;
         let data = FrameData::Empty;
 
         Ok(Frame::from_data_with_generic_descriptor(desc, data))
@@ -495,6 +648,7 @@ impl<'a> From<VideoFrame<'a>> for Frame<'a> {
             dts: frame.dts,
             duration: frame.duration,
             time_base: frame.time_base,
+            sequence: frame.sequence,
             metadata: frame.metadata,
             data: frame.data,
         }
@@ -513,6 +667,7 @@ impl<'a> TryFrom<Frame<'a>> for VideoFrame<'a> {
                 dts: frame.dts,
                 duration: frame.duration,
                 time_base: frame.time_base,
+                sequence: frame.sequence,
                 metadata: frame.metadata,
                 data: frame.data,
             })
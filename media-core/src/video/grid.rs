@@ -0,0 +1,162 @@
+use super::{
+    frame::VideoFrame,
+    video::{Dimensions, VideoFrameDescriptor},
+};
+use crate::{
+    frame::{DataMappable, Frame, FrameData},
+    invalid_error, invalid_param_error, unsupported_error, FrameDescriptor, Result,
+};
+
+// split `size` into `count` spans whose boundaries are multiples of `align`
+fn calc_tile_bounds(size: u32, count: u32, align: u32) -> Result<Vec<u32>> {
+    let mut bounds: Vec<u32> = (0..count).map(|i| (size as u64 * i as u64 / count as u64) as u32 / align * align).collect();
+    bounds.push(size);
+
+    if bounds.windows(2).any(|span| span[0] >= span[1]) {
+        return Err(invalid_param_error!(count));
+    }
+
+    Ok(bounds)
+}
+
+impl Frame<'_> {
+    pub fn split_grid(&self, cols: u32, rows: u32) -> Result<Vec<Frame<'static>>> {
+        let FrameDescriptor::Video(desc) = &self.desc else {
+            return Err(invalid_error!("not video frame"));
+        };
+
+        if cols == 0 {
+            return Err(invalid_param_error!(cols));
+        }
+        if rows == 0 {
+            return Err(invalid_param_error!(rows));
+        }
+
+        // tile origins must not split subsampled chroma samples
+        let (chroma_width, chroma_height) = desc.format.calc_chroma_dimensions(2, 2);
+        let x_bounds = calc_tile_bounds(desc.width().get(), cols, 2 / chroma_width)?;
+        let y_bounds = calc_tile_bounds(desc.height().get(), rows, 2 / chroma_height)?;
+
+        let mut tiles = Vec::with_capacity((cols * rows) as usize);
+        for y in y_bounds.windows(2) {
+            for x in x_bounds.windows(2) {
+                tiles.push(self.crop(x[0], y[0], x[1] - x[0], y[1] - y[0])?);
+            }
+        }
+
+        Ok(tiles)
+    }
+
+    pub fn stitch_grid(tiles: &[Frame], cols: u32, rows: u32) -> Result<Frame<'static>> {
+        if cols == 0 {
+            return Err(invalid_param_error!(cols));
+        }
+        if rows == 0 {
+            return Err(invalid_param_error!(rows));
+        }
+        if tiles.len() != (cols * rows) as usize {
+            return Err(invalid_param_error!(tiles.len()));
+        }
+
+        let descs = tiles
+            .iter()
+            .map(|tile| match &tile.desc {
+                FrameDescriptor::Video(desc) => Ok(desc),
+                _ => Err(invalid_error!("not video frame")),
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        // the first row defines the column widths, the first column the row heights
+        let widths: Vec<u32> = descs[..cols as usize].iter().map(|desc| desc.width().get()).collect();
+        let heights: Vec<u32> = descs.iter().step_by(cols as usize).map(|desc| desc.height().get()).collect();
+
+        let first = &tiles[0];
+        let mut dst_desc = descs[0].clone();
+        dst_desc.dimensions = Dimensions::new(widths.iter().sum(), heights.iter().sum())?;
+        dst_desc.crop_left = 0;
+        dst_desc.crop_top = 0;
+        dst_desc.crop_right = 0;
+        dst_desc.crop_bottom = 0;
+
+        let mut dst = Frame::video_creator().create_with_descriptor(dst_desc.clone())?;
+
+        let mut top = 0;
+        for (row, height) in heights.iter().enumerate() {
+            let mut left = 0;
+            for (col, width) in widths.iter().enumerate() {
+                let index = row * cols as usize + col;
+                let desc = descs[index];
+
+                if desc.format != dst_desc.format || desc.width().get() != *width || desc.height().get() != *height {
+                    return Err(invalid_error!("tile mismatch"));
+                }
+
+                VideoFrame::paste_internal(desc, &tiles[index].data, &dst_desc, &mut dst.data, left, top)?;
+                left += width;
+            }
+            top += height;
+        }
+
+        dst.copy_props_from(first);
+
+        Ok(dst)
+    }
+}
+
+impl VideoFrame<'_> {
+    // the inverse of crop, copies the whole source to (left, top)
+    fn paste_internal(
+        src_desc: &VideoFrameDescriptor,
+        src_data: &FrameData,
+        dst_desc: &VideoFrameDescriptor,
+        dst_data: &mut FrameData,
+        left: u32,
+        top: u32,
+    ) -> Result<()> {
+        let format = src_desc.format;
+
+        if !format.is_planar() && !format.is_biplanar() && !format.is_packed() {
+            return Err(unsupported_error!(format));
+        }
+
+        let width = src_desc.width().get();
+        let height = src_desc.height().get();
+
+        if left.checked_add(width).is_none_or(|right| right > dst_desc.width().get()) {
+            return Err(invalid_param_error!(left));
+        }
+
+        if top.checked_add(height).is_none_or(|bottom| bottom > dst_desc.height().get()) {
+            return Err(invalid_param_error!(top));
+        }
+
+        let guard = src_data.map()?;
+        let mut dst_guard = dst_data.map_mut()?;
+        let src_planes = guard.try_planes()?;
+        let mut dst_planes = dst_guard.try_planes_mut()?;
+
+        if src_planes.len() != dst_planes.len() {
+            return Err(invalid_error!("planes size mismatch"));
+        }
+
+        for (plane_index, (src_plane, dst_plane)) in src_planes.iter().zip(dst_planes.iter_mut()).enumerate() {
+            let x_offset = format.calc_plane_row_bytes(plane_index, left) as usize;
+            let y_offset = format.calc_plane_height(plane_index, top) as usize;
+            let row_bytes = format.calc_plane_row_bytes(plane_index, width) as usize;
+            let rows = format.calc_plane_height(plane_index, height) as usize;
+
+            let src_stride = src_plane.stride().ok_or_else(|| invalid_error!("plane stride"))?;
+            let dst_stride = dst_plane.stride().ok_or_else(|| invalid_error!("plane stride"))?;
+            let src = src_plane.data().ok_or_else(|| invalid_error!("plane data"))?;
+            let dst = dst_plane.data_mut().ok_or_else(|| invalid_error!("plane data"))?;
+
+            for row in 0..rows {
+                let src_start = row * src_stride;
+                let dst_start = (y_offset + row) * dst_stride + x_offset;
+                dst[dst_start..dst_start + row_bytes].copy_from_slice(&src[src_start..src_start + row_bytes]);
+            }
+        }
+
+        Ok(())
+    }
+}
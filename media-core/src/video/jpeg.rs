@@ -0,0 +1,512 @@
+use std::{f32::consts::PI, sync::LazyLock};
+
+use zune_jpeg::zune_core::colorspace::ColorSpace;
+
+use super::{
+    convert::ConversionMode,
+    frame::VideoFrame,
+    mjpeg::jpeg_decoder,
+    video::{ColorMatrix, ColorRange, PixelFormat, VideoFrameDescriptor},
+};
+use crate::{
+    frame::{DataMappable, Frame, FrameData},
+    invalid_error, invalid_param_error, unsupported_error, FrameDescriptor, Result,
+};
+
+// natural order index of each zigzag position
+const ZIGZAG: [usize; 64] = [
+    0, 1, 8, 16, 9, 2, 3, 10, 17, 24, 32, 25, 18, 11, 4, 5, 12, 19, 26, 33, 40, 48, 41, 34, 27, 20, 13, 6, 7, 14, 21, 28, 35, 42, 49, 56, 57, 50, 43,
+    36, 29, 22, 15, 23, 30, 37, 44, 51, 58, 59, 52, 45, 38, 31, 39, 46, 53, 60, 61, 54, 47, 55, 62, 63,
+];
+
+// ITU T.81 Annex K example tables
+const LUMA_QUANT_TABLE: [u16; 64] = [
+    16, 11, 10, 16, 24, 40, 51, 61, 12, 12, 14, 19, 26, 58, 60, 55, 14, 13, 16, 24, 40, 57, 69, 56, 14, 17, 22, 29, 51, 87, 80, 62, 18, 22, 37, 56,
+    68, 109, 103, 77, 24, 35, 55, 64, 81, 104, 113, 92, 49, 64, 78, 87, 103, 121, 120, 101, 72, 92, 95, 98, 112, 100, 103, 99,
+];
+
+const CHROMA_QUANT_TABLE: [u16; 64] = [
+    17, 18, 24, 47, 99, 99, 99, 99, 18, 21, 26, 66, 99, 99, 99, 99, 24, 26, 56, 99, 99, 99, 99, 99, 47, 66, 99, 99, 99, 99, 99, 99, 99, 99, 99, 99,
+    99, 99, 99, 99, 99, 99, 99, 99, 99, 99, 99, 99, 99, 99, 99, 99, 99, 99, 99, 99, 99, 99, 99, 99, 99, 99, 99, 99,
+];
+
+const LUMA_DC_BITS: [u8; 16] = [0, 1, 5, 1, 1, 1, 1, 1, 1, 0, 0, 0, 0, 0, 0, 0];
+const LUMA_DC_VALUES: [u8; 12] = [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11];
+const CHROMA_DC_BITS: [u8; 16] = [0, 3, 1, 1, 1, 1, 1, 1, 1, 1, 1, 0, 0, 0, 0, 0];
+const CHROMA_DC_VALUES: [u8; 12] = [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11];
+
+const LUMA_AC_BITS: [u8; 16] = [0, 2, 1, 3, 3, 2, 4, 3, 5, 5, 4, 4, 0, 0, 1, 0x7D];
+const LUMA_AC_VALUES: [u8; 162] = [
+    0x01, 0x02, 0x03, 0x00, 0x04, 0x11, 0x05, 0x12, 0x21, 0x31, 0x41, 0x06, 0x13, 0x51, 0x61, 0x07, 0x22, 0x71, 0x14, 0x32, 0x81, 0x91, 0xA1, 0x08,
+    0x23, 0x42, 0xB1, 0xC1, 0x15, 0x52, 0xD1, 0xF0, 0x24, 0x33, 0x62, 0x72, 0x82, 0x09, 0x0A, 0x16, 0x17, 0x18, 0x19, 0x1A, 0x25, 0x26, 0x27, 0x28,
+    0x29, 0x2A, 0x34, 0x35, 0x36, 0x37, 0x38, 0x39, 0x3A, 0x43, 0x44, 0x45, 0x46, 0x47, 0x48, 0x49, 0x4A, 0x53, 0x54, 0x55, 0x56, 0x57, 0x58, 0x59,
+    0x5A, 0x63, 0x64, 0x65, 0x66, 0x67, 0x68, 0x69, 0x6A, 0x73, 0x74, 0x75, 0x76, 0x77, 0x78, 0x79, 0x7A, 0x83, 0x84, 0x85, 0x86, 0x87, 0x88, 0x89,
+    0x8A, 0x92, 0x93, 0x94, 0x95, 0x96, 0x97, 0x98, 0x99, 0x9A, 0xA2, 0xA3, 0xA4, 0xA5, 0xA6, 0xA7, 0xA8, 0xA9, 0xAA, 0xB2, 0xB3, 0xB4, 0xB5, 0xB6,
+    0xB7, 0xB8, 0xB9, 0xBA, 0xC2, 0xC3, 0xC4, 0xC5, 0xC6, 0xC7, 0xC8, 0xC9, 0xCA, 0xD2, 0xD3, 0xD4, 0xD5, 0xD6, 0xD7, 0xD8, 0xD9, 0xDA, 0xE1, 0xE2,
+    0xE3, 0xE4, 0xE5, 0xE6, 0xE7, 0xE8, 0xE9, 0xEA, 0xF1, 0xF2, 0xF3, 0xF4, 0xF5, 0xF6, 0xF7, 0xF8, 0xF9, 0xFA,
+];
+
+const CHROMA_AC_BITS: [u8; 16] = [0, 2, 1, 2, 4, 4, 3, 4, 7, 5, 4, 4, 0, 1, 2, 0x77];
+const CHROMA_AC_VALUES: [u8; 162] = [
+    0x00, 0x01, 0x02, 0x03, 0x11, 0x04, 0x05, 0x21, 0x31, 0x06, 0x12, 0x41, 0x51, 0x07, 0x61, 0x71, 0x13, 0x22, 0x32, 0x81, 0x08, 0x14, 0x42, 0x91,
+    0xA1, 0xB1, 0xC1, 0x09, 0x23, 0x33, 0x52, 0xF0, 0x15, 0x62, 0x72, 0xD1, 0x0A, 0x16, 0x24, 0x34, 0xE1, 0x25, 0xF1, 0x17, 0x18, 0x19, 0x1A, 0x26,
+    0x27, 0x28, 0x29, 0x2A, 0x35, 0x36, 0x37, 0x38, 0x39, 0x3A, 0x43, 0x44, 0x45, 0x46, 0x47, 0x48, 0x49, 0x4A, 0x53, 0x54, 0x55, 0x56, 0x57, 0x58,
+    0x59, 0x5A, 0x63, 0x64, 0x65, 0x66, 0x67, 0x68, 0x69, 0x6A, 0x73, 0x74, 0x75, 0x76, 0x77, 0x78, 0x79, 0x7A, 0x82, 0x83, 0x84, 0x85, 0x86, 0x87,
+    0x88, 0x89, 0x8A, 0x92, 0x93, 0x94, 0x95, 0x96, 0x97, 0x98, 0x99, 0x9A, 0xA2, 0xA3, 0xA4, 0xA5, 0xA6, 0xA7, 0xA8, 0xA9, 0xAA, 0xB2, 0xB3, 0xB4,
+    0xB5, 0xB6, 0xB7, 0xB8, 0xB9, 0xBA, 0xC2, 0xC3, 0xC4, 0xC5, 0xC6, 0xC7, 0xC8, 0xC9, 0xCA, 0xD2, 0xD3, 0xD4, 0xD5, 0xD6, 0xD7, 0xD8, 0xD9, 0xDA,
+    0xE2, 0xE3, 0xE4, 0xE5, 0xE6, 0xE7, 0xE8, 0xE9, 0xEA, 0xF2, 0xF3, 0xF4, 0xF5, 0xF6, 0xF7, 0xF8, 0xF9, 0xFA,
+];
+
+const MARKER_SOI: u8 = 0xD8;
+const MARKER_EOI: u8 = 0xD9;
+const MARKER_SOF0: u8 = 0xC0;
+const MARKER_DHT: u8 = 0xC4;
+const MARKER_SOS: u8 = 0xDA;
+const MARKER_DQT: u8 = 0xDB;
+const MARKER_APP0: u8 = 0xE0;
+
+// C(u) * cos((2x + 1) * u * PI / 16) / 2, indexed by [x][u]
+static DCT_COS_TABLE: LazyLock<[[f32; 8]; 8]> = LazyLock::new(|| {
+    let mut table = [[0.0; 8]; 8];
+    for (x, row) in table.iter_mut().enumerate() {
+        for (u, value) in row.iter_mut().enumerate() {
+            let c = if u == 0 {
+                1.0 / 2.0f32.sqrt()
+            } else {
+                1.0
+            };
+            *value = c * ((2 * x + 1) as f32 * u as f32 * PI / 16.0).cos() / 2.0;
+        }
+    }
+    table
+});
+
+fn fdct(block: &[f32; 64]) -> [f32; 64] {
+    let table = &*DCT_COS_TABLE;
+    let mut rows = [0.0; 64];
+    for y in 0..8 {
+        for u in 0..8 {
+            rows[y * 8 + u] = (0..8).map(|x| table[x][u] * block[y * 8 + x]).sum();
+        }
+    }
+
+    let mut coefficients = [0.0; 64];
+    for v in 0..8 {
+        for u in 0..8 {
+            coefficients[v * 8 + u] = (0..8).map(|y| table[y][v] * rows[y * 8 + u]).sum();
+        }
+    }
+    coefficients
+}
+
+// IJG quality scaling, in natural order
+fn scale_quant_table(table: &[u16; 64], quality: u8) -> [u16; 64] {
+    let quality = quality.clamp(1, 100) as u32;
+    let scale = if quality < 50 {
+        5000 / quality
+    } else {
+        200 - quality * 2
+    };
+    table.map(|value| ((value as u32 * scale + 50) / 100).clamp(1, 255) as u16)
+}
+
+// bits needed for the magnitude of `value`
+#[inline]
+fn category(value: i32) -> u32 {
+    32 - value.unsigned_abs().leading_zeros()
+}
+
+struct HuffmanEncoder {
+    codes: [(u16, u8); 256], // (code, length) per symbol
+}
+
+impl HuffmanEncoder {
+    fn new(bits: &[u8; 16], values: &[u8]) -> Self {
+        let mut codes = [(0, 0); 256];
+        let mut code = 0u16;
+        let mut index = 0;
+        for (length, &count) in bits.iter().enumerate() {
+            for _ in 0..count {
+                codes[values[index] as usize] = (code, length as u8 + 1);
+                code += 1;
+                index += 1;
+            }
+            code <<= 1;
+        }
+        Self {
+            codes,
+        }
+    }
+}
+
+struct BitWriter {
+    output: Vec<u8>,
+    buffer: u32,
+    count: u32,
+}
+
+impl BitWriter {
+    fn write_bits(&mut self, bits: u32, length: u32) {
+        self.buffer = (self.buffer << length) | (bits & ((1 << length) - 1));
+        self.count += length;
+        while self.count >= 8 {
+            let byte = (self.buffer >> (self.count - 8)) as u8;
+            self.output.push(byte);
+            // a literal 0xFF is followed by a stuffed zero byte
+            if byte == 0xFF {
+                self.output.push(0);
+            }
+            self.count -= 8;
+        }
+        self.buffer &= (1 << self.count) - 1;
+    }
+
+    fn write_symbol(&mut self, encoder: &HuffmanEncoder, symbol: u8) {
+        let (code, length) = encoder.codes[symbol as usize];
+        self.write_bits(code as u32, length as u32);
+    }
+
+    fn flush(&mut self) {
+        // pad with ones
+        if self.count > 0 {
+            self.write_bits(0x7F, 8 - self.count);
+        }
+    }
+}
+
+struct BlockEncoder<'a> {
+    quant_table: &'a [u16; 64],
+    dc_encoder: &'a HuffmanEncoder,
+    ac_encoder: &'a HuffmanEncoder,
+    prev_dc: i32,
+}
+
+impl BlockEncoder<'_> {
+    fn encode(&mut self, writer: &mut BitWriter, block: &[f32; 64]) {
+        let coefficients = fdct(block);
+        let quantized: [i32; 64] = std::array::from_fn(|k| {
+            let index = ZIGZAG[k];
+            (coefficients[index] / self.quant_table[index] as f32).round() as i32
+        });
+
+        let diff = quantized[0] - self.prev_dc;
+        self.prev_dc = quantized[0];
+        let size = category(diff);
+        writer.write_symbol(self.dc_encoder, size as u8);
+        if size > 0 {
+            writer.write_bits(
+                (if diff < 0 {
+                    diff - 1
+                } else {
+                    diff
+                }) as u32,
+                size,
+            );
+        }
+
+        let mut run = 0;
+        for &value in &quantized[1..] {
+            if value == 0 {
+                run += 1;
+                continue;
+            }
+            while run >= 16 {
+                writer.write_symbol(self.ac_encoder, 0xF0);
+                run -= 16;
+            }
+            let size = category(value);
+            writer.write_symbol(self.ac_encoder, ((run << 4) | size) as u8);
+            writer.write_bits(
+                (if value < 0 {
+                    value - 1
+                } else {
+                    value
+                }) as u32,
+                size,
+            );
+            run = 0;
+        }
+        if run > 0 {
+            writer.write_symbol(self.ac_encoder, 0x00);
+        }
+    }
+}
+
+fn write_segment(output: &mut Vec<u8>, marker: u8, data: &[u8]) {
+    output.extend_from_slice(&[0xFF, marker]);
+    output.extend_from_slice(&(data.len() as u16 + 2).to_be_bytes());
+    output.extend_from_slice(data);
+}
+
+fn write_huffman_table(data: &mut Vec<u8>, class_id: u8, bits: &[u8; 16], values: &[u8]) {
+    data.push(class_id);
+    data.extend_from_slice(bits);
+    data.extend_from_slice(values);
+}
+
+// A component sampled with edge replication, `step` apart within the row
+struct SourcePlane<'a> {
+    data: &'a [u8],
+    stride: usize,
+    offset: usize,
+    step: usize,
+    width: usize,
+    height: usize,
+    lut: &'a [f32; 256], // sample value to level shifted full range
+}
+
+impl SourcePlane<'_> {
+    fn read_block(&self, block_x: usize, block_y: usize) -> [f32; 64] {
+        std::array::from_fn(|i| {
+            let x = (block_x * 8 + i % 8).min(self.width - 1);
+            let y = (block_y * 8 + i / 8).min(self.height - 1);
+            self.lut[self.data[y * self.stride + x * self.step + self.offset] as usize]
+        })
+    }
+}
+
+impl Frame<'_> {
+    pub fn encode_jpeg(&self, quality: u8) -> Result<Vec<u8>> {
+        let FrameDescriptor::Video(desc) = &self.desc else {
+            return Err(invalid_error!("not video frame"));
+        };
+
+        VideoFrame::encode_jpeg_converted(desc, &self.data, quality)
+    }
+
+    // decodes to I444 with the chroma upsampled, or Y8 for grayscale
+    pub fn decode_jpeg(data: &[u8]) -> Result<Frame<'static>> {
+        VideoFrame::decode_jpeg_internal(data)
+    }
+}
+
+impl VideoFrame<'_> {
+    // JPEG stores BT.601 YCbCr, formats it cannot sample go through I420 and
+    // other matrices are converted first
+    fn encode_jpeg_converted(desc: &VideoFrameDescriptor, data: &FrameData, quality: u8) -> Result<Vec<u8>> {
+        let needs_matrix = matches!(
+            desc.color_matrix,
+            ColorMatrix::BT709 | ColorMatrix::BT2020NCL | ColorMatrix::BT2020CL | ColorMatrix::SMPTE240M | ColorMatrix::FCC | ColorMatrix::YCgCo
+        );
+
+        let i420_frame = match desc.format {
+            PixelFormat::Y8 => return Self::encode_jpeg_internal(desc, data, quality),
+            PixelFormat::I420 |
+            PixelFormat::YV12 |
+            PixelFormat::NV12 |
+            PixelFormat::NV21 |
+            PixelFormat::I422 |
+            PixelFormat::NV16 |
+            PixelFormat::I444 |
+            PixelFormat::YV24 |
+            PixelFormat::NV24 |
+            PixelFormat::NV42 => None,
+            _ => {
+                let mut i420_desc = desc.clone();
+                i420_desc.format = PixelFormat::I420;
+                let mut i420_frame = VideoFrame::new_with_descriptor(i420_desc.clone())?;
+                Self::convert_video_to_internal(desc, data, &i420_desc, &mut i420_frame.data, ConversionMode::default())?;
+                Some(i420_frame)
+            }
+        };
+        let (desc, data) = i420_frame.as_ref().map_or((desc, data), |frame| (&frame.desc, &frame.data));
+
+        if !needs_matrix {
+            return Self::encode_jpeg_internal(desc, data, quality);
+        }
+
+        let mut bt601_desc = desc.clone();
+        bt601_desc.color_matrix = ColorMatrix::SMPTE170M;
+        let mut bt601_frame = VideoFrame::new_with_descriptor(bt601_desc.clone())?;
+        Self::convert_matrix_internal(desc, data, &bt601_desc, &mut bt601_frame.data)?;
+        Self::encode_jpeg_internal(&bt601_desc, &bt601_frame.data, quality)
+    }
+
+    fn encode_jpeg_internal(desc: &VideoFrameDescriptor, data: &FrameData, quality: u8) -> Result<Vec<u8>> {
+        if !(1..=100).contains(&quality) {
+            return Err(invalid_param_error!(quality));
+        }
+
+        // luma sampling factors and the (plane, offset, step) of each component
+        let (h, v, layout): (usize, usize, &[(usize, usize, usize)]) = match desc.format {
+            PixelFormat::I420 => (2, 2, &[(0, 0, 1), (1, 0, 1), (2, 0, 1)]),
+            PixelFormat::YV12 => (2, 2, &[(0, 0, 1), (2, 0, 1), (1, 0, 1)]),
+            PixelFormat::NV12 => (2, 2, &[(0, 0, 1), (1, 0, 2), (1, 1, 2)]),
+            PixelFormat::NV21 => (2, 2, &[(0, 0, 1), (1, 1, 2), (1, 0, 2)]),
+            PixelFormat::I422 => (2, 1, &[(0, 0, 1), (1, 0, 1), (2, 0, 1)]),
+            PixelFormat::NV16 => (2, 1, &[(0, 0, 1), (1, 0, 2), (1, 1, 2)]),
+            PixelFormat::I444 => (1, 1, &[(0, 0, 1), (1, 0, 1), (2, 0, 1)]),
+            PixelFormat::YV24 => (1, 1, &[(0, 0, 1), (2, 0, 1), (1, 0, 1)]),
+            PixelFormat::NV24 => (1, 1, &[(0, 0, 1), (1, 0, 2), (1, 1, 2)]),
+            PixelFormat::NV42 => (1, 1, &[(0, 0, 1), (1, 1, 2), (1, 0, 2)]),
+            PixelFormat::Y8 => (1, 1, &[(0, 0, 1)]),
+            _ => return Err(unsupported_error!(desc.format)),
+        };
+        let components = layout.len() as u8;
+
+        let width = desc.width().get() as usize;
+        let height = desc.height().get() as usize;
+        if width > u16::MAX as usize || height > u16::MAX as usize {
+            return Err(invalid_error!("dimensions too large for jpeg"));
+        }
+
+        // expand video range to the full range JPEG uses
+        let (luma_lut, chroma_lut): ([f32; 256], [f32; 256]) = if desc.color_range == ColorRange::Full {
+            (std::array::from_fn(|i| i as f32 - 128.0), std::array::from_fn(|i| i as f32 - 128.0))
+        } else {
+            (
+                std::array::from_fn(|i| ((i as f32 - 16.0) * 255.0 / 219.0).clamp(0.0, 255.0) - 128.0),
+                std::array::from_fn(|i| ((i as f32 - 128.0) * 255.0 / 224.0).clamp(-128.0, 127.0)),
+            )
+        };
+
+        let luma_quant = scale_quant_table(&LUMA_QUANT_TABLE, quality);
+        let chroma_quant = scale_quant_table(&CHROMA_QUANT_TABLE, quality);
+
+        let mut output = vec![0xFF, MARKER_SOI];
+        write_segment(&mut output, MARKER_APP0, &[b'J', b'F', b'I', b'F', 0, 1, 1, 0, 0, 1, 0, 1, 0, 0]);
+
+        let mut dqt = vec![0];
+        dqt.extend(ZIGZAG.iter().map(|&index| luma_quant[index] as u8));
+        dqt.push(1);
+        dqt.extend(ZIGZAG.iter().map(|&index| chroma_quant[index] as u8));
+        write_segment(&mut output, MARKER_DQT, &dqt);
+
+        let mut sof = vec![8];
+        sof.extend_from_slice(&(height as u16).to_be_bytes());
+        sof.extend_from_slice(&(width as u16).to_be_bytes());
+        sof.push(components);
+        sof.extend_from_slice(&[1, ((h << 4) | v) as u8, 0]);
+        if components == 3 {
+            sof.extend_from_slice(&[2, 0x11, 1, 3, 0x11, 1]);
+        }
+        write_segment(&mut output, MARKER_SOF0, &sof);
+
+        let mut dht = vec![];
+        write_huffman_table(&mut dht, 0x00, &LUMA_DC_BITS, &LUMA_DC_VALUES);
+        write_huffman_table(&mut dht, 0x10, &LUMA_AC_BITS, &LUMA_AC_VALUES);
+        write_huffman_table(&mut dht, 0x01, &CHROMA_DC_BITS, &CHROMA_DC_VALUES);
+        write_huffman_table(&mut dht, 0x11, &CHROMA_AC_BITS, &CHROMA_AC_VALUES);
+        write_segment(&mut output, MARKER_DHT, &dht);
+
+        let mut sos = vec![components, 1, 0x00];
+        if components == 3 {
+            sos.extend_from_slice(&[2, 0x11, 3, 0x11]);
+        }
+        sos.extend_from_slice(&[0, 63, 0]);
+        write_segment(&mut output, MARKER_SOS, &sos);
+
+        let guard = data.map()?;
+        let planes = guard.try_planes()?;
+        let (chroma_width, chroma_height) = desc.format.calc_chroma_dimensions(width as u32, height as u32);
+
+        let mut sources = Vec::with_capacity(layout.len());
+        for (component, &(plane_index, offset, step)) in layout.iter().enumerate() {
+            let (plane_width, plane_height, lut) = if component == 0 {
+                (width, height, &luma_lut)
+            } else {
+                (chroma_width as usize, chroma_height as usize, &chroma_lut)
+            };
+            sources.push(SourcePlane {
+                data: planes.plane_data(plane_index).ok_or_else(|| invalid_error!("plane data"))?,
+                stride: planes.plane_stride(plane_index).ok_or_else(|| invalid_error!("plane stride"))?,
+                offset,
+                step,
+                width: plane_width,
+                height: plane_height,
+                lut,
+            });
+        }
+
+        let luma_dc = HuffmanEncoder::new(&LUMA_DC_BITS, &LUMA_DC_VALUES);
+        let luma_ac = HuffmanEncoder::new(&LUMA_AC_BITS, &LUMA_AC_VALUES);
+        let chroma_dc = HuffmanEncoder::new(&CHROMA_DC_BITS, &CHROMA_DC_VALUES);
+        let chroma_ac = HuffmanEncoder::new(&CHROMA_AC_BITS, &CHROMA_AC_VALUES);
+        let mut encoders = [
+            BlockEncoder {
+                quant_table: &luma_quant,
+                dc_encoder: &luma_dc,
+                ac_encoder: &luma_ac,
+                prev_dc: 0,
+            },
+            BlockEncoder {
+                quant_table: &chroma_quant,
+                dc_encoder: &chroma_dc,
+                ac_encoder: &chroma_ac,
+                prev_dc: 0,
+            },
+            BlockEncoder {
+                quant_table: &chroma_quant,
+                dc_encoder: &chroma_dc,
+                ac_encoder: &chroma_ac,
+                prev_dc: 0,
+            },
+        ];
+
+        let mut writer = BitWriter {
+            output,
+            buffer: 0,
+            count: 0,
+        };
+        let mcu_cols = width.div_ceil(8 * h);
+        let mcu_rows = height.div_ceil(8 * v);
+        for mcu_y in 0..mcu_rows {
+            for mcu_x in 0..mcu_cols {
+                for y in 0..v {
+                    for x in 0..h {
+                        encoders[0].encode(&mut writer, &sources[0].read_block(mcu_x * h + x, mcu_y * v + y));
+                    }
+                }
+                for (source, encoder) in sources.iter().zip(encoders.iter_mut()).skip(1) {
+                    encoder.encode(&mut writer, &source.read_block(mcu_x, mcu_y));
+                }
+            }
+        }
+        writer.flush();
+
+        let mut output = writer.output;
+        output.extend_from_slice(&[0xFF, MARKER_EOI]);
+        Ok(output)
+    }
+
+    fn decode_jpeg_internal(data: &[u8]) -> Result<Frame<'static>> {
+        let mut decoder = jpeg_decoder(data, ColorSpace::YCbCr);
+        decoder.decode_headers().map_err(|err| invalid_error!(err.to_string()))?;
+        let grayscale = decoder.input_colorspace() == Some(ColorSpace::Luma);
+        if grayscale {
+            decoder.set_options(decoder.options().jpeg_set_out_colorspace(ColorSpace::Luma));
+        }
+
+        let samples = decoder.decode().map_err(|err| invalid_error!(err.to_string()))?;
+        let (width, height) = decoder.dimensions().ok_or_else(|| invalid_error!("jpeg dimensions"))?;
+        let format = if grayscale {
+            PixelFormat::Y8
+        } else {
+            PixelFormat::I444
+        };
+
+        let mut desc = VideoFrameDescriptor::try_new(format, width as u32, height as u32)?.with_color_range(ColorRange::Full);
+        desc.color_matrix = ColorMatrix::SMPTE170M;
+        let mut frame = Frame::video_creator().create_with_descriptor(desc)?;
+        {
+            let mut guard = frame.map_mut()?;
+            let mut planes = guard.try_planes_mut()?;
+            let components = planes.len();
+            for plane_index in 0..components {
+                let stride = planes.plane_stride(plane_index).ok_or_else(|| invalid_error!("plane stride"))?;
+                let dst = planes.plane_data_mut(plane_index).ok_or_else(|| invalid_error!("plane data"))?;
+                for (src_row, dst_row) in samples.chunks_exact(width * components).zip(dst.chunks_mut(stride)) {
+                    for (value, pixel) in dst_row[..width].iter_mut().zip(src_row.chunks_exact(components)) {
+                        *value = pixel[plane_index];
+                    }
+                }
+            }
+        }
+
+        Ok(frame)
+    }
+
+    pub fn encode_jpeg(&self, quality: u8) -> Result<Vec<u8>> {
+        Self::encode_jpeg_converted(&self.desc, &self.data, quality)
+    }
+}
@@ -0,0 +1,150 @@
+use yuv::YuvStandardMatrix;
+
+use super::{
+    frame::VideoFrame,
+    video::{ColorMatrix, ColorRange, PixelFormat, VideoFrameDescriptor},
+};
+use crate::{
+    frame::{DataMappable, Frame, FrameData},
+    invalid_error, unsupported_error, FrameDescriptor, Result,
+};
+
+type Matrix = [[f32; 3]; 3];
+
+// Y'CbCr of the source matrix to R'G'B' to Y'CbCr of the target matrix
+fn calc_conversion_matrix(src: ColorMatrix, dst: ColorMatrix) -> Matrix {
+    let src = YuvStandardMatrix::from(src).get_kr_kb();
+    let (kr, kb) = (src.kr, src.kb);
+    let kg = 1.0 - kr - kb;
+    let to_rgb = [[1.0, 0.0, 2.0 * (1.0 - kr)], [1.0, -2.0 * kb * (1.0 - kb) / kg, -2.0 * kr * (1.0 - kr) / kg], [1.0, 2.0 * (1.0 - kb), 0.0]];
+
+    let dst = YuvStandardMatrix::from(dst).get_kr_kb();
+    let (kr, kb) = (dst.kr, dst.kb);
+    let kg = 1.0 - kr - kb;
+    let to_yuv = [[kr, kg, kb], [-kr / (2.0 * (1.0 - kb)), -kg / (2.0 * (1.0 - kb)), 0.5], [0.5, -kg / (2.0 * (1.0 - kr)), -kb / (2.0 * (1.0 - kr))]];
+
+    let mut matrix = [[0.0; 3]; 3];
+    for (row, to_yuv_row) in matrix.iter_mut().zip(to_yuv.iter()) {
+        for (col, value) in row.iter_mut().enumerate() {
+            *value = (0..3).map(|i| to_yuv_row[i] * to_rgb[i][col]).sum();
+        }
+    }
+
+    matrix
+}
+
+#[inline]
+fn clamp_u8(value: f32) -> u8 {
+    value.round().clamp(0.0, 255.0) as u8
+}
+
+impl Frame<'_> {
+    pub fn convert_matrix(&self, target: ColorMatrix) -> Result<Frame<'static>> {
+        let FrameDescriptor::Video(src_desc) = &self.desc else {
+            return Err(invalid_error!("not video frame"));
+        };
+
+        let mut dst_desc = src_desc.clone();
+        dst_desc.color_matrix = target;
+
+        let mut dst = Frame::video_creator().create_with_descriptor(dst_desc.clone())?;
+        VideoFrame::convert_matrix_internal(src_desc, &self.data, &dst_desc, &mut dst.data)?;
+
+        dst.copy_props_from(self);
+
+        Ok(dst)
+    }
+}
+
+impl VideoFrame<'_> {
+    pub(super) fn convert_matrix_internal(
+        src_desc: &VideoFrameDescriptor,
+        src_data: &FrameData,
+        dst_desc: &VideoFrameDescriptor,
+        dst_data: &mut FrameData,
+    ) -> Result<()> {
+        let format = src_desc.format;
+
+        if format != dst_desc.format {
+            return Err(invalid_error!("pixel format mismatch"));
+        }
+
+        // (U plane, U offset, V plane, V offset, chroma sample step)
+        let (u_plane, u_offset, v_plane, v_offset, step) = match format {
+            PixelFormat::I420 | PixelFormat::I422 | PixelFormat::I444 | PixelFormat::I440 => (1, 0, 2, 0, 1),
+            PixelFormat::YV12 | PixelFormat::YV16 | PixelFormat::YV24 => (2, 0, 1, 0, 1),
+            PixelFormat::NV12 | PixelFormat::NV16 | PixelFormat::NV24 => (1, 0, 1, 1, 2),
+            PixelFormat::NV21 | PixelFormat::NV61 | PixelFormat::NV42 => (1, 1, 1, 0, 2),
+            _ => return Err(unsupported_error!(format)),
+        };
+
+        let matrix = calc_conversion_matrix(src_desc.color_matrix, dst_desc.color_matrix);
+        // chroma offsets are expressed in luma code values
+        let luma_scale = if src_desc.color_range == ColorRange::Full {
+            1.0
+        } else {
+            219.0 / 224.0
+        };
+
+        let width = src_desc.width().get();
+        let height = src_desc.height().get();
+        let (chroma_width, chroma_height) = format.calc_chroma_dimensions(width, height);
+        let (chroma_x, chroma_y) = format.calc_chroma_dimensions(2, 2);
+        let (shift_x, shift_y) = ((chroma_x == 1) as usize, (chroma_y == 1) as usize);
+
+        let guard = src_data.map()?;
+        let mut dst_guard = dst_data.map_mut()?;
+        let src_planes = guard.try_planes()?;
+        let mut dst_planes = dst_guard.try_planes_mut()?;
+
+        let src_u = src_planes.plane_data(u_plane).ok_or_else(|| invalid_error!("plane data"))?;
+        let src_u_stride = src_planes.plane_stride(u_plane).ok_or_else(|| invalid_error!("plane stride"))?;
+        let src_v = src_planes.plane_data(v_plane).ok_or_else(|| invalid_error!("plane data"))?;
+        let src_v_stride = src_planes.plane_stride(v_plane).ok_or_else(|| invalid_error!("plane stride"))?;
+
+        let chroma_at = |x: usize, y: usize| -> (f32, f32) {
+            let cb = src_u[y * src_u_stride + x * step + u_offset] as f32 - 128.0;
+            let cr = src_v[y * src_v_stride + x * step + v_offset] as f32 - 128.0;
+            (cb, cr)
+        };
+
+        let src_y = src_planes.plane_data(0).ok_or_else(|| invalid_error!("plane data"))?;
+        let src_y_stride = src_planes.plane_stride(0).ok_or_else(|| invalid_error!("plane stride"))?;
+        let dst_y_stride = dst_planes.plane_stride(0).ok_or_else(|| invalid_error!("plane stride"))?;
+        let dst_y = dst_planes.plane_data_mut(0).ok_or_else(|| invalid_error!("plane data"))?;
+
+        for y in 0..height as usize {
+            for x in 0..width as usize {
+                let (cb, cr) = chroma_at(x >> shift_x, y >> shift_y);
+                let luma = src_y[y * src_y_stride + x] as f32 + (matrix[0][1] * cb + matrix[0][2] * cr) * luma_scale;
+                dst_y[y * dst_y_stride + x] = clamp_u8(luma);
+            }
+        }
+
+        // a neutral color stays neutral, so chroma does not depend on luma
+        for (plane, offset, row) in [(u_plane, u_offset, 1), (v_plane, v_offset, 2)] {
+            let dst_stride = dst_planes.plane_stride(plane).ok_or_else(|| invalid_error!("plane stride"))?;
+            let dst = dst_planes.plane_data_mut(plane).ok_or_else(|| invalid_error!("plane data"))?;
+
+            for y in 0..chroma_height as usize {
+                for x in 0..chroma_width as usize {
+                    let (cb, cr) = chroma_at(x, y);
+                    dst[y * dst_stride + x * step + offset] = clamp_u8(128.0 + matrix[row][1] * cb + matrix[row][2] * cr);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn convert_matrix(&self, target: ColorMatrix) -> Result<VideoFrame<'static>> {
+        let mut dst_desc = self.desc.clone();
+        dst_desc.color_matrix = target;
+
+        let mut dst = VideoFrame::new_with_descriptor(dst_desc.clone())?;
+        Self::convert_matrix_internal(&self.desc, &self.data, &dst_desc, &mut dst.data)?;
+        dst.copy_props_from(self);
+
+        Ok(dst)
+    }
+}
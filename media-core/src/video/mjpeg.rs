@@ -0,0 +1,105 @@
+use zune_jpeg::{
+    zune_core::{bytestream::ZCursor, colorspace::ColorSpace, options::DecoderOptions},
+    JpegDecoder,
+};
+
+use super::video::{ColorMatrix, ColorRange, CompressionFormat, PixelFormat, VideoFormat};
+use crate::{
+    frame::{DataMappable, Frame, FrameData},
+    invalid_error, unsupported_error,
+    variant::Variant,
+    FrameDescriptor, Result,
+};
+
+// larger pictures are rejected before the output is allocated
+const MAX_DECODE_DIMENSION: usize = 16384;
+
+// zune-jpeg in strict mode, so truncated or corrupt scans are errors rather
+// than pictures padded with gray
+pub(super) fn jpeg_decoder(data: &[u8], colorspace: ColorSpace) -> JpegDecoder<ZCursor<&[u8]>> {
+    let options = DecoderOptions::default()
+        .set_strict_mode(true)
+        .set_max_width(MAX_DECODE_DIMENSION)
+        .set_max_height(MAX_DECODE_DIMENSION)
+        .jpeg_set_out_colorspace(colorspace);
+    JpegDecoder::new_with_options(ZCursor::new(data), options)
+}
+
+impl Frame<'_> {
+    // decodes a compressed camera frame, a data frame holding {format, data},
+    // into an I420 or RGBA32 frame of the picture's dimensions
+    pub fn decode_mjpeg_to(&self, dst: &mut Frame) -> Result<()> {
+        let data = match (&self.desc, &self.data) {
+            (FrameDescriptor::Data(_), FrameData::Variant(variant)) => variant,
+            _ => return Err(invalid_error!("not data frame")),
+        };
+
+        if data["format"].get_uint32() != Some(VideoFormat::Compression(CompressionFormat::MJPEG).into()) {
+            return Err(invalid_error!("not mjpeg frame"));
+        }
+
+        let Variant::Buffer(buffer) = &data["data"] else {
+            return Err(invalid_error!("no mjpeg data"));
+        };
+
+        let FrameDescriptor::Video(dst_desc) = &mut dst.desc else {
+            return Err(invalid_error!("not video frame"));
+        };
+
+        // the decoder upsamples whatever chroma sampling the camera used
+        let colorspace = match dst_desc.format {
+            PixelFormat::I420 => ColorSpace::YCbCr,
+            PixelFormat::RGBA32 => ColorSpace::RGBA,
+            _ => return Err(unsupported_error!(dst_desc.format)),
+        };
+
+        let mut decoder = jpeg_decoder(buffer, colorspace);
+        decoder.decode_headers().map_err(|err| invalid_error!(err.to_string()))?;
+        let (width, height) = decoder.dimensions().ok_or_else(|| invalid_error!("jpeg dimensions"))?;
+        if (width as u32, height as u32) != (dst_desc.width().get(), dst_desc.height().get()) {
+            return Err(invalid_error!("video frame dimensions mismatch"));
+        }
+
+        let samples = decoder.decode().map_err(|err| invalid_error!(err.to_string()))?;
+        if dst_desc.format == PixelFormat::I420 {
+            dst_desc.color_range = ColorRange::Full;
+            dst_desc.color_matrix = ColorMatrix::SMPTE170M;
+        }
+
+        let mut guard = dst.data.map_mut()?;
+        let mut planes = guard.try_planes_mut()?;
+        if colorspace == ColorSpace::RGBA {
+            let stride = planes.plane_stride(0).ok_or_else(|| invalid_error!("plane stride"))?;
+            let plane = planes.plane_data_mut(0).ok_or_else(|| invalid_error!("plane data"))?;
+            for (src_row, dst_row) in samples.chunks_exact(width * 4).zip(plane.chunks_mut(stride)) {
+                dst_row[..width * 4].copy_from_slice(src_row);
+            }
+            return Ok(());
+        }
+
+        let stride = planes.plane_stride(0).ok_or_else(|| invalid_error!("plane stride"))?;
+        let plane = planes.plane_data_mut(0).ok_or_else(|| invalid_error!("plane data"))?;
+        for (src_row, dst_row) in samples.chunks_exact(width * 3).zip(plane.chunks_mut(stride)) {
+            for (value, pixel) in dst_row[..width].iter_mut().zip(src_row.chunks_exact(3)) {
+                *value = pixel[0];
+            }
+        }
+
+        // each chroma sample averages its 2x2 block, clamped at the edges
+        let samples = samples.as_slice();
+        for plane_index in 1..3 {
+            let stride = planes.plane_stride(plane_index).ok_or_else(|| invalid_error!("plane stride"))?;
+            let plane = planes.plane_data_mut(plane_index).ok_or_else(|| invalid_error!("plane data"))?;
+            for (chroma_y, dst_row) in plane.chunks_mut(stride).take(height.div_ceil(2)).enumerate() {
+                let rows = [chroma_y * 2, (chroma_y * 2 + 1).min(height - 1)];
+                for (chroma_x, value) in dst_row[..width.div_ceil(2)].iter_mut().enumerate() {
+                    let columns = [chroma_x * 2, (chroma_x * 2 + 1).min(width - 1)];
+                    let sum = rows.iter().flat_map(|y| columns.iter().map(move |x| samples[(y * width + x) * 3 + plane_index] as u32)).sum::<u32>();
+                    *value = ((sum + 2) / 4) as u8;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
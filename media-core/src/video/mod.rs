@@ -1,11 +1,34 @@
+mod alpha;
+#[cfg(feature = "ndarray")]
+mod array;
+mod blur;
 mod convert;
+mod crop;
+mod decimate;
+mod fill;
 mod frame;
+mod grid;
+#[cfg(feature = "jpeg")]
+mod jpeg;
+mod matrix;
+#[cfg(feature = "mjpeg")]
+mod mjpeg;
+#[cfg(feature = "image")]
+mod png;
+mod rotate;
 mod scale;
+mod stats;
+mod transfer;
+mod v4l2;
 #[allow(clippy::module_inception)]
 mod video;
 
 #[cfg(all(feature = "video", any(target_os = "macos", target_os = "ios")))]
 pub(crate) mod pixel_buffer;
 
+pub use convert::{ConversionMode, ConvertOptions, Converter};
+pub use crop::*;
 pub use frame::*;
+#[cfg(feature = "image")]
+pub use png::PngFrameSink;
 pub use video::*;
@@ -1,4 +1,8 @@
-use std::{borrow::Cow, num::NonZeroU32, sync::LazyLock};
+use std::{
+    borrow::Cow,
+    num::NonZeroU32,
+    sync::{Arc, LazyLock, Mutex},
+};
 
 use core_foundation::{base::*, boolean::*, dictionary::*, number::CFNumber, string::*};
 use core_video::{
@@ -23,7 +27,7 @@ use crate::{
     frame::*,
     invalid_param_error, none_param_error,
     video::{ColorMatrix, ColorPrimaries, ColorRange, ColorTransferCharacteristics, PixelFormat, VideoFrame, VideoFrameDescriptor},
-    Result,
+    FrameDescriptorSpec, Result,
 };
 
 const ITU_R_601_4: &str = "ITU_R_601_4";
@@ -60,10 +64,23 @@ static PIXEL_FORMATS: LazyLock<[[u32; ColorRange::COUNT]; PixelFormat::COUNT]> =
 });
 
 fn into_cv_format(format: PixelFormat, color_range: ColorRange) -> u32 {
-    PIXEL_FORMATS[format as usize][color_range as usize]
+    let Some(formats) = PIXEL_FORMATS.get(format as usize) else {
+        return 0;
+    };
+
+    match formats.get(color_range as usize).copied().unwrap_or(0) {
+        // fall back to the first color range supported by the format
+        0 => formats.iter().copied().find(|&f| f != 0).unwrap_or(0),
+        f => f,
+    }
 }
 
 fn from_cv_format(format: u32) -> (Option<PixelFormat>, ColorRange) {
+    // zero marks unsupported entries in the table
+    if format == 0 {
+        return (None, ColorRange::Unspecified);
+    }
+
     for (i, formats) in PIXEL_FORMATS.iter().enumerate() {
         for (j, &f) in formats.iter().enumerate() {
             if f == format {
@@ -398,6 +415,8 @@ impl VideoFrame<'_> {
     }
 
     pub fn new_pixel_buffer_with_descriptor(desc: VideoFrameDescriptor) -> Result<VideoFrame<'static>> {
+        desc.validate()?;
+
         let pixel_format = into_cv_format(desc.format, desc.color_range);
         #[cfg(target_os = "macos")]
         let compatibility_key: CFString = {
@@ -422,9 +441,9 @@ impl VideoFrame<'_> {
             (compatibility_key, CFBoolean::true_value().as_CFType()),
         ]);
 
-        let width = desc.width().get() - desc.crop_left - desc.crop_right;
-        let height = desc.height().get() - desc.crop_top - desc.crop_bottom;
-        let pixel_buffer = CVPixelBuffer::new(pixel_format, width as usize, height as usize, Some(&options))
+        // crop only marks the visible region, the buffer covers the full dimensions
+        // like memory frames do
+        let pixel_buffer = CVPixelBuffer::new(pixel_format, desc.width().get() as usize, desc.height().get() as usize, Some(&options))
             .map_err(|_| Error::CreationFailed(stringify!(CVPixelBuffer).into()))?;
 
         let buffer = pixel_buffer.as_buffer();
@@ -455,7 +474,7 @@ impl VideoFrame<'_> {
             buffer.set_attachment(&CVImageBufferKeys::GammaLevel.into(), &gamma.as_CFType(), kCVAttachmentMode_ShouldPropagate);
         }
 
-        Ok(Frame::from_data_with_generic_descriptor(desc, FrameData::PixelBuffer(PixelBuffer(pixel_buffer))))
+        Ok(Frame::from_data_with_generic_descriptor(desc, FrameData::PixelBuffer(PixelBuffer::new(pixel_buffer))))
     }
 
     pub fn from_pixel_buffer(pixel_buffer: &CVPixelBuffer) -> Result<Self> {
@@ -531,60 +550,120 @@ impl VideoFrame<'_> {
             }
         }
 
-        Ok(Frame::from_data_with_generic_descriptor(desc, FrameData::PixelBuffer(PixelBuffer(pixel_buffer.clone()))))
+        Ok(Frame::from_data_with_generic_descriptor(desc, FrameData::PixelBuffer(PixelBuffer::new(pixel_buffer.clone()))))
     }
 }
 
+impl<D: FrameDescriptorSpec> Frame<'_, D> {
+    // the backing pixel buffer, for passing frames to CoreVideo based APIs
+    pub fn pixel_buffer(&self) -> Option<&CVPixelBuffer> {
+        match &self.data {
+            FrameData::PixelBuffer(data) => Some(&data.buffer),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Default)]
+struct LockState {
+    depth: usize,
+    read_only: bool,
+}
+
 #[derive(Clone)]
-pub(crate) struct PixelBuffer(CVPixelBuffer);
+pub(crate) struct PixelBuffer {
+    buffer: CVPixelBuffer,
+    // nested maps share a single base address lock
+    lock_state: Arc<Mutex<LockState>>,
+}
 
 unsafe impl Send for PixelBuffer {}
 unsafe impl Sync for PixelBuffer {}
 
+impl PixelBuffer {
+    fn new(buffer: CVPixelBuffer) -> Self {
+        Self {
+            buffer,
+            lock_state: Arc::new(Mutex::new(LockState::default())),
+        }
+    }
+
+    fn lock(&self, read_only: bool) -> Result<()> {
+        let mut state = self.lock_state.lock().map_err(|_| failed_error!("lock state"))?;
+
+        if state.depth == 0 {
+            let flags = if read_only {
+                kCVPixelBufferLock_ReadOnly
+            } else {
+                0
+            };
+            if self.buffer.lock_base_address(flags) != kCVReturnSuccess {
+                return Err(failed_error!("lock base address"));
+            }
+            state.read_only = read_only;
+        } else if state.read_only && !read_only {
+            return Err(failed_error!("pixel buffer is locked read only"));
+        }
+
+        state.depth += 1;
+
+        Ok(())
+    }
+
+    fn unlock(&self) -> Result<()> {
+        let mut state = self.lock_state.lock().map_err(|_| failed_error!("lock state"))?;
+
+        if state.depth == 0 {
+            return Err(failed_error!("pixel buffer is not locked"));
+        }
+
+        state.depth -= 1;
+
+        if state.depth == 0 {
+            let flags = if state.read_only {
+                kCVPixelBufferLock_ReadOnly
+            } else {
+                0
+            };
+            if self.buffer.unlock_base_address(flags) != kCVReturnSuccess {
+                return Err(failed_error!("unlock base address"));
+            }
+        }
+
+        Ok(())
+    }
+}
+
 impl DataMappable for PixelBuffer {
     fn map(&self) -> Result<MappedGuard<'_>> {
-        if self.0.lock_base_address(kCVPixelBufferLock_ReadOnly) != kCVReturnSuccess {
-            return Err(failed_error!("lock base address"));
-        }
+        self.lock(true)?;
 
-        Ok(MappedGuard {
-            data_ref: DataRef::Immutable(self),
-        })
+        Ok(MappedGuard::new(DataRef::Immutable(self)))
     }
 
     fn map_mut(&mut self) -> Result<MappedGuard<'_>> {
-        if self.0.lock_base_address(0) != kCVReturnSuccess {
-            return Err(failed_error!("lock base address"));
-        }
+        self.lock(false)?;
 
-        Ok(MappedGuard {
-            data_ref: DataRef::Mutable(self),
-        })
+        Ok(MappedGuard::new(DataRef::Mutable(self)))
     }
 
     fn unmap(&self) -> Result<()> {
-        if self.0.unlock_base_address(kCVPixelBufferLock_ReadOnly) != kCVReturnSuccess {
-            return Err(failed_error!("unlock base address"));
-        }
-        Ok(())
+        self.unlock()
     }
 
     fn unmap_mut(&mut self) -> Result<()> {
-        if self.0.unlock_base_address(0) != kCVReturnSuccess {
-            return Err(failed_error!("unlock base address"));
-        }
-        Ok(())
+        self.unlock()
     }
 
     fn planes(&self) -> Option<MappedPlanes<'_>> {
         let mut planes = SmallVec::new();
 
-        if self.0.is_planar() {
-            let plane_count = self.0.get_plane_count();
+        if self.buffer.is_planar() {
+            let plane_count = self.buffer.get_plane_count();
             for i in 0..plane_count {
-                let base_address = unsafe { self.0.get_base_address_of_plane(i) as *const u8 };
-                let bytes_per_row = self.0.get_bytes_per_row_of_plane(i);
-                let height = self.0.get_height_of_plane(i);
+                let base_address = unsafe { self.buffer.get_base_address_of_plane(i) as *const u8 };
+                let bytes_per_row = self.buffer.get_bytes_per_row_of_plane(i);
+                let height = self.buffer.get_height_of_plane(i);
                 let slice = unsafe { std::slice::from_raw_parts(base_address, bytes_per_row * height) };
                 planes.push(MappedPlane::Video {
                     data: MappedData::Ref(slice),
@@ -593,9 +672,9 @@ impl DataMappable for PixelBuffer {
                 });
             }
         } else {
-            let base_address = unsafe { self.0.get_base_address() as *const u8 };
-            let bytes_per_row = self.0.get_bytes_per_row();
-            let height = self.0.get_height();
+            let base_address = unsafe { self.buffer.get_base_address() as *const u8 };
+            let bytes_per_row = self.buffer.get_bytes_per_row();
+            let height = self.buffer.get_height();
             let slice = unsafe { std::slice::from_raw_parts(base_address, bytes_per_row * height) };
             planes.push(MappedPlane::Video {
                 data: MappedData::Ref(slice),
@@ -612,29 +691,29 @@ impl DataMappable for PixelBuffer {
     fn planes_mut(&mut self) -> Option<MappedPlanes<'_>> {
         let mut planes = SmallVec::new();
 
-        if self.0.is_planar() {
-            let plane_count = self.0.get_plane_count();
+        if self.buffer.is_planar() {
+            let plane_count = self.buffer.get_plane_count();
             for i in 0..plane_count {
-                let base_address = unsafe { self.0.get_base_address_of_plane(i) as *mut u8 };
-                let bytes_per_row = self.0.get_bytes_per_row_of_plane(i);
-                let height = self.0.get_height_of_plane(i);
+                let base_address = unsafe { self.buffer.get_base_address_of_plane(i) as *mut u8 };
+                let bytes_per_row = self.buffer.get_bytes_per_row_of_plane(i);
+                let height = self.buffer.get_height_of_plane(i);
                 let slice = unsafe { std::slice::from_raw_parts_mut(base_address, bytes_per_row * height) };
-                planes[i] = MappedPlane::Video {
+                planes.push(MappedPlane::Video {
                     data: MappedData::RefMut(slice),
                     stride: bytes_per_row,
                     height: height as u32,
-                };
+                });
             }
         } else {
-            let base_address = unsafe { self.0.get_base_address() as *mut u8 };
-            let bytes_per_row = self.0.get_bytes_per_row();
-            let height = self.0.get_height();
+            let base_address = unsafe { self.buffer.get_base_address() as *mut u8 };
+            let bytes_per_row = self.buffer.get_bytes_per_row();
+            let height = self.buffer.get_height();
             let slice = unsafe { std::slice::from_raw_parts_mut(base_address, bytes_per_row * height) };
-            planes[0] = MappedPlane::Video {
+            planes.push(MappedPlane::Video {
                 data: MappedData::RefMut(slice),
                 stride: bytes_per_row,
                 height: height as u32,
-            };
+            });
         }
 
         Some(MappedPlanes {
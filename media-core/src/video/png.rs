@@ -0,0 +1,115 @@
+use std::{
+    fs,
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+use super::{
+    frame::VideoFrame,
+    video::{PixelFormat, VideoFrameDescriptor},
+};
+use crate::{
+    failed_error,
+    frame::{DataMappable, Frame, FrameData},
+    invalid_error, unsupported_error, FrameDescriptor, Result,
+};
+
+impl Frame<'_> {
+    pub fn dump_png<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let FrameDescriptor::Video(desc) = &self.desc else {
+            return Err(invalid_error!("not video frame"));
+        };
+
+        let data = if desc.format == PixelFormat::RGBA32 {
+            VideoFrame::encode_png_internal(desc, &self.data)?
+        } else {
+            let mut rgba_desc = desc.clone();
+            rgba_desc.format = PixelFormat::RGBA32;
+            let mut rgba_frame = Frame::video_creator().create_with_descriptor(rgba_desc.clone())?;
+            self.convert_video_to(&mut rgba_frame)?;
+            VideoFrame::encode_png_internal(&rgba_desc, &rgba_frame.data)?
+        };
+
+        fs::write(path, data).map_err(|err| failed_error!(err.to_string()))
+    }
+}
+
+impl VideoFrame<'_> {
+    fn encode_png_internal(desc: &VideoFrameDescriptor, data: &FrameData) -> Result<Vec<u8>> {
+        if desc.format != PixelFormat::RGBA32 {
+            return Err(unsupported_error!(desc.format));
+        }
+
+        let width = desc.width().get();
+        let height = desc.height().get();
+        let row_bytes = width as usize * 4;
+
+        let guard = data.map()?;
+        let planes = guard.try_planes()?;
+        let stride = planes.plane_stride(0).ok_or_else(|| invalid_error!("plane stride"))?;
+        let src = planes.plane_data(0).ok_or_else(|| invalid_error!("plane data"))?;
+
+        let mut output = Vec::new();
+        let mut encoder = png::Encoder::new(&mut output, width, height);
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_depth(png::BitDepth::Eight);
+        // fast and good enough for debugging
+        encoder.set_compression(png::Compression::Fast);
+
+        let mut writer = encoder.write_header().map_err(|err| failed_error!(err.to_string()))?;
+        let mut stream = writer.stream_writer().map_err(|err| failed_error!(err.to_string()))?;
+        for row in src.chunks(stride).take(height as usize) {
+            stream.write_all(&row[..row_bytes]).map_err(|err| failed_error!(err.to_string()))?;
+        }
+        stream.finish().map_err(|err| failed_error!(err.to_string()))?;
+        writer.finish().map_err(|err| failed_error!(err.to_string()))?;
+
+        Ok(output)
+    }
+
+    pub fn dump_png<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let data = if self.desc.format == PixelFormat::RGBA32 {
+            Self::encode_png_internal(&self.desc, &self.data)?
+        } else {
+            let mut rgba_desc = self.desc.clone();
+            rgba_desc.format = PixelFormat::RGBA32;
+            let mut rgba_frame = VideoFrame::new_with_descriptor(rgba_desc.clone())?;
+            self.convert_to(&mut rgba_frame)?;
+            Self::encode_png_internal(&rgba_desc, &rgba_frame.data)?
+        };
+
+        fs::write(path, data).map_err(|err| failed_error!(err.to_string()))
+    }
+}
+
+// Writes frames as `<prefix>000000.png`, `<prefix>000001.png`, ... into a
+// directory
+pub struct PngFrameSink {
+    dir: PathBuf,
+    prefix: String,
+    index: u64,
+}
+
+impl PngFrameSink {
+    pub fn new<P: AsRef<Path>>(dir: P, prefix: &str) -> Result<Self> {
+        fs::create_dir_all(dir.as_ref()).map_err(|err| failed_error!(err.to_string()))?;
+
+        Ok(Self {
+            dir: dir.as_ref().to_path_buf(),
+            prefix: prefix.to_string(),
+            index: 0,
+        })
+    }
+
+    pub fn write(&mut self, frame: &Frame) -> Result<PathBuf> {
+        let path = self.dir.join(format!("{}{:06}.png", self.prefix, self.index));
+        frame.dump_png(&path)?;
+        self.index += 1;
+
+        Ok(path)
+    }
+
+    pub fn count(&self) -> u64 {
+        self.index
+    }
+}
@@ -0,0 +1,161 @@
+use super::{
+    frame::VideoFrame,
+    video::{Dimensions, PixelFormat, Rotation, VideoFrameDescriptor},
+};
+use crate::{
+    frame::{DataMappable, Frame, FrameData},
+    invalid_error, unsupported_error, FrameDescriptor, Result,
+};
+
+fn check_rotation(format: PixelFormat, rotation: Rotation) -> Result<()> {
+    // packed YUV macropixels and high bit depth samples are not handled
+    if format.depth() != 8 || (format.is_packed() && format.is_yuv()) || !(format.is_planar() || format.is_biplanar() || format.is_packed()) {
+        return Err(unsupported_error!(format));
+    }
+
+    // 4:2:2 chroma would have to be resampled to 4:4:0 when turned sideways
+    let (chroma_width, chroma_height) = format.calc_chroma_dimensions(2, 2);
+    if matches!(rotation, Rotation::Rotation90 | Rotation::Rotation270) && chroma_width != chroma_height {
+        return Err(unsupported_error!(format));
+    }
+
+    Ok(())
+}
+
+// swaps the dimensions for quarter turns and moves the crop with the picture
+fn rotated_descriptor(desc: &VideoFrameDescriptor, rotation: Rotation) -> Result<VideoFrameDescriptor> {
+    let mut dst_desc = desc.clone();
+    let (left, top, right, bottom) = (desc.crop_left, desc.crop_top, desc.crop_right, desc.crop_bottom);
+    let (width, height) = (desc.width().get(), desc.height().get());
+
+    match rotation {
+        Rotation::None => {}
+        Rotation::Rotation90 => {
+            dst_desc.dimensions = Dimensions::new(height, width)?;
+            (dst_desc.crop_left, dst_desc.crop_top, dst_desc.crop_right, dst_desc.crop_bottom) = (bottom, left, top, right);
+        }
+        Rotation::Rotation180 => {
+            (dst_desc.crop_left, dst_desc.crop_top, dst_desc.crop_right, dst_desc.crop_bottom) = (right, bottom, left, top);
+        }
+        Rotation::Rotation270 => {
+            dst_desc.dimensions = Dimensions::new(height, width)?;
+            (dst_desc.crop_left, dst_desc.crop_top, dst_desc.crop_right, dst_desc.crop_bottom) = (top, right, bottom, left);
+        }
+    }
+    dst_desc.rotation = Rotation::None;
+
+    Ok(dst_desc)
+}
+
+impl Frame<'_> {
+    // rotates clockwise into dst, whose dimensions are swapped for 90 and 270
+    pub fn rotate_to(&self, dst: &mut Frame, rotation: Rotation) -> Result<()> {
+        let FrameDescriptor::Video(src_desc) = &self.desc else {
+            return Err(invalid_error!("not video frame"));
+        };
+
+        let FrameDescriptor::Video(dst_desc) = &dst.desc else {
+            return Err(invalid_error!("not video frame"));
+        };
+
+        VideoFrame::rotate_internal(src_desc, &self.data, dst_desc, &mut dst.data, rotation)
+    }
+
+    // returns an upright copy of the frame, applying the descriptor's rotation
+    pub fn apply_rotation(&self) -> Result<Frame<'static>> {
+        let FrameDescriptor::Video(src_desc) = &self.desc else {
+            return Err(invalid_error!("not video frame"));
+        };
+
+        check_rotation(src_desc.format, src_desc.rotation)?;
+
+        let dst_desc = rotated_descriptor(src_desc, src_desc.rotation)?;
+        let mut dst = Frame::video_creator().create_with_descriptor(dst_desc.clone())?;
+        VideoFrame::rotate_internal(src_desc, &self.data, &dst_desc, &mut dst.data, src_desc.rotation)?;
+
+        dst.copy_props_from(self);
+
+        Ok(dst)
+    }
+}
+
+impl VideoFrame<'_> {
+    fn rotate_internal(
+        src_desc: &VideoFrameDescriptor,
+        src_data: &FrameData,
+        dst_desc: &VideoFrameDescriptor,
+        dst_data: &mut FrameData,
+        rotation: Rotation,
+    ) -> Result<()> {
+        let format = src_desc.format;
+
+        if format != dst_desc.format {
+            return Err(invalid_error!("pixel format mismatch"));
+        }
+
+        check_rotation(format, rotation)?;
+
+        let src_width = src_desc.width().get();
+        let src_height = src_desc.height().get();
+        let expected = match rotation {
+            Rotation::None | Rotation::Rotation180 => (src_width, src_height),
+            Rotation::Rotation90 | Rotation::Rotation270 => (src_height, src_width),
+        };
+
+        if (dst_desc.width().get(), dst_desc.height().get()) != expected {
+            return Err(invalid_error!("dimensions mismatch"));
+        }
+
+        let guard = src_data.map()?;
+        let mut dst_guard = dst_data.map_mut()?;
+        let src_planes = guard.try_planes()?;
+        let mut dst_planes = dst_guard.try_planes_mut()?;
+
+        if src_planes.len() != dst_planes.len() {
+            return Err(invalid_error!("planes size mismatch"));
+        }
+
+        for plane_index in 0..src_planes.len() {
+            // interleaved UV pairs and RGBA pixels move as one unit
+            let unit = format.calc_plane_row_bytes(plane_index, 1) as usize;
+            let width = format.calc_plane_row_bytes(plane_index, src_width) as usize / unit;
+            let height = format.calc_plane_height(plane_index, src_height) as usize;
+
+            let src_stride = src_planes.plane_stride(plane_index).ok_or_else(|| invalid_error!("plane stride"))?;
+            let dst_stride = dst_planes.plane_stride(plane_index).ok_or_else(|| invalid_error!("plane stride"))?;
+            let src = src_planes.plane_data(plane_index).ok_or_else(|| invalid_error!("plane data"))?;
+            let dst = dst_planes.plane_data_mut(plane_index).ok_or_else(|| invalid_error!("plane data"))?;
+
+            for y in 0..height {
+                let src_row = &src[y * src_stride..y * src_stride + width * unit];
+                for (x, pixel) in src_row.chunks_exact(unit).enumerate() {
+                    let (dst_x, dst_y) = match rotation {
+                        Rotation::None => (x, y),
+                        Rotation::Rotation90 => (height - 1 - y, x),
+                        Rotation::Rotation180 => (width - 1 - x, height - 1 - y),
+                        Rotation::Rotation270 => (y, width - 1 - x),
+                    };
+                    let dst_start = dst_y * dst_stride + dst_x * unit;
+                    dst[dst_start..dst_start + unit].copy_from_slice(pixel);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn rotate_to(&self, dst: &mut VideoFrame, rotation: Rotation) -> Result<()> {
+        Self::rotate_internal(&self.desc, &self.data, &dst.desc, &mut dst.data, rotation)
+    }
+
+    pub fn apply_rotation(&self) -> Result<VideoFrame<'static>> {
+        check_rotation(self.desc.format, self.desc.rotation)?;
+
+        let dst_desc = rotated_descriptor(&self.desc, self.desc.rotation)?;
+        let mut dst = VideoFrame::new_with_descriptor(dst_desc.clone())?;
+        Self::rotate_internal(&self.desc, &self.data, &dst_desc, &mut dst.data, self.desc.rotation)?;
+        dst.copy_props_from(self);
+
+        Ok(dst)
+    }
+}
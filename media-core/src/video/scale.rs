@@ -1,15 +1,18 @@
 use std::{borrow::Cow, fmt::Debug};
 
 use bytemuck::{self, Pod};
-use pic_scale::{BufferStore, ImageStore, ImageStoreMut, LinearScaler, ResamplingFunction, Scaling, ScalingU16};
+use pic_scale::{
+    BufferStore, ImageStore, ImageStoreMut, LinearScaler, PicScaleError, ResamplingFunction, Scaler, Scaling, ScalingU16, TransferFunction,
+    WorkloadStrategy,
+};
 
 use super::{
     frame::VideoFrame,
-    video::{PixelFormat, ScaleFilter, VideoFrameDescriptor},
+    video::{ColorTransferCharacteristics, Dimensions, PixelFormat, ScaleFilter, VideoFrameDescriptor},
 };
 use crate::{
     frame::{DataMappable, Frame, FrameData, MappedPlane},
-    invalid_error, FrameDescriptor, Result,
+    invalid_error, unsupported_error, FrameDescriptor, Result,
 };
 
 impl From<ScaleFilter> for ResamplingFunction {
@@ -17,39 +20,122 @@ impl From<ScaleFilter> for ResamplingFunction {
         match filter {
             ScaleFilter::Nearest => ResamplingFunction::Nearest,
             ScaleFilter::Bilinear => ResamplingFunction::Bilinear,
-            ScaleFilter::Bicubic => ResamplingFunction::Bicubic,
+            // the same a = -0.5 kernel, pic-scale's Bicubic evaluates its outer lobe wrongly
+            ScaleFilter::Bicubic => ResamplingFunction::CatmullRom,
         }
     }
 }
 
+// unspecified content is assumed to be sRGB
+impl From<ColorTransferCharacteristics> for TransferFunction {
+    fn from(transfer: ColorTransferCharacteristics) -> Self {
+        match transfer {
+            ColorTransferCharacteristics::BT709 |
+            ColorTransferCharacteristics::SMPTE170M |
+            ColorTransferCharacteristics::IEC61966_2_4 |
+            ColorTransferCharacteristics::BT2020_10 |
+            ColorTransferCharacteristics::BT2020_12 => TransferFunction::Rec709,
+            ColorTransferCharacteristics::BT470M => TransferFunction::Gamma2p2,
+            ColorTransferCharacteristics::BT470BG => TransferFunction::Gamma2p8,
+            ColorTransferCharacteristics::SMPTE240M => TransferFunction::Smpte240,
+            ColorTransferCharacteristics::Linear => TransferFunction::Linear,
+            ColorTransferCharacteristics::Log => TransferFunction::Log100,
+            ColorTransferCharacteristics::LogSqrt => TransferFunction::Log100Sqrt10,
+            ColorTransferCharacteristics::BT1361E => TransferFunction::Bt1361,
+            ColorTransferCharacteristics::SMPTE2084 => TransferFunction::Pq,
+            ColorTransferCharacteristics::SMPTE428 => TransferFunction::Smpte428,
+            ColorTransferCharacteristics::ARIB_STD_B67 => TransferFunction::Hlg,
+            ColorTransferCharacteristics::Reserved | ColorTransferCharacteristics::Unspecified | ColorTransferCharacteristics::IEC61966_2_1 => {
+                TransferFunction::Srgb
+            }
+        }
+    }
+}
+
+// the scaler reads and writes tightly packed rows, so padded planes are
+// copied into and out of packed buffers
 fn into_image_store<'a, T, const N: usize>(src: &'a MappedPlane, width: u32, height: u32) -> Result<ImageStore<'a, T, N>>
 where
     T: Debug + Pod,
 {
+    let data = src.data().ok_or_else(|| invalid_error!("plane data"))?;
+    let stride = src.stride().ok_or_else(|| invalid_error!("plane stride"))?;
+    let row_bytes = width as usize * N * size_of::<T>();
+
+    if stride < row_bytes || data.len() < stride * (height as usize - 1) + row_bytes {
+        return Err(invalid_error!("plane size"));
+    }
+
+    let buffer = if stride == row_bytes {
+        Cow::Borrowed(bytemuck::cast_slice(&data[..row_bytes * height as usize]))
+    } else {
+        Cow::Owned(data.chunks(stride).take(height as usize).flat_map(|row| bytemuck::cast_slice(&row[..row_bytes]).iter().copied()).collect())
+    };
+
     Ok(ImageStore::<T, N> {
-        buffer: Cow::Borrowed(bytemuck::cast_slice(src.data().unwrap())),
+        buffer,
         channels: N,
         width: width as usize,
         height: height as usize,
-        stride: src.stride().unwrap() / size_of::<T>(),
-        bit_depth: 0,
+        stride: width as usize * N,
+        bit_depth: size_of::<T>() * 8,
     })
 }
 
-fn into_image_store_mut<'a, T, const N: usize>(dst: &'a mut MappedPlane, width: u32, height: u32) -> Result<ImageStoreMut<'a, T, N>>
+#[allow(clippy::too_many_arguments)]
+fn scale_plane<S, T, const N: usize, F>(
+    scaler: &S,
+    src: &MappedPlane,
+    src_width: u32,
+    src_height: u32,
+    dst: &mut MappedPlane,
+    dst_width: u32,
+    dst_height: u32,
+    resize: F,
+) -> Result<()>
 where
     T: Debug + Pod,
+    F: for<'a> FnOnce(&'a S, &ImageStore<'a, T, N>, &mut ImageStoreMut<'a, T, N>) -> std::result::Result<(), PicScaleError>,
 {
-    let stride = dst.stride().unwrap() / size_of::<T>();
+    let src = into_image_store::<T, N>(src, src_width, src_height)?;
+    let stride = dst.stride().ok_or_else(|| invalid_error!("plane stride"))?;
+    let data = dst.data_mut().ok_or_else(|| invalid_error!("plane data"))?;
+    let row_bytes = dst_width as usize * N * size_of::<T>();
+    let height = dst_height as usize;
 
-    Ok(ImageStoreMut::<T, N> {
-        buffer: BufferStore::Borrowed(bytemuck::cast_slice_mut(dst.data_mut().unwrap())),
-        channels: N,
-        width: width as usize,
-        height: height as usize,
-        stride,
-        bit_depth: 0,
-    })
+    if stride < row_bytes || data.len() < stride * (height - 1) + row_bytes {
+        return Err(invalid_error!("plane size"));
+    }
+
+    let mut packed = if stride == row_bytes {
+        None
+    } else {
+        Some(vec![T::zeroed(); dst_width as usize * N * height])
+    };
+
+    {
+        let buffer = match packed.as_mut() {
+            Some(packed) => packed.as_mut_slice(),
+            None => bytemuck::cast_slice_mut(&mut data[..row_bytes * height]),
+        };
+        let mut dst = ImageStoreMut::<T, N> {
+            buffer: BufferStore::Borrowed(buffer),
+            channels: N,
+            width: dst_width as usize,
+            height,
+            stride: dst_width as usize * N,
+            bit_depth: size_of::<T>() * 8,
+        };
+        resize(scaler, &src, &mut dst).map_err(|e| invalid_error!(e.to_string()))?;
+    }
+
+    if let Some(packed) = packed {
+        for (dst_row, src_row) in data.chunks_mut(stride).zip(bytemuck::cast_slice::<T, u8>(&packed).chunks(row_bytes)) {
+            dst_row[..row_bytes].copy_from_slice(src_row);
+        }
+    }
+
+    Ok(())
 }
 
 impl Frame<'_> {
@@ -60,6 +146,41 @@ impl Frame<'_> {
 
         VideoFrame::scale_to_internal(src_desc, &self.data, dst_desc, &mut dst.data, scale_filter)
     }
+
+    pub fn scale_to_linear(&self, dst: &mut Frame<'_>, scale_filter: ScaleFilter) -> Result<()> {
+        let (FrameDescriptor::Video(src_desc), FrameDescriptor::Video(dst_desc)) = (&self.desc, &dst.desc) else {
+            return Err(invalid_error!("not video frame"));
+        };
+
+        VideoFrame::scale_to_linear_internal(src_desc, &self.data, dst_desc, &mut dst.data, scale_filter)
+    }
+
+    pub fn scale_fit_to(&self, dst: &mut Frame<'_>, scale_filter: ScaleFilter, background: [u8; 3]) -> Result<()> {
+        let (FrameDescriptor::Video(src_desc), FrameDescriptor::Video(dst_desc)) = (&self.desc, &dst.desc) else {
+            return Err(invalid_error!("not video frame"));
+        };
+
+        VideoFrame::scale_fit_to_internal(src_desc, &self.data, dst_desc, &mut dst.data, scale_filter, background)
+    }
+}
+
+// largest size with the source aspect ratio that fits the destination, and its
+// centered offset, both on whole chroma samples
+fn fit_rect(format: PixelFormat, src_width: u32, src_height: u32, dst_width: u32, dst_height: u32) -> (u32, u32, u32, u32) {
+    let (chroma_width, chroma_height) = format.calc_chroma_dimensions(4, 4);
+    let (align_x, align_y) = (4 / chroma_width, 4 / chroma_height);
+
+    let (width, height) = if src_width as u64 * dst_height as u64 > src_height as u64 * dst_width as u64 {
+        (dst_width, ((src_height as u64 * dst_width as u64 + src_width as u64 / 2) / src_width as u64) as u32)
+    } else {
+        (((src_width as u64 * dst_height as u64 + src_height as u64 / 2) / src_height as u64) as u32, dst_height)
+    };
+
+    let align = |value: u32, align: u32, max: u32| (value / align * align).max(align).min(max);
+    let width = align(width, align_x, dst_width);
+    let height = align(height, align_y, dst_height);
+
+    (width, height, (dst_width - width) / 2 / align_x * align_x, (dst_height - height) / 2 / align_y * align_y)
 }
 
 impl VideoFrame<'_> {
@@ -69,6 +190,35 @@ impl VideoFrame<'_> {
         dst_desc: &VideoFrameDescriptor,
         dst_data: &mut FrameData,
         scale_filter: ScaleFilter,
+    ) -> Result<()> {
+        // the default speed strategy is biased towards brighter values
+        let mut scaler = Scaler::new(scale_filter.into());
+        scaler.set_workload_strategy(WorkloadStrategy::PreferQuality);
+        Self::scale_with(&scaler, src_desc, src_data, dst_desc, dst_data)
+    }
+
+    // only RGB samples are light intensities that can be linearized
+    fn scale_to_linear_internal(
+        src_desc: &VideoFrameDescriptor,
+        src_data: &FrameData,
+        dst_desc: &VideoFrameDescriptor,
+        dst_data: &mut FrameData,
+        scale_filter: ScaleFilter,
+    ) -> Result<()> {
+        if !src_desc.format.is_rgb() {
+            return Err(unsupported_error!(src_desc.format));
+        }
+
+        let transfer_function = src_desc.color_transfer_characteristics.into();
+        Self::scale_with(&LinearScaler::new_with_transfer(scale_filter.into(), transfer_function), src_desc, src_data, dst_desc, dst_data)
+    }
+
+    fn scale_with<S: Scaling + ScalingU16>(
+        scaler: &S,
+        src_desc: &VideoFrameDescriptor,
+        src_data: &FrameData,
+        dst_desc: &VideoFrameDescriptor,
+        dst_data: &mut FrameData,
     ) -> Result<()> {
         if src_desc.format != dst_desc.format {
             return Err(invalid_error!("pixel format mismatch"));
@@ -76,23 +226,27 @@ impl VideoFrame<'_> {
 
         let guard = src_data.map()?;
         let mut dst_guard = dst_data.map_mut()?;
-        let src_planes = guard.planes().unwrap();
-        let mut dst_planes = dst_guard.planes_mut().unwrap();
-
-        let resampling_function: ResamplingFunction = scale_filter.into();
-        let scaler = LinearScaler::new(resampling_function);
+        let src_planes = guard.try_planes()?;
+        let mut dst_planes = dst_guard.try_planes_mut()?;
 
         let format = src_desc.format;
+        let (src_width, src_height) = (src_desc.width().get(), src_desc.height().get());
+        let (dst_width, dst_height) = (dst_desc.width().get(), dst_desc.height().get());
+        let (src_chroma_width, src_chroma_height) = format.calc_chroma_dimensions(src_width, src_height);
+        let (dst_chroma_width, dst_chroma_height) = format.calc_chroma_dimensions(dst_width, dst_height);
+        let src = &src_planes.planes;
+        let dst = &mut dst_planes.planes;
+
         match format {
             PixelFormat::ARGB32 | PixelFormat::BGRA32 | PixelFormat::ABGR32 | PixelFormat::RGBA32 => {
-                let src = into_image_store::<u8, 4>(&src_planes.planes[0], src_desc.width().get(), src_desc.height().get())?;
-                let mut dst = into_image_store_mut::<u8, 4>(&mut dst_planes.planes[0], dst_desc.width().get(), dst_desc.height().get())?;
-                scaler.resize_rgba(&src, &mut dst, true).map_err(|e| invalid_error!(e.to_string()))
+                scale_plane::<_, u8, 4, _>(scaler, &src[0], src_width, src_height, &mut dst[0], dst_width, dst_height, |scaler, src, dst| {
+                    scaler.resize_rgba(src, dst, true)
+                })
             }
             PixelFormat::RGB24 | PixelFormat::BGR24 => {
-                let src = into_image_store::<u8, 3>(&src_planes.planes[0], src_desc.width().get(), src_desc.height().get())?;
-                let mut dst = into_image_store_mut::<u8, 3>(&mut dst_planes.planes[0], dst_desc.width().get(), dst_desc.height().get())?;
-                scaler.resize_rgb(&src, &mut dst).map_err(|e| invalid_error!(e.to_string()))
+                scale_plane::<_, u8, 3, _>(scaler, &src[0], src_width, src_height, &mut dst[0], dst_width, dst_height, |scaler, src, dst| {
+                    scaler.resize_rgb(src, dst)
+                })
             }
             PixelFormat::I420 |
             PixelFormat::I422 |
@@ -101,34 +255,43 @@ impl VideoFrame<'_> {
             PixelFormat::YV12 |
             PixelFormat::YV16 |
             PixelFormat::YV24 => {
-                let (src_chroma_width, src_chroma_height) = format.calc_chroma_dimensions(src_desc.width().get(), src_desc.height().get());
-                let (dst_chroma_width, dst_chroma_height) = format.calc_chroma_dimensions(dst_desc.width().get(), dst_desc.height().get());
-                let src_y = into_image_store::<u8, 1>(&src_planes.planes[0], src_desc.width().get(), src_desc.height().get())?;
-                let src_u = into_image_store::<u8, 1>(&src_planes.planes[1], src_chroma_width, src_chroma_height)?;
-                let src_v = into_image_store::<u8, 1>(&src_planes.planes[2], src_chroma_width, src_chroma_height)?;
-                let mut dst_y = into_image_store_mut::<u8, 1>(&mut dst_planes.planes[0], dst_desc.width().get(), dst_desc.height().get())?;
-                scaler.resize_plane(&src_y, &mut dst_y).map_err(|e| invalid_error!(e.to_string()))?;
-                let mut dst_u = into_image_store_mut::<u8, 1>(&mut dst_planes.planes[1], dst_chroma_width, dst_chroma_height)?;
-                scaler.resize_plane(&src_u, &mut dst_u).map_err(|e| invalid_error!(e.to_string()))?;
-                let mut dst_v = into_image_store_mut::<u8, 1>(&mut dst_planes.planes[2], dst_chroma_width, dst_chroma_height)?;
-                scaler.resize_plane(&src_v, &mut dst_v).map_err(|e| invalid_error!(e.to_string()))?;
+                // each plane is scaled on its own, chroma at its subsampled size
+                scale_plane::<_, u8, 1, _>(scaler, &src[0], src_width, src_height, &mut dst[0], dst_width, dst_height, |scaler, src, dst| {
+                    scaler.resize_plane(src, dst)
+                })?;
+                for i in 1..3 {
+                    scale_plane::<_, u8, 1, _>(
+                        scaler,
+                        &src[i],
+                        src_chroma_width,
+                        src_chroma_height,
+                        &mut dst[i],
+                        dst_chroma_width,
+                        dst_chroma_height,
+                        |scaler, src, dst| scaler.resize_plane(src, dst),
+                    )?;
+                }
                 Ok(())
             }
             PixelFormat::NV12 | PixelFormat::NV21 | PixelFormat::NV16 | PixelFormat::NV61 | PixelFormat::NV24 | PixelFormat::NV42 => {
-                let (src_chroma_width, src_chroma_height) = format.calc_chroma_dimensions(src_desc.width().get(), src_desc.height().get());
-                let (dst_chroma_width, dst_chroma_height) = format.calc_chroma_dimensions(dst_desc.width().get(), dst_desc.height().get());
-                let src_y = into_image_store::<u8, 1>(&src_planes.planes[0], src_desc.width().get(), src_desc.height().get())?;
-                let src_uv = into_image_store::<u8, 2>(&src_planes.planes[1], src_chroma_width, src_chroma_height)?;
-                let mut dst_y = into_image_store_mut::<u8, 1>(&mut dst_planes.planes[0], dst_desc.width().get(), dst_desc.height().get())?;
-                scaler.resize_plane(&src_y, &mut dst_y).map_err(|e| invalid_error!(e.to_string()))?;
-                let mut dst_uv = into_image_store_mut::<u8, 2>(&mut dst_planes.planes[1], dst_chroma_width, dst_chroma_height)?;
-                scaler.resize_cbcr8(&src_uv, &mut dst_uv).map_err(|e| invalid_error!(e.to_string()))?;
-                Ok(())
+                scale_plane::<_, u8, 1, _>(scaler, &src[0], src_width, src_height, &mut dst[0], dst_width, dst_height, |scaler, src, dst| {
+                    scaler.resize_plane(src, dst)
+                })?;
+                scale_plane::<_, u8, 2, _>(
+                    scaler,
+                    &src[1],
+                    src_chroma_width,
+                    src_chroma_height,
+                    &mut dst[1],
+                    dst_chroma_width,
+                    dst_chroma_height,
+                    |scaler, src, dst| scaler.resize_cbcr8(src, dst),
+                )
             }
             PixelFormat::ARGB64 | PixelFormat::BGRA64 | PixelFormat::ABGR64 | PixelFormat::RGBA64 => {
-                let src = into_image_store::<u16, 4>(&src_planes.planes[0], src_desc.width().get(), src_desc.height().get())?;
-                let mut dst = into_image_store_mut::<u16, 4>(&mut dst_planes.planes[0], dst_desc.width().get(), dst_desc.height().get())?;
-                scaler.resize_rgba_u16(&src, &mut dst, true).map_err(|e| invalid_error!(e.to_string()))
+                scale_plane::<_, u16, 4, _>(scaler, &src[0], src_width, src_height, &mut dst[0], dst_width, dst_height, |scaler, src, dst| {
+                    scaler.resize_rgba_u16(src, dst, true)
+                })
             }
             PixelFormat::I010 |
             PixelFormat::I210 |
@@ -142,17 +305,21 @@ impl VideoFrame<'_> {
             PixelFormat::I216 |
             PixelFormat::I416 |
             PixelFormat::I44016 => {
-                let (src_chroma_width, src_chroma_height) = format.calc_chroma_dimensions(src_desc.width().get(), src_desc.height().get());
-                let (dst_chroma_width, dst_chroma_height) = format.calc_chroma_dimensions(dst_desc.width().get(), dst_desc.height().get());
-                let src_y = into_image_store::<u16, 1>(&src_planes.planes[0], src_desc.width().get(), src_desc.height().get())?;
-                let src_u = into_image_store::<u16, 1>(&src_planes.planes[1], src_chroma_width, src_chroma_height)?;
-                let src_v = into_image_store::<u16, 1>(&src_planes.planes[2], src_chroma_width, src_chroma_height)?;
-                let mut dst_y = into_image_store_mut::<u16, 1>(&mut dst_planes.planes[0], dst_desc.width().get(), dst_desc.height().get())?;
-                scaler.resize_plane_u16(&src_y, &mut dst_y).map_err(|e| invalid_error!(e.to_string()))?;
-                let mut dst_u = into_image_store_mut::<u16, 1>(&mut dst_planes.planes[1], dst_chroma_width, dst_chroma_height)?;
-                scaler.resize_plane_u16(&src_u, &mut dst_u).map_err(|e| invalid_error!(e.to_string()))?;
-                let mut dst_v = into_image_store_mut::<u16, 1>(&mut dst_planes.planes[2], dst_chroma_width, dst_chroma_height)?;
-                scaler.resize_plane_u16(&src_v, &mut dst_v).map_err(|e| invalid_error!(e.to_string()))?;
+                scale_plane::<_, u16, 1, _>(scaler, &src[0], src_width, src_height, &mut dst[0], dst_width, dst_height, |scaler, src, dst| {
+                    scaler.resize_plane_u16(src, dst)
+                })?;
+                for i in 1..3 {
+                    scale_plane::<_, u16, 1, _>(
+                        scaler,
+                        &src[i],
+                        src_chroma_width,
+                        src_chroma_height,
+                        &mut dst[i],
+                        dst_chroma_width,
+                        dst_chroma_height,
+                        |scaler, src, dst| scaler.resize_plane_u16(src, dst),
+                    )?;
+                }
                 Ok(())
             }
             PixelFormat::P010 |
@@ -164,32 +331,148 @@ impl VideoFrame<'_> {
             PixelFormat::P016 |
             PixelFormat::P216 |
             PixelFormat::P416 => {
-                let (src_chroma_width, src_chroma_height) = format.calc_chroma_dimensions(src_desc.width().get(), src_desc.height().get());
-                let (dst_chroma_width, dst_chroma_height) = format.calc_chroma_dimensions(dst_desc.width().get(), dst_desc.height().get());
-                let src_y = into_image_store::<u16, 1>(&src_planes.planes[0], src_desc.width().get(), src_desc.height().get())?;
-                let src_uv = into_image_store::<u16, 2>(&src_planes.planes[1], src_chroma_width, src_chroma_height)?;
-                let mut dst_y = into_image_store_mut::<u16, 1>(&mut dst_planes.planes[0], dst_desc.width().get(), dst_desc.height().get())?;
-                scaler.resize_plane_u16(&src_y, &mut dst_y).map_err(|e| invalid_error!(e.to_string()))?;
-                let mut dst_uv = into_image_store_mut::<u16, 2>(&mut dst_planes.planes[1], dst_chroma_width, dst_chroma_height)?;
-                scaler.resize_cbcr_u16(&src_uv, &mut dst_uv).map_err(|e| invalid_error!(e.to_string()))?;
-                Ok(())
+                scale_plane::<_, u16, 1, _>(scaler, &src[0], src_width, src_height, &mut dst[0], dst_width, dst_height, |scaler, src, dst| {
+                    scaler.resize_plane_u16(src, dst)
+                })?;
+                scale_plane::<_, u16, 2, _>(
+                    scaler,
+                    &src[1],
+                    src_chroma_width,
+                    src_chroma_height,
+                    &mut dst[1],
+                    dst_chroma_width,
+                    dst_chroma_height,
+                    |scaler, src, dst| scaler.resize_cbcr_u16(src, dst),
+                )
             }
             PixelFormat::Y8 => {
-                let src = into_image_store::<u8, 1>(&src_planes.planes[0], src_desc.width().get(), src_desc.height().get())?;
-                let mut dst = into_image_store_mut::<u8, 1>(&mut dst_planes.planes[0], dst_desc.width().get(), dst_desc.height().get())?;
-                scaler.resize_plane(&src, &mut dst).map_err(|e| invalid_error!(e.to_string()))
+                scale_plane::<_, u8, 1, _>(scaler, &src[0], src_width, src_height, &mut dst[0], dst_width, dst_height, |scaler, src, dst| {
+                    scaler.resize_plane(src, dst)
+                })
             }
+            // similar to UV component interleaving
             PixelFormat::YA8 => {
-                let src = into_image_store::<u8, 2>(&src_planes.planes[0], src_desc.width().get(), src_desc.height().get())?;
-                let mut dst = into_image_store_mut::<u8, 2>(&mut dst_planes.planes[0], dst_desc.width().get(), dst_desc.height().get())?;
-                // similar to UV component interleaving
-                scaler.resize_cbcr8(&src, &mut dst).map_err(|e| invalid_error!(e.to_string()))
+                scale_plane::<_, u8, 2, _>(scaler, &src[0], src_width, src_height, &mut dst[0], dst_width, dst_height, |scaler, src, dst| {
+                    scaler.resize_cbcr8(src, dst)
+                })
             }
-            _ => Err(invalid_error!("unsupported pixel format".to_string())),
+            _ => Err(unsupported_error!(format)),
         }
     }
 
     pub fn scale_to(&self, dst: &mut VideoFrame<'_>, scale_filter: ScaleFilter) -> Result<()> {
         Self::scale_to_internal(&self.desc, &self.data, &dst.desc, &mut dst.data, scale_filter)
     }
+
+    pub fn scale_to_linear(&self, dst: &mut VideoFrame<'_>, scale_filter: ScaleFilter) -> Result<()> {
+        Self::scale_to_linear_internal(&self.desc, &self.data, &dst.desc, &mut dst.data, scale_filter)
+    }
+
+    fn fill_background(dst_desc: &VideoFrameDescriptor, dst_data: &mut FrameData, background: [u8; 3]) -> Result<()> {
+        let format = dst_desc.format;
+        let (chroma_width, chroma_height) = format.calc_chroma_dimensions(4, 4);
+        let (block_width, block_height) = (4 / chroma_width, 4 / chroma_height);
+
+        // convert one block of the color to get its encoding in the destination format
+        let mut rgba_desc = dst_desc.clone();
+        rgba_desc.format = PixelFormat::RGBA32;
+        rgba_desc.dimensions = Dimensions::new(block_width, block_height)?;
+        rgba_desc.crop_left = 0;
+        rgba_desc.crop_top = 0;
+        rgba_desc.crop_right = 0;
+        rgba_desc.crop_bottom = 0;
+        rgba_desc.premultiplied = false;
+        let mut rgba = VideoFrame::new_with_descriptor(rgba_desc.clone())?;
+        rgba.fill_from_fn(|_, _| [background[0], background[1], background[2], u8::MAX])?;
+
+        let mut block_desc = rgba_desc;
+        block_desc.format = format;
+        let mut block = VideoFrame::new_with_descriptor(block_desc)?;
+        rgba.convert_to(&mut block)?;
+
+        let width = dst_desc.width().get();
+        let height = dst_desc.height().get();
+
+        let block_guard = block.data.map()?;
+        let mut dst_guard = dst_data.map_mut()?;
+        let block_planes = block_guard.try_planes()?;
+        let mut dst_planes = dst_guard.try_planes_mut()?;
+
+        for plane_index in 0..dst_planes.len() {
+            let unit = format.calc_plane_row_bytes(plane_index, block_width) as usize;
+            let row_bytes = format.calc_plane_row_bytes(plane_index, width) as usize;
+            let rows = format.calc_plane_height(plane_index, height) as usize;
+
+            let pattern = &block_planes.plane_data(plane_index).ok_or_else(|| invalid_error!("plane data"))?[..unit];
+            let stride = dst_planes.plane_stride(plane_index).ok_or_else(|| invalid_error!("plane stride"))?;
+            let data = dst_planes.plane_data_mut(plane_index).ok_or_else(|| invalid_error!("plane data"))?;
+
+            for row in data.chunks_mut(stride).take(rows) {
+                for chunk in row[..row_bytes].chunks_mut(unit) {
+                    chunk.copy_from_slice(&pattern[..chunk.len()]);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn scale_fit_to_internal(
+        src_desc: &VideoFrameDescriptor,
+        src_data: &FrameData,
+        dst_desc: &VideoFrameDescriptor,
+        dst_data: &mut FrameData,
+        scale_filter: ScaleFilter,
+        background: [u8; 3],
+    ) -> Result<()> {
+        if src_desc.format != dst_desc.format {
+            return Err(invalid_error!("pixel format mismatch"));
+        }
+
+        let format = dst_desc.format;
+        let (dst_width, dst_height) = (dst_desc.width().get(), dst_desc.height().get());
+        let (width, height, left, top) = fit_rect(format, src_desc.width().get(), src_desc.height().get(), dst_width, dst_height);
+
+        if width == dst_width && height == dst_height {
+            return Self::scale_to_internal(src_desc, src_data, dst_desc, dst_data, scale_filter);
+        }
+
+        let mut fitted_desc = dst_desc.clone();
+        fitted_desc.dimensions = Dimensions::new(width, height)?;
+        fitted_desc.crop_left = 0;
+        fitted_desc.crop_top = 0;
+        fitted_desc.crop_right = 0;
+        fitted_desc.crop_bottom = 0;
+        let mut fitted = VideoFrame::new_with_descriptor(fitted_desc.clone())?;
+        Self::scale_to_internal(src_desc, src_data, &fitted_desc, &mut fitted.data, scale_filter)?;
+
+        Self::fill_background(dst_desc, dst_data, background)?;
+
+        let fitted_guard = fitted.data.map()?;
+        let mut dst_guard = dst_data.map_mut()?;
+        let fitted_planes = fitted_guard.try_planes()?;
+        let mut dst_planes = dst_guard.try_planes_mut()?;
+
+        for plane_index in 0..dst_planes.len() {
+            let row_bytes = format.calc_plane_row_bytes(plane_index, width) as usize;
+            let rows = format.calc_plane_height(plane_index, height) as usize;
+            let offset_x = format.calc_plane_row_bytes(plane_index, left) as usize;
+            let offset_y = format.calc_plane_height(plane_index, top) as usize;
+
+            let src_stride = fitted_planes.plane_stride(plane_index).ok_or_else(|| invalid_error!("plane stride"))?;
+            let src = fitted_planes.plane_data(plane_index).ok_or_else(|| invalid_error!("plane data"))?;
+            let dst_stride = dst_planes.plane_stride(plane_index).ok_or_else(|| invalid_error!("plane stride"))?;
+            let dst = dst_planes.plane_data_mut(plane_index).ok_or_else(|| invalid_error!("plane data"))?;
+
+            for (src_row, dst_row) in src.chunks(src_stride).zip(dst.chunks_mut(dst_stride).skip(offset_y)).take(rows) {
+                dst_row[offset_x..offset_x + row_bytes].copy_from_slice(&src_row[..row_bytes]);
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn scale_fit_to(&self, dst: &mut VideoFrame<'_>, scale_filter: ScaleFilter, background: [u8; 3]) -> Result<()> {
+        Self::scale_fit_to_internal(&self.desc, &self.data, &dst.desc, &mut dst.data, scale_filter, background)
+    }
 }
@@ -0,0 +1,142 @@
+use super::{
+    frame::VideoFrame,
+    video::{PixelFormat, VideoFrameDescriptor},
+};
+use crate::{
+    frame::{DataMappable, Frame, FrameData, MappedPlane},
+    invalid_error, invalid_param_error, unsupported_error,
+    utils::crc32,
+    FrameDescriptor, Result,
+};
+
+#[derive(Clone, Copy)]
+struct LumaAccumulator {
+    min: u8,
+    max: u8,
+    sum: u64,
+}
+
+impl LumaAccumulator {
+    fn new() -> Self {
+        Self {
+            min: u8::MAX,
+            max: u8::MIN,
+            sum: 0,
+        }
+    }
+
+    // visit only `width` pixels of each row, skipping stride padding
+    fn accumulate<F>(&mut self, plane: &MappedPlane, width: u32, height: u32, bytes_per_pixel: usize, luma: F) -> Result<()>
+    where
+        F: Fn(&[u8]) -> u8,
+    {
+        let data = plane.data().ok_or_else(|| invalid_error!("plane data"))?;
+        let stride = plane.stride().ok_or_else(|| invalid_error!("plane stride"))?;
+        let row_bytes = width as usize * bytes_per_pixel;
+
+        if stride < row_bytes || data.len() < stride * (height as usize - 1) + row_bytes {
+            return Err(invalid_error!("plane size"));
+        }
+
+        for row in data.chunks(stride).take(height as usize) {
+            for pixel in row[..row_bytes].chunks_exact(bytes_per_pixel) {
+                let y = luma(pixel);
+                self.min = self.min.min(y);
+                self.max = self.max.max(y);
+                self.sum += y as u64;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+// Full range BT.601 weights, scaled by 256
+#[inline]
+fn rgb_to_luma(r: u8, g: u8, b: u8) -> u8 {
+    ((77 * r as u32 + 150 * g as u32 + 29 * b as u32 + 128) >> 8) as u8
+}
+
+impl Frame<'_> {
+    pub fn luma_stats(&self) -> Result<(u8, u8, f32)> {
+        let FrameDescriptor::Video(desc) = &self.desc else {
+            return Err(invalid_error!("not video frame"));
+        };
+
+        VideoFrame::luma_stats_internal(desc, &self.data)
+    }
+
+    pub fn plane_crc32(&self, plane_index: usize) -> Result<u32> {
+        let FrameDescriptor::Video(desc) = &self.desc else {
+            return Err(invalid_error!("not video frame"));
+        };
+
+        VideoFrame::plane_crc32_internal(desc, &self.data, plane_index)
+    }
+}
+
+impl VideoFrame<'_> {
+    fn luma_stats_internal(desc: &VideoFrameDescriptor, data: &FrameData) -> Result<(u8, u8, f32)> {
+        let guard = data.map()?;
+        let planes = guard.try_planes()?;
+        let plane = planes.planes.first().ok_or_else(|| invalid_error!("invalid plane count"))?;
+
+        let width = desc.width().get();
+        let height = desc.height().get();
+        let mut acc = LumaAccumulator::new();
+
+        match desc.format {
+            PixelFormat::ARGB32 => acc.accumulate(plane, width, height, 4, |p| rgb_to_luma(p[1], p[2], p[3]))?,
+            PixelFormat::BGRA32 => acc.accumulate(plane, width, height, 4, |p| rgb_to_luma(p[2], p[1], p[0]))?,
+            PixelFormat::ABGR32 => acc.accumulate(plane, width, height, 4, |p| rgb_to_luma(p[3], p[2], p[1]))?,
+            PixelFormat::RGBA32 => acc.accumulate(plane, width, height, 4, |p| rgb_to_luma(p[0], p[1], p[2]))?,
+            PixelFormat::RGB24 => acc.accumulate(plane, width, height, 3, |p| rgb_to_luma(p[0], p[1], p[2]))?,
+            PixelFormat::BGR24 => acc.accumulate(plane, width, height, 3, |p| rgb_to_luma(p[2], p[1], p[0]))?,
+            PixelFormat::I420 |
+            PixelFormat::I422 |
+            PixelFormat::I444 |
+            PixelFormat::I440 |
+            PixelFormat::NV12 |
+            PixelFormat::NV21 |
+            PixelFormat::NV16 |
+            PixelFormat::NV61 |
+            PixelFormat::NV24 |
+            PixelFormat::NV42 |
+            PixelFormat::YV12 |
+            PixelFormat::YV16 |
+            PixelFormat::YV24 |
+            PixelFormat::Y8 |
+            PixelFormat::YA8 => acc.accumulate(plane, width, height, 1, |p| p[0])?,
+            // each pair of bytes carries one luma sample
+            PixelFormat::YUYV | PixelFormat::YVYU => acc.accumulate(plane, width, height, 2, |p| p[0])?,
+            PixelFormat::UYVY | PixelFormat::VYUY => acc.accumulate(plane, width, height, 2, |p| p[1])?,
+            _ => return Err(unsupported_error!(desc.format)),
+        }
+
+        let mean = acc.sum as f64 / (width as u64 * height as u64) as f64;
+
+        Ok((acc.min, acc.max, mean as f32))
+    }
+
+    pub fn luma_stats(&self) -> Result<(u8, u8, f32)> {
+        Self::luma_stats_internal(&self.desc, &self.data)
+    }
+
+    // only the visible bytes of each row, so stride padding does not affect the
+    // result
+    fn plane_crc32_internal(desc: &VideoFrameDescriptor, data: &FrameData, plane_index: usize) -> Result<u32> {
+        let guard = data.map()?.with_video_format(Some(desc));
+        let plane = guard.plane(plane_index).ok_or_else(|| invalid_param_error!(plane_index))?;
+        let height = desc.format.calc_plane_height(plane_index, desc.height().get()) as usize;
+
+        if plane.stride < plane.row_bytes || plane.data.len() < plane.stride * (height - 1) + plane.row_bytes {
+            return Err(invalid_error!("plane size"));
+        }
+
+        Ok(plane.data.chunks(plane.stride).take(height).fold(0, |crc, row| crc32(crc, &row[..plane.row_bytes])))
+    }
+
+    pub fn plane_crc32(&self, plane_index: usize) -> Result<u32> {
+        Self::plane_crc32_internal(&self.desc, &self.data, plane_index)
+    }
+}
@@ -0,0 +1,137 @@
+use pic_scale::TransferFunction;
+
+use super::{
+    frame::VideoFrame,
+    video::{ColorTransferCharacteristics, PixelFormat, VideoFrameDescriptor},
+};
+use crate::{
+    frame::{DataMappable, Frame, FrameData},
+    invalid_error, unsupported_error, FrameDescriptor, Result,
+};
+
+// (bytes per pixel, alpha offset) of the 8-bit RGB formats
+fn rgb_layout(format: PixelFormat) -> Result<(usize, Option<usize>)> {
+    match format {
+        PixelFormat::RGB24 | PixelFormat::BGR24 => Ok((3, None)),
+        PixelFormat::ARGB32 | PixelFormat::ABGR32 => Ok((4, Some(0))),
+        PixelFormat::BGRA32 | PixelFormat::RGBA32 => Ok((4, Some(3))),
+        _ => Err(unsupported_error!(format)),
+    }
+}
+
+fn transfer_table(transfer_function: TransferFunction, to_linear: bool) -> [u8; 256] {
+    let mut table = [0u8; 256];
+    for (value, entry) in table.iter_mut().enumerate() {
+        let value = value as f32 / 255.0;
+        let value = if to_linear {
+            transfer_function.linearize(value)
+        } else {
+            transfer_function.gamma(value)
+        };
+        *entry = (value.clamp(0.0, 1.0) * 255.0).round() as u8;
+    }
+    table
+}
+
+impl Frame<'_> {
+    // decodes the descriptor's transfer characteristics, sRGB when unspecified.
+    // Linear light is kept in 8-bit samples, so the darkest ~50 codes collapse
+    // onto a handful of values and to_gamma does not restore them. Use
+    // scale_to_linear to scale without that loss
+    pub fn to_linear(&self) -> Result<Frame<'static>> {
+        self.apply_transfer(true)
+    }
+
+    // encodes linear light with the sRGB transfer
+    pub fn to_gamma(&self) -> Result<Frame<'static>> {
+        self.apply_transfer(false)
+    }
+
+    fn apply_transfer(&self, to_linear: bool) -> Result<Frame<'static>> {
+        let FrameDescriptor::Video(src_desc) = &self.desc else {
+            return Err(invalid_error!("not video frame"));
+        };
+
+        let (dst_desc, transfer_function) = VideoFrame::transfer_descriptor(src_desc, to_linear);
+        let mut dst = Frame::video_creator().create_with_descriptor(dst_desc)?;
+        VideoFrame::apply_transfer_internal(src_desc, &self.data, &mut dst.data, transfer_function, to_linear)?;
+
+        dst.copy_props_from(self);
+
+        Ok(dst)
+    }
+}
+
+impl VideoFrame<'_> {
+    fn transfer_descriptor(src_desc: &VideoFrameDescriptor, to_linear: bool) -> (VideoFrameDescriptor, TransferFunction) {
+        let mut dst_desc = src_desc.clone();
+        if to_linear {
+            dst_desc.color_transfer_characteristics = ColorTransferCharacteristics::Linear;
+            (dst_desc, src_desc.color_transfer_characteristics.into())
+        } else {
+            dst_desc.color_transfer_characteristics = ColorTransferCharacteristics::IEC61966_2_1;
+            (dst_desc, TransferFunction::Srgb)
+        }
+    }
+
+    fn apply_transfer_internal(
+        src_desc: &VideoFrameDescriptor,
+        src_data: &FrameData,
+        dst_data: &mut FrameData,
+        transfer_function: TransferFunction,
+        to_linear: bool,
+    ) -> Result<()> {
+        let (bytes_per_pixel, alpha_offset) = rgb_layout(src_desc.format)?;
+        let table = transfer_table(transfer_function, to_linear);
+
+        let width = src_desc.width().get() as usize;
+        let height = src_desc.height().get() as usize;
+
+        let guard = src_data.map()?;
+        let mut dst_guard = dst_data.map_mut()?;
+        let src_planes = guard.try_planes()?;
+        let mut dst_planes = dst_guard.try_planes_mut()?;
+
+        let src_stride = src_planes.plane_stride(0).ok_or_else(|| invalid_error!("plane stride"))?;
+        let dst_stride = dst_planes.plane_stride(0).ok_or_else(|| invalid_error!("plane stride"))?;
+        let src = src_planes.plane_data(0).ok_or_else(|| invalid_error!("plane data"))?;
+        let dst = dst_planes.plane_data_mut(0).ok_or_else(|| invalid_error!("plane data"))?;
+
+        let row_bytes = width * bytes_per_pixel;
+        for (src_row, dst_row) in src.chunks(src_stride).zip(dst.chunks_mut(dst_stride)).take(height) {
+            for (src_pixel, dst_pixel) in
+                src_row[..row_bytes].chunks_exact(bytes_per_pixel).zip(dst_row[..row_bytes].chunks_exact_mut(bytes_per_pixel))
+            {
+                for (index, (src_value, dst_value)) in src_pixel.iter().zip(dst_pixel.iter_mut()).enumerate() {
+                    *dst_value = if Some(index) == alpha_offset {
+                        *src_value
+                    } else {
+                        table[*src_value as usize]
+                    };
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn to_linear(&self) -> Result<VideoFrame<'static>> {
+        let (dst_desc, transfer_function) = Self::transfer_descriptor(&self.desc, true);
+        let mut dst = VideoFrame::new_with_descriptor(dst_desc)?;
+        Self::apply_transfer_internal(&self.desc, &self.data, &mut dst.data, transfer_function, true)?;
+
+        dst.copy_props_from(self);
+
+        Ok(dst)
+    }
+
+    pub fn to_gamma(&self) -> Result<VideoFrame<'static>> {
+        let (dst_desc, transfer_function) = Self::transfer_descriptor(&self.desc, false);
+        let mut dst = VideoFrame::new_with_descriptor(dst_desc)?;
+        Self::apply_transfer_internal(&self.desc, &self.data, &mut dst.data, transfer_function, false)?;
+
+        dst.copy_props_from(self);
+
+        Ok(dst)
+    }
+}
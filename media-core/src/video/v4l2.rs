@@ -0,0 +1,59 @@
+use super::{frame::VideoFrame, video::VideoFrameDescriptor};
+use crate::{
+    frame::{DataMappable, Frame, FrameData},
+    invalid_error, unsupported_error, FrameDescriptor, Result,
+};
+
+impl Frame<'_> {
+    pub fn to_v4l2_buffer(&self) -> Result<Vec<u8>> {
+        let FrameDescriptor::Video(desc) = &self.desc else {
+            return Err(invalid_error!("not video frame"));
+        };
+
+        VideoFrame::to_v4l2_buffer_internal(desc, &self.data)
+    }
+}
+
+impl VideoFrame<'_> {
+    // planes back to back with no row padding, as V4L2 expects
+    fn to_v4l2_buffer_internal(desc: &VideoFrameDescriptor, data: &FrameData) -> Result<Vec<u8>> {
+        let format = desc.format;
+
+        if !format.is_planar() && !format.is_biplanar() && !format.is_packed() {
+            return Err(unsupported_error!(format));
+        }
+
+        let width = desc.width().get();
+        let height = desc.height().get();
+
+        let guard = data.map()?;
+        let planes = guard.try_planes()?;
+
+        let size = (0..planes.planes.len())
+            .map(|plane_index| format.calc_plane_row_bytes(plane_index, width) as usize * format.calc_plane_height(plane_index, height) as usize)
+            .sum();
+        let mut buffer = Vec::with_capacity(size);
+
+        for (plane_index, plane) in planes.planes.iter().enumerate() {
+            let row_bytes = format.calc_plane_row_bytes(plane_index, width) as usize;
+            let rows = format.calc_plane_height(plane_index, height) as usize;
+
+            let stride = plane.stride().ok_or_else(|| invalid_error!("plane stride"))?;
+            let src = plane.data().ok_or_else(|| invalid_error!("plane data"))?;
+
+            if stride < row_bytes || src.len() < stride * (rows - 1) + row_bytes {
+                return Err(invalid_error!("plane size"));
+            }
+
+            for row in src.chunks(stride).take(rows) {
+                buffer.extend_from_slice(&row[..row_bytes]);
+            }
+        }
+
+        Ok(buffer)
+    }
+
+    pub fn to_v4l2_buffer(&self) -> Result<Vec<u8>> {
+        Self::to_v4l2_buffer_internal(&self.desc, &self.data)
+    }
+}
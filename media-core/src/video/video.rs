@@ -13,7 +13,7 @@ use crate::{
     align_to, ceil_rshift,
     error::Error,
     frame::{Frame, PlaneDescriptor, PlaneVec},
-    invalid_error, invalid_param_error,
+    invalid_error, invalid_param_error, unsupported_error,
     video::VideoFrame,
     FrameDescriptor, FrameDescriptorSpec, MediaType, Result,
 };
@@ -56,8 +56,40 @@ impl Dimensions {
     pub const QHD: Self = Self::new_unchecked(2560, 1440);
     pub const UHD_4K: Self = Self::new_unchecked(3840, 2160);
     pub const UHD_8K: Self = Self::new_unchecked(7680, 4320);
+
+    // the constants above in ascending area order
+    pub fn standard_resolutions() -> &'static [Dimensions] {
+        &STANDARD_RESOLUTIONS
+    }
+
+    pub fn name(&self) -> Option<&'static str> {
+        STANDARD_RESOLUTIONS.iter().position(|dimensions| dimensions == self).map(|index| STANDARD_RESOLUTION_NAMES[index])
+    }
 }
 
+const STANDARD_RESOLUTIONS: [Dimensions; 17] = [
+    Dimensions::SQCIF,
+    Dimensions::QQVGA,
+    Dimensions::QCIF,
+    Dimensions::QVGA,
+    Dimensions::CIF,
+    Dimensions::VGA,
+    Dimensions::SD,
+    Dimensions::SVGA,
+    Dimensions::XGA,
+    Dimensions::HD,
+    Dimensions::SXGA,
+    Dimensions::UXGA,
+    Dimensions::FHD,
+    Dimensions::QXGA,
+    Dimensions::QHD,
+    Dimensions::UHD_4K,
+    Dimensions::UHD_8K,
+];
+
+const STANDARD_RESOLUTION_NAMES: [&str; 17] =
+    ["SQCIF", "QQVGA", "QCIF", "QVGA", "CIF", "VGA", "SD", "SVGA", "XGA", "HD", "SXGA", "UXGA", "FHD", "QXGA", "QHD", "UHD_4K", "UHD_8K"];
+
 #[derive(Clone, Copy, Debug, Default, EnumCount, Eq, PartialEq)]
 #[repr(u8)]
 pub enum ColorRange {
@@ -302,8 +334,47 @@ pub enum PixelFormat {
     P016,   // biplanar YUV 4:2:0, 16 bits per channel
     P216,   // biplanar YUV 4:2:2, 16 bits per channel
     P416,   // biplanar YUV 4:4:4, 16 bits per channel
+    Y16,    // greyscale, 16 bits Y, little-endian
 }
 
+// V4L2 single-plane FourCC codes, the V4L2 backend maps its formats through
+// this table. V4L2 has no single-plane code for I444
+const PIXEL_FORMAT_FOURCCS: &[(PixelFormat, &str)] = &[
+    (PixelFormat::RGB24, "RGB3"),
+    (PixelFormat::BGR24, "BGR3"),
+    (PixelFormat::I420, "YU12"),
+    (PixelFormat::I422, "422P"),
+    (PixelFormat::NV12, "NV12"),
+    (PixelFormat::NV21, "NV21"),
+    (PixelFormat::NV16, "NV16"),
+    (PixelFormat::NV61, "NV61"),
+    (PixelFormat::NV24, "NV24"),
+    (PixelFormat::NV42, "NV42"),
+    (PixelFormat::YV12, "YV12"),
+    (PixelFormat::YV16, "YV16"),
+    (PixelFormat::YV24, "YV24"),
+    (PixelFormat::YUYV, "YUYV"),
+    (PixelFormat::YVYU, "YVYU"),
+    (PixelFormat::UYVY, "UYVY"),
+    (PixelFormat::VYUY, "VYUY"),
+    (PixelFormat::Y8, "GREY"),
+    (PixelFormat::Y16, "Y16 "),
+    (PixelFormat::P010, "P010"),
+    (PixelFormat::P210, "P210"),
+];
+
+// Accepted when parsing, never produced, YU16 and YU24 are the DRM codes
+// libcamera uses
+const PIXEL_FORMAT_FOURCC_ALIASES: &[(PixelFormat, &str)] = &[
+    (PixelFormat::I420, "I420"),
+    (PixelFormat::I420, "IYUV"),
+    (PixelFormat::I422, "YU16"),
+    (PixelFormat::I444, "YU24"),
+    (PixelFormat::YUYV, "YUY2"),
+    (PixelFormat::Y8, "Y800"),
+    (PixelFormat::Y8, "Y8  "),
+];
+
 impl From<PixelFormat> for usize {
     fn from(value: PixelFormat) -> Self {
         value as usize
@@ -830,6 +901,15 @@ static PIXEL_FORMAT_DESC: [PixelFormatDescriptor; PixelFormat::COUNT] = [
         flags: pix_fmt_flags!(YUV | BiPlanar),
         component_bytes: [2, 4, 0, 0],
     },
+    // Y16
+    PixelFormatDescriptor {
+        components: 1,
+        chroma_shift_x: 0,
+        chroma_shift_y: 0,
+        depth: 16,
+        flags: PixelFormatFlags::Planar,
+        component_bytes: [2, 0, 0, 0],
+    },
 ];
 
 impl PixelFormat {
@@ -860,6 +940,10 @@ impl PixelFormat {
         PIXEL_FORMAT_DESC[*self as usize].depth
     }
 
+    pub fn has_alpha(&self) -> bool {
+        PIXEL_FORMAT_DESC[*self as usize].flags.contains(PixelFormatFlags::Alpha)
+    }
+
     pub fn is_rgb(&self) -> bool {
         PIXEL_FORMAT_DESC[*self as usize].flags.contains(PixelFormatFlags::RGB)
     }
@@ -880,11 +964,31 @@ impl PixelFormat {
         PIXEL_FORMAT_DESC[*self as usize].flags.contains(PixelFormatFlags::BiPlanar)
     }
 
+    pub fn fourcc_str(&self) -> Option<&'static str> {
+        PIXEL_FORMAT_FOURCCS.iter().find(|(format, _)| format == self).map(|(_, fourcc)| *fourcc)
+    }
+
+    // short codes are padded with spaces, "Y16" matches "Y16 "
+    pub fn from_fourcc_str(fourcc: &str) -> Result<Self> {
+        if fourcc.is_empty() || fourcc.len() > 4 || !fourcc.is_ascii() {
+            return Err(invalid_param_error!(fourcc));
+        }
+
+        let padded = format!("{:<4}", fourcc);
+        PIXEL_FORMAT_FOURCCS
+            .iter()
+            .chain(PIXEL_FORMAT_FOURCC_ALIASES)
+            .find(|(_, code)| *code == padded)
+            .map(|(format, _)| *format)
+            .ok_or_else(|| unsupported_error!(fourcc))
+    }
+
     pub fn calc_plane_row_bytes(&self, plane_index: usize, width: u32) -> u32 {
         let desc = &PIXEL_FORMAT_DESC[*self as usize];
         let component_bytes = desc.component_bytes[plane_index];
 
-        if plane_index > 0 && (self.is_planar() || self.is_biplanar()) {
+        if (plane_index > 0 && (self.is_planar() || self.is_biplanar())) || (self.is_packed() && self.is_yuv()) {
+            // packed YUV stores subsampled pixel groups, e.g. 2 pixels per YUYV macropixel
             ceil_rshift(width, desc.chroma_shift_x as u32) * component_bytes as u32
         } else {
             width * component_bytes as u32
@@ -1085,6 +1189,53 @@ pub enum ScaleFilter {
     Bicubic,
 }
 
+// SMPTE ST 2086 mastering display color volume, as stored in the mdcv box
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct MasteringDisplay {
+    pub display_primaries: [(u16, u16); 3], // (x, y) in 0.00002 units, G, B, R order
+    pub white_point: (u16, u16),
+    pub max_luminance: u32, // 0.0001 cd/m2 units
+    pub min_luminance: u32,
+}
+
+impl MasteringDisplay {
+    pub fn from_mdcv(data: &[u8]) -> Result<Self> {
+        if data.len() < 24 {
+            return Err(invalid_param_error!(data.len()));
+        }
+
+        let read_u16 = |pos: usize| u16::from_be_bytes([data[pos], data[pos + 1]]);
+        let read_u32 = |pos: usize| u32::from_be_bytes([data[pos], data[pos + 1], data[pos + 2], data[pos + 3]]);
+
+        Ok(Self {
+            display_primaries: [(read_u16(0), read_u16(2)), (read_u16(4), read_u16(6)), (read_u16(8), read_u16(10))],
+            white_point: (read_u16(12), read_u16(14)),
+            max_luminance: read_u32(16),
+            min_luminance: read_u32(20),
+        })
+    }
+}
+
+// CTA-861.3 content light level, as stored in the clli box
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct ContentLightLevel {
+    pub max_cll: u16, // cd/m2
+    pub max_fall: u16,
+}
+
+impl ContentLightLevel {
+    pub fn from_clli(data: &[u8]) -> Result<Self> {
+        if data.len() < 4 {
+            return Err(invalid_param_error!(data.len()));
+        }
+
+        Ok(Self {
+            max_cll: u16::from_be_bytes([data[0], data[1]]),
+            max_fall: u16::from_be_bytes([data[2], data[3]]),
+        })
+    }
+}
+
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct VideoFrameDescriptor {
     pub format: PixelFormat,
@@ -1098,10 +1249,13 @@ pub struct VideoFrameDescriptor {
     pub origin: Origin,
     pub transparent: bool,
     pub extra_alpha: bool,
+    pub premultiplied: bool, // color channels are already multiplied by alpha
     pub crop_left: u32,
     pub crop_top: u32,
     pub crop_right: u32,
     pub crop_bottom: u32,
+    pub mastering_display: Option<MasteringDisplay>,
+    pub content_light_level: Option<ContentLightLevel>,
 }
 
 impl VideoFrameDescriptor {
@@ -1121,10 +1275,13 @@ impl VideoFrameDescriptor {
             origin: Origin::default(),
             transparent: false,
             extra_alpha: false,
+            premultiplied: false,
             crop_left: 0,
             crop_top: 0,
             crop_right: 0,
             crop_bottom: 0,
+            mastering_display: None,
+            content_light_level: None,
         }
     }
 
@@ -1135,6 +1292,21 @@ impl VideoFrameDescriptor {
         Ok(Self::new(format, width, height))
     }
 
+    // the crop rectangle must leave at least one row and column
+    pub fn validate(&self) -> Result<()> {
+        let crop_width = self.crop_left.checked_add(self.crop_right);
+        if crop_width.is_none_or(|crop_width| crop_width >= self.width().get()) {
+            return Err(invalid_error!("crop_left + crop_right exceeds width"));
+        }
+
+        let crop_height = self.crop_top.checked_add(self.crop_bottom);
+        if crop_height.is_none_or(|crop_height| crop_height >= self.height().get()) {
+            return Err(invalid_error!("crop_top + crop_bottom exceeds height"));
+        }
+
+        Ok(())
+    }
+
     #[inline]
     pub fn width(&self) -> NonZeroU32 {
         self.dimensions.width
@@ -1144,6 +1316,38 @@ impl VideoFrameDescriptor {
     pub fn height(&self) -> NonZeroU32 {
         self.dimensions.height
     }
+
+    pub fn with_color_range(mut self, color_range: ColorRange) -> Self {
+        self.color_range = color_range;
+        self
+    }
+
+    // BT.601 NTSC, limited range
+    pub fn with_bt601(mut self) -> Self {
+        self.color_range = ColorRange::Video;
+        self.color_matrix = ColorMatrix::SMPTE170M;
+        self.color_primaries = ColorPrimaries::SMPTE170M;
+        self.color_transfer_characteristics = ColorTransferCharacteristics::SMPTE170M;
+        self
+    }
+
+    // BT.709 HD, limited range
+    pub fn with_bt709(mut self) -> Self {
+        self.color_range = ColorRange::Video;
+        self.color_matrix = ColorMatrix::BT709;
+        self.color_primaries = ColorPrimaries::BT709;
+        self.color_transfer_characteristics = ColorTransferCharacteristics::BT709;
+        self
+    }
+
+    // BT.2100 PQ, limited range
+    pub fn with_bt2020_pq(mut self) -> Self {
+        self.color_range = ColorRange::Video;
+        self.color_matrix = ColorMatrix::BT2020NCL;
+        self.color_primaries = ColorPrimaries::BT2020;
+        self.color_transfer_characteristics = ColorTransferCharacteristics::SMPTE2084;
+        self
+    }
 }
 
 impl From<VideoFrameDescriptor> for FrameDescriptor {
@@ -93,3 +93,65 @@ fn test_audio_frame_from_buffer() {
     assert_eq!(desc.samples.get(), 1024);
     assert_eq!(desc.sample_rate.get(), 44100);
 }
+
+#[test]
+fn test_audio_duration() {
+    use media_core::rational::Rational64;
+
+    let frame = Frame::audio_creator().create(SampleFormat::F32, 2, 1024, 48000).unwrap();
+    assert_eq!(frame.audio_duration(), Some(Rational64::new(1024, 48000)));
+
+    let audio_frame = AudioFrame::new(SampleFormat::S16, 1, 480, 48000).unwrap();
+    assert_eq!(audio_frame.audio_duration(), Rational64::new(1, 100));
+
+    #[cfg(feature = "video")]
+    {
+        let video_frame = Frame::video_creator().create(media_core::video::PixelFormat::I420, 16, 16).unwrap();
+        assert_eq!(video_frame.audio_duration(), None);
+    }
+}
+
+fn fill_planar(frame: &mut Frame, values: &[f32]) {
+    let mut guard = frame.map_mut().unwrap();
+    let mut planes = guard.planes_mut().unwrap();
+    for (index, value) in values.iter().enumerate() {
+        let data: &mut [f32] = bytemuck::cast_slice_mut(planes.plane_data_mut(index).unwrap());
+        data.fill(*value);
+    }
+}
+
+#[test]
+fn test_downmix() {
+    let mut src = Frame::audio_creator().create(SampleFormat::F32P, 6, 4, 48000).unwrap();
+    fill_planar(&mut src, &[1.0, 2.0, 3.0, 4.0, 5.0, 6.0]);
+    let mut dst = Frame::audio_creator().create(SampleFormat::F32, 2, 4, 48000).unwrap();
+
+    // custom broadcast style mix, LFE at -12dB on the left only
+    let coefficients = vec![vec![1.0, 0.0, 0.5, 0.25, 0.5, 0.0], vec![0.0, 1.0, 0.5, 0.0, 0.0, 0.5]];
+    src.downmix_to(&mut dst, Some(&coefficients)).unwrap();
+    {
+        let guard = dst.map().unwrap();
+        let planes = guard.planes().unwrap();
+        let data: &[f32] = bytemuck::cast_slice(planes.plane_data(0).unwrap());
+        for pair in data[..8].chunks(2) {
+            assert_eq!(pair, [1.0 + 1.5 + 1.0 + 2.5, 2.0 + 1.5 + 3.0]);
+        }
+    }
+
+    assert!(src.downmix_to(&mut dst, Some(&coefficients[..1])).is_err());
+    assert!(src.downmix_to(&mut dst, Some(&[vec![1.0; 5], vec![1.0; 5]])).is_err());
+
+    // the default keeps the fronts and drops LFE
+    let layout = channel_layout::ChannelLayout::default_from_channels(6).unwrap();
+    let default_coefficients = layout.downmix_coefficients(&channel_layout::ChannelLayout::default_from_channels(2).unwrap());
+    assert_eq!(default_coefficients[0][0], 1.0);
+    assert_eq!(default_coefficients[1][1], 1.0);
+    assert_eq!(default_coefficients[0][3], 0.0);
+    assert_eq!(default_coefficients[1][3], 0.0);
+    src.downmix_to(&mut dst, None).unwrap();
+    let guard = dst.map().unwrap();
+    let planes = guard.planes().unwrap();
+    let data: &[f32] = bytemuck::cast_slice(planes.plane_data(0).unwrap());
+    let expected: f32 = default_coefficients[0].iter().zip([1.0, 2.0, 3.0, 4.0, 5.0, 6.0]).map(|(weight, value)| weight * value).sum();
+    assert!((data[0] - expected).abs() < 1e-5);
+}
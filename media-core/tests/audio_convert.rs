@@ -1,7 +1,10 @@
 use std::fmt::Debug;
 
 use bytemuck::Pod;
-use media_core::{audio::SampleFormat, frame::Frame};
+use media_core::{
+    audio::{AudioDither, SampleFormat},
+    frame::Frame,
+};
 
 fn test_audio_convert<I, O>(src_fmt: SampleFormat, dst_fmt: SampleFormat, channels: u8, input_value: I, output_value: O)
 where
@@ -234,3 +237,44 @@ fn test_f64_to_f32() {
     test_audio_convert(SampleFormat::F64P, SampleFormat::F32, 2, 0.0f64, 0.0f32);
     test_audio_convert(SampleFormat::F64, SampleFormat::F32P, 2, 1.0f64, 1.0f32);
 }
+
+#[test]
+fn test_f32_to_s16_dither() {
+    let samples = 960;
+    let sample_rate = 48000;
+    // a DC level of 0.3 LSB, which plain rounding collapses to zero
+    let level = 0.3f32 / (1u16 << 15) as f32;
+
+    let mut input_frame = Frame::audio_creator().create(SampleFormat::F32, 1, samples, sample_rate).unwrap();
+    let mut output_frame = Frame::audio_creator().create(SampleFormat::S16, 1, samples, sample_rate).unwrap();
+    {
+        let mut guard = input_frame.map_mut().unwrap();
+        for mut plane in guard.planes_mut().unwrap() {
+            plane.as_mut_slice_of::<f32>().unwrap().fill(level);
+        }
+    }
+
+    let mut convert = |dither: &mut AudioDither| {
+        input_frame.convert_audio_to_with_dither(&mut output_frame, dither).unwrap();
+        let guard = output_frame.map().unwrap();
+        let planes = guard.planes().unwrap();
+        planes.into_iter().flat_map(|plane| plane.as_slice_of::<i16>().unwrap().to_vec()).collect::<Vec<i16>>()
+    };
+
+    let mut dither = AudioDither::new();
+    let frames = (0..4).map(|_| convert(&mut dither)).collect::<Vec<_>>();
+    // the noise keeps running across frames instead of repeating
+    assert!(frames.windows(2).all(|pair| pair[0] != pair[1]));
+    // and restarts from the same state for a new stream
+    assert_eq!(convert(&mut AudioDither::new()), frames[0]);
+
+    let output = frames.concat().into_iter().map(f64::from).collect::<Vec<f64>>();
+    let mean = output.iter().sum::<f64>() / output.len() as f64;
+    let variance = output.iter().map(|sample| (sample - mean).powi(2)).sum::<f64>() / output.len() as f64;
+    assert!(variance > 0.0);
+    // error feedback keeps the average on the input level, and the triangular
+    // noise of +-1 LSB plus the shaped error stays within a couple of steps
+    assert!((mean - 0.3).abs() < 0.01, "mean: {}", mean);
+    assert!(output.iter().all(|sample| (-2.0..=3.0).contains(sample)));
+    assert!(output.contains(&0.0) && output.contains(&1.0));
+}
@@ -0,0 +1,38 @@
+use media_core::{data::*, frame::*, variant::Variant};
+
+#[derive(Default)]
+struct BufferingSink {
+    pending: Vec<Frame<'static>>,
+    flushed: Vec<Frame<'static>>,
+}
+
+impl BufferingSink {
+    fn push(&mut self, frame: Frame<'static>) {
+        if frame.is_eos() {
+            self.flushed.append(&mut self.pending);
+        } else {
+            self.pending.push(frame);
+        }
+    }
+}
+
+#[test]
+fn test_eos() {
+    let eos = Frame::eos();
+    assert!(eos.is_eos());
+    assert!(eos.is_data());
+    assert!(eos.data().is_none());
+
+    let frame = Frame::data_creator().create_from_variant(&Variant::from(1)).unwrap();
+    assert!(!frame.is_eos());
+
+    let mut sink = BufferingSink::default();
+    sink.push(frame);
+    sink.push(Frame::data_creator().create(DataFormat::Variant).unwrap());
+    assert_eq!(sink.pending.len(), 2);
+    assert!(sink.flushed.is_empty());
+
+    sink.push(eos);
+    assert!(sink.pending.is_empty());
+    assert_eq!(sink.flushed.len(), 2);
+}
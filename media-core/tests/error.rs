@@ -0,0 +1,32 @@
+#![cfg(feature = "backtrace")]
+
+use std::backtrace::BacktraceStatus;
+
+use media_core::{error::Error, unsupported_error, video::PixelFormat};
+
+fn convert(format: PixelFormat) -> Result<(), Error> {
+    Err(unsupported_error!(format))
+}
+
+#[test]
+fn test_error_backtrace() {
+    let err = convert(PixelFormat::RGB30).unwrap_err();
+    let backtrace = err.backtrace().unwrap();
+    assert_eq!(backtrace.status(), BacktraceStatus::Captured);
+    assert!(!backtrace.to_string().is_empty());
+    assert!(format!("{:?}", err).starts_with("Unsupported(\"RGB30\")\n\nBacktrace:\n"));
+
+    // the error itself is the same with or without the feature
+    assert_eq!(err.to_string(), "Unsupported: RGB30");
+    assert!(matches!(&err, Error::Unsupported(message) if message == "RGB30"));
+
+    // the backtrace travels with the error, across threads too, and later
+    // errors do not replace it
+    let expected = backtrace.to_string();
+    let _later = convert(PixelFormat::Y8).unwrap_err();
+    let other = std::thread::spawn(move || err.backtrace().map(|backtrace| backtrace.to_string())).join().unwrap();
+    assert_eq!(other, Some(expected));
+
+    // errors built directly carry none
+    assert!(Error::Failed("direct".into()).backtrace().is_none());
+}
@@ -0,0 +1,41 @@
+#![cfg(all(debug_assertions, feature = "video"))]
+
+use media_core::{frame_pool::FramePool, video::*, FrameDescriptor};
+
+#[test]
+fn test_frame_pool_outstanding() {
+    let pool: std::sync::Arc<FramePool> = FramePool::new();
+    let desc = FrameDescriptor::Video(VideoFrameDescriptor::try_new(PixelFormat::I420, 64, 48).unwrap());
+
+    let frames: Vec<_> = (0..4).map(|_| pool.get_frame_with_descriptor(desc.clone()).unwrap()).collect();
+    assert_eq!(pool.outstanding(), 4);
+    assert_eq!(pool.leaked_count(), 0);
+
+    pool.set_leak_threshold(Some(2));
+    assert_eq!(pool.leaked_count(), 2);
+
+    drop(frames);
+    assert_eq!(pool.outstanding(), 0);
+    assert_eq!(pool.leaked_count(), 0);
+    assert_eq!(pool.available(), 4);
+
+    let frame = pool.get_frame().unwrap();
+    assert_eq!(pool.outstanding(), 1);
+    assert_eq!(pool.available(), 3);
+    drop(frame);
+    assert_eq!(pool.outstanding(), 0);
+
+    // clones share one pooled frame, which is released once both are gone
+    let frames: Vec<_> = (0..2).map(|_| pool.get_frame().unwrap()).collect();
+    let frame = frames[0].clone();
+    assert_eq!(pool.outstanding(), 2);
+    drop(frame);
+    assert_eq!(pool.outstanding(), 2);
+    let mut frames = frames.into_iter();
+    drop(frames.next());
+    assert_eq!(pool.outstanding(), 1);
+    assert_eq!(pool.available(), 3);
+    drop(frames);
+    assert_eq!(pool.outstanding(), 0);
+    assert_eq!(pool.available(), 4);
+}
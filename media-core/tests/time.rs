@@ -0,0 +1,24 @@
+use media_core::{rational::Rational64, time::*};
+
+#[test]
+fn test_fps_to_duration() {
+    assert_eq!(fps_to_duration(29.97), Rational64::new(1001, 30000));
+    assert_eq!(fps_to_duration(23.976), Rational64::new(1001, 24000));
+    assert_eq!(fps_to_duration(59.94), Rational64::new(1001, 60000));
+    assert_eq!(fps_to_duration(25.0), Rational64::new(1, 25));
+    assert_eq!(fps_to_rational(29.97), FPS_29_97);
+    assert_eq!(fps_to_rational(30.0), FPS_30);
+
+    // round trip through the exact rational
+    assert!((duration_to_fps(fps_to_duration(29.97)) - 29.97).abs() < 0.001);
+    assert_eq!(fps_to_duration(duration_to_fps(FPS_59_94.recip())), FPS_59_94.recip());
+    assert_eq!(fps_to_duration(duration_to_fps(FPS_24.recip())), Rational64::new(1, 24));
+
+    assert_eq!(fps_to_duration(12.5), Rational64::new(2, 25));
+    // very slow rates keep their value rather than rounding to zero
+    assert_eq!(fps_to_rational(0.004), Rational64::new(1, 250));
+    assert_eq!(fps_to_duration(0.001), Rational64::from_integer(1000));
+    assert_eq!(fps_to_duration(0.5), Rational64::from_integer(2));
+    assert_eq!(fps_to_duration(0.0), Rational64::new(0, 1));
+    assert_eq!(duration_to_fps(Rational64::new(0, 1)), 0.0);
+}
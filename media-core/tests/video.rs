@@ -1,4 +1,4 @@
-use media_core::{frame::*, video::*};
+use media_core::{error::Error, frame::*, video::*};
 
 #[test]
 fn test_pixel_format() {
@@ -46,6 +46,100 @@ fn test_video_frame() {
     assert_eq!(desc.format, PixelFormat::I420);
     assert_eq!(desc.width().get(), 640);
     assert_eq!(desc.height().get(), 480);
+    assert_eq!(frame.sequence, None);
+}
+
+#[test]
+fn test_copy_props_from() {
+    use media_core::{rational::Rational64, variant::Variant};
+
+    let mut frame = Frame::video_creator().create(PixelFormat::I420, 16, 8).unwrap();
+    frame.source = Some("camera0".to_string());
+    frame.pts = Some(3003);
+    frame.dts = Some(3000);
+    frame.duration = Some(1001);
+    frame.time_base = Some(Rational64::new(1, 30000));
+    frame.sequence = Some(7);
+    let mut metadata = Variant::new_dict();
+    metadata["exposure"] = 10.into();
+    frame.metadata = Some(metadata);
+
+    // derived frames carry every property of their source
+    for derived in [frame.crop(2, 2, 8, 4).unwrap(), frame.decimate(2).unwrap(), frame.box_blur_luma(1).unwrap()] {
+        assert_eq!(derived.source, frame.source);
+        assert_eq!((derived.pts, derived.dts, derived.duration), (frame.pts, frame.dts, frame.duration));
+        assert_eq!(derived.time_base, frame.time_base);
+        assert_eq!(derived.sequence, frame.sequence);
+        assert_eq!(derived.metadata, frame.metadata);
+    }
+}
+
+#[test]
+fn test_descriptor_validate_crop() {
+    let desc = VideoFrameDescriptor::try_new(PixelFormat::I420, 64, 48).unwrap();
+    assert!(desc.validate().is_ok());
+
+    for (left, top, right, bottom) in
+        [(64, 0, 0, 0), (40, 0, 24, 0), (0, 0, 100, 0), (0, 48, 0, 0), (0, 30, 0, 18), (u32::MAX, 0, 1, 0), (0, 1, 0, u32::MAX)]
+    {
+        let mut desc = desc.clone();
+        desc.crop_left = left;
+        desc.crop_top = top;
+        desc.crop_right = right;
+        desc.crop_bottom = bottom;
+
+        assert!(matches!(desc.validate(), Err(Error::Invalid(_))));
+        assert!(matches!(Frame::video_creator().create_with_descriptor(desc.clone()), Err(Error::Invalid(_))));
+        assert!(matches!(VideoFrame::new_with_descriptor(desc.clone()), Err(Error::Invalid(_))));
+        assert!(matches!(Frame::video_creator().create_empty_with_descriptor(desc), Err(Error::Invalid(_))));
+    }
+
+    let mut desc = desc;
+    desc.crop_left = 32;
+    desc.crop_right = 31;
+    desc.crop_top = 24;
+    desc.crop_bottom = 23;
+    assert!(Frame::video_creator().create_with_descriptor(desc).is_ok());
+}
+
+#[test]
+fn test_standard_resolutions() {
+    let resolutions = Dimensions::standard_resolutions();
+    assert_eq!(resolutions.first(), Some(&Dimensions::SQCIF));
+    assert_eq!(resolutions.last(), Some(&Dimensions::UHD_8K));
+    assert!(resolutions.windows(2).all(|pair| pair[0].width.get() * pair[0].height.get() < pair[1].width.get() * pair[1].height.get()));
+
+    assert_eq!(Dimensions::FHD.name(), Some("FHD"));
+    assert_eq!(Dimensions::UHD_4K.name(), Some("UHD_4K"));
+    assert_eq!(Dimensions::new(1920, 1200).unwrap().name(), None);
+}
+
+#[test]
+fn test_descriptor_with_bt709() {
+    let desc = VideoFrameDescriptor::try_new(PixelFormat::I420, 1920, 1080).unwrap().with_bt709();
+    assert_eq!(desc.color_range, ColorRange::Video);
+    assert_eq!(desc.color_matrix, ColorMatrix::BT709);
+    assert_eq!(desc.color_primaries, ColorPrimaries::BT709);
+    assert_eq!(desc.color_transfer_characteristics, ColorTransferCharacteristics::BT709);
+
+    let desc = desc.with_color_range(ColorRange::Full);
+    assert_eq!(desc.color_range, ColorRange::Full);
+    assert_eq!(desc.color_matrix, ColorMatrix::BT709);
+}
+
+#[test]
+fn test_y16_frame() {
+    let width = 5;
+    let height = 3;
+    let buffer = vec![0u8; (width * height * 2) as usize];
+
+    let frame = Frame::video_creator().create_from_buffer(PixelFormat::Y16, width, height, buffer).unwrap();
+    let guard = frame.map().unwrap();
+    let planes = guard.planes().unwrap();
+    assert_eq!(planes.len(), 1);
+    assert_eq!(planes.plane_stride(0), Some(width as usize * 2));
+    assert_eq!(PixelFormat::Y16.calc_plane_row_bytes(0, width), width * 2);
+    assert_eq!(PixelFormat::Y16.depth(), 16);
 }
 
 #[test]
@@ -66,3 +160,765 @@ fn test_video_frame_from_buffer() {
     assert_eq!(desc.width().get(), 640);
     assert_eq!(desc.height().get(), 480);
 }
+
+#[test]
+fn test_luma_stats() {
+    let width = 256;
+    let height = 4;
+    let stride = 300;
+    let mut buffer = vec![0u8; (stride * height) as usize];
+    for row in buffer.chunks_mut(stride as usize) {
+        for (x, value) in row.iter_mut().enumerate() {
+            // padding is filled with 255 so including it would skew the mean
+            *value = if x < width as usize {
+                x as u8
+            } else {
+                255
+            };
+        }
+    }
+
+    let frame = Frame::video_creator().create_from_aligned_buffer(PixelFormat::Y8, width, height, stride, buffer.as_slice()).unwrap();
+    let (min, max, mean) = frame.luma_stats().unwrap();
+    assert_eq!(min, 0);
+    assert_eq!(max, 255);
+    assert!((mean - 127.5).abs() < 0.01, "mean: {}", mean);
+
+    let frame = Frame::video_creator().create(PixelFormat::RGBA32, 16, 16).unwrap();
+    assert_eq!(frame.luma_stats().unwrap(), (0, 0, 0.0));
+}
+
+#[test]
+fn test_rotate_to() {
+    let width = 6u32;
+    let height = 4u32;
+
+    // every sample holds its plane index and coordinates
+    let fill = |frame: &mut Frame| {
+        let mut guard = frame.map_mut().unwrap();
+        let mut planes = guard.planes_mut().unwrap();
+        for plane in 0..planes.len() {
+            let stride = planes.plane_stride(plane).unwrap();
+            let data = planes.plane_data_mut(plane).unwrap();
+            for (y, row) in data.chunks_mut(stride).enumerate() {
+                for (x, value) in row.iter_mut().enumerate() {
+                    *value = (plane * 64 + y * 16 + x) as u8;
+                }
+            }
+        }
+    };
+
+    // I420 and NV12 chroma follows the luma, UV pairs stay together
+    for (format, unit) in [(PixelFormat::I420, [1, 1, 1]), (PixelFormat::NV12, [1, 2, 0]), (PixelFormat::RGBA32, [4, 0, 0])] {
+        let mut src = Frame::video_creator().create(format, width, height).unwrap();
+        fill(&mut src);
+
+        let mut dst = Frame::video_creator().create(format, height, width).unwrap();
+        src.rotate_to(&mut dst, Rotation::Rotation90).unwrap();
+
+        let guard = dst.map().unwrap();
+        let planes = guard.planes().unwrap();
+        for plane in 0..planes.len() {
+            let stride = planes.plane_stride(plane).unwrap();
+            let data = planes.plane_data(plane).unwrap();
+            let unit = unit[plane];
+            let src_width = format.calc_plane_row_bytes(plane, width) as usize / unit;
+            let src_height = format.calc_plane_height(plane, height) as usize;
+            for y in 0..src_width {
+                for x in 0..src_height {
+                    for byte in 0..unit {
+                        let (src_x, src_y) = (y, src_height - 1 - x);
+                        assert_eq!(data[y * stride + x * unit + byte], (plane * 64 + src_y * 16 + src_x * unit + byte) as u8, "{:?}", format);
+                    }
+                }
+            }
+        }
+    }
+
+    let mut src = Frame::video_creator().create(PixelFormat::I420, width, height).unwrap();
+    fill(&mut src);
+    let mut dst = Frame::video_creator().create(PixelFormat::I420, width, height).unwrap();
+    src.rotate_to(&mut dst, Rotation::Rotation180).unwrap();
+    {
+        let guard = dst.map().unwrap();
+        let planes = guard.planes().unwrap();
+        let stride = planes.plane_stride(0).unwrap();
+        assert_eq!(planes.plane_data(0).unwrap()[0], (3 * 16 + 5) as u8);
+        assert_eq!(planes.plane_data(0).unwrap()[3 * stride + 5], 0);
+    }
+
+    // 270 undoes 90
+    let mut rotated = Frame::video_creator().create(PixelFormat::I420, height, width).unwrap();
+    let mut restored = Frame::video_creator().create(PixelFormat::I420, width, height).unwrap();
+    src.rotate_to(&mut rotated, Rotation::Rotation90).unwrap();
+    rotated.rotate_to(&mut restored, Rotation::Rotation270).unwrap();
+    {
+        let src_guard = src.map().unwrap();
+        let guard = restored.map().unwrap();
+        let (src_planes, planes) = (src_guard.planes().unwrap(), guard.planes().unwrap());
+        for plane in 0..3 {
+            let rows = PixelFormat::I420.calc_plane_height(plane, height) as usize;
+            let row_bytes = PixelFormat::I420.calc_plane_row_bytes(plane, width) as usize;
+            let (src_stride, stride) = (src_planes.plane_stride(plane).unwrap(), planes.plane_stride(plane).unwrap());
+            for y in 0..rows {
+                assert_eq!(
+                    src_planes.plane_data(plane).unwrap()[y * src_stride..][..row_bytes],
+                    planes.plane_data(plane).unwrap()[y * stride..][..row_bytes]
+                );
+            }
+        }
+    }
+
+    // quarter turns need swapped destination dimensions
+    assert!(matches!(src.rotate_to(&mut dst, Rotation::Rotation90), Err(Error::Invalid(_))));
+
+    let src = Frame::video_creator().create(PixelFormat::I010, width, height).unwrap();
+    let mut dst = Frame::video_creator().create(PixelFormat::I010, width, height).unwrap();
+    assert!(matches!(src.rotate_to(&mut dst, Rotation::Rotation180), Err(Error::Unsupported(_))));
+
+    let mut desc = VideoFrameDescriptor::try_new(PixelFormat::NV12, width, height).unwrap();
+    desc.rotation = Rotation::Rotation270;
+    desc.crop_left = 2;
+    let mut src = Frame::video_creator().create_with_descriptor(desc).unwrap();
+    fill(&mut src);
+    let upright = src.apply_rotation().unwrap();
+    let desc = upright.video_descriptor().unwrap();
+    assert_eq!((desc.width().get(), desc.height().get()), (height, width));
+    assert_eq!((desc.crop_left, desc.crop_bottom), (0, 2));
+    assert_eq!(desc.rotation, Rotation::None);
+
+    // timing is kept on both frame types
+    let mut desc = VideoFrameDescriptor::try_new(PixelFormat::I420, width, height).unwrap();
+    desc.rotation = Rotation::Rotation90;
+    let mut src = VideoFrame::new_with_descriptor(desc).unwrap();
+    src.pts = Some(3003);
+    assert_eq!(src.apply_rotation().unwrap().pts, Some(3003));
+}
+
+#[test]
+fn test_crop_to_roi() {
+    let width = 64;
+    let height = 32;
+    let mut frame = Frame::video_creator().create(PixelFormat::I420, width, height).unwrap();
+    {
+        let mut guard = frame.map_mut().unwrap();
+        let mut planes = guard.planes_mut().unwrap();
+        let stride = planes.plane_stride(0).unwrap();
+        let data = planes.plane_data_mut(0).unwrap();
+        for y in 0..height as usize {
+            for x in 0..width as usize {
+                data[y * stride + x] = (x + y) as u8;
+            }
+        }
+    }
+
+    assert!(frame.crop_to_roi().is_err());
+
+    frame.pts = Some(42);
+    frame.set_roi(8, 4, 16, 10);
+    let cropped = frame.crop_to_roi().unwrap();
+    let desc = cropped.video_descriptor().unwrap();
+    assert_eq!(desc.width().get(), 16);
+    assert_eq!(desc.height().get(), 10);
+    assert_eq!(cropped.pts, Some(42));
+    assert_eq!(cropped.roi(), None);
+
+    let guard = cropped.map().unwrap();
+    let planes = guard.planes().unwrap();
+    let stride = planes.plane_stride(0).unwrap();
+    let data = planes.plane_data(0).unwrap();
+    for y in 0..10 {
+        for x in 0..16 {
+            assert_eq!(data[y * stride + x], (x + 8 + y + 4) as u8);
+        }
+    }
+
+    // odd origin would split 4:2:0 chroma samples
+    frame.set_roi(3, 4, 16, 10);
+    assert!(frame.crop_to_roi().is_err());
+    frame.set_roi(56, 4, 16, 10);
+    assert!(frame.crop_to_roi().is_err());
+}
+
+#[test]
+fn test_extract_alpha() {
+    let width = 16;
+    let height = 4;
+    let mut frame = Frame::video_creator().create(PixelFormat::BGRA32, width, height).unwrap();
+    {
+        let mut guard = frame.map_mut().unwrap();
+        let mut planes = guard.planes_mut().unwrap();
+        let stride = planes.plane_stride(0).unwrap();
+        let data = planes.plane_data_mut(0).unwrap();
+        for y in 0..height as usize {
+            for x in 0..width as usize {
+                let pixel = &mut data[y * stride + x * 4..y * stride + x * 4 + 4];
+                pixel.copy_from_slice(&[1, 2, 3, (x * 16 + y) as u8]);
+            }
+        }
+    }
+    frame.pts = Some(1001);
+    let mut metadata = media_core::variant::Variant::new_dict();
+    metadata["exposure"] = 10.into();
+    frame.metadata = Some(metadata);
+
+    let alpha = frame.extract_alpha().unwrap();
+    assert_eq!(alpha.pts, frame.pts);
+    assert_eq!(alpha.metadata, frame.metadata);
+    let desc = alpha.video_descriptor().unwrap();
+    assert_eq!(desc.format, PixelFormat::Y8);
+    assert_eq!(desc.width().get(), width);
+    assert_eq!(desc.height().get(), height);
+
+    let guard = alpha.map().unwrap();
+    let planes = guard.planes().unwrap();
+    let stride = planes.plane_stride(0).unwrap();
+    let data = planes.plane_data(0).unwrap();
+    for y in 0..height as usize {
+        for x in 0..width as usize {
+            assert_eq!(data[y * stride + x], (x * 16 + y) as u8);
+        }
+    }
+
+    let frame = Frame::video_creator().create(PixelFormat::I420, width, height).unwrap();
+    assert!(frame.extract_alpha().is_err());
+}
+
+#[test]
+fn test_set_alpha_from_luma() {
+    let width = 16;
+    let height = 4;
+    let mut frame = Frame::video_creator().create(PixelFormat::RGBA32, width, height).unwrap();
+    {
+        let mut guard = frame.map_mut().unwrap();
+        let mut planes = guard.planes_mut().unwrap();
+        for pixel in planes.plane_data_mut(0).unwrap().chunks_exact_mut(4) {
+            pixel.copy_from_slice(&[10, 20, 30, 255]);
+        }
+    }
+
+    let mut mask = Frame::video_creator().create(PixelFormat::Y8, width, height).unwrap();
+    {
+        let mut guard = mask.map_mut().unwrap();
+        let mut planes = guard.planes_mut().unwrap();
+        let stride = planes.plane_stride(0).unwrap();
+        let data = planes.plane_data_mut(0).unwrap();
+        for y in 0..height as usize {
+            for x in 0..width as usize {
+                data[y * stride + x] = (x * 16 + y) as u8;
+            }
+        }
+    }
+
+    frame.set_alpha_from_luma(&mask).unwrap();
+    {
+        let guard = frame.map().unwrap();
+        let planes = guard.planes().unwrap();
+        let stride = planes.plane_stride(0).unwrap();
+        let data = planes.plane_data(0).unwrap();
+        for y in 0..height as usize {
+            for x in 0..width as usize {
+                assert_eq!(&data[y * stride + x * 4..y * stride + x * 4 + 4], &[10, 20, 30, (x * 16 + y) as u8]);
+            }
+        }
+    }
+
+    // round trip through extract_alpha
+    let alpha = frame.extract_alpha().unwrap();
+    assert_eq!(alpha.plane_crc32(0).unwrap(), mask.plane_crc32(0).unwrap());
+
+    let mask = Frame::video_creator().create(PixelFormat::Y8, width, height * 2).unwrap();
+    assert!(frame.set_alpha_from_luma(&mask).is_err());
+
+    let mask = Frame::video_creator().create(PixelFormat::I420, width, height).unwrap();
+    assert!(frame.set_alpha_from_luma(&mask).is_err());
+
+    let mut frame = Frame::video_creator().create(PixelFormat::I420, width, height).unwrap();
+    let mask = Frame::video_creator().create(PixelFormat::Y8, width, height).unwrap();
+    assert!(frame.set_alpha_from_luma(&mask).is_err());
+}
+
+#[cfg(any(target_os = "macos", target_os = "ios"))]
+#[test]
+fn test_pixel_buffer_color_range_fallback() {
+    use std::num::NonZeroU32;
+
+    // I420 has no unspecified range pixel buffer format, video range is used
+    let mut desc = VideoFrameDescriptor::new(PixelFormat::I420, NonZeroU32::new(64).unwrap(), NonZeroU32::new(64).unwrap());
+    desc.color_range = ColorRange::from(usize::MAX);
+    assert_eq!(desc.color_range, ColorRange::Unspecified);
+
+    let frame = VideoFrame::new_pixel_buffer_with_descriptor(desc).unwrap();
+    assert_eq!(frame.descriptor().format, PixelFormat::I420);
+}
+
+#[cfg(any(target_os = "macos", target_os = "ios"))]
+#[test]
+fn test_pixel_buffer_crop() {
+    let mut desc = VideoFrameDescriptor::try_new(PixelFormat::NV12, 64, 48).unwrap();
+    desc.crop_left = 8;
+    desc.crop_top = 4;
+    desc.crop_right = 8;
+    desc.crop_bottom = 4;
+
+    // the buffer covers the full dimensions, crop stays in the descriptor
+    let frame = Frame::video_creator().create_pixel_buffer_with_descriptor(desc).unwrap();
+    let guard = frame.map().unwrap();
+    for (plane_index, (row_bytes, height)) in [(64, 48), (64, 24)].into_iter().enumerate() {
+        let plane = guard.plane(plane_index).unwrap();
+        assert_eq!(plane.row_bytes, row_bytes);
+        assert_eq!(plane.height, height);
+        assert!(plane.stride >= row_bytes);
+        assert!(plane.data.len() >= plane.stride * (height as usize - 1) + row_bytes);
+    }
+}
+
+#[cfg(any(target_os = "macos", target_os = "ios"))]
+#[test]
+fn test_pixel_buffer_invalid_crop() {
+    let mut desc = VideoFrameDescriptor::try_new(PixelFormat::NV12, 64, 48).unwrap();
+    desc.crop_left = 40;
+    desc.crop_right = 24;
+
+    assert!(matches!(Frame::video_creator().create_pixel_buffer_with_descriptor(desc.clone()), Err(Error::Invalid(_))));
+    assert!(matches!(VideoFrame::new_pixel_buffer_with_descriptor(desc), Err(Error::Invalid(_))));
+}
+
+#[test]
+fn test_fill_from_fn() {
+    let width = 4;
+    let height = 4;
+    let stride = 20;
+    let buffer = vec![0xAAu8; (stride * height) as usize];
+
+    let mut frame = Frame::video_creator().create_from_aligned_buffer(PixelFormat::BGRA32, width, height, stride, buffer).unwrap();
+    frame.fill_from_fn(|x, y| [x as u8, y as u8, (x + y) as u8, 0xFF]).unwrap();
+
+    let guard = frame.map().unwrap();
+    let planes = guard.planes().unwrap();
+    let data = planes.plane_data(0).unwrap();
+    for (y, row) in data.chunks(stride as usize).enumerate() {
+        for (x, pixel) in row[..width as usize * 4].chunks_exact(4).enumerate() {
+            assert_eq!(pixel, [x as u8, y as u8, (x + y) as u8, 0xFF]);
+        }
+        assert!(row[width as usize * 4..].iter().all(|&v| v == 0xAA));
+    }
+}
+
+#[test]
+fn test_to_v4l2_buffer() {
+    let width = 20;
+    let height = 4;
+    let stride = 32;
+    let mut buffer = vec![0xAAu8; (stride * height + stride * height / 2) as usize];
+    let (luma, chroma) = buffer.split_at_mut((stride * height) as usize);
+    for (y, row) in luma.chunks_mut(stride as usize).enumerate() {
+        for (x, value) in row[..width as usize].iter_mut().enumerate() {
+            *value = (y * width as usize + x) as u8;
+        }
+    }
+    for (y, row) in chroma.chunks_mut(stride as usize).enumerate() {
+        for (x, value) in row[..width as usize].iter_mut().enumerate() {
+            *value = 0x80 + (y * width as usize + x) as u8;
+        }
+    }
+
+    let frame = Frame::video_creator().create_from_aligned_buffer(PixelFormat::NV12, width, height, stride, buffer).unwrap();
+    let output = frame.to_v4l2_buffer().unwrap();
+    assert_eq!(output.len(), (width * height * 3 / 2) as usize);
+
+    let (luma, chroma) = output.split_at((width * height) as usize);
+    for (i, value) in luma.iter().enumerate() {
+        assert_eq!(*value, i as u8);
+    }
+    for (i, value) in chroma.iter().enumerate() {
+        assert_eq!(*value, 0x80 + i as u8);
+    }
+}
+
+#[test]
+fn test_convert_matrix() {
+    let mut desc = VideoFrameDescriptor::try_new(PixelFormat::I420, 4, 4).unwrap();
+    desc.color_range = ColorRange::Video;
+    desc.color_matrix = ColorMatrix::SMPTE170M;
+
+    // saturated red in BT.601 video range
+    let mut frame = Frame::video_creator().create_with_descriptor(desc).unwrap();
+    {
+        let mut guard = frame.map_mut().unwrap();
+        let mut planes = guard.planes_mut().unwrap();
+        for (index, value) in [(0, 82u8), (1, 90), (2, 240)] {
+            planes.plane_data_mut(index).unwrap().fill(value);
+        }
+    }
+
+    let converted = frame.convert_matrix(ColorMatrix::BT709).unwrap();
+    assert_eq!(converted.video_descriptor().unwrap().color_matrix, ColorMatrix::BT709);
+
+    // the same red in BT.709 has lower luma and less negative Cb
+    let guard = converted.map().unwrap();
+    let planes = guard.planes().unwrap();
+    for (index, expected) in [(0, 63i32), (1, 102), (2, 240)] {
+        let value = planes.plane_data(index).unwrap()[0] as i32;
+        assert!((value - expected).abs() <= 1, "plane {}: {}", index, value);
+    }
+}
+
+#[test]
+fn test_split_stitch_grid() {
+    let mut desc = VideoFrameDescriptor::try_new(PixelFormat::I420, 64, 64).unwrap();
+    desc.color_range = ColorRange::Video;
+    desc.color_matrix = ColorMatrix::SMPTE170M;
+
+    let mut frame = Frame::video_creator().create_with_descriptor(desc).unwrap();
+    {
+        let mut guard = frame.map_mut().unwrap();
+        let mut planes = guard.planes_mut().unwrap();
+        for index in 0..3 {
+            for (i, value) in planes.plane_data_mut(index).unwrap().iter_mut().enumerate() {
+                *value = (i * 7 + index * 31) as u8;
+            }
+        }
+    }
+
+    let tiles = frame.split_grid(2, 2).unwrap();
+    assert_eq!(tiles.len(), 4);
+    let processed: Vec<_> = tiles.iter().map(|tile| tile.convert_matrix(ColorMatrix::BT709).unwrap()).collect();
+    let stitched = Frame::stitch_grid(&processed, 2, 2).unwrap();
+    let expected = frame.convert_matrix(ColorMatrix::BT709).unwrap();
+
+    let stitched_guard = stitched.map().unwrap();
+    let stitched_planes = stitched_guard.planes().unwrap();
+    let expected_guard = expected.map().unwrap();
+    let expected_planes = expected_guard.planes().unwrap();
+    for index in 0..3 {
+        let width = if index == 0 {
+            64
+        } else {
+            32
+        };
+        let stitched_rows = stitched_planes.plane_data(index).unwrap().chunks(stitched_planes.plane_stride(index).unwrap());
+        let expected_rows = expected_planes.plane_data(index).unwrap().chunks(expected_planes.plane_stride(index).unwrap());
+        for (stitched_row, expected_row) in stitched_rows.zip(expected_rows) {
+            assert_eq!(stitched_row[..width], expected_row[..width]);
+        }
+    }
+}
+
+#[test]
+fn test_box_blur_luma() {
+    let mut frame = Frame::video_creator().create(PixelFormat::I420, 8, 8).unwrap();
+    {
+        let mut guard = frame.map_mut().unwrap();
+        let mut planes = guard.planes_mut().unwrap();
+        let stride = planes.plane_stride(0).unwrap();
+        let luma = planes.plane_data_mut(0).unwrap();
+        luma.fill(0);
+        luma[4 * stride + 4] = 255;
+        planes.plane_data_mut(1).unwrap().fill(0x40);
+        planes.plane_data_mut(2).unwrap().fill(0xC0);
+    }
+
+    let blurred = frame.box_blur_luma(1).unwrap();
+
+    // the impulse spreads evenly over its 3x3 neighborhood
+    let guard = blurred.map().unwrap();
+    let planes = guard.planes().unwrap();
+    let stride = planes.plane_stride(0).unwrap();
+    let luma = planes.plane_data(0).unwrap();
+    for y in 0..8 {
+        for x in 0..8 {
+            let expected = if (3..=5).contains(&x) && (3..=5).contains(&y) {
+                28
+            } else {
+                0
+            };
+            assert_eq!(luma[y * stride + x], expected, "({}, {})", x, y);
+        }
+    }
+    assert!(planes.plane_data(1).unwrap()[..4].iter().all(|&v| v == 0x40));
+    assert!(planes.plane_data(2).unwrap()[..4].iter().all(|&v| v == 0xC0));
+}
+
+#[test]
+fn test_box_blur_luma_large_radius() {
+    let mut frame = Frame::video_creator().create(PixelFormat::Y8, 8, 8).unwrap();
+    frame.map_mut().unwrap().planes_mut().unwrap().plane_data_mut(0).unwrap().fill(200);
+
+    // the window sum exceeds 32 bits, a flat picture stays flat
+    let blurred = frame.box_blur_luma(4096).unwrap();
+    let guard = blurred.map().unwrap();
+    let planes = guard.planes().unwrap();
+    let stride = planes.plane_stride(0).unwrap();
+    let luma = planes.plane_data(0).unwrap();
+    assert!(luma.chunks(stride).all(|row| row[..8].iter().all(|&v| v == 200)));
+}
+
+#[test]
+fn test_plane_view() {
+    let width = 10;
+    let height = 4;
+    let stride = 32;
+    let buffer = vec![0u8; (stride * height) as usize];
+
+    let frame = Frame::video_creator().create_from_aligned_buffer(PixelFormat::RGB24, width, height, stride, buffer).unwrap();
+    let guard = frame.map().unwrap();
+    let plane = guard.plane(0).unwrap();
+    assert_eq!(plane.stride, stride as usize);
+    assert_eq!(plane.height, height);
+    assert_eq!(plane.row_bytes, width as usize * 3);
+    assert!(plane.row_bytes < plane.stride);
+    assert_eq!(plane.data.len(), (stride * height) as usize);
+    assert!(guard.plane(1).is_none());
+}
+
+#[cfg(any(target_os = "macos", target_os = "ios"))]
+#[test]
+fn test_pixel_buffer_nested_map() {
+    let mut frame = VideoFrame::new_pixel_buffer(PixelFormat::NV12, 64, 64).unwrap();
+    {
+        let mut guard = frame.map_mut().unwrap();
+        let mut planes = guard.planes_mut().unwrap();
+        planes.plane_data_mut(0).unwrap().fill(0x5A);
+    }
+
+    let outer = frame.map().unwrap();
+    let inner = frame.map().unwrap();
+    assert!(inner.planes().unwrap().plane_data(0).is_some());
+    drop(inner);
+
+    // the outer guard still holds the lock, so its planes stay valid
+    let planes = outer.planes().unwrap();
+    assert!(planes.plane_data(0).unwrap()[..64].iter().all(|&v| v == 0x5A));
+}
+
+#[test]
+fn test_chroma_planes() {
+    use media_core::frame::ChromaPlanes;
+
+    let width = 13;
+    let height = 7;
+    let mut frame = Frame::video_creator().create(PixelFormat::NV12, width, height).unwrap();
+    {
+        let mut guard = frame.map_mut().unwrap();
+        let mut planes = guard.planes_mut().unwrap();
+        for (i, value) in planes.plane_data_mut(1).unwrap().iter_mut().enumerate() {
+            *value = i as u8;
+        }
+    }
+
+    let guard = frame.map().unwrap();
+    let Some(ChromaPlanes::Interleaved(plane)) = guard.chroma_planes() else {
+        panic!("NV12 chroma is interleaved");
+    };
+    assert_eq!(guard.chroma_planes().unwrap().dimensions(), (width.div_ceil(2), height.div_ceil(2)));
+    assert_eq!(plane.row_bytes, width.div_ceil(2) as usize * 2);
+    for (y, row) in plane.data.chunks(plane.stride).take(plane.height as usize).enumerate() {
+        for (x, value) in row[..plane.row_bytes].iter().enumerate() {
+            assert_eq!(*value, (y * plane.stride + x) as u8);
+        }
+    }
+
+    // YV12 stores V before U
+    let frame = Frame::video_creator().create(PixelFormat::YV12, width, height).unwrap();
+    let guard = frame.map().unwrap();
+    let Some(ChromaPlanes::Separate(u, v)) = guard.chroma_planes() else {
+        panic!("YV12 chroma is planar");
+    };
+    let planes = guard.planes().unwrap();
+    assert_eq!(u.data.as_ptr(), planes.plane_data(2).unwrap().as_ptr());
+    assert_eq!(v.data.as_ptr(), planes.plane_data(1).unwrap().as_ptr());
+    assert_eq!((u.row_bytes, u.height), (width.div_ceil(2) as usize, height.div_ceil(2)));
+
+    let frame = Frame::video_creator().create(PixelFormat::RGBA32, width, height).unwrap();
+    assert!(frame.map().unwrap().chroma_planes().is_none());
+}
+
+#[test]
+fn test_pixel_format_fourcc() {
+    for fourcc in ["YUYV", "NV12"] {
+        let format = PixelFormat::from_fourcc_str(fourcc).unwrap();
+        assert_eq!(format.fourcc_str(), Some(fourcc));
+    }
+    assert_eq!(PixelFormat::from_fourcc_str("YUYV").unwrap(), PixelFormat::YUYV);
+    assert_eq!(PixelFormat::from_fourcc_str("I420").unwrap().fourcc_str(), Some("YU12"));
+    assert_eq!(PixelFormat::from_fourcc_str("Y16").unwrap(), PixelFormat::Y16);
+    assert_eq!(PixelFormat::I422.fourcc_str(), Some("422P"));
+    assert_eq!(PixelFormat::from_fourcc_str("YU16").unwrap(), PixelFormat::I422);
+    assert_eq!(PixelFormat::ARGB64.fourcc_str(), None);
+
+    assert!(PixelFormat::from_fourcc_str("ABCD").is_err());
+    assert!(PixelFormat::from_fourcc_str("NV12X").is_err());
+    assert!(PixelFormat::from_fourcc_str("").is_err());
+}
+
+#[test]
+fn test_hdr_metadata() {
+    // BT.2020 primaries, D65, 1000 / 0.005 cd/m2
+    let mut mdcv = Vec::new();
+    for value in [8500u16, 39850, 6550, 2300, 35400, 14600, 15635, 16450] {
+        mdcv.extend_from_slice(&value.to_be_bytes());
+    }
+    mdcv.extend_from_slice(&10_000_000u32.to_be_bytes());
+    mdcv.extend_from_slice(&50u32.to_be_bytes());
+
+    let mastering_display = MasteringDisplay::from_mdcv(&mdcv).unwrap();
+    assert_eq!(mastering_display.display_primaries, [(8500, 39850), (6550, 2300), (35400, 14600)]);
+    assert_eq!(mastering_display.white_point, (15635, 16450));
+    assert_eq!(mastering_display.max_luminance, 10_000_000);
+    assert_eq!(mastering_display.min_luminance, 50);
+    assert!(MasteringDisplay::from_mdcv(&mdcv[..20]).is_err());
+
+    let content_light_level = ContentLightLevel::from_clli(&[0x03, 0xE8, 0x01, 0x90]).unwrap();
+    assert_eq!((content_light_level.max_cll, content_light_level.max_fall), (1000, 400));
+    assert!(ContentLightLevel::from_clli(&[0x03, 0xE8]).is_err());
+
+    let mut desc = VideoFrameDescriptor::try_new(PixelFormat::P010, 64, 64).unwrap().with_bt2020_pq();
+    desc.mastering_display = Some(mastering_display);
+    desc.content_light_level = Some(content_light_level);
+
+    let frame = Frame::video_creator().create_with_descriptor(desc.clone()).unwrap().into_owned();
+    let media_core::FrameDescriptor::Video(frame_desc) = frame.descriptor() else {
+        panic!("not video frame");
+    };
+    assert_eq!(frame_desc, &desc);
+    assert_eq!(frame_desc.mastering_display, Some(mastering_display));
+}
+
+#[test]
+fn test_zero_padding() {
+    let (width, height, stride) = (5u32, 3u32, 24usize);
+    let mut frame =
+        Frame::video_creator().create_from_aligned_buffer(PixelFormat::RGB24, width, height, stride as u32, vec![0xEEu8; stride * 3]).unwrap();
+    {
+        let mut guard = frame.map_mut().unwrap();
+        let mut planes = guard.planes_mut().unwrap();
+        for (y, row) in planes.plane_data_mut(0).unwrap().chunks_mut(stride).enumerate() {
+            for (x, value) in row[..15].iter_mut().enumerate() {
+                *value = (y * 15 + x) as u8;
+            }
+        }
+    }
+
+    frame.zero_padding().unwrap();
+
+    let guard = frame.map().unwrap();
+    let plane = guard.plane(0).unwrap();
+    for (y, row) in plane.data.chunks(stride).enumerate() {
+        assert!(row[..15].iter().enumerate().all(|(x, &value)| value == (y * 15 + x) as u8));
+        assert!(row[15..].iter().all(|&value| value == 0));
+    }
+}
+
+#[test]
+fn test_decimate() {
+    let mut frame = Frame::video_creator().create(PixelFormat::I420, 64, 64).unwrap();
+    {
+        let mut guard = frame.map_mut().unwrap();
+        let mut planes = guard.planes_mut().unwrap();
+        for plane_index in 0..3 {
+            let stride = planes.plane_stride(plane_index).unwrap();
+            for (y, row) in planes.plane_data_mut(plane_index).unwrap().chunks_mut(stride).enumerate() {
+                for (x, value) in row.iter_mut().enumerate() {
+                    *value = (x + y * 3 + plane_index * 50) as u8;
+                }
+            }
+        }
+    }
+
+    let decimated = frame.decimate(4).unwrap();
+    let media_core::FrameDescriptor::Video(desc) = decimated.descriptor() else {
+        panic!("not video frame");
+    };
+    assert_eq!((desc.width().get(), desc.height().get()), (16, 16));
+
+    let guard = decimated.map().unwrap();
+    let luma = guard.plane(0).unwrap();
+    assert_eq!(luma.data[0], 0);
+    assert_eq!(luma.data[5], 20);
+    assert_eq!(luma.data[2 * luma.stride + 3], (12 + 2 * 4 * 3) as u8);
+
+    // chroma is sampled from the chroma plane
+    let chroma = guard.plane(1).unwrap();
+    assert_eq!(chroma.row_bytes, 8);
+    assert_eq!(chroma.data[chroma.stride + 1], (4 + 4 * 3 + 50) as u8);
+
+    assert!(frame.decimate(0).is_err());
+    assert!(frame.decimate(65).is_err());
+}
+
+#[test]
+fn test_create_from_separate_planes() {
+    let desc = VideoFrameDescriptor::try_new(PixelFormat::I420, 8, 4).unwrap();
+
+    // padded strides, each plane in its own buffer
+    let y: Vec<u8> = (0..16 * 4).map(|i| (i * 3) as u8).collect();
+    let u = vec![90u8; 8 * 2];
+    let v = vec![200u8; 8 * 2];
+    let frame = Frame::video_creator().create_from_separate_planes(desc.clone(), &[(&y, 16, 4), (&u, 8, 2), (&v, 8, 2)]).unwrap();
+
+    {
+        let guard = frame.map().unwrap();
+        let luma = guard.plane(0).unwrap();
+        assert_eq!(luma.stride, 16);
+        assert_eq!(luma.data[luma.stride + 2], 54);
+        assert_eq!(guard.plane(2).unwrap().data[0], 200);
+    }
+
+    // same content in a contiguous frame converts the same
+    let mut reference = Frame::video_creator().create_with_descriptor(desc.clone()).unwrap();
+    {
+        let mut guard = reference.map_mut().unwrap();
+        let mut planes = guard.planes_mut().unwrap();
+        for (plane_index, (src, src_stride, width)) in [(&y, 16, 8), (&u, 8, 4), (&v, 8, 4)].into_iter().enumerate() {
+            let stride = planes.plane_stride(plane_index).unwrap();
+            for (dst_row, src_row) in planes.plane_data_mut(plane_index).unwrap().chunks_mut(stride).zip(src.chunks(src_stride)) {
+                dst_row[..width].copy_from_slice(&src_row[..width]);
+            }
+        }
+    }
+
+    let mut rgb = Frame::video_creator().create(PixelFormat::RGB24, 8, 4).unwrap();
+    let mut reference_rgb = Frame::video_creator().create(PixelFormat::RGB24, 8, 4).unwrap();
+    frame.convert_video_to(&mut rgb).unwrap();
+    reference.convert_video_to(&mut reference_rgb).unwrap();
+    let guard = rgb.map().unwrap();
+    let reference_guard = reference_rgb.map().unwrap();
+    let (plane, reference_plane) = (guard.plane(0).unwrap(), reference_guard.plane(0).unwrap());
+    for row in 0..4 {
+        assert_eq!(&plane.data[row * plane.stride..][..24], &reference_plane.data[row * reference_plane.stride..][..24]);
+    }
+
+    // too few planes, short stride and short buffers are rejected
+    assert!(Frame::video_creator().create_from_separate_planes(desc.clone(), &[(&y, 16, 4), (&u, 8, 2)]).is_err());
+    assert!(Frame::video_creator().create_from_separate_planes(desc.clone(), &[(&y, 4, 4), (&u, 8, 2), (&v, 8, 2)]).is_err());
+    assert!(Frame::video_creator().create_from_separate_planes(desc, &[(&y, 16, 4), (&u[..8], 8, 2), (&v, 8, 2)]).is_err());
+}
+
+#[test]
+fn test_plane_crc32() {
+    let mut frame = Frame::video_creator().create(PixelFormat::NV12, 32, 16).unwrap();
+    {
+        let mut guard = frame.map_mut().unwrap();
+        let mut planes = guard.planes_mut().unwrap();
+        for plane_index in 0..2 {
+            for (i, value) in planes.plane_data_mut(plane_index).unwrap().iter_mut().enumerate() {
+                *value = (i * 7 + plane_index) as u8;
+            }
+        }
+    }
+    let luma_crc = frame.plane_crc32(0).unwrap();
+    let chroma_crc = frame.plane_crc32(1).unwrap();
+
+    {
+        let mut guard = frame.map_mut().unwrap();
+        let mut planes = guard.planes_mut().unwrap();
+        let stride = planes.plane_stride(1).unwrap();
+        planes.plane_data_mut(1).unwrap()[stride * 3 + 5] ^= 0xFF;
+    }
+
+    // only the corrupted plane changes
+    assert_eq!(frame.plane_crc32(0).unwrap(), luma_crc);
+    assert_ne!(frame.plane_crc32(1).unwrap(), chroma_crc);
+    assert!(frame.plane_crc32(2).is_err());
+}
@@ -0,0 +1,36 @@
+#![cfg(feature = "bytes")]
+
+use bytes::Bytes;
+use media_core::{error::Error, frame::Frame, video::*};
+
+#[test]
+fn test_frame_from_bytes() {
+    let (width, height, stride) = (6u32, 4u32, 8u32);
+    // I420 with padded rows, the chroma planes follow the luma plane
+    let luma_size = (stride * height) as usize;
+    let chroma_size = (stride / 2 * height / 2) as usize;
+    let payload: Vec<u8> = (0..luma_size + chroma_size * 2).map(|i| i as u8).collect();
+    let bytes = Bytes::from(payload.clone());
+    let planes = [(0, stride), (luma_size, stride / 2), (luma_size + chroma_size, stride / 2)];
+
+    let mut frame = Frame::video_creator().create_from_bytes(PixelFormat::I420, width, height, bytes.clone(), &planes).unwrap();
+    // the frame holds a reference to the same allocation
+    assert!(!bytes.is_unique());
+    {
+        let guard = frame.map().unwrap();
+        let plane = guard.plane(0).unwrap();
+        assert_eq!(plane.data.as_ptr(), bytes.as_ptr());
+        assert_eq!(plane.stride, stride as usize);
+        assert_eq!(guard.plane(2).unwrap().data, &payload[luma_size + chroma_size..]);
+    }
+    assert!(matches!(frame.map_mut(), Err(Error::Unsupported(_))));
+
+    // clones and owned frames share it too
+    let owned = frame.clone().into_owned();
+    assert_eq!(owned.map().unwrap().plane(0).unwrap().data.as_ptr(), bytes.as_ptr());
+    drop(frame);
+    drop(owned);
+    assert!(bytes.is_unique());
+
+    assert!(VideoFrame::from_bytes(PixelFormat::I420, width, height, bytes.slice(..luma_size), &planes).is_err());
+}
@@ -1,4 +1,4 @@
-use media_core::{frame::Frame, video::*};
+use media_core::{error::Error, frame::Frame, video::*};
 
 fn test_video_convert(src_fmt: PixelFormat, dst_fmt: PixelFormat, width: u32, height: u32) {
     let input_frame = Frame::video_creator().create(src_fmt, width, height).unwrap();
@@ -39,3 +39,975 @@ fn test_same_format() {
     test_video_convert(PixelFormat::NV12, PixelFormat::NV12, 640, 480);
     test_video_convert(PixelFormat::RGBA32, PixelFormat::RGBA32, 640, 480);
 }
+
+#[test]
+fn test_gray_to_rgb() {
+    let width = 16;
+    let height = 8;
+
+    let mut y8_frame = Frame::video_creator().create(PixelFormat::Y8, width, height).unwrap();
+    {
+        let mut guard = y8_frame.map_mut().unwrap();
+        let mut planes = guard.planes_mut().unwrap();
+        planes.plane_data_mut(0).unwrap().fill(100);
+    }
+
+    let mut rgb_frame = Frame::video_creator().create(PixelFormat::RGB24, width, height).unwrap();
+    y8_frame.convert_video_to(&mut rgb_frame).unwrap();
+    let guard = rgb_frame.map().unwrap();
+    let planes = guard.planes().unwrap();
+    let stride = planes.plane_stride(0).unwrap();
+    let data = planes.plane_data(0).unwrap();
+    for row in data.chunks(stride).take(height as usize) {
+        assert!(row[..width as usize * 3].iter().all(|&v| v == 100));
+    }
+
+    let mut ya8_frame = Frame::video_creator().create(PixelFormat::YA8, width, height).unwrap();
+    {
+        let mut guard = ya8_frame.map_mut().unwrap();
+        let mut planes = guard.planes_mut().unwrap();
+        planes.plane_data_mut(0).unwrap().fill(200);
+        planes.plane_data_mut(1).unwrap().fill(64);
+    }
+
+    let mut bgra_frame = Frame::video_creator().create(PixelFormat::BGRA32, width, height).unwrap();
+    ya8_frame.convert_video_to(&mut bgra_frame).unwrap();
+    let guard = bgra_frame.map().unwrap();
+    let planes = guard.planes().unwrap();
+    let stride = planes.plane_stride(0).unwrap();
+    let data = planes.plane_data(0).unwrap();
+    for row in data.chunks(stride).take(height as usize) {
+        for pixel in row[..width as usize * 4].chunks_exact(4) {
+            assert_eq!(pixel, [200, 200, 200, 64]);
+        }
+    }
+}
+
+fn pixel_formats() -> Vec<PixelFormat> {
+    (0..).map_while(|i: usize| PixelFormat::try_from(i).ok()).collect()
+}
+
+fn can_convert(src_fmt: PixelFormat, dst_fmt: PixelFormat) -> bool {
+    let input_frame = Frame::video_creator().create(src_fmt, 16, 16).unwrap();
+    let mut output_frame = Frame::video_creator().create(dst_fmt, 16, 16).unwrap();
+
+    match input_frame.convert_video_to(&mut output_frame) {
+        Ok(()) => true,
+        Err(Error::Unsupported(_)) => false,
+        Err(err) => panic!("convert from {:?} to {:?} failed: {:?}", src_fmt, dst_fmt, err),
+    }
+}
+
+// Invariants of the conversion table:
+// - RGBA32 and BGRA32 are interchangeable as both source and destination
+// - RGB24 and BGR24 are interchangeable as destination
+// - 8-bit YUV formats that convert to RGBA32 can also be converted from RGBA32
+// - conversions between 8-bit YUV formats exist in both directions
+#[test]
+fn test_conversion_table_invariants() {
+    let formats = pixel_formats();
+    let is_yuv8 = |fmt: &PixelFormat| fmt.is_yuv() && fmt.depth() == 8;
+    let mut violations = Vec::new();
+
+    for &fmt in &formats {
+        // same format copies are not part of the table
+        let is_rgba = matches!(fmt, PixelFormat::BGRA32 | PixelFormat::RGBA32);
+        let is_rgb = matches!(fmt, PixelFormat::BGR24 | PixelFormat::RGB24);
+
+        if !is_rgba && can_convert(fmt, PixelFormat::BGRA32) != can_convert(fmt, PixelFormat::RGBA32) {
+            violations.push(format!("{:?} -> BGRA32/RGBA32", fmt));
+        }
+        if !is_rgba && can_convert(PixelFormat::BGRA32, fmt) != can_convert(PixelFormat::RGBA32, fmt) {
+            violations.push(format!("BGRA32/RGBA32 -> {:?}", fmt));
+        }
+        if !is_rgb && can_convert(fmt, PixelFormat::BGR24) != can_convert(fmt, PixelFormat::RGB24) {
+            violations.push(format!("{:?} -> BGR24/RGB24", fmt));
+        }
+        if is_yuv8(&fmt) && can_convert(fmt, PixelFormat::RGBA32) != can_convert(PixelFormat::RGBA32, fmt) {
+            violations.push(format!("{:?} <-> RGBA32", fmt));
+        }
+    }
+
+    for &src_fmt in formats.iter().filter(|fmt| is_yuv8(fmt)) {
+        for &dst_fmt in formats.iter().filter(|fmt| is_yuv8(fmt) && **fmt != src_fmt) {
+            if can_convert(src_fmt, dst_fmt) && !can_convert(dst_fmt, src_fmt) {
+                violations.push(format!("{:?} -> {:?} without mirror", src_fmt, dst_fmt));
+            }
+        }
+    }
+
+    assert!(violations.is_empty(), "conversion table invariants violated: {:#?}", violations);
+}
+
+#[test]
+fn test_convert_into_slice() {
+    let width = 64;
+    let height = 48;
+    let mut input_frame = Frame::video_creator().create(PixelFormat::NV12, width, height).unwrap();
+    {
+        let mut guard = input_frame.map_mut().unwrap();
+        let mut planes = guard.planes_mut().unwrap();
+        for index in 0..2 {
+            for (i, value) in planes.plane_data_mut(index).unwrap().iter_mut().enumerate() {
+                *value = (i * 13 + index * 71) as u8;
+            }
+        }
+    }
+
+    let mut output_frame = Frame::video_creator().create(PixelFormat::BGRA32, width, height).unwrap();
+    input_frame.convert_video_to(&mut output_frame).unwrap();
+
+    // padded rows, reused across conversions
+    let stride = width as usize * 4 + 32;
+    let mut buffer = vec![0u8; stride * height as usize];
+    input_frame.convert_into_slice(PixelFormat::BGRA32, &mut buffer, stride).unwrap();
+
+    let guard = output_frame.map().unwrap();
+    let planes = guard.planes().unwrap();
+    let expected_stride = planes.plane_stride(0).unwrap();
+    let expected = planes.plane_data(0).unwrap();
+    for row in 0..height as usize {
+        let row_bytes = width as usize * 4;
+        assert_eq!(buffer[row * stride..row * stride + row_bytes], expected[row * expected_stride..row * expected_stride + row_bytes]);
+    }
+
+    assert!(input_frame.convert_into_slice(PixelFormat::BGRA32, &mut buffer[..stride], stride).is_err());
+    assert!(input_frame.convert_into_slice(PixelFormat::BGRA32, &mut buffer, width as usize).is_err());
+}
+
+#[test]
+fn test_converter() {
+    let converter = Converter::new(PixelFormat::NV12, PixelFormat::BGRA32).unwrap();
+    let mut output_frame = Frame::video_creator().create(PixelFormat::BGRA32, 64, 48).unwrap();
+
+    for value in [0x10u8, 0x80, 0xEB] {
+        let mut input_frame = Frame::video_creator().create(PixelFormat::NV12, 64, 48).unwrap();
+        {
+            let mut guard = input_frame.map_mut().unwrap();
+            let mut planes = guard.planes_mut().unwrap();
+            planes.plane_data_mut(0).unwrap().fill(value);
+            planes.plane_data_mut(1).unwrap().fill(0x80);
+        }
+
+        converter.convert(&input_frame, &mut output_frame).unwrap();
+
+        let mut expected_frame = Frame::video_creator().create(PixelFormat::BGRA32, 64, 48).unwrap();
+        input_frame.convert_video_to(&mut expected_frame).unwrap();
+        assert_eq!(output_frame.map().unwrap().planes().unwrap().plane_data(0), expected_frame.map().unwrap().planes().unwrap().plane_data(0));
+    }
+
+    // the formats are checked against the ones the converter was built for
+    let input_frame = Frame::video_creator().create(PixelFormat::I420, 64, 48).unwrap();
+    assert!(converter.convert(&input_frame, &mut output_frame).is_err());
+
+    assert!(matches!(Converter::new(PixelFormat::RGB30, PixelFormat::NV12), Err(Error::Unsupported(_))));
+}
+
+#[test]
+fn test_y16_to_y8() {
+    let samples = [0u16, 32768, 65535, 900, 1500, 2500];
+    let buffer: Vec<u8> = samples.iter().flat_map(|sample| sample.to_le_bytes()).collect();
+    let input_frame = Frame::video_creator().create_from_buffer(PixelFormat::Y16, samples.len() as u32, 1, buffer).unwrap();
+    let mut output_frame = Frame::video_creator().create(PixelFormat::Y8, samples.len() as u32, 1).unwrap();
+
+    // the full 16-bit range maps onto the full 8-bit range
+    input_frame.convert_video_to(&mut output_frame).unwrap();
+    assert_eq!(output_frame.map().unwrap().planes().unwrap().plane_data(0).unwrap()[..3], [0, 128, 255]);
+
+    // a narrower window is stretched and clamped
+    input_frame.convert_y16_to_y8(&mut output_frame, Some((1000, 2000))).unwrap();
+    assert_eq!(output_frame.map().unwrap().planes().unwrap().plane_data(0).unwrap()[..6], [0, 255, 255, 0, 128, 255]);
+
+    let mut rgba_frame = Frame::video_creator().create(PixelFormat::RGBA32, samples.len() as u32, 1).unwrap();
+    input_frame.convert_video_to(&mut rgba_frame).unwrap();
+    assert_eq!(rgba_frame.map().unwrap().planes().unwrap().plane_data(0).unwrap()[4..8], [128, 128, 128, 255]);
+
+    // the low byte comes first whatever the host byte order
+    let input_frame = Frame::video_creator().create_from_buffer(PixelFormat::Y16, 2, 1, vec![0xFF, 0x00, 0x00, 0xFF]).unwrap();
+    let mut output_frame = Frame::video_creator().create(PixelFormat::Y8, 2, 1).unwrap();
+    input_frame.convert_video_to(&mut output_frame).unwrap();
+    assert_eq!(output_frame.map().unwrap().planes().unwrap().plane_data(0).unwrap()[..2], [1, 254]);
+}
+
+#[test]
+fn test_copy_padded_and_contiguous() {
+    let width = 10;
+    let height = 5;
+    let row_bytes = width as usize * 3;
+    let padded_stride = 32;
+
+    let pattern = |stride: usize| -> Vec<u8> { (0..stride * height as usize).map(|i| (i * 7 + 3) as u8).collect() };
+    let read_rows = |frame: &Frame| -> Vec<u8> {
+        let guard = frame.map().unwrap();
+        let plane = guard.plane(0).unwrap();
+        plane.data.chunks(plane.stride).take(height as usize).flat_map(|row| row[..row_bytes].to_vec()).collect()
+    };
+
+    let packed = Frame::video_creator().create_from_packed_buffer(PixelFormat::RGB24, width, height, row_bytes as u32, pattern(row_bytes)).unwrap();
+    let padded =
+        Frame::video_creator().create_from_aligned_buffer(PixelFormat::RGB24, width, height, padded_stride, pattern(padded_stride as usize)).unwrap();
+
+    for src in [&packed, &padded] {
+        let expected = read_rows(src);
+        let mut contiguous_dst = Frame::video_creator()
+            .create_from_packed_buffer(PixelFormat::RGB24, width, height, row_bytes as u32, vec![0u8; row_bytes * height as usize])
+            .unwrap();
+        let mut padded_dst = Frame::video_creator()
+            .create_from_aligned_buffer(PixelFormat::RGB24, width, height, padded_stride, vec![0u8; (padded_stride * height) as usize])
+            .unwrap();
+        let mut allocated_dst = Frame::video_creator().create(PixelFormat::RGB24, width, height).unwrap();
+
+        for dst in [&mut contiguous_dst, &mut padded_dst, &mut allocated_dst] {
+            src.convert_video_to(dst).unwrap();
+            assert_eq!(read_rows(dst), expected);
+        }
+    }
+
+    // multi-plane formats take the same path per plane
+    let mut input_frame = Frame::video_creator().create(PixelFormat::I420, 66, 34).unwrap();
+    {
+        let mut guard = input_frame.map_mut().unwrap();
+        let mut planes = guard.planes_mut().unwrap();
+        for index in 0..3 {
+            for (i, value) in planes.plane_data_mut(index).unwrap().iter_mut().enumerate() {
+                *value = (i * 11 + index * 29) as u8;
+            }
+        }
+    }
+    let mut output_frame = Frame::video_creator().create(PixelFormat::I420, 66, 34).unwrap();
+    input_frame.convert_video_to(&mut output_frame).unwrap();
+    for index in 0..3 {
+        let src_guard = input_frame.map().unwrap();
+        let dst_guard = output_frame.map().unwrap();
+        let src_plane = src_guard.plane(index).unwrap();
+        let dst_plane = dst_guard.plane(index).unwrap();
+        for (src_row, dst_row) in src_plane.data.chunks(src_plane.stride).zip(dst_plane.data.chunks(dst_plane.stride)).take(src_plane.height as usize)
+        {
+            assert_eq!(src_row[..src_plane.row_bytes], dst_row[..dst_plane.row_bytes]);
+        }
+    }
+}
+
+#[test]
+fn test_chroma_location_upsampling() {
+    let width = 16;
+    let height = 4;
+
+    // a vertical chroma edge between chroma columns 3 and 4
+    let convert_sited = |location: ChromaLocation| -> Vec<u8> {
+        let mut desc = VideoFrameDescriptor::try_new(PixelFormat::I420, width, height).unwrap();
+        desc.chroma_location = location;
+        let mut frame = Frame::video_creator().create_with_descriptor(desc).unwrap();
+        {
+            let mut guard = frame.map_mut().unwrap();
+            let mut planes = guard.planes_mut().unwrap();
+            planes.plane_data_mut(0).unwrap().fill(128);
+            let stride = planes.plane_stride(1).unwrap();
+            for (i, value) in planes.plane_data_mut(1).unwrap().iter_mut().enumerate() {
+                *value = if i % stride < 4 {
+                    64
+                } else {
+                    192
+                };
+            }
+            planes.plane_data_mut(2).unwrap().fill(128);
+        }
+
+        let mut output_frame = Frame::video_creator().create(PixelFormat::RGB24, width, height).unwrap();
+        frame.convert_video_to(&mut output_frame).unwrap();
+        let guard = output_frame.map().unwrap();
+        let planes = guard.planes().unwrap();
+        // blue of the first row
+        planes.plane_data(0).unwrap()[..width as usize * 3].iter().skip(2).step_by(3).cloned().collect()
+    };
+
+    // the same chroma upsampled by hand to full resolution
+    let convert_expected = |chroma: &[u8]| -> Vec<u8> {
+        let mut frame = Frame::video_creator().create(PixelFormat::I444, width, height).unwrap();
+        {
+            let mut guard = frame.map_mut().unwrap();
+            let mut planes = guard.planes_mut().unwrap();
+            planes.plane_data_mut(0).unwrap().fill(128);
+            let stride = planes.plane_stride(1).unwrap();
+            for (i, value) in planes.plane_data_mut(1).unwrap().iter_mut().enumerate() {
+                *value = chroma.get(i % stride).cloned().unwrap_or(0);
+            }
+            planes.plane_data_mut(2).unwrap().fill(128);
+        }
+
+        let mut output_frame = Frame::video_creator().create(PixelFormat::RGB24, width, height).unwrap();
+        frame.convert_video_to(&mut output_frame).unwrap();
+        let guard = output_frame.map().unwrap();
+        let planes = guard.planes().unwrap();
+        planes.plane_data(0).unwrap()[..width as usize * 3].iter().skip(2).step_by(3).cloned().collect()
+    };
+
+    // left siting puts the chroma edge half a pixel left of center siting
+    let left_chroma = [64, 64, 64, 64, 64, 64, 64, 128, 192, 192, 192, 192, 192, 192, 192, 192];
+    let center_chroma = [64, 64, 64, 64, 64, 64, 64, 96, 160, 192, 192, 192, 192, 192, 192, 192];
+
+    let left = convert_sited(ChromaLocation::Left);
+    let center = convert_sited(ChromaLocation::Center);
+    for (actual, expected) in [(&left, convert_expected(&left_chroma)), (&center, convert_expected(&center_chroma))] {
+        assert!(actual.iter().zip(&expected).all(|(a, e)| a.abs_diff(*e) <= 1), "{:?} != {:?}", actual, expected);
+    }
+    assert!(left[7] > center[7] && left[8] > center[8]);
+
+    // unspecified siting keeps replicating chroma samples
+    let unspecified = convert_sited(ChromaLocation::Unspecified);
+    assert_eq!(unspecified[6], unspecified[7]);
+    assert_eq!(unspecified[8], unspecified[9]);
+}
+
+#[test]
+fn test_convert_undersized_dst() {
+    let width = 64;
+    let height = 48;
+    let input_frame = Frame::video_creator().create(PixelFormat::I420, width, height).unwrap();
+
+    // rows of an RGB24 frame cannot hold RGBA32 pixels
+    let stride = width * 3;
+    let mut output_frame =
+        Frame::video_creator().create_from_packed_buffer(PixelFormat::RGBA32, width, height, stride, vec![0u8; (stride * height) as usize]).unwrap();
+    assert!(matches!(input_frame.convert_video_to(&mut output_frame), Err(Error::Invalid(_))));
+
+    let converter = Converter::new(PixelFormat::I420, PixelFormat::RGBA32).unwrap();
+    assert!(matches!(converter.convert(&input_frame, &mut output_frame), Err(Error::Invalid(_))));
+}
+
+#[test]
+fn test_rgb64_to_rgb32() {
+    // one ARGB64 pixel, big-endian channels
+    let buffer = vec![0xFF, 0xEE, 0x12, 0x34, 0x56, 0x78, 0x9A, 0xBC];
+    let input_frame = Frame::video_creator().create_from_buffer(PixelFormat::ARGB64, 1, 1, buffer).unwrap();
+    let mut bgra_frame = Frame::video_creator().create(PixelFormat::BGRA32, 1, 1).unwrap();
+    input_frame.convert_video_to(&mut bgra_frame).unwrap();
+    assert_eq!(bgra_frame.map().unwrap().planes().unwrap().plane_data(0).unwrap()[..4], [0x9A, 0x56, 0x12, 0xFF]);
+
+    let buffer = vec![0x12, 0x34, 0x56, 0x78, 0x9A, 0xBC, 0xFF, 0xEE];
+    let input_frame = Frame::video_creator().create_from_buffer(PixelFormat::RGBA64, 1, 1, buffer).unwrap();
+    let mut rgba_frame = Frame::video_creator().create(PixelFormat::RGBA32, 1, 1).unwrap();
+    input_frame.convert_video_to(&mut rgba_frame).unwrap();
+    assert_eq!(rgba_frame.map().unwrap().planes().unwrap().plane_data(0).unwrap()[..4], [0x12, 0x56, 0x9A, 0xFF]);
+
+    // expansion replicates the byte
+    let mut rgba64_frame = Frame::video_creator().create(PixelFormat::RGBA64, 1, 1).unwrap();
+    rgba_frame.convert_video_to(&mut rgba64_frame).unwrap();
+    assert_eq!(rgba64_frame.map().unwrap().planes().unwrap().plane_data(0).unwrap()[..8], [0x12, 0x12, 0x56, 0x56, 0x9A, 0x9A, 0xFF, 0xFF]);
+
+    let mut argb64_frame = Frame::video_creator().create(PixelFormat::ARGB64, 1, 1).unwrap();
+    bgra_frame.convert_video_to(&mut argb64_frame).unwrap();
+    assert_eq!(argb64_frame.map().unwrap().planes().unwrap().plane_data(0).unwrap()[..8], [0xFF, 0xFF, 0x12, 0x12, 0x56, 0x56, 0x9A, 0x9A]);
+}
+
+#[test]
+fn test_convert_variant_frame() {
+    let variant_frame = Frame::data_creator().create_from_variant(&media_core::variant::Variant::new()).unwrap();
+    assert!(matches!(variant_frame.map(), Err(Error::Unsupported(_))));
+
+    // no panic on frames without planes
+    let mut video_frame = Frame::video_creator().create(PixelFormat::RGBA32, 16, 16).unwrap();
+    assert!(matches!(variant_frame.convert_video_to(&mut video_frame), Err(Error::Unsupported(_))));
+    assert!(matches!(video_frame.convert_video_to(&mut variant_frame.clone()), Err(Error::Unsupported(_))));
+    assert!(matches!(variant_frame.convert_to_with(&mut video_frame, &ConvertOptions::default()), Err(Error::Unsupported(_))));
+
+    let guard = video_frame.map().unwrap();
+    assert!(guard.try_planes().is_ok());
+}
+
+#[test]
+fn test_convert_compressed_frame() {
+    use media_core::variant::Variant;
+
+    let mut variant = Variant::new_dict();
+    variant["format"] = u32::from(VideoFormat::Compression(CompressionFormat::MJPEG)).into();
+    variant["data"] = Variant::Buffer(vec![0xFF, 0xD8, 0xFF, 0xD9]);
+    let mjpeg_frame = Frame::data_creator().create_from_variant(&variant).unwrap();
+    assert!(mjpeg_frame.is_compressed_video());
+
+    let mut video_frame = Frame::video_creator().create(PixelFormat::I420, 16, 16).unwrap();
+    let expected = "decode compressed frame first";
+    assert!(matches!(mjpeg_frame.convert_video_to(&mut video_frame), Err(err @ Error::Unsupported(_)) if err.to_string().contains(expected)));
+    assert!(matches!(mjpeg_frame.convert_to(&mut video_frame), Err(err @ Error::Unsupported(_)) if err.to_string().contains(expected)));
+    assert!(matches!(mjpeg_frame.convert_to_with(&mut video_frame, &ConvertOptions::default()), Err(Error::Unsupported(_))));
+
+    // raw pixel formats are not compressed
+    variant["format"] = u32::from(VideoFormat::Pixel(PixelFormat::YUYV)).into();
+    assert!(!Frame::data_creator().create_from_variant(&variant).unwrap().is_compressed_video());
+}
+
+#[test]
+fn test_convert_in_place() {
+    let mut frame = Frame::video_creator().create(PixelFormat::BGRA32, 5, 3).unwrap();
+    let ptr = {
+        let mut guard = frame.map_mut().unwrap();
+        let mut planes = guard.planes_mut().unwrap();
+        let data = planes.plane_data_mut(0).unwrap();
+        for pixel in data.chunks_exact_mut(4) {
+            pixel.copy_from_slice(&[10, 20, 30, 40]);
+        }
+        data.as_ptr()
+    };
+
+    frame.convert_in_place(PixelFormat::RGBA32).unwrap();
+    assert_eq!(frame.video_descriptor().unwrap().format, PixelFormat::RGBA32);
+    {
+        let guard = frame.map().unwrap();
+        let plane = guard.plane(0).unwrap();
+        assert_eq!(plane.data.as_ptr(), ptr);
+        assert_eq!(&plane.data[..4], &[30, 20, 10, 40]);
+        assert_eq!(&plane.data[2 * plane.stride + 16..][..4], &[30, 20, 10, 40]);
+    }
+
+    frame.convert_in_place(PixelFormat::ARGB32).unwrap();
+    assert_eq!(&frame.map().unwrap().plane(0).unwrap().data[..4], &[40, 30, 20, 10]);
+
+    // size changing conversions need a second frame
+    assert!(frame.convert_in_place(PixelFormat::RGB24).is_err());
+    assert!(frame.convert_in_place(PixelFormat::I420).is_err());
+    assert_eq!(frame.video_descriptor().unwrap().format, PixelFormat::ARGB32);
+
+    let mut frame = Frame::video_creator().create(PixelFormat::NV12, 4, 4).unwrap();
+    {
+        let mut guard = frame.map_mut().unwrap();
+        let mut planes = guard.planes_mut().unwrap();
+        planes.plane_data_mut(1).unwrap()[..2].copy_from_slice(&[1, 2]);
+    }
+    frame.convert_in_place(PixelFormat::NV21).unwrap();
+    assert_eq!(&frame.map().unwrap().plane(1).unwrap().data[..2], &[2, 1]);
+    assert!(frame.convert_in_place(PixelFormat::NV61).is_err());
+}
+
+#[test]
+fn test_convert_into_new() {
+    use media_core::rational::Rational64;
+
+    let mut frame = Frame::video_creator().create(PixelFormat::I420, 16, 8).unwrap();
+    frame.source = Some("camera0".to_string());
+    frame.pts = Some(3003);
+    frame.dts = Some(3000);
+    frame.duration = Some(1001);
+    frame.time_base = Some(Rational64::new(1, 30000));
+    frame.sequence = Some(7);
+
+    let converted = frame.convert_into_new(PixelFormat::RGBA32).unwrap();
+    let desc = converted.video_descriptor().unwrap();
+    assert_eq!(desc.format, PixelFormat::RGBA32);
+    assert_eq!((desc.width().get(), desc.height().get()), (16, 8));
+    assert_eq!(converted.source.as_deref(), Some("camera0"));
+    assert_eq!(converted.pts, Some(3003));
+    assert_eq!(converted.dts, Some(3000));
+    assert_eq!(converted.duration, Some(1001));
+    assert_eq!(converted.time_base, frame.time_base);
+    assert_eq!(converted.sequence, Some(7));
+
+    assert!(frame.convert_into_new(PixelFormat::Y16).is_err());
+}
+
+#[test]
+fn test_rgb24_to_bgr24() {
+    let (width, height, stride) = (4u32, 2u32, 16u32);
+    let mut buffer = vec![0u8; (stride * height) as usize];
+    for (y, row) in buffer.chunks_mut(stride as usize).enumerate() {
+        for (x, pixel) in row[..12].chunks_exact_mut(3).enumerate() {
+            pixel.copy_from_slice(&[(x * 10 + y) as u8, 100, 200]);
+        }
+    }
+
+    let src = Frame::video_creator().create_from_aligned_buffer(PixelFormat::RGB24, width, height, stride, buffer).unwrap();
+    let mut dst = Frame::video_creator().create_from_aligned_buffer(PixelFormat::BGR24, width, height, stride, vec![0xEEu8; 32]).unwrap();
+    src.convert_video_to(&mut dst).unwrap();
+
+    {
+        let guard = dst.map().unwrap();
+        let plane = guard.plane(0).unwrap();
+        for y in 0..height as usize {
+            let row = &plane.data[y * plane.stride..][..plane.stride];
+            for (x, pixel) in row[..12].chunks_exact(3).enumerate() {
+                assert_eq!(pixel, &[200, 100, (x * 10 + y) as u8]);
+            }
+            // padding between rows is left alone
+            assert!(row[12..].iter().all(|&value| value == 0xEE));
+        }
+    }
+
+    let mut back = Frame::video_creator().create(PixelFormat::RGB24, width, height).unwrap();
+    dst.convert_video_to(&mut back).unwrap();
+    let guard = back.map().unwrap();
+    let plane = guard.plane(0).unwrap();
+    assert_eq!(&plane.data[plane.stride..][..6], &[1, 100, 200, 11, 100, 200]);
+}
+
+#[test]
+fn test_convert_premultiplied() {
+    let rgba_frame = |pixel: [u8; 4], premultiplied: bool| {
+        let mut desc = VideoFrameDescriptor::try_new(PixelFormat::RGBA32, 4, 4).unwrap().with_color_range(ColorRange::Full);
+        desc.premultiplied = premultiplied;
+        let mut frame = Frame::video_creator().create_with_descriptor(desc).unwrap();
+        {
+            let mut guard = frame.map_mut().unwrap();
+            let mut planes = guard.planes_mut().unwrap();
+            for value in planes.plane_data_mut(0).unwrap().chunks_exact_mut(4) {
+                value.copy_from_slice(&pixel);
+            }
+        }
+        frame
+    };
+    let luma = |frame: &Frame| {
+        let mut dst = Frame::video_creator().create(PixelFormat::I420, 4, 4).unwrap();
+        frame.convert_video_to(&mut dst).unwrap();
+        let value = dst.map().unwrap().plane(0).unwrap().data[0];
+        value
+    };
+
+    // the same bytes read as premultiplied hold twice the color at alpha 128
+    let premultiplied = luma(&rgba_frame([100, 50, 24, 128], true));
+    let straight = luma(&rgba_frame([100, 50, 24, 128], false));
+    let reference = luma(&rgba_frame([199, 100, 48, 128], false));
+    assert!(premultiplied > straight);
+    assert!(premultiplied.abs_diff(reference) <= 1, "{} vs {}", premultiplied, reference);
+
+    // the prepared converter takes the same path
+    let frame = rgba_frame([100, 50, 24, 128], true);
+    let mut dst = Frame::video_creator().create(PixelFormat::I420, 4, 4).unwrap();
+    Converter::new(PixelFormat::RGBA32, PixelFormat::I420).unwrap().convert(&frame, &mut dst).unwrap();
+    assert_eq!(dst.map().unwrap().plane(0).unwrap().data[0], premultiplied);
+
+    // dropping alpha to packed RGB divides it out too, keeping it does not
+    for (format, expected) in [(PixelFormat::RGB24, [199, 100, 48]), (PixelFormat::BGR24, [48, 100, 199])] {
+        let mut dst = Frame::video_creator().create(format, 4, 4).unwrap();
+        frame.convert_video_to(&mut dst).unwrap();
+        assert_eq!(&dst.map().unwrap().plane(0).unwrap().data[..3], &expected);
+    }
+    let mut dst = Frame::video_creator().create(PixelFormat::BGRA32, 4, 4).unwrap();
+    frame.convert_video_to(&mut dst).unwrap();
+    assert_eq!(&dst.map().unwrap().plane(0).unwrap().data[..4], &[24, 50, 100, 128]);
+}
+
+#[test]
+fn test_convert_to_with() {
+    let desc = VideoFrameDescriptor::try_new(PixelFormat::RGBA32, 4, 4).unwrap().with_color_range(ColorRange::Full);
+    let mut src = Frame::video_creator().create_with_descriptor(desc).unwrap();
+    {
+        let mut guard = src.map_mut().unwrap();
+        let mut planes = guard.planes_mut().unwrap();
+        planes.plane_data_mut(0).unwrap().fill(255);
+    }
+
+    let convert = |dst_range: ColorRange| {
+        let options = ConvertOptions {
+            src_range: Some(ColorRange::Full),
+            dst_range: Some(dst_range),
+            matrix: Some(ColorMatrix::BT709),
+            ..Default::default()
+        };
+        let mut dst = Frame::video_creator().create(PixelFormat::NV12, 4, 4).unwrap();
+        src.convert_to_with(&mut dst, &options).unwrap();
+        dst
+    };
+
+    // full range white lands on video range white
+    let dst = convert(ColorRange::Video);
+    let desc = dst.video_descriptor().unwrap();
+    assert_eq!(desc.color_range, ColorRange::Video);
+    assert_eq!(desc.color_matrix, ColorMatrix::BT709);
+    let guard = dst.map().unwrap();
+    assert!(guard.plane(0).unwrap().data[..4].iter().all(|&luma| luma == 235));
+    assert!(guard.plane(1).unwrap().data[..4].iter().all(|&chroma| chroma.abs_diff(128) <= 1));
+
+    let dst = convert(ColorRange::Full);
+    assert_eq!(dst.map().unwrap().plane(0).unwrap().data[0], 255);
+
+    // YUV to YUV keeps the samples, changing the range is not supported
+    let src = convert(ColorRange::Video);
+    let mut dst = Frame::video_creator().create(PixelFormat::I420, 4, 4).unwrap();
+    let options = ConvertOptions {
+        dst_range: Some(ColorRange::Full),
+        ..Default::default()
+    };
+    assert!(matches!(src.convert_to_with(&mut dst, &options), Err(Error::Unsupported(_))));
+    let options = ConvertOptions {
+        dst_range: Some(ColorRange::Video),
+        ..Default::default()
+    };
+    src.convert_to_with(&mut dst, &options).unwrap();
+    assert_eq!(dst.video_descriptor().unwrap().color_range, ColorRange::Video);
+}
+
+#[test]
+fn test_convert_to_with_mode() {
+    let (width, height) = (16u32, 16u32);
+    let mut src = Frame::video_creator().create(PixelFormat::NV12, width, height).unwrap();
+    {
+        let mut guard = src.map_mut().unwrap();
+        let mut planes = guard.planes_mut().unwrap();
+        let stride = planes.plane_stride(0).unwrap();
+        for (y, row) in planes.plane_data_mut(0).unwrap().chunks_mut(stride).enumerate() {
+            for (x, value) in row[..width as usize].iter_mut().enumerate() {
+                *value = (16 + (x * 13 + y * 7) % 220) as u8;
+            }
+        }
+        let stride = planes.plane_stride(1).unwrap();
+        for (y, row) in planes.plane_data_mut(1).unwrap().chunks_mut(stride).enumerate() {
+            for (x, value) in row[..width as usize].chunks_exact_mut(2).enumerate() {
+                value.copy_from_slice(&[(16 + (x * 29 + y * 11) % 225) as u8, (16 + (x * 17 + y * 31) % 225) as u8]);
+            }
+        }
+    }
+
+    let convert = |mode: ConversionMode| {
+        let options = ConvertOptions {
+            src_range: Some(ColorRange::Video),
+            matrix: Some(ColorMatrix::BT709),
+            mode,
+            ..Default::default()
+        };
+        let mut dst = Frame::video_creator().create(PixelFormat::RGB24, width, height).unwrap();
+        src.convert_to_with(&mut dst, &options).unwrap();
+        let guard = dst.map().unwrap();
+        let plane = guard.plane(0).unwrap();
+        plane.data.chunks(plane.stride).flat_map(|row| row[..width as usize * 3].to_vec()).collect::<Vec<u8>>()
+    };
+
+    // BT.709 video range in floating point
+    let reference = {
+        let guard = src.map().unwrap();
+        let (luma, chroma) = (guard.plane(0).unwrap(), guard.plane(1).unwrap());
+        let mut reference = Vec::new();
+        for y in 0..height as usize {
+            for x in 0..width as usize {
+                let luma_value = (luma.data[y * luma.stride + x] as f32 - 16.0) / 219.0;
+                let chroma_index = (y / 2) * chroma.stride + (x / 2) * 2;
+                let cb = (chroma.data[chroma_index] as f32 - 128.0) / 224.0;
+                let cr = (chroma.data[chroma_index + 1] as f32 - 128.0) / 224.0;
+                let r = luma_value + 1.5748 * cr;
+                let b = luma_value + 1.8556 * cb;
+                let g = (luma_value - 0.2126 * r - 0.0722 * b) / 0.7152;
+                reference.extend([r, g, b].map(|value| value.clamp(0.0, 1.0) * 255.0));
+            }
+        }
+        reference
+    };
+    let error = |output: &[u8]| output.iter().zip(&reference).map(|(value, expected)| (*value as f32 - expected).abs()).sum::<f32>();
+
+    let fast = convert(ConversionMode::Fast);
+    let precise = convert(ConversionMode::Professional);
+    assert_ne!(fast, precise);
+    assert!(error(&precise) < error(&fast), "{} vs {}", error(&precise), error(&fast));
+    // the fast path approximates the blue coefficient but stays near the
+    // reference on average, the precise path is within rounding everywhere
+    assert!(error(&fast) / reference.len() as f32 <= 2.0);
+    assert!(precise.iter().zip(&reference).all(|(value, expected)| (*value as f32 - expected).abs() <= 1.0));
+
+    // planar and packed YUV only convert at the fast precision
+    let options = ConvertOptions {
+        mode: ConversionMode::Professional,
+        ..Default::default()
+    };
+    for format in [PixelFormat::I420, PixelFormat::I422, PixelFormat::I444, PixelFormat::YUYV] {
+        let src = Frame::video_creator().create(format, width, height).unwrap();
+        let mut dst = Frame::video_creator().create(PixelFormat::RGB24, width, height).unwrap();
+        assert!(matches!(src.convert_to_with(&mut dst, &options), Err(Error::Unsupported(_))), "{:?}", format);
+        assert!(src.convert_to_with(&mut dst, &ConvertOptions::default()).is_ok());
+    }
+}
+
+#[test]
+fn test_p410_to_rgb30() {
+    // BT.601 video range 10-bit red, white and black
+    let samples: [(u16, u16, u16); 3] = [(326, 361, 960), (940, 512, 512), (64, 512, 512)];
+    let expected = [[1023, 0, 0], [1023, 1023, 1023], [0, 0, 0]];
+    let unpack = |pixel: &[u8]| {
+        let value = u32::from_be_bytes([pixel[0], pixel[1], pixel[2], pixel[3]]);
+        [(value >> 22) & 0x3ff, (value >> 12) & 0x3ff, (value >> 2) & 0x3ff]
+    };
+    let assert_close = |actual: [u32; 3], expected: [u32; 3]| {
+        assert!(actual.iter().zip(&expected).all(|(actual, expected)| actual.abs_diff(*expected) <= 4), "{:?} vs {:?}", actual, expected);
+    };
+
+    let mut p410 = Frame::video_creator().create(PixelFormat::P410, 3, 1).unwrap();
+    let mut i410 = Frame::video_creator().create(PixelFormat::I410, 3, 1).unwrap();
+    {
+        let mut guard = p410.map_mut().unwrap();
+        let mut planes = guard.planes_mut().unwrap();
+        for (x, (y, u, v)) in samples.iter().enumerate() {
+            // P410 keeps the 10 bits in the high bits of each sample
+            planes.plane_data_mut(0).unwrap()[x * 2..][..2].copy_from_slice(&(y << 6).to_ne_bytes());
+            planes.plane_data_mut(1).unwrap()[x * 4..][..2].copy_from_slice(&(u << 6).to_ne_bytes());
+            planes.plane_data_mut(1).unwrap()[x * 4 + 2..][..2].copy_from_slice(&(v << 6).to_ne_bytes());
+        }
+
+        let mut guard = i410.map_mut().unwrap();
+        let mut planes = guard.planes_mut().unwrap();
+        for (x, (y, u, v)) in samples.iter().enumerate() {
+            for (plane_index, value) in [y, u, v].into_iter().enumerate() {
+                planes.plane_data_mut(plane_index).unwrap()[x * 2..][..2].copy_from_slice(&value.to_ne_bytes());
+            }
+        }
+    }
+
+    let rgb30 = p410.convert_into_new(PixelFormat::RGB30).unwrap();
+    let guard = rgb30.map().unwrap();
+    for (pixel, expected) in guard.plane(0).unwrap().data.chunks_exact(4).zip(expected) {
+        assert_close(unpack(pixel), expected);
+    }
+
+    // BGR30 holds blue in the high bits
+    let bgr30 = i410.convert_into_new(PixelFormat::BGR30).unwrap();
+    let guard = bgr30.map().unwrap();
+    for (pixel, [r, g, b]) in guard.plane(0).unwrap().data.chunks_exact(4).zip(expected) {
+        assert_close(unpack(pixel), [b, g, r]);
+    }
+}
+
+#[test]
+fn test_rgb24_to_yuv() {
+    // odd sizes round the chroma planes up
+    let (width, height) = (5u32, 3u32);
+    let rgb = |x: usize, y: usize| [(x * 50) as u8, (y * 100) as u8, (255 - x * 40) as u8];
+    let packed_frame = |format: PixelFormat| {
+        let mut frame = Frame::video_creator().create(format, width, height).unwrap();
+        {
+            let mut guard = frame.map_mut().unwrap();
+            let mut planes = guard.planes_mut().unwrap();
+            let stride = planes.plane_stride(0).unwrap();
+            for (y, row) in planes.plane_data_mut(0).unwrap().chunks_mut(stride).enumerate() {
+                let bytes_per_pixel = format.calc_plane_row_bytes(0, width) as usize / width as usize;
+                for (x, pixel) in row[..width as usize * bytes_per_pixel].chunks_exact_mut(bytes_per_pixel).enumerate() {
+                    let [r, g, b] = rgb(x, y);
+                    match format {
+                        PixelFormat::RGB24 => pixel.copy_from_slice(&[r, g, b]),
+                        PixelFormat::BGR24 => pixel.copy_from_slice(&[b, g, r]),
+                        _ => pixel.copy_from_slice(&[r, g, b, 0xFF]),
+                    }
+                }
+            }
+        }
+        frame
+    };
+
+    let rgba = packed_frame(PixelFormat::RGBA32);
+    for src_format in [PixelFormat::RGB24, PixelFormat::BGR24] {
+        let src = packed_frame(src_format);
+        for dst_format in [PixelFormat::I420, PixelFormat::NV12, PixelFormat::I422, PixelFormat::I444] {
+            let converted = src.convert_into_new(dst_format).unwrap();
+            let reference = rgba.convert_into_new(dst_format).unwrap();
+
+            // the same pixels give the same YUV as the RGBA32 source
+            let (guard, reference_guard) = (converted.map().unwrap(), reference.map().unwrap());
+            for plane_index in 0..guard.planes().unwrap().len() {
+                let (plane, reference_plane) = (guard.plane(plane_index).unwrap(), reference_guard.plane(plane_index).unwrap());
+                assert_eq!(plane.row_bytes, dst_format.calc_plane_row_bytes(plane_index, width) as usize);
+                for y in 0..dst_format.calc_plane_height(plane_index, height) as usize {
+                    assert_eq!(
+                        &plane.data[y * plane.stride..][..plane.row_bytes],
+                        &reference_plane.data[y * reference_plane.stride..][..plane.row_bytes],
+                        "{:?} -> {:?} plane {} row {}",
+                        src_format,
+                        dst_format,
+                        plane_index,
+                        y
+                    );
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn test_nv_planar_round_trip() {
+    let (width, height) = (7u32, 5u32);
+    let pairs = [
+        (PixelFormat::NV12, PixelFormat::I420, false),
+        (PixelFormat::NV21, PixelFormat::I420, true),
+        (PixelFormat::NV16, PixelFormat::I422, false),
+        (PixelFormat::NV61, PixelFormat::I422, true),
+        (PixelFormat::NV24, PixelFormat::I444, false),
+        (PixelFormat::NV42, PixelFormat::I444, true),
+    ];
+
+    for (nv_format, planar_format, swapped) in pairs {
+        let mut nv = Frame::video_creator().create(nv_format, width, height).unwrap();
+        {
+            let mut guard = nv.map_mut().unwrap();
+            let mut planes = guard.planes_mut().unwrap();
+            for plane_index in 0..2 {
+                for (i, value) in planes.plane_data_mut(plane_index).unwrap().iter_mut().enumerate() {
+                    *value = (i * 7 + plane_index * 100) as u8;
+                }
+            }
+        }
+
+        let planar = nv.convert_into_new(planar_format).unwrap();
+        let back = planar.convert_into_new(nv_format).unwrap();
+
+        let (nv_guard, planar_guard, back_guard) = (nv.map().unwrap(), planar.map().unwrap(), back.map().unwrap());
+        for plane_index in 0..2 {
+            let (plane, back_plane) = (nv_guard.plane(plane_index).unwrap(), back_guard.plane(plane_index).unwrap());
+            for y in 0..nv_format.calc_plane_height(plane_index, height) as usize {
+                assert_eq!(
+                    &plane.data[y * plane.stride..][..plane.row_bytes],
+                    &back_plane.data[y * back_plane.stride..][..plane.row_bytes],
+                    "{:?} plane {} row {}",
+                    nv_format,
+                    plane_index,
+                    y
+                );
+            }
+        }
+
+        // the first chroma sample lands in U or V depending on the order
+        let (u, v) = (planar_guard.plane(1).unwrap().data[0], planar_guard.plane(2).unwrap().data[0]);
+        let (first, second) = (nv_guard.plane(1).unwrap().data[0], nv_guard.plane(1).unwrap().data[1]);
+        if swapped {
+            assert_eq!((v, u), (first, second), "{:?}", nv_format);
+        } else {
+            assert_eq!((u, v), (first, second), "{:?}", nv_format);
+        }
+    }
+}
+
+#[test]
+fn test_packed_nv12_round_trip() {
+    // odd dimensions, so the last column and row are half macropixels and chroma
+    // rows
+    let (width, height) = (7u32, 5u32);
+    let mut nv12 = Frame::video_creator().create(PixelFormat::NV12, width, height).unwrap();
+    {
+        let mut guard = nv12.map_mut().unwrap();
+        let mut planes = guard.planes_mut().unwrap();
+        for plane_index in 0..2 {
+            for (i, value) in planes.plane_data_mut(plane_index).unwrap().iter_mut().enumerate() {
+                *value = (i * 7 + plane_index * 100) as u8;
+            }
+        }
+    }
+
+    for packed_format in [PixelFormat::YUYV, PixelFormat::YVYU, PixelFormat::UYVY, PixelFormat::VYUY] {
+        let packed = nv12.convert_into_new(packed_format).unwrap();
+        let back = packed.convert_into_new(PixelFormat::NV12).unwrap();
+
+        let (nv12_guard, back_guard) = (nv12.map().unwrap(), back.map().unwrap());
+        for plane_index in 0..2 {
+            let (plane, back_plane) = (nv12_guard.plane(plane_index).unwrap(), back_guard.plane(plane_index).unwrap());
+            for y in 0..PixelFormat::NV12.calc_plane_height(plane_index, height) as usize {
+                assert_eq!(
+                    &plane.data[y * plane.stride..][..plane.row_bytes],
+                    &back_plane.data[y * back_plane.stride..][..plane.row_bytes],
+                    "{:?} plane {} row {}",
+                    packed_format,
+                    plane_index,
+                    y
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn test_argb32_swaps() {
+    let (width, height) = (4u32, 4u32);
+    // (format, byte positions of R G B A)
+    let layouts = [
+        (PixelFormat::ARGB32, [1, 2, 3, 0]),
+        (PixelFormat::ABGR32, [3, 2, 1, 0]),
+        (PixelFormat::BGRA32, [2, 1, 0, 3]),
+        (PixelFormat::RGBA32, [0, 1, 2, 3]),
+    ];
+    let rgba = |x: usize, y: usize| [(x * 60) as u8, (y * 60) as u8, 0x80 | (x + y) as u8, 0x10 + (x * 4 + y) as u8];
+
+    for (src_format, src_layout) in layouts {
+        let mut src = Frame::video_creator().create(src_format, width, height).unwrap();
+        {
+            let mut guard = src.map_mut().unwrap();
+            let mut planes = guard.planes_mut().unwrap();
+            let stride = planes.plane_stride(0).unwrap();
+            for (y, row) in planes.plane_data_mut(0).unwrap().chunks_mut(stride).enumerate() {
+                for (x, pixel) in row[..16].chunks_exact_mut(4).enumerate() {
+                    for (component, position) in rgba(x, y).into_iter().zip(src_layout) {
+                        pixel[position] = component;
+                    }
+                }
+            }
+        }
+
+        for (dst_format, dst_layout) in layouts {
+            // the ARGB32 and ABGR32 layouts only convert to and from BGRA32 and RGBA32
+            let is_rgba = |format| matches!(format, PixelFormat::BGRA32 | PixelFormat::RGBA32);
+            if src_format == dst_format || (!is_rgba(src_format) && !is_rgba(dst_format)) {
+                continue;
+            }
+
+            let dst = src.convert_into_new(dst_format).unwrap();
+            let guard = dst.map().unwrap();
+            let plane = guard.plane(0).unwrap();
+            for (y, row) in plane.data.chunks(plane.stride).enumerate() {
+                for (x, pixel) in row[..16].chunks_exact(4).enumerate() {
+                    let components = dst_layout.map(|position| pixel[position]);
+                    assert_eq!(components, rgba(x, y), "{:?} -> {:?} at {},{}", src_format, dst_format, x, y);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(feature = "rayon")]
+#[test]
+fn test_convert_to_parallel() {
+    fn fill(frame: &mut Frame) {
+        let mut guard = frame.map_mut().unwrap();
+        let mut planes = guard.planes_mut().unwrap();
+        let mut seed = 0x2545_f491u32;
+        for plane_index in 0..planes.len() {
+            for value in planes.plane_data_mut(plane_index).unwrap() {
+                seed ^= seed << 13;
+                seed ^= seed >> 17;
+                seed ^= seed << 5;
+                *value = seed as u8;
+            }
+        }
+    }
+
+    fn assert_same(a: &Frame, b: &Frame) {
+        let (a_guard, b_guard) = (a.map().unwrap(), b.map().unwrap());
+        let (a_planes, b_planes) = (a_guard.planes().unwrap(), b_guard.planes().unwrap());
+        let desc = a.video_descriptor().unwrap();
+        for plane_index in 0..a_planes.len() {
+            let row_bytes = desc.format.calc_plane_row_bytes(plane_index, desc.width().get()) as usize;
+            let rows = desc.format.calc_plane_height(plane_index, desc.height().get()) as usize;
+            let (a_stride, b_stride) = (a_planes.plane_stride(plane_index).unwrap(), b_planes.plane_stride(plane_index).unwrap());
+            for y in 0..rows {
+                assert_eq!(
+                    a_planes.plane_data(plane_index).unwrap()[y * a_stride..][..row_bytes],
+                    b_planes.plane_data(plane_index).unwrap()[y * b_stride..][..row_bytes],
+                    "{:?} plane {} row {}",
+                    desc.format,
+                    plane_index,
+                    y
+                );
+            }
+        }
+    }
+
+    // 363 rows do not split evenly into bands
+    let (width, height) = (640, 363);
+    for (src_format, dst_format) in [(PixelFormat::NV12, PixelFormat::BGRA32), (PixelFormat::RGBA32, PixelFormat::I420)] {
+        let mut src = Frame::video_creator().create(src_format, width, height).unwrap();
+        fill(&mut src);
+
+        let mut expected = Frame::video_creator().create(dst_format, width, height).unwrap();
+        src.convert_video_to(&mut expected).unwrap();
+
+        for threads in [1, 2, 4, 7, 1000] {
+            let mut output = Frame::video_creator().create(dst_format, width, height).unwrap();
+            src.convert_to_parallel(&mut output, threads).unwrap();
+            assert_same(&expected, &output);
+        }
+    }
+
+    let src = Frame::video_creator().create(PixelFormat::NV12, width, height).unwrap();
+    let mut output = Frame::video_creator().create(PixelFormat::BGRA32, width, height).unwrap();
+    assert!(matches!(src.convert_to_parallel(&mut output, 0), Err(Error::InvalidParameter(..))));
+    let mut output = Frame::video_creator().create(PixelFormat::BGRA32, width, height + 1).unwrap();
+    assert!(matches!(src.convert_to_parallel(&mut output, 4), Err(Error::Invalid(_))));
+}
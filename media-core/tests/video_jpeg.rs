@@ -0,0 +1,221 @@
+#![cfg(feature = "jpeg")]
+
+use media_core::{frame::Frame, video::*, FrameDescriptor};
+
+fn gradient_frame(format: PixelFormat, width: u32, height: u32) -> Frame<'static> {
+    gradient_frame_with_descriptor(VideoFrameDescriptor::try_new(format, width, height).unwrap().with_color_range(ColorRange::Full))
+}
+
+fn gradient_frame_with_descriptor(desc: VideoFrameDescriptor) -> Frame<'static> {
+    let mut frame = Frame::video_creator().create_with_descriptor(desc).unwrap();
+    {
+        let mut guard = frame.map_mut().unwrap();
+        let mut planes = guard.planes_mut().unwrap();
+        for plane_index in 0..planes.len() {
+            let stride = planes.plane_stride(plane_index).unwrap();
+            let data = planes.plane_data_mut(plane_index).unwrap();
+            for (y, row) in data.chunks_mut(stride).enumerate() {
+                for (x, value) in row.iter_mut().enumerate() {
+                    *value = (64 + x * 2 + y + plane_index * 16).min(255) as u8;
+                }
+            }
+        }
+    }
+    frame
+}
+
+fn mean_plane_error(a: &Frame, b: &Frame, plane_index: usize) -> f64 {
+    let a_guard = a.map().unwrap();
+    let b_guard = b.map().unwrap();
+    let a_plane = a_guard.plane(plane_index).unwrap();
+    let b_plane = b_guard.plane(plane_index).unwrap();
+
+    let mut sum = 0u64;
+    for y in 0..a_plane.height as usize {
+        let a_row = &a_plane.data[y * a_plane.stride..][..a_plane.row_bytes];
+        let b_row = &b_plane.data[y * b_plane.stride..][..b_plane.row_bytes];
+        sum += a_row.iter().zip(b_row).map(|(a, b)| a.abs_diff(*b) as u64).sum::<u64>();
+    }
+    sum as f64 / (a_plane.height as usize * a_plane.row_bytes) as f64
+}
+
+// compares a subsampled plane against every other sample of its upsampled
+// counterpart
+fn mean_subsampled_error(subsampled: &Frame, upsampled: &Frame, plane_index: usize) -> f64 {
+    let a_guard = subsampled.map().unwrap();
+    let b_guard = upsampled.map().unwrap();
+    let a_plane = a_guard.plane(plane_index).unwrap();
+    let b_plane = b_guard.plane(plane_index).unwrap();
+
+    let mut sum = 0u64;
+    for y in 0..a_plane.height as usize {
+        let a_row = &a_plane.data[y * a_plane.stride..][..a_plane.row_bytes];
+        let b_row = &b_plane.data[y * 2 * b_plane.stride..][..b_plane.row_bytes];
+        sum += a_row.iter().zip(b_row.iter().step_by(2)).map(|(a, b)| a.abs_diff(*b) as u64).sum::<u64>();
+    }
+    sum as f64 / (a_plane.height as usize * a_plane.row_bytes) as f64
+}
+
+#[test]
+fn test_jpeg_round_trip() {
+    // not a multiple of the MCU size, so edge blocks are padded
+    let frame = gradient_frame(PixelFormat::I420, 100, 60);
+    let jpeg = frame.encode_jpeg(90).unwrap();
+    assert_eq!(&jpeg[..2], &[0xFF, 0xD8]);
+    assert_eq!(&jpeg[jpeg.len() - 2..], &[0xFF, 0xD9]);
+
+    let decoded = Frame::decode_jpeg(&jpeg).unwrap();
+    let FrameDescriptor::Video(desc) = decoded.descriptor() else {
+        panic!("not video frame");
+    };
+    assert_eq!(desc.format, PixelFormat::I444);
+    assert_eq!((desc.width().get(), desc.height().get()), (100, 60));
+    assert_eq!(desc.color_range, ColorRange::Full);
+
+    assert!(mean_plane_error(&frame, &decoded, 0) < 2.0);
+    for plane_index in 1..3 {
+        let error = mean_subsampled_error(&frame, &decoded, plane_index);
+        assert!(error < 3.0, "plane {} error {}", plane_index, error);
+    }
+
+    // lower quality gives a smaller file
+    assert!(frame.encode_jpeg(20).unwrap().len() < jpeg.len());
+    assert!(frame.encode_jpeg(0).is_err());
+    assert!(Frame::decode_jpeg(&jpeg[2..]).is_err());
+}
+
+#[test]
+fn test_jpeg_sampling() {
+    let frame = gradient_frame(PixelFormat::I444, 40, 24);
+    let decoded = Frame::decode_jpeg(&frame.encode_jpeg(95).unwrap()).unwrap();
+    let FrameDescriptor::Video(desc) = decoded.descriptor() else {
+        panic!("not video frame");
+    };
+    assert_eq!(desc.format, PixelFormat::I444);
+    for plane_index in 0..3 {
+        assert!(mean_plane_error(&frame, &decoded, plane_index) < 2.0);
+    }
+
+    // subsampled chroma comes back upsampled
+    let frame = gradient_frame(PixelFormat::NV12, 64, 48);
+    let decoded = Frame::decode_jpeg(&frame.encode_jpeg(90).unwrap()).unwrap();
+    let FrameDescriptor::Video(desc) = decoded.descriptor() else {
+        panic!("not video frame");
+    };
+    assert_eq!(desc.format, PixelFormat::I444);
+    assert!(mean_plane_error(&frame, &decoded, 0) < 2.0);
+
+    let frame = gradient_frame(PixelFormat::Y8, 30, 20);
+    let decoded = Frame::decode_jpeg(&frame.encode_jpeg(90).unwrap()).unwrap();
+    let FrameDescriptor::Video(desc) = decoded.descriptor() else {
+        panic!("not video frame");
+    };
+    assert_eq!(desc.format, PixelFormat::Y8);
+    assert!(mean_plane_error(&frame, &decoded, 0) < 2.0);
+}
+
+#[test]
+fn test_jpeg_bt709() {
+    let mut desc = VideoFrameDescriptor::try_new(PixelFormat::I444, 48, 32).unwrap().with_color_range(ColorRange::Full);
+    desc.color_matrix = ColorMatrix::BT709;
+    let bt709 = gradient_frame_with_descriptor(desc);
+
+    // both frame types convert to the BT.601 matrix JPEG stores
+    let jpeg = bt709.encode_jpeg(95).unwrap();
+    let video_frame = VideoFrame::try_from(bt709.clone()).unwrap();
+    assert_eq!(video_frame.encode_jpeg(95).unwrap(), jpeg);
+
+    let decoded = Frame::decode_jpeg(&jpeg).unwrap();
+    let FrameDescriptor::Video(desc) = decoded.descriptor() else {
+        panic!("not video frame");
+    };
+    assert_eq!(desc.color_matrix, ColorMatrix::SMPTE170M);
+
+    let round_trip = decoded.convert_matrix(ColorMatrix::BT709).unwrap();
+    for plane_index in 0..3 {
+        let error = mean_plane_error(&bt709, &round_trip, plane_index);
+        assert!(error < 2.0, "plane {} error {}", plane_index, error);
+    }
+    // read as BT.709 without converting back, the colors shift
+    assert!(mean_plane_error(&bt709, &decoded, 1) + mean_plane_error(&bt709, &decoded, 2) > 4.0);
+}
+
+#[test]
+fn test_jpeg_malformed() {
+    let jpeg = gradient_frame(PixelFormat::I420, 48, 32).encode_jpeg(80).unwrap();
+
+    for len in [0, 2, 20, jpeg.len() / 2, jpeg.len() - 16] {
+        assert!(Frame::decode_jpeg(&jpeg[..len]).is_err(), "truncated to {}", len);
+    }
+
+    // corrupt bytes may still decode to something, but must never panic
+    let mut state = 0x2545_f491u32;
+    for _ in 0..2000 {
+        let mut data = jpeg.clone();
+        for _ in 0..4 {
+            state = state.wrapping_mul(1_103_515_245).wrapping_add(12345);
+            let index = (state >> 8) as usize % data.len();
+            data[index] = (state >> 24) as u8;
+        }
+        let _ = Frame::decode_jpeg(&data);
+    }
+
+    // the SOF0 header claims 65535x65535
+    let mut data = jpeg.clone();
+    let sof = data.windows(2).position(|marker| marker == [0xFF, 0xC0]).unwrap();
+    data[sof + 5..sof + 9].copy_from_slice(&[0xFF, 0xFF, 0xFF, 0xFF]);
+    assert!(Frame::decode_jpeg(&data).is_err());
+}
+
+#[cfg(feature = "mjpeg")]
+fn mjpeg_data_frame(jpeg: Vec<u8>, width: u32, height: u32) -> Frame<'static> {
+    let mut variant = media_core::variant::Variant::new_dict();
+    variant["format"] = u32::from(VideoFormat::Compression(CompressionFormat::MJPEG)).into();
+    variant["width"] = width.into();
+    variant["height"] = height.into();
+    variant["data"] = jpeg.into();
+    Frame::data_creator().create_from_variant(&variant).unwrap()
+}
+
+#[cfg(feature = "mjpeg")]
+#[test]
+fn test_decode_mjpeg_to() {
+    // 4:2:0 and the 4:2:2 most UVC cameras send
+    for format in [PixelFormat::I420, PixelFormat::I422] {
+        let frame = gradient_frame(format, 100, 60);
+        let jpeg = frame.encode_jpeg(90).unwrap();
+        let mjpeg_frame = mjpeg_data_frame(jpeg.clone(), 100, 60);
+
+        let mut i420_frame = Frame::video_creator().create(PixelFormat::I420, 100, 60).unwrap();
+        mjpeg_frame.decode_mjpeg_to(&mut i420_frame).unwrap();
+        assert!(mean_plane_error(&frame, &i420_frame, 0) < 2.0, "{:?}", format);
+        let FrameDescriptor::Video(desc) = i420_frame.descriptor() else {
+            panic!("not video frame");
+        };
+        assert_eq!(desc.color_range, ColorRange::Full);
+
+        let mut rgba_frame = Frame::video_creator().create(PixelFormat::RGBA32, 100, 60).unwrap();
+        mjpeg_frame.decode_mjpeg_to(&mut rgba_frame).unwrap();
+        let mut expected = Frame::video_creator().create(PixelFormat::RGBA32, 100, 60).unwrap();
+        frame.convert_video_to(&mut expected).unwrap();
+        assert!(mean_plane_error(&expected, &rgba_frame, 0) < 3.0, "{:?}", format);
+
+        // cameras often deliver cut off frames
+        let truncated = mjpeg_data_frame(jpeg[..jpeg.len() / 2].to_vec(), 100, 60);
+        assert!(truncated.decode_mjpeg_to(&mut i420_frame).is_err());
+    }
+
+    let frame = gradient_frame(PixelFormat::I420, 100, 60);
+    let mjpeg_frame = mjpeg_data_frame(frame.encode_jpeg(90).unwrap(), 100, 60);
+    let mut nv12_frame = Frame::video_creator().create(PixelFormat::NV12, 100, 60).unwrap();
+    assert!(mjpeg_frame.decode_mjpeg_to(&mut nv12_frame).is_err());
+    let mut small_frame = Frame::video_creator().create(PixelFormat::I420, 50, 30).unwrap();
+    assert!(mjpeg_frame.decode_mjpeg_to(&mut small_frame).is_err());
+    let mut i420_frame = Frame::video_creator().create(PixelFormat::I420, 100, 60).unwrap();
+    assert!(frame.decode_mjpeg_to(&mut i420_frame).is_err());
+
+    let mut variant = mjpeg_frame.data().unwrap().clone();
+    variant["format"] = u32::from(VideoFormat::Pixel(PixelFormat::YUYV)).into();
+    let yuyv_frame = Frame::data_creator().create_from_variant(&variant).unwrap();
+    assert!(yuyv_frame.decode_mjpeg_to(&mut i420_frame).is_err());
+}
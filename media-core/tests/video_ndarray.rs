@@ -0,0 +1,38 @@
+#![cfg(feature = "ndarray")]
+
+use media_core::{frame::Frame, video::*};
+use ndarray::{s, Array3};
+
+#[test]
+fn test_ndarray_hwc() {
+    let array = Array3::from_shape_fn((6, 5, 3), |(y, x, c)| (y * 40 + x * 8 + c) as u8);
+    let mut frame = Frame::from_ndarray_hwc(array.view(), PixelFormat::RGB24).unwrap();
+    assert_eq!(frame.as_ndarray_hwc().unwrap(), array);
+
+    // writes through the view land in the frame
+    frame.as_ndarray_hwc_mut().unwrap()[[2, 3, 1]] = 255;
+    assert_eq!(frame.as_ndarray_hwc().unwrap()[[2, 3, 1]], 255);
+    {
+        let guard = frame.map().unwrap();
+        let plane = guard.plane(0).unwrap();
+        assert_eq!(plane.data[2 * plane.stride + 3 * 3 + 1], 255);
+    }
+
+    // rows padded to a stride are viewed without copying
+    let width = 5;
+    let stride = 32;
+    let buffer: Vec<u8> = (0..stride * 4).map(|i| (i % 251) as u8).collect();
+    let frame = Frame::video_creator().create_from_aligned_buffer(PixelFormat::Y8, width, 4, stride, buffer.as_slice()).unwrap();
+    let view = frame.as_ndarray_hwc().unwrap();
+    assert!(view.is_view());
+    assert_eq!(view.dim(), (4, 5, 1));
+    assert_eq!(view[[3, 4, 0]], buffer[3 * stride as usize + 4]);
+
+    // a non-contiguous source array is copied in logical order
+    let transposed = array.slice(s![.., .., ..;-1]);
+    let frame = Frame::from_ndarray_hwc(transposed, PixelFormat::BGR24).unwrap();
+    assert_eq!(frame.as_ndarray_hwc().unwrap(), transposed);
+
+    assert!(Frame::from_ndarray_hwc(array.view(), PixelFormat::RGBA32).is_err());
+    assert!(Frame::video_creator().create(PixelFormat::I420, 4, 4).unwrap().as_ndarray_hwc().is_err());
+}
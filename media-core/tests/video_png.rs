@@ -0,0 +1,65 @@
+#![cfg(feature = "image")]
+
+use std::{env, fs};
+
+use media_core::{frame::Frame, video::*};
+
+fn decode_png(data: &[u8]) -> (u32, u32, Vec<u8>) {
+    let mut reader = png::Decoder::new(data).read_info().unwrap();
+    let mut pixels = vec![0; reader.output_buffer_size()];
+    let info = reader.next_frame(&mut pixels).unwrap();
+    assert_eq!((info.color_type, info.bit_depth), (png::ColorType::Rgba, png::BitDepth::Eight));
+    pixels.truncate(info.buffer_size());
+    (info.width, info.height, pixels)
+}
+
+#[test]
+fn test_dump_png() {
+    let width = 200;
+    let height = 120;
+    let mut frame = Frame::video_creator().create(PixelFormat::I420, width, height).unwrap();
+    {
+        let mut guard = frame.map_mut().unwrap();
+        let mut planes = guard.planes_mut().unwrap();
+        planes.plane_data_mut(0).unwrap().fill(128);
+        planes.plane_data_mut(1).unwrap().fill(128);
+        planes.plane_data_mut(2).unwrap().fill(128);
+    }
+
+    let dir = env::temp_dir().join(format!("media-core-png-{}", std::process::id()));
+    let mut sink = PngFrameSink::new(&dir, "frame_").unwrap();
+    let first = sink.write(&frame).unwrap();
+    let second = sink.write(&frame).unwrap();
+    assert_eq!(first.file_name().unwrap(), "frame_000000.png");
+    assert_eq!(second.file_name().unwrap(), "frame_000001.png");
+    assert_eq!(sink.count(), 2);
+
+    // limited range mid gray
+    let (png_width, png_height, pixels) = decode_png(&fs::read(&first).unwrap());
+    assert_eq!((png_width, png_height), (width, height));
+    assert_eq!(pixels.len(), (width * height * 4) as usize);
+    for pixel in pixels.chunks(4) {
+        assert!(pixel[..3].iter().all(|&v| v.abs_diff(130) <= 2), "{:?}", pixel);
+        assert_eq!(pixel[3], 255);
+    }
+
+    // RGBA frames are written as is, row padding dropped
+    let mut frame = Frame::video_creator().create(PixelFormat::RGBA32, 37, 11).unwrap();
+    let mut expected = Vec::new();
+    {
+        let mut guard = frame.map_mut().unwrap();
+        let mut planes = guard.planes_mut().unwrap();
+        let stride = planes.plane_stride(0).unwrap();
+        for (y, row) in planes.plane_data_mut(0).unwrap().chunks_mut(stride).enumerate() {
+            for (x, value) in row[..37 * 4].iter_mut().enumerate() {
+                *value = (x * 7 + y * 13) as u8;
+            }
+            expected.extend_from_slice(&row[..37 * 4]);
+        }
+    }
+    let path = dir.join("rgba.png");
+    frame.dump_png(&path).unwrap();
+    assert_eq!(decode_png(&fs::read(&path).unwrap()), (37, 11, expected));
+
+    fs::remove_dir_all(&dir).unwrap();
+}
@@ -1,4 +1,4 @@
-use media_core::{frame::Frame, video::*};
+use media_core::{error::Error, frame::Frame, video::*};
 
 fn test_scale(fmt: PixelFormat, src_width: u32, src_height: u32, dst_width: u32, dst_height: u32, filter: ScaleFilter) {
     let input_frame = Frame::video_creator().create(fmt, src_width, src_height).unwrap();
@@ -27,3 +27,240 @@ fn test_scale_rgb() {
     test_scale(PixelFormat::RGB24, 640, 480, 320, 240, ScaleFilter::Bilinear);
     test_scale(PixelFormat::RGB24, 640, 480, 320, 240, ScaleFilter::Bicubic);
 }
+
+fn fill_i420(frame: &mut Frame, f: impl Fn(usize, usize, usize) -> u8) {
+    let mut guard = frame.map_mut().unwrap();
+    let mut planes = guard.planes_mut().unwrap();
+    for plane_index in 0..3 {
+        let stride = planes.plane_stride(plane_index).unwrap();
+        for (y, row) in planes.plane_data_mut(plane_index).unwrap().chunks_mut(stride).enumerate() {
+            for (x, value) in row.iter_mut().enumerate() {
+                *value = f(plane_index, x, y);
+            }
+        }
+    }
+}
+
+#[test]
+fn test_scale_i420_planes() {
+    let luma = |x: usize, y: usize| ((x / 8 + y / 5) / 2) as u8;
+    let mut src = Frame::video_creator().create(PixelFormat::I420, 1920, 1080).unwrap();
+    fill_i420(&mut src, |plane_index, x, y| match plane_index {
+        0 => luma(x, y),
+        1 => 60,
+        _ => 190,
+    });
+
+    let mut dst = Frame::video_creator().create(PixelFormat::I420, 960, 540).unwrap();
+    src.scale_to(&mut dst, ScaleFilter::Bilinear).unwrap();
+
+    let guard = dst.map().unwrap();
+    let y_plane = guard.plane(0).unwrap();
+    let mut max_error = 0;
+    // skip the edges where the filter clamps
+    for y in 1..539 {
+        for x in 1..959 {
+            let reference = ([(0, 0), (1, 0), (0, 1), (1, 1)].iter().map(|(dx, dy)| luma(x * 2 + dx, y * 2 + dy) as u32).sum::<u32>() + 2) / 4;
+            max_error = max_error.max((y_plane.data[y * y_plane.stride + x] as u32).abs_diff(reference));
+        }
+    }
+    assert!(max_error <= 2, "luma error {}", max_error);
+
+    // chroma planes are scaled on their own at half the dimensions
+    for (plane_index, expected) in [(1, 60), (2, 190)] {
+        let plane = guard.plane(plane_index).unwrap();
+        assert_eq!((plane.row_bytes, plane.height), (480, 270));
+        for row in plane.data.chunks(plane.stride).take(270) {
+            assert!(row[..480].iter().all(|&value| value == expected));
+        }
+    }
+    drop(guard);
+
+    // odd sizes round the chroma dimensions up
+    let mut src = Frame::video_creator().create(PixelFormat::I420, 641, 481).unwrap();
+    fill_i420(&mut src, |plane_index, _, _| [100, 70, 170][plane_index]);
+    let mut dst = Frame::video_creator().create(PixelFormat::I420, 321, 241).unwrap();
+    src.scale_to(&mut dst, ScaleFilter::Bilinear).unwrap();
+    let guard = dst.map().unwrap();
+    for (plane_index, (row_bytes, height, expected)) in [(321, 241, 100), (161, 121, 70), (161, 121, 170)].into_iter().enumerate() {
+        let plane = guard.plane(plane_index).unwrap();
+        assert_eq!((plane.row_bytes, plane.height as usize), (row_bytes, height));
+        for row in plane.data.chunks(plane.stride).take(height) {
+            assert!(row[..row_bytes].iter().all(|&value| value == expected));
+        }
+    }
+}
+
+#[test]
+fn test_scale_nv12_preview() {
+    let mut src = Frame::video_creator().create(PixelFormat::NV12, 1920, 1080).unwrap();
+    {
+        let mut guard = src.map_mut().unwrap();
+        let mut planes = guard.planes_mut().unwrap();
+        let stride = planes.plane_stride(0).unwrap();
+        for (y, row) in planes.plane_data_mut(0).unwrap().chunks_mut(stride).enumerate() {
+            row.fill(if y < 540 {
+                50
+            } else {
+                200
+            });
+        }
+        let stride = planes.plane_stride(1).unwrap();
+        for row in planes.plane_data_mut(1).unwrap().chunks_mut(stride) {
+            for pair in row.chunks_exact_mut(2) {
+                pair.copy_from_slice(&[60, 190]);
+            }
+        }
+    }
+
+    for filter in [ScaleFilter::Nearest, ScaleFilter::Bilinear, ScaleFilter::Bicubic] {
+        let mut dst = Frame::video_creator().create(PixelFormat::NV12, 1280, 720).unwrap();
+        src.scale_to(&mut dst, filter).unwrap();
+
+        let guard = dst.map().unwrap();
+        let y_plane = guard.plane(0).unwrap();
+        // flat areas away from the edge stay flat with every filter
+        for (y, row) in y_plane.data.chunks(y_plane.stride).take(720).enumerate().filter(|(y, _)| !(350..370).contains(y)) {
+            let expected = if y < 360 {
+                50
+            } else {
+                200
+            };
+            assert!(row[..1280].iter().all(|&value| value == expected), "{:?} row {}", filter, y);
+        }
+
+        // interleaved chroma keeps its pairs at 640x360 samples
+        let uv_plane = guard.plane(1).unwrap();
+        assert_eq!((uv_plane.row_bytes, uv_plane.height), (1280, 360));
+        for row in uv_plane.data.chunks(uv_plane.stride).take(360) {
+            assert!(row[..1280].chunks_exact(2).all(|pair| pair == [60, 190]), "{:?}", filter);
+        }
+    }
+
+    let src = Frame::video_creator().create(PixelFormat::YUYV, 64, 64).unwrap();
+    let mut dst = Frame::video_creator().create(PixelFormat::YUYV, 32, 32).unwrap();
+    assert!(matches!(src.scale_to(&mut dst, ScaleFilter::Bilinear), Err(Error::Unsupported(_))));
+}
+
+#[test]
+fn test_scale_fit_to() {
+    // left half red, right half blue
+    let mut src = Frame::video_creator().create(PixelFormat::RGBA32, 1920, 1080).unwrap();
+    src.fill_from_fn(|x, _| {
+        if x < 960 {
+            [255, 0, 0, 255]
+        } else {
+            [0, 0, 255, 255]
+        }
+    })
+    .unwrap();
+
+    let mut dst = Frame::video_creator().create(PixelFormat::RGBA32, 640, 640).unwrap();
+    src.scale_fit_to(&mut dst, ScaleFilter::Bilinear, [0, 255, 0]).unwrap();
+
+    // 640x360 image between 140 pixel bars
+    let guard = dst.map().unwrap();
+    let plane = guard.plane(0).unwrap();
+    let pixel = |x: usize, y: usize| &plane.data[y * plane.stride + x * 4..][..4];
+    for y in (0..140).chain(500..640) {
+        for x in 0..640 {
+            assert_eq!(pixel(x, y), [0, 255, 0, 255], "bar at {}x{}", x, y);
+        }
+    }
+    for y in 140..500 {
+        assert_eq!(pixel(0, y), [255, 0, 0, 255]);
+        assert_eq!(pixel(318, y), [255, 0, 0, 255]);
+        assert_eq!(pixel(321, y), [0, 0, 255, 255]);
+        assert_eq!(pixel(639, y), [0, 0, 255, 255]);
+    }
+    drop(guard);
+
+    // pillarbox into a planar frame, bars sit on whole chroma samples
+    let mut src = Frame::video_creator().create(PixelFormat::I420, 640, 480).unwrap();
+    fill_i420(&mut src, |_, _, _| 200);
+    let mut dst = Frame::video_creator().create(PixelFormat::I420, 1280, 720).unwrap();
+    src.scale_fit_to(&mut dst, ScaleFilter::Bilinear, [0, 0, 0]).unwrap();
+
+    let guard = dst.map().unwrap();
+    for plane_index in 0..3 {
+        let plane = guard.plane(plane_index).unwrap();
+        // 960 pixel image at 160
+        let (left, right) = if plane_index == 0 {
+            (160, 1120)
+        } else {
+            (80, 560)
+        };
+        let black = if plane_index == 0 {
+            16
+        } else {
+            128
+        };
+        for row in plane.data.chunks(plane.stride).take(plane.height as usize) {
+            assert!(row[..left].iter().chain(&row[right..plane.row_bytes]).all(|value| *value == black));
+            assert!(row[left..right].iter().all(|value| *value == 200));
+        }
+    }
+
+    let mut dst = Frame::video_creator().create(PixelFormat::NV12, 640, 640).unwrap();
+    assert!(src.scale_fit_to(&mut dst, ScaleFilter::Bilinear, [0, 0, 0]).is_err());
+}
+
+fn mean_rgb(frame: &Frame) -> f64 {
+    let guard = frame.map().unwrap();
+    let plane = guard.plane(0).unwrap();
+    let mut sum = 0u64;
+    for row in plane.data.chunks(plane.stride).take(plane.height as usize) {
+        sum += row[..plane.row_bytes].iter().map(|value| *value as u64).sum::<u64>();
+    }
+    sum as f64 / (plane.row_bytes * plane.height as usize) as f64
+}
+
+#[test]
+fn test_scale_linear_light() {
+    let mut src = Frame::video_creator().create(PixelFormat::RGB24, 64, 64).unwrap();
+    {
+        let mut guard = src.map_mut().unwrap();
+        let mut planes = guard.planes_mut().unwrap();
+        let stride = planes.plane_stride(0).unwrap();
+        for (y, row) in planes.plane_data_mut(0).unwrap().chunks_mut(stride).enumerate() {
+            for (x, pixel) in row[..64 * 3].chunks_exact_mut(3).enumerate() {
+                pixel.fill(if (x + y) % 2 == 0 {
+                    255
+                } else {
+                    0
+                });
+            }
+        }
+    }
+
+    // averaging the encoded values gives 0.5 in gamma space, only ~0.21 in light
+    let mut gamma = Frame::video_creator().create(PixelFormat::RGB24, 32, 32).unwrap();
+    src.scale_to(&mut gamma, ScaleFilter::Bilinear).unwrap();
+    assert!((mean_rgb(&gamma) - 127.5).abs() < 3.0, "gamma mean {}", mean_rgb(&gamma));
+
+    // half the light is sRGB 188
+    let mut linear = Frame::video_creator().create(PixelFormat::RGB24, 32, 32).unwrap();
+    src.scale_to_linear(&mut linear, ScaleFilter::Bilinear).unwrap();
+    assert!((mean_rgb(&linear) - 188.0).abs() < 3.0, "linear mean {}", mean_rgb(&linear));
+
+    // the same through explicit conversions
+    let mut scaled = Frame::video_creator().create(PixelFormat::RGB24, 32, 32).unwrap();
+    src.to_linear().unwrap().scale_to(&mut scaled, ScaleFilter::Bilinear).unwrap();
+    assert!((mean_rgb(&scaled) - 127.5).abs() < 3.0);
+    let encoded = scaled.to_gamma().unwrap();
+    assert_eq!(encoded.video_descriptor().unwrap().color_transfer_characteristics, ColorTransferCharacteristics::IEC61966_2_1);
+    assert!((mean_rgb(&encoded) - 188.0).abs() < 3.0);
+
+    // timing is kept on both frame types
+    let mut src = VideoFrame::new(PixelFormat::RGB24, 8, 8).unwrap();
+    src.pts = Some(3003);
+    src.sequence = Some(7);
+    for converted in [src.to_linear().unwrap(), src.to_gamma().unwrap()] {
+        assert_eq!((converted.pts, converted.sequence), (Some(3003), Some(7)));
+    }
+
+    let src = Frame::video_creator().create(PixelFormat::I420, 64, 64).unwrap();
+    assert!(src.to_linear().is_err());
+    let mut dst = Frame::video_creator().create(PixelFormat::I420, 32, 32).unwrap();
+    assert!(src.scale_to_linear(&mut dst, ScaleFilter::Bilinear).is_err());
+}
@@ -1,6 +1,5 @@
 use std::sync::RwLock;
 
-use env_logger;
 use log::{error, info, warn};
 use media_core::{
     frame::{Frame, SharedFrame},
@@ -1,23 +1,37 @@
 use cfg_if::cfg_if;
 use media_core::{
-    video::{ColorRange, VideoFormat},
+    unsupported_error,
+    variant::Variant,
+    video::{ColorRange, CompressionFormat, PixelFormat, VideoFormat},
     Result,
 };
 
-use crate::{DeviceEvent, DeviceManager};
+use crate::{BackendInfo, DeviceEvent, DeviceManager};
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum CameraBackend {
+    AVFoundation,
+    MediaFoundation,
+    V4L2,
+}
 
 cfg_if! {
     if #[cfg(target_os = "windows")] {
         pub use crate::backend::media_foundation::MediaFoundationDeviceManager as DefaultCameraManager;
+        pub const DEFAULT_CAMERA_BACKEND: CameraBackend = CameraBackend::MediaFoundation;
     } else if #[cfg(any(target_os = "macos", target_os = "ios"))] {
         pub use crate::backend::av_foundation::AVFoundationCaptureDeviceManager as DefaultCameraManager;
+        pub const DEFAULT_CAMERA_BACKEND: CameraBackend = CameraBackend::AVFoundation;
+    } else if #[cfg(target_os = "linux")] {
+        pub use crate::backend::v4l2::V4L2DeviceManager as DefaultCameraManager;
+        pub const DEFAULT_CAMERA_BACKEND: CameraBackend = CameraBackend::V4L2;
     } else {
         compile_error!("unsupported target");
     }
 }
 
-#[derive(Clone, Debug)]
-pub(crate) struct CameraFormat {
+#[derive(Clone, Debug, PartialEq)]
+pub struct CameraFormat {
     pub format: VideoFormat,
     pub color_range: ColorRange,
     pub width: u32,
@@ -25,6 +39,124 @@ pub(crate) struct CameraFormat {
     pub frame_rates: Vec<f32>,
 }
 
+impl CameraFormat {
+    // one entry of the array returned by Device::formats, the same keys on
+    // every backend
+    pub(crate) fn to_variant(&self) -> Variant {
+        let mut format = Variant::new_dict();
+        format["format"] = (Into::<u32>::into(self.format)).into();
+        format["color-range"] = (usize::from(self.color_range) as u32).into();
+        format["width"] = self.width.into();
+        format["height"] = self.height.into();
+        format["frame-rates"] = self.frame_rates.iter().map(|frame_rate| Variant::from(*frame_rate)).collect();
+        format
+    }
+
+    fn from_variant(value: &Variant) -> Option<Self> {
+        Some(Self {
+            format: video_format_from_variant(&value["format"])?,
+            color_range: value["color-range"].get_uint32().map_or(ColorRange::default(), |color_range| ColorRange::from(color_range as usize)),
+            width: value["width"].get_uint32()?,
+            height: value["height"].get_uint32()?,
+            frame_rates: value["frame-rates"].array_iter().map(|iter| iter.filter_map(Variant::get_float).collect()).unwrap_or_default(),
+        })
+    }
+
+    // with the keys Device::configure accepts, the first frame rate is the one in
+    // use
+    pub(crate) fn to_config(&self) -> Variant {
+        let mut config = Variant::new_dict();
+        config["format"] = (Into::<u32>::into(self.format)).into();
+        config["color-range"] = (usize::from(self.color_range) as u32).into();
+        config["width"] = self.width.into();
+        config["height"] = self.height.into();
+        if let Some(frame_rate) = self.frame_rates.first() {
+            config["frame-rate"] = (*frame_rate).into();
+        }
+        config
+    }
+}
+
+// AVFoundation devices used to report the format by name, e.g. "NV12"
+fn video_format_from_variant(value: &Variant) -> Option<VideoFormat> {
+    if let Some(format) = value.get_uint32() {
+        return VideoFormat::try_from(format).ok();
+    }
+
+    let name = value.get_string()?;
+    (0..)
+        .map_while(|index: usize| PixelFormat::try_from(index).ok())
+        .map(VideoFormat::Pixel)
+        .chain([VideoFormat::Compression(CompressionFormat::MJPEG)])
+        .find(|format| format.to_string() == name)
+}
+
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct Resolution {
+    pub width: u32,
+    pub height: u32,
+}
+
+impl Resolution {
+    pub const fn new(width: u32, height: u32) -> Self {
+        Self {
+            width,
+            height,
+        }
+    }
+}
+
+// Static camera properties shared by the backends. The pixel array size falls
+// back to the native resolution when the sensor size is not reported
+pub(crate) fn camera_properties(model: &str, formats: &[CameraFormat], pixel_array_size: Option<Resolution>) -> Variant {
+    let size = |resolution: Resolution| {
+        let mut size = Variant::new_dict();
+        size["width"] = resolution.width.into();
+        size["height"] = resolution.height.into();
+        size
+    };
+
+    let native_resolution = formats
+        .iter()
+        .map(|format| Resolution::new(format.width, format.height))
+        .max_by_key(|resolution| resolution.width as u64 * resolution.height as u64);
+
+    let mut properties = Variant::new_dict();
+    properties["model"] = model.into();
+    if let Some(native_resolution) = native_resolution {
+        properties["native-resolution"] = size(native_resolution);
+    }
+    if let Some(pixel_array_size) = pixel_array_size.or(native_resolution) {
+        properties["pixel-array-size"] = size(pixel_array_size);
+    }
+
+    properties
+}
+
+// Pick the format earliest in `preferred`, then the nearest resolution,
+// preferring the larger one on ties. An empty `preferred` accepts any format.
+// Formats reporting a color range other than `color_range` are skipped unless
+// it is Unspecified, formats that do not report one always match
+pub fn negotiate_format(formats: &Variant, preferred: &[VideoFormat], desired: Resolution, color_range: ColorRange) -> Option<CameraFormat> {
+    formats
+        .array_iter()?
+        .filter_map(CameraFormat::from_variant)
+        .filter(|format| color_range == ColorRange::Unspecified || format.color_range == ColorRange::Unspecified || format.color_range == color_range)
+        .filter_map(|format| {
+            let rank = if preferred.is_empty() {
+                0
+            } else {
+                preferred.iter().position(|preferred| *preferred == format.format)?
+            };
+            let distance = format.width.abs_diff(desired.width) as u64 + format.height.abs_diff(desired.height) as u64;
+            let area = format.width as u64 * format.height as u64;
+
+            Some(((rank, distance, std::cmp::Reverse(area)), format))
+        })
+        .min_by_key(|(key, _)| *key)
+        .map(|(_, format)| format)
+}
+
 pub struct CameraManager<T: DeviceManager> {
     backend: T,
 }
@@ -38,6 +170,10 @@ impl<T: DeviceManager> CameraManager<T> {
         })
     }
 
+    pub fn backend_info(&self) -> BackendInfo {
+        self.backend.backend_info()
+    }
+
     pub fn index(&self, index: usize) -> Option<&T::DeviceType> {
         self.backend.index(index)
     }
@@ -84,4 +220,13 @@ impl CameraManager<DefaultCameraManager> {
     pub fn new_default() -> Result<Self> {
         Self::new()
     }
+
+    // Only one camera backend is compiled in for each target
+    pub fn new_with_backend(backend: CameraBackend) -> Result<Self> {
+        if backend != DEFAULT_CAMERA_BACKEND {
+            return Err(unsupported_error!(backend));
+        }
+
+        Self::new()
+    }
 }
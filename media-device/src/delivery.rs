@@ -0,0 +1,272 @@
+use std::{
+    collections::VecDeque,
+    sync::{
+        atomic::{AtomicU64, Ordering::Relaxed},
+        Arc, Condvar, Mutex,
+    },
+    thread::{self, JoinHandle},
+    time::{Duration, Instant},
+};
+
+use media_core::{error::Error, failed_error, frame::Frame, invalid_param_error, unsupported_error, Result};
+
+use crate::OutputHandler;
+
+pub const DEFAULT_QUEUE_SIZE: usize = 4;
+
+// What happens to captured frames while the output handler is still busy
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum DropPolicy {
+    #[default]
+    Block, // the handler runs on the capture thread
+    DropNewest, // frames arriving at a full queue are dropped
+    DropOldest, // the oldest queued frame makes room for the new one
+}
+
+impl DropPolicy {
+    pub fn from_name(name: &str) -> Result<Self> {
+        match name {
+            "block" => Ok(DropPolicy::Block),
+            "drop-newest" => Ok(DropPolicy::DropNewest),
+            "drop-oldest" => Ok(DropPolicy::DropOldest),
+            _ => Err(unsupported_error!(name)),
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            DropPolicy::Block => "block",
+            DropPolicy::DropNewest => "drop-newest",
+            DropPolicy::DropOldest => "drop-oldest",
+        }
+    }
+}
+
+struct Queue {
+    frames: VecDeque<Frame<'static>>,
+    closed: bool,
+}
+
+type SharedQueue = Arc<(Mutex<Queue>, Condvar)>;
+
+// Decouples the capture thread from the output handler. Except for the block
+// policy, frames are queued and handed to the handler on a delivery thread
+pub struct FrameDelivery {
+    policy: DropPolicy,
+    capacity: usize,
+    handler: OutputHandler,
+    queue: SharedQueue,
+    dropped: AtomicU64,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl FrameDelivery {
+    pub fn new<F>(policy: DropPolicy, capacity: usize, handler: F) -> Result<Self>
+    where
+        F: Fn(Frame) -> Result<()> + Send + Sync + 'static,
+    {
+        Self::new_with_handler(policy, capacity, Arc::new(handler))
+    }
+
+    pub(crate) fn new_with_handler(policy: DropPolicy, capacity: usize, handler: OutputHandler) -> Result<Self> {
+        if capacity == 0 {
+            return Err(invalid_param_error!(capacity));
+        }
+
+        let queue: SharedQueue = Arc::new((
+            Mutex::new(Queue {
+                frames: VecDeque::with_capacity(capacity),
+                closed: false,
+            }),
+            Condvar::new(),
+        ));
+
+        let thread = if policy == DropPolicy::Block {
+            None
+        } else {
+            let queue = queue.clone();
+            let handler = handler.clone();
+            let thread = thread::Builder::new()
+                .name("frame-delivery".to_string())
+                .spawn(move || Self::run(&queue, &handler))
+                .map_err(|err| Error::CreationFailed(err.to_string().into()))?;
+            Some(thread)
+        };
+
+        Ok(Self {
+            policy,
+            capacity,
+            handler,
+            queue,
+            dropped: AtomicU64::new(0),
+            thread,
+        })
+    }
+
+    fn run(queue: &SharedQueue, handler: &OutputHandler) {
+        let (lock, condvar) = &**queue;
+        loop {
+            let frame = {
+                let Ok(mut queue) = lock.lock() else {
+                    return;
+                };
+                loop {
+                    if queue.closed {
+                        return;
+                    }
+                    if let Some(frame) = queue.frames.pop_front() {
+                        break frame;
+                    }
+                    queue = match condvar.wait(queue) {
+                        Ok(queue) => queue,
+                        Err(_) => return,
+                    };
+                }
+            };
+
+            handler(frame).ok();
+        }
+    }
+
+    pub fn policy(&self) -> DropPolicy {
+        self.policy
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    // number of frames discarded because the handler did not keep up
+    pub fn dropped(&self) -> u64 {
+        self.dropped.load(Relaxed)
+    }
+
+    // called from the capture thread, only blocks with the block policy
+    pub fn push(&self, frame: Frame) -> Result<()> {
+        if self.policy == DropPolicy::Block {
+            return (self.handler)(frame);
+        }
+
+        let (lock, condvar) = &*self.queue;
+        let mut queue = lock.lock().map_err(|err| failed_error!(err.to_string()))?;
+        if queue.frames.len() >= self.capacity {
+            self.dropped.fetch_add(1, Relaxed);
+            match self.policy {
+                DropPolicy::DropNewest => return Ok(()),
+                _ => {
+                    queue.frames.pop_front();
+                }
+            }
+        }
+
+        // queued frames outlive the capture buffer they may borrow
+        queue.frames.push_back(frame.into_owned());
+        condvar.notify_one();
+
+        Ok(())
+    }
+}
+
+impl Drop for FrameDelivery {
+    fn drop(&mut self) {
+        let (lock, condvar) = &*self.queue;
+        if let Ok(mut queue) = lock.lock() {
+            queue.closed = true;
+            queue.frames.clear();
+        }
+        condvar.notify_all();
+
+        if let Some(thread) = self.thread.take() {
+            // the handler itself may release the last reference
+            if thread.thread().id() != thread::current().id() {
+                thread.join().ok();
+            }
+        }
+    }
+}
+
+// Bounded queue for pulling frames from a thread of the caller's choosing.
+// When full the oldest frame is dropped, so a slow reader always gets the
+// most recent frames
+#[derive(Clone)]
+pub struct FrameReceiver {
+    capacity: usize,
+    queue: SharedQueue,
+    dropped: Arc<AtomicU64>,
+}
+
+impl FrameReceiver {
+    pub fn new(capacity: usize) -> Result<Self> {
+        if capacity == 0 {
+            return Err(invalid_param_error!(capacity));
+        }
+
+        Ok(Self {
+            capacity,
+            queue: Arc::new((
+                Mutex::new(Queue {
+                    frames: VecDeque::with_capacity(capacity),
+                    closed: false,
+                }),
+                Condvar::new(),
+            )),
+            dropped: Arc::new(AtomicU64::new(0)),
+        })
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    // number of frames discarded because the reader did not keep up
+    pub fn dropped(&self) -> u64 {
+        self.dropped.load(Relaxed)
+    }
+
+    pub fn push(&self, frame: Frame) -> Result<()> {
+        let (lock, condvar) = &*self.queue;
+        let mut queue = lock.lock().map_err(|err| failed_error!(err.to_string()))?;
+        if queue.closed {
+            return Ok(());
+        }
+        if queue.frames.len() >= self.capacity {
+            self.dropped.fetch_add(1, Relaxed);
+            queue.frames.pop_front();
+        }
+
+        queue.frames.push_back(frame.into_owned());
+        condvar.notify_one();
+
+        Ok(())
+    }
+
+    // waits up to `timeout` for the next frame, Error::Again when none arrived
+    pub fn read_frame(&self, timeout: Duration) -> Result<Frame<'static>> {
+        let deadline = Instant::now() + timeout;
+        let (lock, condvar) = &*self.queue;
+        let mut queue = lock.lock().map_err(|err| failed_error!(err.to_string()))?;
+        loop {
+            if let Some(frame) = queue.frames.pop_front() {
+                return Ok(frame);
+            }
+            if queue.closed {
+                return Err(Error::NotRunning("frame receiver closed".into()));
+            }
+
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if remaining.is_zero() {
+                return Err(Error::Again("no frame".into()));
+            }
+            queue = condvar.wait_timeout(queue, remaining).map_err(|err| failed_error!(err.to_string()))?.0;
+        }
+    }
+
+    // wakes up waiting readers, frames still queued can be read
+    pub fn close(&self) {
+        let (lock, condvar) = &*self.queue;
+        if let Ok(mut queue) = lock.lock() {
+            queue.closed = true;
+        }
+        condvar.notify_all();
+    }
+}
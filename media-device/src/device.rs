@@ -1,17 +1,37 @@
-use std::sync::Arc;
+use std::{sync::Arc, time::Duration};
 
-use media_core::{frame::Frame, variant::Variant, Result};
+use media_core::{error::Error, frame::Frame, unsupported_error, variant::Variant, Result};
 
 #[derive(Clone, Debug)]
 pub struct DeviceInformation {
     pub id: String,
     pub name: String,
+    pub path: String, // What the backend opens, the same as id unless id is a stable location
+}
+
+#[derive(Clone, Debug)]
+pub struct BackendInfo {
+    pub name: &'static str,
+    pub version: String,
+}
+
+impl BackendInfo {
+    #[allow(unused)]
+    pub(crate) fn with_os_version(name: &'static str) -> Self {
+        let version = os_ver::os_version();
+
+        Self {
+            name,
+            version: format!("{}.{}.{}.{}", version.major, version.minor, version.patch, version.build),
+        }
+    }
 }
 
 pub enum DeviceEvent {
     Added(DeviceInformation), // Device added
     Removed(String),          // Device removed, removed device ID
     Refreshed(usize),         // All devices refreshed, number of devices
+    Error(String, Error),     // Device failed while running, device ID and error
 }
 
 #[allow(unused)]
@@ -26,6 +46,18 @@ pub trait Device {
     fn control(&mut self, action: &Variant) -> Result<()>;
     fn running(&self) -> bool;
     fn formats(&self) -> Result<Variant>;
+    // static properties such as the model and sensor size
+    fn properties(&self) -> Result<Variant> {
+        Err(unsupported_error!("properties"))
+    }
+    // the configuration in use while running, after any adjustment
+    fn effective_config(&self) -> Result<Variant> {
+        Err(unsupported_error!("effective config"))
+    }
+    // counters for the current or last stream
+    fn statistics(&self) -> Result<Variant> {
+        Err(unsupported_error!("statistics"))
+    }
 }
 
 pub trait OutputDevice: Device {
@@ -34,6 +66,14 @@ pub trait OutputDevice: Device {
         F: Fn(Frame) -> Result<()> + Send + Sync + 'static;
 }
 
+// Pull-based alternative to the output handler, used when no handler is set.
+// Only V4L2 devices implement it so far, AVFoundation and Media Foundation
+// devices still need an output handler
+pub trait InputDevice: Device {
+    fn read_frame(&mut self, timeout: Duration) -> Result<Frame<'static>>;
+    fn dropped_frames(&self) -> u64; // frames discarded because the queue was full
+}
+
 #[allow(unused)]
 pub(crate) type DeviceEventHandler = Box<dyn Fn(&DeviceEvent) + Send + Sync>;
 
@@ -50,6 +90,7 @@ pub trait DeviceManager {
     where
         Self: Sized;
     fn deinit(&mut self);
+    fn backend_info(&self) -> BackendInfo;
     fn index(&self, index: usize) -> Option<&Self::DeviceType>;
     fn index_mut(&mut self, index: usize) -> Option<&mut Self::DeviceType>;
     fn lookup(&self, id: &str) -> Option<&Self::DeviceType>;
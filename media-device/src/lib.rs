@@ -1,11 +1,15 @@
 #[cfg(feature = "video")]
-#[cfg(any(target_os = "windows", target_os = "macos", target_os = "ios"))]
+#[cfg(any(target_os = "windows", target_os = "macos", target_os = "ios", target_os = "linux"))]
 pub mod camera;
 
+mod delivery;
 mod device;
+mod shared;
 
 use cfg_if::cfg_if;
+pub use delivery::*;
 pub use device::*;
+pub use shared::*;
 
 cfg_if! {
     if #[cfg(target_os = "windows")] {
@@ -14,5 +18,8 @@ cfg_if! {
     } else if #[cfg(any(target_os = "macos", target_os = "ios"))] {
         #[path = "mac/mod.rs"]
         pub mod backend;
+    } else if #[cfg(target_os = "linux")] {
+        #[path = "linux/mod.rs"]
+        pub mod backend;
     }
 }
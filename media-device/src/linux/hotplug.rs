@@ -0,0 +1,136 @@
+use std::{
+    io,
+    mem::{size_of, zeroed},
+    os::fd::{AsRawFd, FromRawFd, OwnedFd},
+    sync::{
+        atomic::{AtomicBool, Ordering::SeqCst},
+        Arc,
+    },
+    thread::{self, JoinHandle},
+};
+
+use media_core::{failed_error, Result};
+
+const UEVENT_BUFFER_SIZE: usize = 8192;
+const POLL_TIMEOUT_MS: libc::c_int = 100;
+// the kernel multicast group, udevd rebroadcasts on another one
+const KERNEL_UEVENT_GROUP: u32 = 1;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(crate) enum HotplugAction {
+    Add,
+    Remove,
+}
+
+// Parse a kernel uevent, only video4linux device nodes are of interest
+fn parse_uevent(message: &[u8]) -> Option<(HotplugAction, String)> {
+    let mut action = None;
+    let mut subsystem = None;
+    let mut dev_name = None;
+
+    // the first field is the "action@devpath" summary
+    for field in message.split(|&byte| byte == 0).skip(1) {
+        let field = std::str::from_utf8(field).ok()?;
+        if let Some(value) = field.strip_prefix("ACTION=") {
+            action = match value {
+                "add" => Some(HotplugAction::Add),
+                "remove" => Some(HotplugAction::Remove),
+                _ => return None,
+            };
+        } else if let Some(value) = field.strip_prefix("SUBSYSTEM=") {
+            subsystem = Some(value);
+        } else if let Some(value) = field.strip_prefix("DEVNAME=") {
+            dev_name = Some(value);
+        }
+    }
+
+    if subsystem? != "video4linux" {
+        return None;
+    }
+
+    let dev_name = dev_name?;
+    let path = if dev_name.starts_with('/') {
+        dev_name.to_string()
+    } else {
+        format!("/dev/{}", dev_name)
+    };
+
+    Some((action?, path))
+}
+
+fn open_uevent_socket() -> io::Result<OwnedFd> {
+    let fd = unsafe { libc::socket(libc::AF_NETLINK, libc::SOCK_DGRAM | libc::SOCK_CLOEXEC, libc::NETLINK_KOBJECT_UEVENT) };
+    if fd < 0 {
+        return Err(io::Error::last_os_error());
+    }
+    let socket = unsafe { OwnedFd::from_raw_fd(fd) };
+
+    let mut addr: libc::sockaddr_nl = unsafe { zeroed() };
+    addr.nl_family = libc::AF_NETLINK as libc::sa_family_t;
+    addr.nl_groups = KERNEL_UEVENT_GROUP;
+    let ret =
+        unsafe { libc::bind(fd, &addr as *const libc::sockaddr_nl as *const libc::sockaddr, size_of::<libc::sockaddr_nl>() as libc::socklen_t) };
+    if ret < 0 {
+        return Err(io::Error::last_os_error());
+    }
+
+    Ok(socket)
+}
+
+// Listens for kernel uevents on a thread until dropped
+pub(crate) struct HotplugMonitor {
+    running: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl HotplugMonitor {
+    pub(crate) fn start<F>(callback: F) -> Result<Self>
+    where
+        F: Fn(HotplugAction, String) + Send + 'static,
+    {
+        let socket = open_uevent_socket().map_err(|err| failed_error!(err.to_string()))?;
+        let running = Arc::new(AtomicBool::new(true));
+        let thread_running = running.clone();
+
+        let thread = thread::Builder::new()
+            .name("v4l2-hotplug".to_string())
+            .spawn(move || {
+                let mut buffer = vec![0u8; UEVENT_BUFFER_SIZE];
+                while thread_running.load(SeqCst) {
+                    // wake up regularly to notice the stop request
+                    let mut poll_fd = libc::pollfd {
+                        fd: socket.as_raw_fd(),
+                        events: libc::POLLIN,
+                        revents: 0,
+                    };
+                    if unsafe { libc::poll(&mut poll_fd, 1, POLL_TIMEOUT_MS) } <= 0 {
+                        continue;
+                    }
+
+                    let len = unsafe { libc::recv(socket.as_raw_fd(), buffer.as_mut_ptr() as *mut libc::c_void, buffer.len(), 0) };
+                    if len <= 0 {
+                        continue;
+                    }
+
+                    if let Some((action, path)) = parse_uevent(&buffer[..len as usize]) {
+                        callback(action, path);
+                    }
+                }
+            })
+            .map_err(|err| failed_error!(err.to_string()))?;
+
+        Ok(Self {
+            running,
+            thread: Some(thread),
+        })
+    }
+}
+
+impl Drop for HotplugMonitor {
+    fn drop(&mut self) {
+        self.running.store(false, SeqCst);
+        if let Some(thread) = self.thread.take() {
+            thread.join().ok();
+        }
+    }
+}
@@ -0,0 +1,4 @@
+#[cfg(all(feature = "video", feature = "hotplug"))]
+mod hotplug;
+#[cfg(feature = "video")]
+pub mod v4l2;
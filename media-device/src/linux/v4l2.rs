@@ -0,0 +1,1590 @@
+#[cfg(feature = "hotplug")]
+use std::{collections::HashMap, sync::Mutex};
+use std::{
+    ffi::{c_void, CStr},
+    fs::{self, File, OpenOptions},
+    io,
+    mem::{size_of, zeroed},
+    num::NonZeroU32,
+    os::{fd::AsRawFd, unix::fs::OpenOptionsExt},
+    path::Path,
+    ptr::null_mut,
+    slice::{from_raw_parts, Iter, IterMut},
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering::SeqCst},
+        Arc, OnceLock, RwLock,
+    },
+    thread::{self, JoinHandle},
+    time::Duration,
+};
+
+use media_core::{
+    error::Error,
+    failed_error,
+    frame::Frame,
+    invalid_param_error, not_found_error,
+    time::{MSEC_PER_SEC, USEC_PER_MSEC, USEC_PER_SEC},
+    unsupported_error,
+    variant::Variant,
+    video::{ColorRange, CompressionFormat, PixelFormat, VideoFormat, VideoFrameDescriptor},
+    Result,
+};
+
+#[cfg(feature = "hotplug")]
+use super::hotplug::{HotplugAction, HotplugMonitor};
+use crate::{
+    camera::{camera_properties, CameraFormat, Resolution},
+    BackendInfo, Device, DeviceEvent, DeviceEventHandler, DeviceInformation, DeviceManager, DropPolicy, FrameDelivery, FrameReceiver, InputDevice,
+    OutputDevice, OutputHandler, DEFAULT_QUEUE_SIZE,
+};
+
+#[cfg(feature = "hotplug")]
+const HOTPLUG_OPEN_RETRIES: usize = 10;
+#[cfg(feature = "hotplug")]
+const HOTPLUG_OPEN_RETRY_INTERVAL: std::time::Duration = std::time::Duration::from_millis(50);
+
+const SYSFS_VIDEO4LINUX: &str = "/sys/class/video4linux";
+
+const V4L2_BUF_TYPE_VIDEO_CAPTURE: u32 = 1;
+const V4L2_MEMORY_MMAP: u32 = 1;
+const V4L2_FIELD_ANY: u32 = 0;
+
+const V4L2_CAP_VIDEO_CAPTURE: u32 = 0x00000001;
+const V4L2_CAP_STREAMING: u32 = 0x04000000;
+const V4L2_CAP_DEVICE_CAPS: u32 = 0x80000000;
+const V4L2_CAP_TIMEPERFRAME: u32 = 0x1000;
+
+const V4L2_FRMSIZE_TYPE_DISCRETE: u32 = 1;
+const V4L2_FRMIVAL_TYPE_DISCRETE: u32 = 1;
+
+const V4L2_SEL_TGT_CROP: u32 = 0;
+
+const V4L2_QUANTIZATION_FULL_RANGE: u32 = 1;
+const V4L2_QUANTIZATION_LIM_RANGE: u32 = 2;
+
+#[repr(C)]
+struct V4l2Capability {
+    driver: [u8; 16],
+    card: [u8; 32],
+    bus_info: [u8; 32],
+    version: u32,
+    capabilities: u32,
+    device_caps: u32,
+    reserved: [u32; 3],
+}
+
+#[repr(C)]
+struct V4l2FmtDesc {
+    index: u32,
+    type_: u32,
+    flags: u32,
+    description: [u8; 32],
+    pixelformat: u32,
+    mbus_code: u32,
+    reserved: [u32; 3],
+}
+
+// discrete: [width, height]
+// stepwise: [min_w, max_w, step_w, min_h, max_h, step_h]
+#[repr(C)]
+struct V4l2FrmSizeEnum {
+    index: u32,
+    pixel_format: u32,
+    type_: u32,
+    sizes: [u32; 6],
+    reserved: [u32; 2],
+}
+
+// discrete: [numerator, denominator]
+// stepwise: min, max and step fractions
+#[repr(C)]
+struct V4l2FrmIvalEnum {
+    index: u32,
+    pixel_format: u32,
+    width: u32,
+    height: u32,
+    type_: u32,
+    intervals: [u32; 6],
+    reserved: [u32; 2],
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+struct V4l2PixFormat {
+    width: u32,
+    height: u32,
+    pixelformat: u32,
+    field: u32,
+    bytesperline: u32,
+    sizeimage: u32,
+    colorspace: u32,
+    priv_: u32,
+    flags: u32,
+    ycbcr_enc: u32,
+    quantization: u32,
+    xfer_func: u32,
+}
+
+#[repr(C)]
+union V4l2FormatUnion {
+    pix: V4l2PixFormat,
+    // the kernel union contains pointers, so it is pointer aligned
+    raw_data: [libc::c_ulong; 200 / size_of::<libc::c_ulong>()],
+}
+
+#[repr(C)]
+struct V4l2Format {
+    type_: u32,
+    fmt: V4l2FormatUnion,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+struct V4l2Fract {
+    numerator: u32,
+    denominator: u32,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+struct V4l2Rect {
+    left: i32,
+    top: i32,
+    width: u32,
+    height: u32,
+}
+
+#[repr(C)]
+struct V4l2CropCap {
+    type_: u32,
+    bounds: V4l2Rect,
+    defrect: V4l2Rect,
+    pixelaspect: V4l2Fract,
+}
+
+#[repr(C)]
+struct V4l2Selection {
+    type_: u32,
+    target: u32,
+    flags: u32,
+    r: V4l2Rect,
+    reserved: [u32; 9],
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+struct V4l2CaptureParm {
+    capability: u32,
+    capturemode: u32,
+    timeperframe: V4l2Fract,
+    extendedmode: u32,
+    readbuffers: u32,
+    reserved: [u32; 4],
+}
+
+#[repr(C)]
+union V4l2StreamParmUnion {
+    capture: V4l2CaptureParm,
+    raw_data: [u8; 200],
+}
+
+#[repr(C)]
+struct V4l2StreamParm {
+    type_: u32,
+    parm: V4l2StreamParmUnion,
+}
+
+#[repr(C)]
+struct V4l2RequestBuffers {
+    count: u32,
+    type_: u32,
+    memory: u32,
+    capabilities: u32,
+    flags: u8,
+    reserved: [u8; 3],
+}
+
+#[repr(C)]
+struct V4l2Timecode {
+    type_: u32,
+    flags: u32,
+    frames: u8,
+    seconds: u8,
+    minutes: u8,
+    hours: u8,
+    userbits: [u8; 4],
+}
+
+#[repr(C)]
+union V4l2BufferM {
+    offset: u32,
+    userptr: libc::c_ulong,
+    planes: *mut c_void,
+    fd: i32,
+}
+
+#[repr(C)]
+struct V4l2Buffer {
+    index: u32,
+    type_: u32,
+    bytesused: u32,
+    flags: u32,
+    field: u32,
+    timestamp: libc::timeval,
+    timecode: V4l2Timecode,
+    sequence: u32,
+    memory: u32,
+    m: V4l2BufferM,
+    length: u32,
+    reserved2: u32,
+    request_fd: i32,
+}
+
+#[cfg(target_pointer_width = "64")]
+const _: () = {
+    assert!(size_of::<V4l2Format>() == 208);
+    assert!(size_of::<V4l2Buffer>() == 88);
+    assert!(size_of::<V4l2Selection>() == 64);
+};
+
+const IOC_WRITE: u32 = 1;
+const IOC_READ: u32 = 2;
+
+const fn ioc(dir: u32, nr: u32, size: usize) -> libc::Ioctl {
+    ((dir << 30) | ((size as u32) << 16) | ((b'V' as u32) << 8) | nr) as libc::Ioctl
+}
+
+const VIDIOC_QUERYCAP: libc::Ioctl = ioc(IOC_READ, 0, size_of::<V4l2Capability>());
+const VIDIOC_ENUM_FMT: libc::Ioctl = ioc(IOC_READ | IOC_WRITE, 2, size_of::<V4l2FmtDesc>());
+const VIDIOC_S_FMT: libc::Ioctl = ioc(IOC_READ | IOC_WRITE, 5, size_of::<V4l2Format>());
+const VIDIOC_REQBUFS: libc::Ioctl = ioc(IOC_READ | IOC_WRITE, 8, size_of::<V4l2RequestBuffers>());
+const VIDIOC_QUERYBUF: libc::Ioctl = ioc(IOC_READ | IOC_WRITE, 9, size_of::<V4l2Buffer>());
+const VIDIOC_QBUF: libc::Ioctl = ioc(IOC_READ | IOC_WRITE, 15, size_of::<V4l2Buffer>());
+const VIDIOC_DQBUF: libc::Ioctl = ioc(IOC_READ | IOC_WRITE, 17, size_of::<V4l2Buffer>());
+const VIDIOC_STREAMON: libc::Ioctl = ioc(IOC_WRITE, 18, size_of::<libc::c_int>());
+const VIDIOC_STREAMOFF: libc::Ioctl = ioc(IOC_WRITE, 19, size_of::<libc::c_int>());
+const VIDIOC_G_PARM: libc::Ioctl = ioc(IOC_READ | IOC_WRITE, 21, size_of::<V4l2StreamParm>());
+const VIDIOC_S_PARM: libc::Ioctl = ioc(IOC_READ | IOC_WRITE, 22, size_of::<V4l2StreamParm>());
+const VIDIOC_CROPCAP: libc::Ioctl = ioc(IOC_READ | IOC_WRITE, 58, size_of::<V4l2CropCap>());
+const VIDIOC_S_SELECTION: libc::Ioctl = ioc(IOC_READ | IOC_WRITE, 95, size_of::<V4l2Selection>());
+const VIDIOC_ENUM_FRAMESIZES: libc::Ioctl = ioc(IOC_READ | IOC_WRITE, 74, size_of::<V4l2FrmSizeEnum>());
+const VIDIOC_ENUM_FRAMEINTERVALS: libc::Ioctl = ioc(IOC_READ | IOC_WRITE, 75, size_of::<V4l2FrmIvalEnum>());
+
+fn xioctl<T>(file: &File, request: libc::Ioctl, arg: &mut T) -> io::Result<()> {
+    loop {
+        if unsafe { libc::ioctl(file.as_raw_fd(), request, arg as *mut T) } != -1 {
+            return Ok(());
+        }
+
+        let err = io::Error::last_os_error();
+        if err.kind() != io::ErrorKind::Interrupted {
+            return Err(err);
+        }
+    }
+}
+
+const fn fourcc(code: &[u8; 4]) -> u32 {
+    u32::from_le_bytes(*code)
+}
+
+// raw formats use the PixelFormat FourCC table, only compressed ones are
+// mapped here
+fn from_v4l2_fourcc(pixel_format: u32) -> Option<VideoFormat> {
+    match &pixel_format.to_le_bytes() {
+        b"MJPG" | b"JPEG" => Some(VideoFormat::Compression(CompressionFormat::MJPEG)),
+        code => str::from_utf8(code).ok().and_then(|code| PixelFormat::from_fourcc_str(code).ok()).map(VideoFormat::Pixel),
+    }
+}
+
+fn into_v4l2_fourcc(format: VideoFormat) -> Option<u32> {
+    match format {
+        VideoFormat::Pixel(format) => format.fourcc_str().and_then(|code| code.as_bytes().try_into().ok()).map(u32::from_le_bytes),
+        VideoFormat::Compression(CompressionFormat::MJPEG) => Some(fourcc(b"MJPG")),
+    }
+}
+
+fn from_v4l2_quantization(quantization: u32) -> ColorRange {
+    match quantization {
+        V4L2_QUANTIZATION_FULL_RANGE => ColorRange::Full,
+        V4L2_QUANTIZATION_LIM_RANGE => ColorRange::Video,
+        _ => ColorRange::Unspecified,
+    }
+}
+
+fn open_device(path: &str) -> io::Result<File> {
+    OpenOptions::new().read(true).write(true).custom_flags(libc::O_NONBLOCK).open(path)
+}
+
+// The parent device path in sysfs plus the node index, e.g.
+// "pci0000:00/0000:00:14.0/usb1/1-2/1-2:1.0/video-index0". Unlike the
+// /dev/videoN numbering it stays the same across reboots for a given port
+fn get_device_location(path: &str) -> Option<String> {
+    let node = Path::new(SYSFS_VIDEO4LINUX).join(Path::new(path).file_name()?);
+    let device = fs::canonicalize(node.join("device")).ok()?;
+    let device = device.strip_prefix("/sys/devices").ok()?.to_str()?;
+    let index = fs::read_to_string(node.join("index")).ok().and_then(|index| index.trim().parse::<u32>().ok()).unwrap_or(0);
+
+    Some(format!("{}/video-index{}", device, index))
+}
+
+fn query_capability(file: &File) -> io::Result<V4l2Capability> {
+    let mut capability: V4l2Capability = unsafe { zeroed() };
+    xioctl(file, VIDIOC_QUERYCAP, &mut capability)?;
+    Ok(capability)
+}
+
+// the sensor area the driver can capture from, not reported by UVC cameras
+fn get_crop_cap(file: &File) -> Option<V4l2CropCap> {
+    let mut crop_cap: V4l2CropCap = unsafe { zeroed() };
+    crop_cap.type_ = V4L2_BUF_TYPE_VIDEO_CAPTURE;
+    xioctl(file, VIDIOC_CROPCAP, &mut crop_cap).ok()?;
+
+    (crop_cap.bounds.width != 0 && crop_cap.bounds.height != 0).then_some(crop_cap)
+}
+
+fn get_crop_bounds(file: &File) -> Option<V4l2Rect> {
+    get_crop_cap(file).map(|crop_cap| crop_cap.bounds)
+}
+
+fn get_pixel_array_size(file: &File) -> Option<Resolution> {
+    get_crop_bounds(file).map(|bounds| Resolution::new(bounds.width, bounds.height))
+}
+
+// selects the sensor region to capture from, clamped to the crop bounds,
+// returns the region the driver applied
+fn set_scaler_crop(file: &File, (x, y, width, height): (u32, u32, u32, u32)) -> Result<(u32, u32, u32, u32)> {
+    let bounds = get_crop_bounds(file).ok_or_else(|| unsupported_error!("scaler crop"))?;
+    let bounds_left = bounds.left.max(0) as u32;
+    let bounds_top = bounds.top.max(0) as u32;
+    let left = x.clamp(bounds_left, bounds_left + bounds.width);
+    let top = y.clamp(bounds_top, bounds_top + bounds.height);
+    let right = x.saturating_add(width).min(bounds_left + bounds.width);
+    let bottom = y.saturating_add(height).min(bounds_top + bounds.height);
+
+    if right <= left || bottom <= top {
+        return Err(invalid_param_error!("scaler crop"));
+    }
+
+    let mut selection: V4l2Selection = unsafe { zeroed() };
+    selection.type_ = V4L2_BUF_TYPE_VIDEO_CAPTURE;
+    selection.target = V4L2_SEL_TGT_CROP;
+    selection.r = V4l2Rect {
+        left: left as i32,
+        top: top as i32,
+        width: right - left,
+        height: bottom - top,
+    };
+    xioctl(file, VIDIOC_S_SELECTION, &mut selection).map_err(|err| Error::SetFailed(err.to_string().into()))?;
+
+    let rect = selection.r;
+    Ok((rect.left.max(0) as u32, rect.top.max(0) as u32, rect.width, rect.height))
+}
+
+// the crop persists in the driver, so it is put back to the default when no
+// region is configured, None if the driver does not support cropping
+fn reset_scaler_crop(file: &File) -> Option<(u32, u32, u32, u32)> {
+    let default = get_crop_cap(file)?.defrect;
+    set_scaler_crop(file, (default.left.max(0) as u32, default.top.max(0) as u32, default.width, default.height)).ok()
+}
+
+fn get_frame_sizes(file: &File, pixel_format: u32) -> Vec<(u32, u32)> {
+    let mut sizes = vec![];
+    let mut frame_size: V4l2FrmSizeEnum = unsafe { zeroed() };
+    frame_size.pixel_format = pixel_format;
+
+    while xioctl(file, VIDIOC_ENUM_FRAMESIZES, &mut frame_size).is_ok() {
+        if frame_size.type_ == V4L2_FRMSIZE_TYPE_DISCRETE {
+            sizes.push((frame_size.sizes[0], frame_size.sizes[1]));
+        } else {
+            // stepwise or continuous, only report the bounds
+            sizes.push((frame_size.sizes[0], frame_size.sizes[3]));
+            sizes.push((frame_size.sizes[1], frame_size.sizes[4]));
+            break;
+        }
+        frame_size.index += 1;
+    }
+
+    sizes
+}
+
+fn get_frame_rates(file: &File, pixel_format: u32, width: u32, height: u32) -> Vec<f32> {
+    let mut frame_rates = vec![];
+    let mut frame_interval: V4l2FrmIvalEnum = unsafe { zeroed() };
+    frame_interval.pixel_format = pixel_format;
+    frame_interval.width = width;
+    frame_interval.height = height;
+
+    while xioctl(file, VIDIOC_ENUM_FRAMEINTERVALS, &mut frame_interval).is_ok() {
+        let intervals = &frame_interval.intervals;
+        if frame_interval.type_ == V4L2_FRMIVAL_TYPE_DISCRETE {
+            if intervals[0] != 0 {
+                frame_rates.push(intervals[1] as f32 / intervals[0] as f32);
+            }
+        } else {
+            // stepwise or continuous, report the max and min frame rates
+            if intervals[0] != 0 {
+                frame_rates.push(intervals[1] as f32 / intervals[0] as f32);
+            }
+            if intervals[2] != 0 {
+                frame_rates.push(intervals[3] as f32 / intervals[2] as f32);
+            }
+            break;
+        }
+        frame_interval.index += 1;
+    }
+
+    frame_rates
+}
+
+fn get_formats(file: &File) -> Vec<CameraFormat> {
+    let mut formats = vec![];
+    let mut format_desc: V4l2FmtDesc = unsafe { zeroed() };
+    format_desc.type_ = V4L2_BUF_TYPE_VIDEO_CAPTURE;
+
+    while xioctl(file, VIDIOC_ENUM_FMT, &mut format_desc).is_ok() {
+        if let Some(format) = from_v4l2_fourcc(format_desc.pixelformat) {
+            for (width, height) in get_frame_sizes(file, format_desc.pixelformat) {
+                formats.push(CameraFormat {
+                    format,
+                    color_range: ColorRange::Unspecified,
+                    width,
+                    height,
+                    frame_rates: get_frame_rates(file, format_desc.pixelformat, width, height),
+                });
+            }
+        }
+        format_desc.index += 1;
+    }
+
+    formats
+}
+
+const SIMILAR_FORMAT_DIFF: f32 = 1.0;
+const DIFFERENT_FORMAT_DIFF: f32 = 2.0;
+
+fn select_supported_format(
+    formats: &[CameraFormat],
+    width: Option<u32>,
+    height: Option<u32>,
+    video_format: Option<VideoFormat>,
+    frame_rate: Option<f32>,
+) -> Option<(CameraFormat, Option<f32>)> {
+    let mut min_diff = f32::INFINITY;
+    let mut matched_format = None;
+
+    for camera_format in formats {
+        let resolution_diff = match (width, height) {
+            (Some(width), Some(height)) => (camera_format.width as f32 - width as f32).abs() + (camera_format.height as f32 - height as f32).abs(),
+            _ => 0.0,
+        };
+
+        // the nearest rate, the faster one on ties, formats without listed
+        // rates are only taken when nothing else is available
+        let (frame_rate_diff, matched_frame_rate) = match frame_rate {
+            Some(frame_rate) => camera_format
+                .frame_rates
+                .iter()
+                .map(|supported_frame_rate| ((supported_frame_rate - frame_rate).abs(), *supported_frame_rate))
+                .min_by(|a, b| a.0.total_cmp(&b.0).then(b.1.total_cmp(&a.1)))
+                .map_or((f32::MAX, None), |(diff, supported_frame_rate)| (diff, Some(supported_frame_rate))),
+            None => (0.0, camera_format.frame_rates.first().cloned()),
+        };
+
+        let format_diff = match video_format {
+            Some(video_format) => {
+                if camera_format.format == video_format {
+                    0.0
+                } else if camera_format.format.is_yuv() && video_format.is_yuv() {
+                    SIMILAR_FORMAT_DIFF
+                } else {
+                    DIFFERENT_FORMAT_DIFF
+                }
+            }
+            // prefer formats that can be delivered as raw frames
+            None => match camera_format.format {
+                VideoFormat::Pixel(_) => 0.0,
+                VideoFormat::Compression(_) => DIFFERENT_FORMAT_DIFF,
+            },
+        };
+
+        let diff = resolution_diff + frame_rate_diff + format_diff;
+
+        if diff < min_diff {
+            min_diff = diff;
+            matched_format = Some((camera_format.clone(), matched_frame_rate));
+        }
+    }
+
+    matched_format
+}
+
+// Returns the format the driver actually chose
+fn set_format(file: &File, camera_format: &CameraFormat, frame_rate: Option<f32>) -> Result<(CameraFormat, V4l2PixFormat)> {
+    let pixel_format = into_v4l2_fourcc(camera_format.format).ok_or_else(|| unsupported_error!(camera_format.format))?;
+
+    let mut format: V4l2Format = unsafe { zeroed() };
+    format.type_ = V4L2_BUF_TYPE_VIDEO_CAPTURE;
+    format.fmt.pix = V4l2PixFormat {
+        width: camera_format.width,
+        height: camera_format.height,
+        pixelformat: pixel_format,
+        field: V4L2_FIELD_ANY,
+        ..unsafe { zeroed() }
+    };
+    xioctl(file, VIDIOC_S_FMT, &mut format).map_err(|err| Error::SetFailed(err.to_string().into()))?;
+    let pix = unsafe { format.fmt.pix };
+
+    let mut param: V4l2StreamParm = unsafe { zeroed() };
+    param.type_ = V4L2_BUF_TYPE_VIDEO_CAPTURE;
+    xioctl(file, VIDIOC_G_PARM, &mut param).map_err(|err| Error::GetFailed(err.to_string().into()))?;
+
+    if let Some(frame_rate) = frame_rate {
+        if unsafe { param.parm.capture.capability } & V4L2_CAP_TIMEPERFRAME != 0 {
+            param.parm.capture.timeperframe = V4l2Fract {
+                numerator: MSEC_PER_SEC as u32,
+                denominator: (frame_rate * MSEC_PER_SEC as f32).round() as u32,
+            };
+            xioctl(file, VIDIOC_S_PARM, &mut param).map_err(|err| Error::SetFailed(err.to_string().into()))?;
+        }
+    }
+
+    let time_per_frame = unsafe { param.parm.capture.timeperframe };
+    let frame_rates = if time_per_frame.numerator != 0 {
+        vec![time_per_frame.denominator as f32 / time_per_frame.numerator as f32]
+    } else {
+        vec![]
+    };
+
+    let camera_format = CameraFormat {
+        format: from_v4l2_fourcc(pix.pixelformat).ok_or_else(|| unsupported_error!(pix.pixelformat))?,
+        color_range: from_v4l2_quantization(pix.quantization),
+        width: pix.width,
+        height: pix.height,
+        frame_rates,
+    };
+
+    Ok((camera_format, pix))
+}
+
+// Shared with the devices so capture threads can report failures
+type SharedEventHandler = Arc<RwLock<Option<DeviceEventHandler>>>;
+
+const BUFFER_COUNT: u32 = 4;
+const POLL_TIMEOUT_MS: i32 = 100;
+
+struct MappedBuffer {
+    ptr: *mut c_void,
+    length: usize,
+}
+
+impl MappedBuffer {
+    fn data(&self) -> &[u8] {
+        unsafe { from_raw_parts(self.ptr as *const u8, self.length) }
+    }
+}
+
+impl Drop for MappedBuffer {
+    fn drop(&mut self) {
+        unsafe {
+            libc::munmap(self.ptr, self.length);
+        }
+    }
+}
+
+// Written by the capture thread and read through Device::statistics
+struct StreamStatistics {
+    frames_dequeued: AtomicU64,
+    frames_delivered: AtomicU64,
+    frames_dropped: AtomicU64,  // gaps in the driver's buffer sequence
+    last_sequence: AtomicU64,   // u64::MAX before the first buffer
+    first_timestamp: AtomicU64, // in microseconds
+    last_timestamp: AtomicU64,  // in microseconds
+}
+
+impl StreamStatistics {
+    fn new() -> Self {
+        Self {
+            frames_dequeued: AtomicU64::new(0),
+            frames_delivered: AtomicU64::new(0),
+            frames_dropped: AtomicU64::new(0),
+            last_sequence: AtomicU64::new(u64::MAX),
+            first_timestamp: AtomicU64::new(0),
+            last_timestamp: AtomicU64::new(0),
+        }
+    }
+
+    fn record(&self, sequence: u32, timestamp: u64) {
+        let last_sequence = self.last_sequence.swap(sequence as u64, SeqCst);
+        if last_sequence != u64::MAX {
+            let gap = (sequence as u64).saturating_sub(last_sequence + 1);
+            self.frames_dropped.fetch_add(gap, SeqCst);
+        }
+
+        if self.frames_dequeued.fetch_add(1, SeqCst) == 0 {
+            self.first_timestamp.store(timestamp, SeqCst);
+        }
+        self.last_timestamp.store(timestamp, SeqCst);
+    }
+
+    // the average time between dequeued buffers in milliseconds
+    fn frame_interval(&self) -> f64 {
+        let frames = self.frames_dequeued.load(SeqCst);
+        if frames < 2 {
+            return 0.0;
+        }
+
+        let elapsed = self.last_timestamp.load(SeqCst).saturating_sub(self.first_timestamp.load(SeqCst));
+        elapsed as f64 / (frames - 1) as f64 / USEC_PER_MSEC as f64
+    }
+
+    fn to_variant(&self) -> Variant {
+        let mut variant = Variant::new_dict();
+        variant["frames-delivered"] = self.frames_delivered.load(SeqCst).into();
+        variant["frames-dropped"] = self.frames_dropped.load(SeqCst).into();
+        variant["requests-queued"] = BUFFER_COUNT.into();
+        variant["frame-interval"] = self.frame_interval().into();
+        variant
+    }
+}
+
+struct Stream {
+    file: File,
+    buffers: Vec<MappedBuffer>,
+    info: DeviceInformation,
+    format: CameraFormat,
+    pix: V4l2PixFormat,
+    handler: OutputHandler,
+    event_handler: SharedEventHandler,
+    sequence: Arc<AtomicU64>, // kept by the device, numbering continues across restarts
+    statistics: Arc<StreamStatistics>,
+}
+
+// The mapped buffers are only accessed from the capture thread
+unsafe impl Send for Stream {}
+
+impl Stream {
+    #[allow(clippy::too_many_arguments)]
+    fn new(
+        file: File,
+        info: DeviceInformation,
+        format: CameraFormat,
+        pix: V4l2PixFormat,
+        handler: OutputHandler,
+        event_handler: SharedEventHandler,
+        sequence: Arc<AtomicU64>,
+        statistics: Arc<StreamStatistics>,
+    ) -> Result<Self> {
+        let mut request: V4l2RequestBuffers = unsafe { zeroed() };
+        request.count = BUFFER_COUNT;
+        request.type_ = V4L2_BUF_TYPE_VIDEO_CAPTURE;
+        request.memory = V4L2_MEMORY_MMAP;
+        xioctl(&file, VIDIOC_REQBUFS, &mut request).map_err(|err| Error::CreationFailed(err.to_string().into()))?;
+
+        let mut stream = Self {
+            file,
+            buffers: Vec::with_capacity(request.count as usize),
+            info,
+            format,
+            pix,
+            handler,
+            event_handler,
+            sequence,
+            statistics,
+        };
+
+        for index in 0..request.count {
+            let mut buffer = stream.new_buffer(index);
+            xioctl(&stream.file, VIDIOC_QUERYBUF, &mut buffer).map_err(|err| Error::CreationFailed(err.to_string().into()))?;
+
+            let ptr = unsafe {
+                libc::mmap(
+                    null_mut(),
+                    buffer.length as usize,
+                    libc::PROT_READ | libc::PROT_WRITE,
+                    libc::MAP_SHARED,
+                    stream.file.as_raw_fd(),
+                    buffer.m.offset as libc::off_t,
+                )
+            };
+            if ptr == libc::MAP_FAILED {
+                return Err(Error::CreationFailed(io::Error::last_os_error().to_string().into()));
+            }
+
+            stream.buffers.push(MappedBuffer {
+                ptr,
+                length: buffer.length as usize,
+            });
+
+            xioctl(&stream.file, VIDIOC_QBUF, &mut buffer).map_err(|err| Error::StartFailed(err.to_string().into()))?;
+        }
+
+        let mut buffer_type = V4L2_BUF_TYPE_VIDEO_CAPTURE as libc::c_int;
+        xioctl(&stream.file, VIDIOC_STREAMON, &mut buffer_type).map_err(|err| Error::StartFailed(err.to_string().into()))?;
+
+        Ok(stream)
+    }
+
+    fn new_buffer(&self, index: u32) -> V4l2Buffer {
+        let mut buffer: V4l2Buffer = unsafe { zeroed() };
+        buffer.index = index;
+        buffer.type_ = V4L2_BUF_TYPE_VIDEO_CAPTURE;
+        buffer.memory = V4L2_MEMORY_MMAP;
+        buffer
+    }
+
+    fn run(&self, running: &AtomicBool) {
+        while running.load(SeqCst) {
+            let mut poll_fd = libc::pollfd {
+                fd: self.file.as_raw_fd(),
+                events: libc::POLLIN,
+                revents: 0,
+            };
+
+            match unsafe { libc::poll(&mut poll_fd, 1, POLL_TIMEOUT_MS) } {
+                0 => continue,
+                ret if ret < 0 => {
+                    let err = io::Error::last_os_error();
+                    if err.kind() == io::ErrorKind::Interrupted {
+                        continue;
+                    }
+                    self.report_error(err);
+                    break;
+                }
+                _ => {}
+            }
+
+            let mut buffer = self.new_buffer(0);
+            if let Err(err) = xioctl(&self.file, VIDIOC_DQBUF, &mut buffer) {
+                if err.kind() == io::ErrorKind::WouldBlock {
+                    continue;
+                }
+                self.report_error(err);
+                break;
+            }
+
+            self.statistics.record(buffer.sequence, buffer.timestamp.tv_sec as u64 * USEC_PER_SEC + buffer.timestamp.tv_usec as u64);
+
+            if let Some(mapped_buffer) = self.buffers.get(buffer.index as usize) {
+                let data = mapped_buffer.data();
+                let length = (buffer.bytesused as usize).min(data.len());
+                self.output(&data[..length], &buffer);
+            }
+
+            if let Err(err) = xioctl(&self.file, VIDIOC_QBUF, &mut buffer) {
+                self.report_error(err);
+                break;
+            }
+        }
+    }
+
+    // the capture loop stops, e.g. the camera was unplugged
+    fn report_error(&self, err: io::Error) {
+        if let Ok(handler) = self.event_handler.read() {
+            if let Some(handler) = handler.as_ref() {
+                handler(&DeviceEvent::Error(self.info.id.clone(), failed_error!(err.to_string())));
+            }
+        }
+    }
+
+    fn output(&self, data: &[u8], buffer: &V4l2Buffer) {
+        let VideoFormat::Pixel(pixel_format) = self.format.format else {
+            self.output_compressed(data, buffer);
+            return;
+        };
+
+        let (Some(width), Some(height), Some(stride)) =
+            (NonZeroU32::new(self.format.width), NonZeroU32::new(self.format.height), NonZeroU32::new(self.pix.bytesperline))
+        else {
+            return;
+        };
+
+        let size = self.pix.sizeimage as usize;
+        if data.len() < size {
+            return;
+        }
+
+        let mut desc = VideoFrameDescriptor::new(pixel_format, width, height);
+        desc.color_range = self.format.color_range;
+
+        if let Ok(video_frame) = Frame::video_creator().create_from_aligned_buffer_with_descriptor(desc, stride, &data[..size]) {
+            self.deliver(video_frame, buffer);
+        }
+    }
+
+    // compressed pictures are passed on as a data frame holding
+    // {format, width, height, data}, see Frame::decode_mjpeg_to
+    fn output_compressed(&self, data: &[u8], buffer: &V4l2Buffer) {
+        if data.is_empty() {
+            return;
+        }
+
+        let mut variant = Variant::new_dict();
+        variant["format"] = u32::from(self.format.format).into();
+        variant["width"] = self.format.width.into();
+        variant["height"] = self.format.height.into();
+        variant["data"] = data.to_vec().into();
+
+        if let Ok(data_frame) = Frame::data_creator().create_from_variant(&variant) {
+            self.deliver(data_frame, buffer);
+        }
+    }
+
+    fn deliver(&self, mut frame: Frame, buffer: &V4l2Buffer) {
+        frame.source = Some(self.info.id.clone());
+        frame.pts = Some((buffer.timestamp.tv_sec as u64 * MSEC_PER_SEC + buffer.timestamp.tv_usec as u64 / USEC_PER_MSEC) as i64);
+        frame.sequence = Some(self.sequence.fetch_add(1, SeqCst));
+        let handler = self.handler.as_ref();
+        handler(frame).ok();
+        self.statistics.frames_delivered.fetch_add(1, SeqCst);
+    }
+}
+
+impl Drop for Stream {
+    fn drop(&mut self) {
+        let mut buffer_type = V4L2_BUF_TYPE_VIDEO_CAPTURE as libc::c_int;
+        xioctl(&self.file, VIDIOC_STREAMOFF, &mut buffer_type).ok();
+
+        // buffers must be unmapped before they can be released
+        self.buffers.clear();
+
+        let mut request: V4l2RequestBuffers = unsafe { zeroed() };
+        request.type_ = V4L2_BUF_TYPE_VIDEO_CAPTURE;
+        request.memory = V4L2_MEMORY_MMAP;
+        xioctl(&self.file, VIDIOC_REQBUFS, &mut request).ok();
+    }
+}
+
+pub struct V4L2DeviceManager {
+    devices: Option<Vec<V4L2Device>>,
+    handler: SharedEventHandler,
+    #[cfg(feature = "hotplug")]
+    hotplug: Option<HotplugMonitor>,
+    #[cfg(feature = "hotplug")]
+    devices_changed: Arc<AtomicBool>, // set by the monitor, the device list is synced on the next mutable access
+    #[cfg(feature = "hotplug")]
+    listed_devices: ListedDevices,
+}
+
+// node path to device ID of the listed devices, a removed node can no longer
+// be resolved to its location
+#[cfg(feature = "hotplug")]
+type ListedDevices = Arc<Mutex<HashMap<String, String>>>;
+
+impl DeviceManager for V4L2DeviceManager {
+    type DeviceType = V4L2Device;
+    type Iter<'a>
+        = Iter<'a, V4L2Device>
+    where
+        Self: 'a;
+    type IterMut<'a>
+        = IterMut<'a, V4L2Device>
+    where
+        Self: 'a;
+
+    fn init() -> Result<Self>
+    where
+        Self: Sized,
+    {
+        Ok(Self::new())
+    }
+
+    fn deinit(&mut self) {
+        #[cfg(feature = "hotplug")]
+        {
+            self.hotplug = None;
+        }
+        self.devices = None;
+    }
+
+    fn backend_info(&self) -> BackendInfo {
+        BackendInfo::with_os_version("V4L2")
+    }
+
+    fn index(&self, index: usize) -> Option<&Self::DeviceType> {
+        self.devices.as_ref().and_then(|devices| devices.get(index))
+    }
+
+    fn index_mut(&mut self, index: usize) -> Option<&mut Self::DeviceType> {
+        self.sync_devices();
+        self.devices.as_mut().and_then(|devices| devices.get_mut(index))
+    }
+
+    // matches the stable sysfs location or the device node
+    fn lookup(&self, id: &str) -> Option<&Self::DeviceType> {
+        self.devices.as_ref().and_then(|devices| devices.iter().find(|device| device.matches(id)))
+    }
+
+    fn lookup_mut(&mut self, id: &str) -> Option<&mut Self::DeviceType> {
+        self.sync_devices();
+        self.devices.as_mut().and_then(|devices| devices.iter_mut().find(|device| device.matches(id)))
+    }
+
+    fn iter(&self) -> Iter<'_, V4L2Device> {
+        self.devices.as_deref().unwrap_or(&[]).iter()
+    }
+
+    fn iter_mut(&mut self) -> IterMut<'_, V4L2Device> {
+        self.sync_devices();
+        self.devices.as_deref_mut().unwrap_or(&mut []).iter_mut()
+    }
+
+    fn refresh(&mut self) -> Result<()> {
+        #[cfg(feature = "hotplug")]
+        self.devices_changed.store(false, SeqCst);
+
+        let devices: Vec<V4L2Device> = Self::get_device_paths()?
+            .iter()
+            .filter_map(|path| DeviceInformation::from_device_path(path))
+            .map(|info| V4L2Device::new(info, self.handler.clone()))
+            .collect();
+
+        let count = devices.len();
+        self.devices = Some(devices);
+        #[cfg(feature = "hotplug")]
+        self.update_listed_devices();
+        if let Some(handler) = self.handler.read().map_err(|err| failed_error!(err.to_string()))?.as_ref() {
+            handler(&DeviceEvent::Refreshed(count));
+        }
+        Ok(())
+    }
+
+    fn set_change_handler<F>(&mut self, handler: F) -> Result<()>
+    where
+        F: Fn(&DeviceEvent) + Send + Sync + 'static,
+    {
+        *self.handler.write().map_err(|err| failed_error!(err.to_string()))? = Some(Box::new(handler));
+
+        #[cfg(feature = "hotplug")]
+        if self.hotplug.is_none() {
+            self.hotplug = Some(Self::start_hotplug(self.handler.clone(), self.devices_changed.clone(), self.listed_devices.clone())?);
+        }
+
+        Ok(())
+    }
+}
+
+impl Default for V4L2DeviceManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl V4L2DeviceManager {
+    pub fn new() -> Self {
+        Self {
+            devices: None,
+            handler: Arc::new(RwLock::new(None)),
+            #[cfg(feature = "hotplug")]
+            hotplug: None,
+            #[cfg(feature = "hotplug")]
+            devices_changed: Arc::new(AtomicBool::new(false)),
+            #[cfg(feature = "hotplug")]
+            listed_devices: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    #[cfg(feature = "hotplug")]
+    fn start_hotplug(handler: SharedEventHandler, devices_changed: Arc<AtomicBool>, listed_devices: ListedDevices) -> Result<HotplugMonitor> {
+        HotplugMonitor::start(move |action, path| {
+            let Some(event) = Self::hotplug_event(action, path, &listed_devices) else {
+                return;
+            };
+
+            devices_changed.store(true, SeqCst);
+            if let Ok(handler) = handler.read() {
+                if let Some(handler) = handler.as_ref() {
+                    handler(&event);
+                }
+            }
+        })
+    }
+
+    // Removals are only reported for listed devices, by their ID rather than
+    // the node path, nodes that are not cameras are ignored
+    #[cfg(feature = "hotplug")]
+    fn hotplug_event(action: HotplugAction, path: String, listed_devices: &Mutex<HashMap<String, String>>) -> Option<DeviceEvent> {
+        match action {
+            HotplugAction::Add => {
+                // udev may still be setting up the node permissions
+                let info = (0..HOTPLUG_OPEN_RETRIES).find_map(|retry| {
+                    if retry > 0 {
+                        thread::sleep(HOTPLUG_OPEN_RETRY_INTERVAL);
+                    }
+                    DeviceInformation::from_device_path(&path)
+                })?;
+                listed_devices.lock().ok()?.insert(path, info.id.clone());
+                Some(DeviceEvent::Added(info))
+            }
+            HotplugAction::Remove => listed_devices.lock().ok()?.remove(&path).map(DeviceEvent::Removed),
+        }
+    }
+
+    #[cfg(feature = "hotplug")]
+    fn update_listed_devices(&self) {
+        if let Ok(mut listed_devices) = self.listed_devices.lock() {
+            *listed_devices = self.iter().map(|device| (device.info.path.clone(), device.info.id.clone())).collect();
+        }
+    }
+
+    // Apply hot-plug changes, devices that are still present are kept so
+    // running ones are not stopped
+    fn sync_devices(&mut self) {
+        #[cfg(feature = "hotplug")]
+        if self.devices_changed.swap(false, SeqCst) {
+            let Ok(paths) = Self::get_device_paths() else {
+                return;
+            };
+            let devices = self.devices.get_or_insert_with(Vec::new);
+            devices.retain(|device| paths.contains(&device.info.path));
+            for path in paths {
+                if devices.iter().any(|device| device.info.path == path) {
+                    continue;
+                }
+                if let Some(info) = DeviceInformation::from_device_path(&path) {
+                    devices.push(V4L2Device::new(info, self.handler.clone()));
+                }
+            }
+            self.update_listed_devices();
+        }
+    }
+
+    // sysfs lists every video node, /dev is scanned where sysfs is not mounted
+    fn get_device_paths() -> Result<Vec<String>> {
+        let entries = fs::read_dir(SYSFS_VIDEO4LINUX).or_else(|_| fs::read_dir("/dev")).map_err(|err| failed_error!(err.to_string()))?;
+
+        let mut nodes: Vec<u32> = entries
+            .filter_map(|entry| entry.ok())
+            .filter_map(|entry| entry.file_name().to_str().and_then(|name| name.strip_prefix("video")).and_then(|number| number.parse().ok()))
+            .collect();
+        nodes.sort_unstable();
+
+        Ok(nodes.iter().map(|number| format!("/dev/video{}", number)).collect())
+    }
+}
+
+impl DeviceInformation {
+    // Only capture nodes are cameras, metadata nodes are skipped
+    fn from_device_path(path: &str) -> Option<Self> {
+        let file = open_device(path).ok()?;
+        let capability = query_capability(&file).ok()?;
+
+        let capabilities = if capability.capabilities & V4L2_CAP_DEVICE_CAPS != 0 {
+            capability.device_caps
+        } else {
+            capability.capabilities
+        };
+
+        if capabilities & V4L2_CAP_VIDEO_CAPTURE == 0 || capabilities & V4L2_CAP_STREAMING == 0 {
+            return None;
+        }
+
+        let name = CStr::from_bytes_until_nul(&capability.card).ok()?.to_string_lossy().into_owned();
+
+        // the node path stands in for the location where sysfs is not mounted
+        Some(Self {
+            id: get_device_location(path).unwrap_or_else(|| path.to_string()),
+            name,
+            path: path.to_string(),
+        })
+    }
+}
+
+// what configure asked for, unset keys are left to format selection
+#[derive(Clone, Copy, Debug, Default)]
+struct RequestedFormat {
+    format: Option<VideoFormat>,
+    width: Option<u32>,
+    height: Option<u32>,
+    frame_rate: Option<f32>,
+}
+
+pub struct V4L2Device {
+    info: DeviceInformation,
+    running: bool,
+    formats: OnceLock<Vec<CameraFormat>>, // enumerated on the first query, cleared by configure
+    requested_format: RequestedFormat,
+    current_format: Option<CameraFormat>,
+    accept_adjusted: bool,
+    scaler_crop: Option<(u32, u32, u32, u32)>,
+    applied_scaler_crop: Option<(u32, u32, u32, u32)>,
+    drop_policy: DropPolicy,
+    queue_size: usize,
+    queue_depth: usize,
+    handler: Option<OutputHandler>,
+    delivery: Option<Arc<FrameDelivery>>,
+    receiver: Option<FrameReceiver>,
+    event_handler: SharedEventHandler,
+    sequence: Arc<AtomicU64>,
+    statistics: Arc<StreamStatistics>,
+    stream: Option<(Arc<AtomicBool>, JoinHandle<()>)>,
+}
+
+impl Device for V4L2Device {
+    fn name(&self) -> &str {
+        &self.info.name
+    }
+
+    fn id(&self) -> &str {
+        &self.info.id
+    }
+
+    fn start(&mut self) -> Result<()> {
+        if self.running {
+            return Ok(());
+        }
+
+        // a slow handler must not hold up requeuing the capture buffers, without
+        // one frames are queued for read_frame, which never blocks the stream
+        let (handler, delivery, receiver): (OutputHandler, _, _) = match &self.handler {
+            Some(handler) => {
+                let delivery = Arc::new(FrameDelivery::new_with_handler(self.drop_policy, self.queue_size, handler.clone())?);
+                let stream_delivery = delivery.clone();
+                (Arc::new(move |frame| stream_delivery.push(frame)), Some(delivery), None)
+            }
+            None => {
+                let receiver = FrameReceiver::new(self.queue_depth)?;
+                let stream_receiver = receiver.clone();
+                (Arc::new(move |frame| stream_receiver.push(frame)), None, Some(receiver))
+            }
+        };
+        let file = open_device(&self.info.path).map_err(|err| Error::OpenFailed(err.to_string().into()))?;
+        let formats = get_formats(&file);
+
+        let RequestedFormat {
+            format: video_format,
+            width,
+            height,
+            frame_rate,
+        } = self.requested_format;
+
+        // the crop is applied first, the format then sets the scaled output size
+        let applied_scaler_crop = match self.scaler_crop {
+            Some(scaler_crop) => Some(set_scaler_crop(&file, scaler_crop)?),
+            None => reset_scaler_crop(&file),
+        };
+
+        let (camera_format, frame_rate) =
+            select_supported_format(&formats, width, height, video_format, frame_rate).ok_or_else(|| not_found_error!("video formats"))?;
+        let (current_format, pix) = set_format(&file, &camera_format, frame_rate)?;
+
+        // the driver may round the size or fall back to another format
+        if !self.accept_adjusted &&
+            (width.is_some_and(|width| width != current_format.width) ||
+                height.is_some_and(|height| height != current_format.height) ||
+                video_format.is_some_and(|video_format| video_format != current_format.format))
+        {
+            return Err(Error::SetFailed(
+                format!("format adjusted to {:?} {}x{}", current_format.format, current_format.width, current_format.height).into(),
+            ));
+        }
+
+        let statistics = Arc::new(StreamStatistics::new());
+        let stream = Stream::new(
+            file,
+            self.info.clone(),
+            current_format.clone(),
+            pix,
+            handler,
+            self.event_handler.clone(),
+            self.sequence.clone(),
+            statistics.clone(),
+        )?;
+        let running = Arc::new(AtomicBool::new(true));
+        let stream_running = running.clone();
+        let thread = thread::Builder::new()
+            .name(format!("v4l2-{}", self.info.path))
+            .spawn(move || stream.run(&stream_running))
+            .map_err(|err| Error::StartFailed(err.to_string().into()))?;
+
+        self.stream = Some((running, thread));
+        self.running = true;
+        self.current_format = Some(current_format);
+        self.applied_scaler_crop = applied_scaler_crop;
+        self.formats = OnceLock::from(formats);
+        self.statistics = statistics;
+        self.delivery = delivery;
+        self.receiver = receiver;
+
+        Ok(())
+    }
+
+    fn stop(&mut self) -> Result<()> {
+        if !self.running {
+            return Err(Error::NotRunning(self.info.name.clone().into()));
+        }
+
+        self.running = false;
+
+        if let Some((running, thread)) = self.stream.take() {
+            running.store(false, SeqCst);
+            thread.join().map_err(|_| Error::StopFailed(self.info.name.clone().into()))?;
+        }
+
+        if let Some(receiver) = &self.receiver {
+            receiver.close();
+        }
+
+        Ok(())
+    }
+
+    fn configure(&mut self, options: &Variant) -> Result<()> {
+        self.formats.take();
+
+        // sensor region {x, y, width, height} for digital zoom, an empty dict
+        // goes back to the default crop
+        if options.dict_contains("scaler-crop") {
+            let scaler_crop = &options["scaler-crop"];
+            self.scaler_crop = match (
+                scaler_crop["x"].get_uint32(),
+                scaler_crop["y"].get_uint32(),
+                scaler_crop["width"].get_uint32(),
+                scaler_crop["height"].get_uint32(),
+            ) {
+                (Some(x), Some(y), Some(width), Some(height)) => Some((x, y, width, height)),
+                _ if scaler_crop.is_empty() => None,
+                _ => return Err(invalid_param_error!(scaler_crop)),
+            };
+        }
+
+        // keys missing from the options keep their previous value
+        if let Some(width) = options["width"].get_uint32() {
+            self.requested_format.width = (width != 0).then_some(width);
+        }
+        if let Some(height) = options["height"].get_uint32() {
+            self.requested_format.height = (height != 0).then_some(height);
+        }
+        if let Some(video_format) = options["format"].get_uint32() {
+            self.requested_format.format = Some(VideoFormat::try_from(video_format)?);
+        }
+        if let Some(frame_rate) = options["frame-rate"].get_float() {
+            self.requested_format.frame_rate = (frame_rate > 0.0).then_some(frame_rate);
+        }
+        // when disabled, start fails instead of using a format the driver adjusted
+        if let Some(accept_adjusted) = options["accept-adjusted"].get_bool() {
+            self.accept_adjusted = accept_adjusted;
+        }
+        if let Some(drop_policy) = options["drop-policy"].get_string() {
+            self.drop_policy = DropPolicy::from_name(&drop_policy)?;
+        }
+        if let Some(queue_size) = options["queue-size"].get_uint32() {
+            if queue_size == 0 {
+                return Err(invalid_param_error!(queue_size));
+            }
+            self.queue_size = queue_size as usize;
+        }
+        // frames waiting for read_frame, the oldest is dropped when full
+        if let Some(queue_depth) = options["queue-depth"].get_uint32() {
+            if queue_depth == 0 {
+                return Err(invalid_param_error!(queue_depth));
+            }
+            self.queue_depth = queue_depth as usize;
+        }
+
+        // the format can only be changed while the buffers are released
+        if self.running {
+            self.stop()?;
+            self.start()?;
+        }
+
+        Ok(())
+    }
+
+    fn control(&mut self, _action: &Variant) -> Result<()> {
+        Err(Error::NotImplemented)
+    }
+
+    fn running(&self) -> bool {
+        self.running
+    }
+
+    fn formats(&self) -> Result<Variant> {
+        let mut formats = Variant::new_array();
+        for video_format in self.cached_formats()? {
+            formats.array_add(video_format.to_variant());
+        }
+
+        Ok(formats)
+    }
+
+    fn properties(&self) -> Result<Variant> {
+        let file = open_device(&self.info.path).map_err(|err| Error::OpenFailed(err.to_string().into()))?;
+        let formats = self.formats.get_or_init(|| get_formats(&file));
+
+        let mut properties = camera_properties(&self.info.name, formats, get_pixel_array_size(&file));
+        properties["path"] = self.info.path.as_str().into();
+
+        Ok(properties)
+    }
+
+    fn effective_config(&self) -> Result<Variant> {
+        if !self.running {
+            return Err(Error::NotRunning(self.info.name.clone().into()));
+        }
+
+        self.current_format.as_ref().map(CameraFormat::to_config).ok_or_else(|| not_found_error!("current format"))
+    }
+
+    fn statistics(&self) -> Result<Variant> {
+        Ok(self.statistics.to_variant())
+    }
+}
+
+impl OutputDevice for V4L2Device {
+    fn set_output_handler<F>(&mut self, handler: F) -> Result<()>
+    where
+        F: Fn(Frame) -> Result<()> + Send + Sync + 'static,
+    {
+        self.handler = Some(Arc::new(handler));
+        Ok(())
+    }
+}
+
+impl InputDevice for V4L2Device {
+    // frames left in the queue can still be read after stop
+    fn read_frame(&mut self, timeout: Duration) -> Result<Frame<'static>> {
+        match &self.receiver {
+            Some(receiver) => receiver.read_frame(timeout),
+            None if self.running => Err(unsupported_error!("frames are delivered to the output handler")),
+            None => Err(Error::NotRunning(self.info.name.clone().into())),
+        }
+    }
+
+    fn dropped_frames(&self) -> u64 {
+        self.receiver.as_ref().map_or(0, FrameReceiver::dropped)
+    }
+}
+
+impl V4L2Device {
+    fn new(info: DeviceInformation, event_handler: SharedEventHandler) -> Self {
+        Self {
+            info,
+            running: false,
+            formats: OnceLock::new(),
+            requested_format: RequestedFormat::default(),
+            current_format: None,
+            accept_adjusted: true,
+            scaler_crop: None,
+            applied_scaler_crop: None,
+            drop_policy: DropPolicy::default(),
+            queue_size: DEFAULT_QUEUE_SIZE,
+            queue_depth: DEFAULT_QUEUE_SIZE,
+            handler: None,
+            delivery: None,
+            receiver: None,
+            event_handler,
+            sequence: Arc::new(AtomicU64::new(0)),
+            statistics: Arc::new(StreamStatistics::new()),
+            stream: None,
+        }
+    }
+
+    // The device node, e.g. /dev/video0, which may change across reboots
+    pub fn path(&self) -> &str {
+        &self.info.path
+    }
+
+    fn matches(&self, id: &str) -> bool {
+        self.info.id == id || self.info.path == id
+    }
+
+    fn cached_formats(&self) -> Result<&[CameraFormat]> {
+        if let Some(formats) = self.formats.get() {
+            return Ok(formats);
+        }
+
+        let file = open_device(&self.info.path).map_err(|err| Error::OpenFailed(err.to_string().into()))?;
+        Ok(self.formats.get_or_init(|| get_formats(&file)))
+    }
+
+    // Enumerates the formats again, e.g. after a control changed what the driver
+    // offers
+    pub fn refresh_formats(&mut self) -> Result<()> {
+        self.formats.take();
+        self.cached_formats().map(|_| ())
+    }
+
+    // The format in use after start, including any adjustment by the driver
+    pub fn current_format(&self) -> Option<&CameraFormat> {
+        self.current_format.as_ref().filter(|_| self.running)
+    }
+
+    // Frames the drop policy discarded before they reached the output handler,
+    // counted since the last start
+    pub fn handler_dropped_frames(&self) -> u64 {
+        self.delivery.as_ref().map_or(0, |delivery| delivery.dropped())
+    }
+
+    // The sensor region in use after start, after clamping to the pixel array
+    pub fn scaler_crop(&self) -> Option<(u32, u32, u32, u32)> {
+        self.applied_scaler_crop.filter(|_| self.running)
+    }
+}
+
+impl Drop for V4L2Device {
+    fn drop(&mut self) {
+        if self.running {
+            self.stop().ok();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{io::Write, os::fd::FromRawFd, sync::Mutex};
+
+    use super::*;
+
+    // a pipe with data pending polls readable, but the buffer ioctls fail on
+    // it, as they do on a device that went away mid-stream
+    #[test]
+    fn test_capture_failure_reports_error() {
+        let mut fds = [0; 2];
+        assert_eq!(unsafe { libc::pipe(fds.as_mut_ptr()) }, 0);
+        let (read_end, mut write_end) = unsafe { (File::from_raw_fd(fds[0]), File::from_raw_fd(fds[1])) };
+        write_end.write_all(&[0]).unwrap();
+
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let handler_events = events.clone();
+        let event_handler: SharedEventHandler = Arc::new(RwLock::new(Some(Box::new(move |event: &DeviceEvent| {
+            if let DeviceEvent::Error(id, err) = event {
+                handler_events.lock().unwrap().push((id.clone(), err.to_string()));
+            }
+        }))));
+
+        let stream = Stream {
+            file: read_end,
+            buffers: Vec::new(),
+            info: DeviceInformation {
+                id: "video-test".to_string(),
+                name: "test".to_string(),
+                path: "/dev/video-test".to_string(),
+            },
+            format: CameraFormat {
+                format: VideoFormat::Pixel(PixelFormat::YUYV),
+                color_range: ColorRange::default(),
+                width: 640,
+                height: 480,
+                frame_rates: vec![30.0],
+            },
+            pix: unsafe { zeroed() },
+            handler: Arc::new(|_| Ok(())),
+            event_handler,
+            sequence: Arc::new(AtomicU64::new(0)),
+            statistics: Arc::new(StreamStatistics::new()),
+        };
+
+        // the loop ends on the failure instead of spinning
+        let running = AtomicBool::new(true);
+        stream.run(&running);
+        assert!(running.load(SeqCst));
+
+        let events = events.lock().unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].0, "video-test");
+    }
+
+    #[test]
+    fn test_stream_statistics() {
+        let statistics = StreamStatistics::new();
+        assert_eq!(statistics.frame_interval(), 0.0);
+
+        // sequence 3 and 4 were never dequeued
+        for (sequence, timestamp) in [(0, 1_000_000), (1, 1_033_000), (2, 1_066_000), (5, 1_165_000)] {
+            statistics.record(sequence, timestamp);
+        }
+
+        let variant = statistics.to_variant();
+        assert_eq!(variant["frames-dropped"].get::<u64>(), 2);
+        assert_eq!(variant["requests-queued"].get::<u32>(), BUFFER_COUNT);
+        assert_eq!(variant["frame-interval"].get::<f64>(), 55.0);
+    }
+
+    #[test]
+    fn test_formats_cache() {
+        let info = DeviceInformation {
+            id: "video-test".to_string(),
+            name: "test".to_string(),
+            path: "/dev/video-test".to_string(),
+        };
+        let mut device = V4L2Device::new(info, Arc::new(RwLock::new(None)));
+        device.formats = OnceLock::from(vec![CameraFormat {
+            format: VideoFormat::Pixel(PixelFormat::YUYV),
+            color_range: ColorRange::default(),
+            width: 640,
+            height: 480,
+            frame_rates: vec![30.0],
+        }]);
+
+        // the cached formats are returned without opening the device
+        assert_eq!(device.formats().unwrap().len(), 1);
+        assert_eq!(device.formats().unwrap().len(), 1);
+
+        // configure clears the cache, the next query goes to the device
+        device.configure(&Variant::new_dict()).unwrap();
+        assert!(matches!(device.formats(), Err(Error::OpenFailed(_))));
+        assert!(device.refresh_formats().is_err());
+    }
+
+    #[test]
+    fn test_v4l2_fourcc() {
+        for (code, format) in [
+            (b"YUYV", VideoFormat::Pixel(PixelFormat::YUYV)),
+            (b"422P", VideoFormat::Pixel(PixelFormat::I422)),
+            (b"Y16 ", VideoFormat::Pixel(PixelFormat::Y16)),
+            (b"MJPG", VideoFormat::Compression(CompressionFormat::MJPEG)),
+        ] {
+            assert_eq!(from_v4l2_fourcc(fourcc(code)), Some(format));
+            assert_eq!(into_v4l2_fourcc(format), Some(fourcc(code)));
+        }
+        assert_eq!(from_v4l2_fourcc(fourcc(b"JPEG")), Some(VideoFormat::Compression(CompressionFormat::MJPEG)));
+        assert_eq!(from_v4l2_fourcc(fourcc(b"ABCD")), None);
+    }
+
+    #[test]
+    fn test_select_nearest_frame_rate() {
+        let yuyv = VideoFormat::Pixel(PixelFormat::YUYV);
+        let formats = [CameraFormat {
+            format: yuyv,
+            color_range: ColorRange::default(),
+            width: 640,
+            height: 480,
+            frame_rates: vec![30.0, 15.0, 10.0],
+        }];
+
+        for (requested, expected) in [(60.0, 30.0), (20.0, 15.0), (25.0, 30.0), (12.5, 15.0), (5.0, 10.0)] {
+            let (format, frame_rate) = select_supported_format(&formats, Some(640), Some(480), Some(yuyv), Some(requested)).unwrap();
+            assert_eq!((format.width, format.height), (640, 480));
+            assert_eq!(frame_rate, Some(expected), "{requested}");
+        }
+    }
+
+    #[test]
+    fn test_configure_rejects_empty_queues() {
+        let info = DeviceInformation {
+            id: "video-test".to_string(),
+            name: "test".to_string(),
+            path: "/dev/video-test".to_string(),
+        };
+        let mut device = V4L2Device::new(info, Arc::new(RwLock::new(None)));
+
+        for key in ["queue-size", "queue-depth"] {
+            let mut options = Variant::new_dict();
+            options[key] = 0.into();
+            assert!(matches!(device.configure(&options), Err(Error::InvalidParameter(..))));
+        }
+        assert_eq!((device.queue_size, device.queue_depth), (DEFAULT_QUEUE_SIZE, DEFAULT_QUEUE_SIZE));
+    }
+
+    #[cfg(feature = "hotplug")]
+    #[test]
+    fn test_hotplug_removal_reports_listed_devices() {
+        let listed_devices = Mutex::new(HashMap::from([("/dev/video-test".to_string(), "usb-0000:00:14.0-1".to_string())]));
+
+        // metadata nodes and other devices that were never listed are ignored
+        assert!(V4L2DeviceManager::hotplug_event(HotplugAction::Remove, "/dev/video-other".to_string(), &listed_devices).is_none());
+
+        let event = V4L2DeviceManager::hotplug_event(HotplugAction::Remove, "/dev/video-test".to_string(), &listed_devices);
+        assert!(matches!(event, Some(DeviceEvent::Removed(id)) if id == "usb-0000:00:14.0-1"));
+        assert!(V4L2DeviceManager::hotplug_event(HotplugAction::Remove, "/dev/video-test".to_string(), &listed_devices).is_none());
+    }
+}
@@ -1,6 +1,9 @@
 use std::{
     slice::{Iter, IterMut},
-    sync::Arc,
+    sync::{
+        atomic::{AtomicU64, Ordering::Relaxed},
+        Arc,
+    },
 };
 
 #[cfg(target_os = "macos")]
@@ -46,7 +49,10 @@ use objc2::{
 use objc2_foundation::{NSArray, NSMutableArray, NSMutableDictionary, NSNumber, NSObject, NSObjectProtocol, NSString};
 use os_ver::if_greater_than;
 
-use crate::{camera::CameraFormat, Device, DeviceEvent, DeviceEventHandler, DeviceInformation, DeviceManager, OutputDevice, OutputHandler};
+use crate::{
+    camera::{camera_properties, CameraFormat},
+    BackendInfo, Device, DeviceEvent, DeviceEventHandler, DeviceInformation, DeviceManager, OutputDevice, OutputHandler,
+};
 
 pub struct AVFoundationCaptureDeviceManager {
     devices: Option<Vec<AVFoundationCaptureDevice>>,
@@ -76,6 +82,10 @@ impl DeviceManager for AVFoundationCaptureDeviceManager {
 
     fn deinit(&mut self) {}
 
+    fn backend_info(&self) -> BackendInfo {
+        BackendInfo::with_os_version("AVFoundation")
+    }
+
     fn index(&self, index: usize) -> Option<&Self::DeviceType> {
         self.devices.as_ref().and_then(|devices| devices.get(index))
     }
@@ -188,9 +198,11 @@ impl AVFoundationCaptureDeviceManager {
 
 impl DeviceInformation {
     fn from_av_capture_device(device: &AVCaptureDevice) -> Self {
+        let id = device.unique_id().to_string();
         Self {
             name: device.localized_name().to_string(),
-            id: device.unique_id().to_string(),
+            path: id.clone(),
+            id,
         }
     }
 }
@@ -198,6 +210,7 @@ impl DeviceInformation {
 pub struct OutputDelegateIvars {
     info: Option<DeviceInformation>,
     handler: Option<OutputHandler>,
+    sequence: AtomicU64,
 }
 
 impl OutputDelegateIvars {
@@ -205,6 +218,7 @@ impl OutputDelegateIvars {
         Self {
             info: None,
             handler: None,
+            sequence: AtomicU64::new(0),
         }
     }
 
@@ -252,6 +266,7 @@ declare_class!(
                         video_frame.source = Some(info.id.clone());
                     }
                     video_frame.pts = Some((sample_buffer.get_presentation_time_stamp().get_seconds() * MSEC_PER_SEC as f64) as i64);
+                    video_frame.sequence = Some(self.ivars().sequence.fetch_add(1, Relaxed));
                     handler(video_frame).ok();
                 }
             }
@@ -593,17 +608,24 @@ impl Device for AVFoundationCaptureDevice {
         let video_formats = self.formats.as_ref().ok_or_else(|| not_found_error!("video formats"))?;
         let mut formats = Variant::new_array();
         for video_format in video_formats {
-            let mut format = Variant::new_dict();
-            format["format"] = video_format.format.to_string().into();
-            format["color-range"] = (video_format.color_range as u32).into();
-            format["width"] = video_format.width.into();
-            format["height"] = video_format.height.into();
-            format["frame-rates"] = video_format.frame_rates.iter().map(|frame_rate| Variant::from(*frame_rate)).collect();
-            formats.array_add(format);
+            formats.array_add(video_format.to_variant());
         }
 
         Ok(formats)
     }
+
+    // the sensor size is not exposed, formats are only known while running
+    fn properties(&self) -> Result<Variant> {
+        Ok(camera_properties(&self.info.name, self.formats.as_deref().unwrap_or_default(), None))
+    }
+
+    fn effective_config(&self) -> Result<Variant> {
+        if !self.running {
+            return Err(Error::NotRunning(self.info.name.clone().into()));
+        }
+
+        self.current_format.as_ref().map(CameraFormat::to_config).ok_or_else(|| not_found_error!("current format"))
+    }
 }
 
 impl OutputDevice for AVFoundationCaptureDevice {
@@ -0,0 +1,100 @@
+use std::sync::{
+    atomic::{AtomicU64, Ordering::Relaxed},
+    Arc, Mutex, RwLock,
+};
+
+use media_core::{failed_error, frame::Frame, not_found_error, Result};
+
+use crate::{OutputDevice, OutputHandler};
+
+pub type SubscriptionId = u64;
+
+type Subscribers = Arc<RwLock<Vec<(SubscriptionId, OutputHandler)>>>;
+
+// Fans the frames of one device out to several subscribers. The device is
+// started by the first subscriber and stopped after the last one leaves
+pub struct SharedDevice<D: OutputDevice> {
+    device: Mutex<D>,
+    subscribers: Subscribers,
+    next_id: AtomicU64,
+}
+
+impl<D: OutputDevice> SharedDevice<D> {
+    pub fn new(mut device: D) -> Result<Self> {
+        let subscribers: Subscribers = Arc::new(RwLock::new(Vec::new()));
+        let handler_subscribers = subscribers.clone();
+
+        device.set_output_handler(move |frame: Frame| {
+            let subscribers = handler_subscribers.read().map_err(|err| failed_error!(err.to_string()))?;
+            let Some(((_, last), others)) = subscribers.split_last() else {
+                return Ok(());
+            };
+
+            // every subscriber gets the frame even if an earlier one fails
+            let mut result = Ok(());
+            for (_, handler) in others {
+                result = result.and(handler(frame.clone()));
+            }
+            result.and(last(frame))
+        })?;
+
+        Ok(Self {
+            device: Mutex::new(device),
+            subscribers,
+            next_id: AtomicU64::new(0),
+        })
+    }
+
+    pub fn subscribe<F>(&self, handler: F) -> Result<SubscriptionId>
+    where
+        F: Fn(Frame) -> Result<()> + Send + Sync + 'static,
+    {
+        let mut device = self.device.lock().map_err(|err| failed_error!(err.to_string()))?;
+        let id = self.next_id.fetch_add(1, Relaxed);
+        let first = {
+            let mut subscribers = self.subscribers.write().map_err(|err| failed_error!(err.to_string()))?;
+            subscribers.push((id, Arc::new(handler)));
+            subscribers.len() == 1
+        };
+
+        if first && !device.running() {
+            if let Err(err) = device.start() {
+                self.subscribers.write().map_err(|err| failed_error!(err.to_string()))?.retain(|(subscriber, _)| *subscriber != id);
+                return Err(err);
+            }
+        }
+
+        Ok(id)
+    }
+
+    pub fn unsubscribe(&self, id: SubscriptionId) -> Result<()> {
+        let mut device = self.device.lock().map_err(|err| failed_error!(err.to_string()))?;
+        // released before stopping, the capture thread may be waiting for it
+        let last = {
+            let mut subscribers = self.subscribers.write().map_err(|err| failed_error!(err.to_string()))?;
+            let index = subscribers.iter().position(|(subscriber, _)| *subscriber == id).ok_or_else(|| not_found_error!(id))?;
+            subscribers.remove(index);
+            subscribers.is_empty()
+        };
+
+        if last && device.running() {
+            device.stop()?;
+        }
+
+        Ok(())
+    }
+
+    pub fn subscriber_count(&self) -> usize {
+        self.subscribers.read().map(|subscribers| subscribers.len()).unwrap_or_default()
+    }
+
+    pub fn running(&self) -> bool {
+        self.device.lock().map(|device| device.running()).unwrap_or_default()
+    }
+
+    // Access to the device, e.g. to configure it, not to replace its output handler
+    pub fn with_device<R>(&self, f: impl FnOnce(&mut D) -> R) -> Result<R> {
+        let mut device = self.device.lock().map_err(|err| failed_error!(err.to_string()))?;
+        Ok(f(&mut device))
+    }
+}
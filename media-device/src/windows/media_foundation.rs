@@ -4,7 +4,10 @@ use std::{
     ptr::null_mut,
     slice::{from_raw_parts, Iter, IterMut},
     sync::{
-        atomic::{AtomicBool, Ordering::SeqCst},
+        atomic::{
+            AtomicBool, AtomicU64,
+            Ordering::{Relaxed, SeqCst},
+        },
         Arc, Condvar, Mutex, Weak,
     },
     time::Duration,
@@ -39,7 +42,10 @@ use windows::{
     },
 };
 
-use crate::{camera::CameraFormat, Device, DeviceEvent, DeviceEventHandler, DeviceInformation, DeviceManager, OutputDevice, OutputHandler};
+use crate::{
+    camera::{camera_properties, CameraFormat},
+    BackendInfo, Device, DeviceEvent, DeviceEventHandler, DeviceInformation, DeviceManager, OutputDevice, OutputHandler,
+};
 
 pub struct MediaFoundationDeviceManager {
     devices: Option<Vec<MediaFoundationDevice>>,
@@ -80,6 +86,10 @@ impl DeviceManager for MediaFoundationDeviceManager {
         }
     }
 
+    fn backend_info(&self) -> BackendInfo {
+        BackendInfo::with_os_version("MediaFoundation")
+    }
+
     fn index(&self, index: usize) -> Option<&Self::DeviceType> {
         self.devices.as_ref().and_then(|devices| devices.get(index))
     }
@@ -213,6 +223,7 @@ impl DeviceInformation {
         };
 
         Ok(Self {
+            path: id.clone(),
             id,
             name,
         })
@@ -311,6 +322,7 @@ struct SourceReaderCallback {
     source_reader: Weak<Mutex<IMFSourceReader>>,
     running: AtomicBool,
     signal: Option<Arc<(Mutex<bool>, Condvar)>>,
+    sequence: AtomicU64,
 }
 
 impl SourceReaderCallback {
@@ -323,6 +335,7 @@ impl SourceReaderCallback {
             source_reader: Weak::new(),
             running: AtomicBool::new(false),
             signal: None,
+            sequence: AtomicU64::new(0),
         }
     }
 
@@ -411,6 +424,7 @@ impl IMFSourceReaderCallback_Impl for SourceReaderCallback_Impl {
                 if let Ok(mut video_frame) = video_frame {
                     video_frame.source = Some(self.info.id.clone());
                     video_frame.pts = Some(lltimestamp * 100 / NSEC_PER_MSEC as i64); // lltimestamp is in 100ns units
+                    video_frame.sequence = Some(self.sequence.fetch_add(1, Relaxed));
                     let handler = self.handler.as_ref();
                     handler(video_frame).ok();
                 }
@@ -780,16 +794,24 @@ impl Device for MediaFoundationDevice {
         let video_formats = self.formats.as_ref().ok_or_else(|| not_found_error!("video formats"))?;
         let mut formats = Variant::new_array();
         for video_format in video_formats {
-            let mut format = Variant::new_dict();
-            format["format"] = (Into::<u32>::into(video_format.format)).into();
-            format["width"] = video_format.width.into();
-            format["height"] = video_format.height.into();
-            format["frame-rates"] = video_format.frame_rates.iter().map(|frame_rate| Variant::from(*frame_rate)).collect();
-            formats.array_add(format);
+            formats.array_add(video_format.to_variant());
         }
 
         Ok(formats)
     }
+
+    // the sensor size is not exposed, formats are only known while running
+    fn properties(&self) -> Result<Variant> {
+        Ok(camera_properties(&self.info.name, self.formats.as_deref().unwrap_or_default(), None))
+    }
+
+    fn effective_config(&self) -> Result<Variant> {
+        if !self.running {
+            return Err(Error::NotRunning(self.info.name.clone().into()));
+        }
+
+        self.current_format.as_ref().map(CameraFormat::to_config).ok_or_else(|| not_found_error!("current format"))
+    }
 }
 
 impl OutputDevice for MediaFoundationDevice {
@@ -0,0 +1,114 @@
+#![cfg(feature = "video")]
+
+use std::{
+    sync::{Arc, Mutex},
+    thread,
+    time::{Duration, Instant},
+};
+
+use media_core::{error::Error, frame::Frame, video::PixelFormat};
+use media_device::{DropPolicy, FrameDelivery, FrameReceiver};
+
+fn captured_frame(pts: i64) -> Frame<'static> {
+    let mut frame = Frame::video_creator().create(PixelFormat::I420, 16, 16).unwrap();
+    frame.pts = Some(pts);
+    frame
+}
+
+fn slow_delivery(policy: DropPolicy) -> (FrameDelivery, Arc<Mutex<Vec<i64>>>) {
+    let received = Arc::new(Mutex::new(Vec::new()));
+    let handler_received = received.clone();
+    let delivery = FrameDelivery::new(policy, 2, move |frame| {
+        thread::sleep(Duration::from_millis(20));
+        handler_received.lock().unwrap().push(frame.pts.unwrap());
+        Ok(())
+    })
+    .unwrap();
+
+    (delivery, received)
+}
+
+fn wait_for(received: &Mutex<Vec<i64>>, last: i64) -> Vec<i64> {
+    let deadline = Instant::now() + Duration::from_secs(5);
+    while Instant::now() < deadline && received.lock().unwrap().last() != Some(&last) {
+        thread::sleep(Duration::from_millis(5));
+    }
+    received.lock().unwrap().clone()
+}
+
+#[test]
+fn test_drop_oldest() {
+    let (delivery, received) = slow_delivery(DropPolicy::DropOldest);
+
+    // the capture side is never held up by the 20ms handler
+    let start = Instant::now();
+    for pts in 0..20 {
+        delivery.push(captured_frame(pts)).unwrap();
+    }
+    assert!(start.elapsed() < Duration::from_millis(200));
+
+    let received = wait_for(&received, 19);
+    assert_eq!(received.last(), Some(&19));
+    assert!(received.len() < 20);
+    assert!(received.windows(2).all(|pair| pair[0] < pair[1]));
+    assert_eq!(delivery.dropped(), 20 - received.len() as u64);
+}
+
+#[test]
+fn test_drop_newest() {
+    let (delivery, received) = slow_delivery(DropPolicy::DropNewest);
+    for pts in 0..20 {
+        delivery.push(captured_frame(pts)).unwrap();
+    }
+
+    // the first frames are kept, later ones dropped
+    thread::sleep(Duration::from_millis(200));
+    let received = received.lock().unwrap().clone();
+    assert_eq!(received[..2], [0, 1]);
+    assert!(received.len() <= 3);
+    assert_eq!(delivery.dropped(), 20 - received.len() as u64);
+}
+
+#[test]
+fn test_block() {
+    let (delivery, received) = slow_delivery(DropPolicy::Block);
+    for pts in 0..5 {
+        delivery.push(captured_frame(pts)).unwrap();
+    }
+    assert_eq!(*received.lock().unwrap(), [0, 1, 2, 3, 4]);
+    assert_eq!(delivery.dropped(), 0);
+
+    assert_eq!(DropPolicy::from_name("drop-oldest").unwrap(), DropPolicy::DropOldest);
+    assert_eq!(DropPolicy::from_name(DropPolicy::DropNewest.name()).unwrap(), DropPolicy::DropNewest);
+    assert!(DropPolicy::from_name("latest").is_err());
+    assert!(FrameDelivery::new(DropPolicy::DropOldest, 0, |_| Ok(())).is_err());
+}
+
+#[test]
+fn test_frame_receiver() {
+    let receiver = FrameReceiver::new(2).unwrap();
+    for pts in 0..5 {
+        receiver.push(captured_frame(pts)).unwrap();
+    }
+
+    // only the two most recent frames are kept
+    assert_eq!(receiver.dropped(), 3);
+    assert_eq!(receiver.read_frame(Duration::ZERO).unwrap().pts, Some(3));
+    assert_eq!(receiver.read_frame(Duration::ZERO).unwrap().pts, Some(4));
+    assert!(matches!(receiver.read_frame(Duration::from_millis(20)), Err(Error::Again(_))));
+
+    // a waiting reader is woken by the capture side
+    let sender = receiver.clone();
+    let thread = thread::spawn(move || {
+        thread::sleep(Duration::from_millis(20));
+        sender.push(captured_frame(5)).unwrap();
+    });
+    assert_eq!(receiver.read_frame(Duration::from_secs(5)).unwrap().pts, Some(5));
+    thread.join().unwrap();
+
+    receiver.push(captured_frame(6)).unwrap();
+    receiver.close();
+    assert_eq!(receiver.read_frame(Duration::ZERO).unwrap().pts, Some(6));
+    assert!(matches!(receiver.read_frame(Duration::from_secs(5)), Err(Error::NotRunning(_))));
+    assert!(FrameReceiver::new(0).is_err());
+}
@@ -0,0 +1,523 @@
+#![cfg(all(feature = "video", any(target_os = "windows", target_os = "macos", target_os = "linux")))]
+
+use media_device::{camera::DefaultCameraManager, DeviceManager};
+
+#[test]
+fn test_backend_info() {
+    let mut manager = DefaultCameraManager::init().unwrap();
+    let info = manager.backend_info();
+    manager.deinit();
+
+    assert!(!info.name.is_empty());
+    assert!(!info.version.is_empty());
+}
+
+#[test]
+fn test_camera_backend_selection() {
+    use media_core::error::Error;
+    use media_device::camera::{CameraBackend, CameraManager, DEFAULT_CAMERA_BACKEND};
+
+    let manager = CameraManager::new_with_backend(DEFAULT_CAMERA_BACKEND).unwrap();
+    let expected_name = match DEFAULT_CAMERA_BACKEND {
+        CameraBackend::AVFoundation => "AVFoundation",
+        CameraBackend::MediaFoundation => "MediaFoundation",
+        CameraBackend::V4L2 => "V4L2",
+    };
+    assert_eq!(manager.backend_info().name, expected_name);
+
+    let uncompiled_backend = match DEFAULT_CAMERA_BACKEND {
+        CameraBackend::MediaFoundation => CameraBackend::V4L2,
+        _ => CameraBackend::MediaFoundation,
+    };
+    assert!(matches!(CameraManager::new_with_backend(uncompiled_backend), Err(Error::Unsupported(_))));
+}
+
+// requires a camera at /dev/video0, run with --ignored
+#[cfg(target_os = "linux")]
+#[test]
+#[ignore]
+fn test_v4l2_capture() {
+    use std::{sync::mpsc, time::Duration};
+
+    use media_core::{
+        variant::Variant,
+        video::{PixelFormat, VideoFormat},
+    };
+    use media_device::{camera::CameraManager, Device, OutputDevice};
+
+    const DEVICE_PATH: &str = "/dev/video0";
+
+    let mut manager = CameraManager::new_default().unwrap();
+    let device = manager.lookup_mut(DEVICE_PATH).unwrap();
+
+    let formats = device.formats().unwrap();
+    assert!(!formats.is_empty());
+
+    // compressed frames are not delivered, request a raw format
+    let mut options = Variant::new_dict();
+    options["format"] = u32::from(VideoFormat::Pixel(PixelFormat::YUYV)).into();
+    options["width"] = 640.into();
+    options["height"] = 480.into();
+    device.configure(&options).unwrap();
+
+    let (sender, receiver) = mpsc::channel();
+    device
+        .set_output_handler(move |frame| {
+            sender.send((frame.pts, frame.sequence)).ok();
+            Ok(())
+        })
+        .unwrap();
+    device.start().unwrap();
+
+    // the numbering has no gaps and continues when configure restarts the stream
+    let mut last_sequence = None;
+    for index in 0..10 {
+        if index == 5 {
+            device.configure(&options).unwrap();
+        }
+
+        let (pts, sequence) = receiver.recv_timeout(Duration::from_secs(5)).unwrap();
+        assert!(pts.is_some());
+        assert_eq!(sequence, Some(last_sequence.map_or(0, |last_sequence| last_sequence + 1)));
+        last_sequence = sequence;
+    }
+
+    device.stop().unwrap();
+}
+
+// requires a camera at /dev/video0, run with --ignored
+#[cfg(target_os = "linux")]
+#[test]
+#[ignore]
+fn test_v4l2_handler_dropped_frames() {
+    use std::{thread, time::Duration};
+
+    use media_core::variant::Variant;
+    use media_device::{camera::CameraManager, Device, OutputDevice};
+
+    const DEVICE_PATH: &str = "/dev/video0";
+
+    let mut manager = CameraManager::new_default().unwrap();
+    let device = manager.lookup_mut(DEVICE_PATH).unwrap();
+
+    let mut options = Variant::new_dict();
+    options["drop-policy"] = "drop-newest".into();
+    options["queue-size"] = 1.into();
+    device.configure(&options).unwrap();
+
+    // a handler far slower than the frame rate
+    device
+        .set_output_handler(|_| {
+            thread::sleep(Duration::from_millis(500));
+            Ok(())
+        })
+        .unwrap();
+    assert_eq!(device.handler_dropped_frames(), 0);
+    device.start().unwrap();
+    thread::sleep(Duration::from_secs(2));
+    device.stop().unwrap();
+
+    // still readable after stop
+    assert!(device.handler_dropped_frames() > 0);
+}
+
+// requires a camera at /dev/video0, run with --ignored
+#[cfg(target_os = "linux")]
+#[test]
+#[ignore]
+fn test_v4l2_error_event() {
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering::SeqCst},
+        Arc,
+    };
+
+    use media_device::{camera::CameraManager, Device, DeviceEvent, OutputDevice};
+
+    const DEVICE_PATH: &str = "/dev/video0";
+
+    let errors = Arc::new(AtomicUsize::new(0));
+    let handler_errors = errors.clone();
+
+    let mut manager = CameraManager::new_default().unwrap();
+    manager
+        .set_change_handler(move |event| {
+            if let DeviceEvent::Error(..) = event {
+                handler_errors.fetch_add(1, SeqCst);
+            }
+        })
+        .unwrap();
+
+    let device = manager.lookup_mut(DEVICE_PATH).unwrap();
+
+    device.set_output_handler(|_| Ok(())).unwrap();
+    device.start().unwrap();
+    device.stop().unwrap();
+
+    // stopping on request is not a failure
+    assert_eq!(errors.load(SeqCst), 0);
+}
+
+#[test]
+fn test_negotiate_format() {
+    use media_core::{
+        variant::Variant,
+        video::{ColorRange, CompressionFormat, PixelFormat, VideoFormat},
+    };
+    use media_device::camera::{negotiate_format, Resolution};
+
+    let mut formats = Variant::new_array();
+    for (format, width, height) in [
+        (VideoFormat::Compression(CompressionFormat::MJPEG), 1280, 720),
+        (VideoFormat::Pixel(PixelFormat::YUYV), 640, 480),
+        (VideoFormat::Pixel(PixelFormat::NV12), 640, 480),
+        (VideoFormat::Pixel(PixelFormat::NV12), 1920, 1080),
+        (VideoFormat::Pixel(PixelFormat::NV12), 1280, 720),
+    ] {
+        let mut entry = Variant::new_dict();
+        entry["format"] = u32::from(format).into();
+        entry["width"] = width.into();
+        entry["height"] = height.into();
+        entry["frame-rates"] = [30.0f32, 15.0].into_iter().map(Variant::from).collect();
+        formats.array_add(entry);
+    }
+
+    let nv12 = VideoFormat::Pixel(PixelFormat::NV12);
+    let yuyv = VideoFormat::Pixel(PixelFormat::YUYV);
+
+    // the preferred format wins over a closer resolution in another format
+    let format = negotiate_format(&formats, &[nv12, yuyv], Resolution::new(1280, 720), ColorRange::Unspecified).unwrap();
+    assert_eq!((format.format, format.width, format.height), (nv12, 1280, 720));
+    assert_eq!(format.frame_rates, vec![30.0, 15.0]);
+
+    // equally near to 720p and 1080p, the larger resolution wins
+    let format = negotiate_format(&formats, &[nv12], Resolution::new(1600, 900), ColorRange::Unspecified).unwrap();
+    assert_eq!((format.width, format.height), (1920, 1080));
+
+    let format = negotiate_format(&formats, &[yuyv, nv12], Resolution::new(1920, 1080), ColorRange::Unspecified).unwrap();
+    assert_eq!((format.format, format.width, format.height), (yuyv, 640, 480));
+
+    assert!(negotiate_format(&formats, &[VideoFormat::Pixel(PixelFormat::I420)], Resolution::new(640, 480), ColorRange::Unspecified).is_none());
+
+    // each range of a format is listed separately where the backend reports it
+    let mut formats = Variant::new_array();
+    for color_range in [ColorRange::Video, ColorRange::Full] {
+        let mut entry = Variant::new_dict();
+        entry["format"] = u32::from(nv12).into();
+        entry["color-range"] = (usize::from(color_range) as u32).into();
+        entry["width"] = 1280.into();
+        entry["height"] = 720.into();
+        formats.array_add(entry);
+    }
+
+    for color_range in [ColorRange::Video, ColorRange::Full] {
+        let format = negotiate_format(&formats, &[nv12], Resolution::new(1280, 720), color_range).unwrap();
+        assert_eq!(format.color_range, color_range);
+    }
+    let format = negotiate_format(&formats, &[nv12], Resolution::new(1280, 720), ColorRange::Unspecified).unwrap();
+    assert_eq!(format.color_range, ColorRange::Video);
+
+    // formats listed by name are still accepted
+    let mut formats = Variant::new_array();
+    for name in ["MJPEG", "NV12"] {
+        let mut entry = Variant::new_dict();
+        entry["format"] = name.into();
+        entry["width"] = 1280.into();
+        entry["height"] = 720.into();
+        formats.array_add(entry);
+    }
+
+    let format = negotiate_format(&formats, &[nv12], Resolution::new(1280, 720), ColorRange::Unspecified).unwrap();
+    assert_eq!(format.format, nv12);
+    let mjpeg = VideoFormat::Compression(CompressionFormat::MJPEG);
+    let format = negotiate_format(&formats, &[mjpeg], Resolution::new(1280, 720), ColorRange::Unspecified).unwrap();
+    assert_eq!(format.format, mjpeg);
+}
+
+// requires a camera at /dev/video0, run with --ignored
+#[cfg(target_os = "linux")]
+#[test]
+#[ignore]
+fn test_v4l2_accept_adjusted() {
+    use std::{sync::mpsc, time::Duration};
+
+    use media_core::{
+        error::Error,
+        variant::Variant,
+        video::{PixelFormat, VideoFormat},
+        FrameDescriptor,
+    };
+    use media_device::{camera::CameraManager, Device, OutputDevice};
+
+    const DEVICE_PATH: &str = "/dev/video0";
+
+    let mut manager = CameraManager::new_default().unwrap();
+    let device = manager.lookup_mut(DEVICE_PATH).unwrap();
+
+    // an odd size that drivers round to one they support
+    let mut options = Variant::new_dict();
+    options["format"] = u32::from(VideoFormat::Pixel(PixelFormat::YUYV)).into();
+    options["width"] = 641.into();
+    options["height"] = 479.into();
+    options["accept-adjusted"] = true.into();
+    device.configure(&options).unwrap();
+
+    let (sender, receiver) = mpsc::sync_channel(1);
+    device
+        .set_output_handler(move |frame| {
+            if let FrameDescriptor::Video(desc) = frame.descriptor() {
+                sender.try_send((desc.width().get(), desc.height().get())).ok();
+            }
+            Ok(())
+        })
+        .unwrap();
+    device.start().unwrap();
+
+    let current_format = device.current_format().unwrap().clone();
+    assert!(current_format.width > 0 && current_format.height > 0);
+    assert_eq!(receiver.recv_timeout(Duration::from_secs(5)).unwrap(), (current_format.width, current_format.height));
+
+    device.stop().unwrap();
+    assert!(device.current_format().is_none());
+
+    // the 641x479 request is still in place, no driver delivers it unchanged
+    let mut options = Variant::new_dict();
+    options["accept-adjusted"] = false.into();
+    device.configure(&options).unwrap();
+    assert!(matches!(device.start(), Err(Error::SetFailed(_))));
+    assert!(!device.running());
+}
+
+// requires a camera at /dev/video0, run with --ignored
+#[cfg(target_os = "linux")]
+#[test]
+#[ignore]
+fn test_v4l2_effective_config() {
+    use std::{sync::mpsc, time::Duration};
+
+    use media_core::{
+        variant::Variant,
+        video::{PixelFormat, VideoFormat},
+        FrameDescriptor,
+    };
+    use media_device::{camera::CameraManager, Device, OutputDevice};
+
+    const DEVICE_PATH: &str = "/dev/video0";
+
+    let mut manager = CameraManager::new_default().unwrap();
+    let device = manager.lookup_mut(DEVICE_PATH).unwrap();
+
+    let mut options = Variant::new_dict();
+    options["format"] = u32::from(VideoFormat::Pixel(PixelFormat::YUYV)).into();
+    options["width"] = 641.into();
+    options["height"] = 479.into();
+    device.configure(&options).unwrap();
+    assert!(device.effective_config().is_err());
+
+    let (sender, receiver) = mpsc::sync_channel(1);
+    device
+        .set_output_handler(move |frame| {
+            if let FrameDescriptor::Video(desc) = frame.descriptor() {
+                sender.try_send((desc.format, desc.width().get(), desc.height().get())).ok();
+            }
+            Ok(())
+        })
+        .unwrap();
+    device.start().unwrap();
+
+    // the size the driver rounded to, as delivered in the frames
+    let config = device.effective_config().unwrap();
+    let format = VideoFormat::try_from(config["format"].get_uint32().unwrap()).unwrap();
+    let (width, height) = (config["width"].get_uint32().unwrap(), config["height"].get_uint32().unwrap());
+    if let VideoFormat::Pixel(pixel_format) = format {
+        assert_eq!(receiver.recv_timeout(Duration::from_secs(5)).unwrap(), (pixel_format, width, height));
+    }
+
+    device.stop().unwrap();
+}
+
+// requires a camera at /dev/video0, run with --ignored
+#[cfg(target_os = "linux")]
+#[test]
+#[ignore]
+fn test_v4l2_read_frame() {
+    use std::time::Duration;
+
+    use media_core::{variant::Variant, FrameDescriptor};
+    use media_device::{camera::CameraManager, Device, InputDevice};
+
+    const DEVICE_PATH: &str = "/dev/video0";
+
+    let mut manager = CameraManager::new_default().unwrap();
+    let device = manager.lookup_mut(DEVICE_PATH).unwrap();
+
+    let mut options = Variant::new_dict();
+    options["queue-depth"] = 2.into();
+    device.configure(&options).unwrap();
+    assert!(device.read_frame(Duration::ZERO).is_err());
+
+    // no output handler, frames are pulled instead
+    device.start().unwrap();
+    let frame = device.read_frame(Duration::from_secs(5)).unwrap();
+    assert!(matches!(frame.descriptor(), FrameDescriptor::Video(_)));
+    device.stop().unwrap();
+}
+
+// manual, plug in a camera within 30 seconds of starting the test
+#[cfg(all(target_os = "linux", feature = "hotplug"))]
+#[test]
+#[ignore]
+fn test_v4l2_hotplug() {
+    use std::{sync::mpsc, time::Duration};
+
+    use media_device::{camera::CameraManager, DeviceEvent};
+
+    let mut manager = CameraManager::new_default().unwrap();
+    let (sender, receiver) = mpsc::channel();
+    manager
+        .set_change_handler(move |event| {
+            if let DeviceEvent::Added(info) = event {
+                sender.send(info.id.clone()).ok();
+            }
+        })
+        .unwrap();
+
+    let id = receiver.recv_timeout(Duration::from_secs(30)).unwrap();
+    assert!(manager.lookup_mut(&id).is_some());
+}
+
+// requires a camera at /dev/video0, run with --ignored
+#[cfg(target_os = "linux")]
+#[test]
+#[ignore]
+fn test_v4l2_properties() {
+    use media_device::{camera::CameraManager, Device};
+
+    const DEVICE_PATH: &str = "/dev/video0";
+
+    let manager = CameraManager::new_default().unwrap();
+    let device = manager.lookup(DEVICE_PATH).unwrap();
+
+    let properties = device.properties().unwrap();
+    assert!(!properties["model"].get_string().unwrap().is_empty());
+
+    let pixel_array_size = &properties["pixel-array-size"];
+    let width = pixel_array_size["width"].get_uint32().unwrap();
+    let height = pixel_array_size["height"].get_uint32().unwrap();
+    assert!((16..=16384).contains(&width));
+    assert!((16..=16384).contains(&height));
+}
+
+// requires a camera at /dev/video0 and sysfs, run with --ignored
+#[cfg(target_os = "linux")]
+#[test]
+#[ignore]
+fn test_v4l2_location() {
+    use media_device::{camera::CameraManager, Device};
+
+    const DEVICE_PATH: &str = "/dev/video0";
+
+    let manager = CameraManager::new_default().unwrap();
+    let device = manager.lookup(DEVICE_PATH).unwrap();
+
+    // the id is the sysfs location, the node path still opens the device
+    let location = device.id();
+    assert!(location.contains("/video-index"));
+    assert_eq!(device.path(), DEVICE_PATH);
+    assert_eq!(device.properties().unwrap()["path"].get_string(), Some(DEVICE_PATH.to_string()));
+    assert_eq!(manager.lookup(location).map(|device| device.path()), Some(DEVICE_PATH));
+}
+
+// requires a camera at /dev/video0 with crop support, run with --ignored
+#[cfg(target_os = "linux")]
+#[test]
+#[ignore]
+fn test_v4l2_scaler_crop() {
+    use std::{sync::mpsc, time::Duration};
+
+    use media_core::{
+        error::Error,
+        variant::Variant,
+        video::{PixelFormat, VideoFormat},
+    };
+    use media_device::{camera::CameraManager, Device, OutputDevice};
+
+    const DEVICE_PATH: &str = "/dev/video0";
+    const WIDTH: usize = 640;
+    const HEIGHT: usize = 480;
+
+    let mut manager = CameraManager::new_default().unwrap();
+    let device = manager.lookup_mut(DEVICE_PATH).unwrap();
+
+    let properties = device.properties().unwrap();
+    let width = properties["pixel-array-size"]["width"].get_uint32().unwrap();
+    let height = properties["pixel-array-size"]["height"].get_uint32().unwrap();
+
+    // the luma of YUYV frames is every other byte
+    let (sender, receiver) = mpsc::sync_channel(1);
+    device
+        .set_output_handler(move |frame| {
+            let guard = frame.map()?;
+            let plane = guard.plane(0).unwrap();
+            let luma = plane.data.chunks(plane.stride).take(HEIGHT).flat_map(|row| row[..WIDTH * 2].iter().step_by(2).copied()).collect::<Vec<u8>>();
+            sender.try_send(luma).ok();
+            Ok(())
+        })
+        .unwrap();
+
+    // skip a few frames while exposure settles
+    let capture = |device: &mut dyn Device| -> Vec<u8> {
+        device.start().unwrap();
+        let mut luma = receiver.recv_timeout(Duration::from_secs(5)).unwrap();
+        for _ in 0..10 {
+            luma = receiver.recv_timeout(Duration::from_secs(5)).unwrap();
+        }
+        device.stop().unwrap();
+        luma
+    };
+
+    let mut options = Variant::new_dict();
+    options["format"] = u32::from(VideoFormat::Pixel(PixelFormat::YUYV)).into();
+    options["width"] = (WIDTH as u32).into();
+    options["height"] = (HEIGHT as u32).into();
+    options["scaler-crop"] = Variant::new_dict();
+    device.configure(&options).unwrap();
+    let full = capture(device);
+    let default_crop = device.scaler_crop().unwrap();
+
+    // a partial region is rejected
+    let mut scaler_crop = Variant::new_dict();
+    scaler_crop["x"] = (width / 4).into();
+    scaler_crop["y"] = (height / 4).into();
+    let mut options = Variant::new_dict();
+    options["scaler-crop"] = scaler_crop.clone();
+    assert!(matches!(device.configure(&options), Err(Error::InvalidParameter(..))));
+
+    // a centered half size region
+    scaler_crop["width"] = (width / 2).into();
+    scaler_crop["height"] = (height / 2).into();
+    options["scaler-crop"] = scaler_crop;
+    device.configure(&options).unwrap();
+    let zoomed = capture(device);
+
+    // the driver may align the region
+    let (x, y, crop_width, crop_height) = device.scaler_crop().unwrap();
+    assert!(x.abs_diff(width / 4) <= 16 && y.abs_diff(height / 4) <= 16);
+    assert!(crop_width.abs_diff(width / 2) <= 32 && crop_height.abs_diff(height / 2) <= 32);
+
+    // the zoomed frame matches the center of the full frame scaled up by two
+    // more closely than the full frame itself
+    let mean_error = |sample: &dyn Fn(usize, usize) -> u8| {
+        let sum =
+            (0..HEIGHT).flat_map(|y| (0..WIDTH).map(move |x| (y, x))).map(|(y, x)| zoomed[y * WIDTH + x].abs_diff(sample(y, x)) as u64).sum::<u64>();
+        sum as f64 / (WIDTH * HEIGHT) as f64
+    };
+    let center_error = mean_error(&|y, x| full[(HEIGHT / 4 + y / 2) * WIDTH + WIDTH / 4 + x / 2]);
+    let full_error = mean_error(&|y, x| full[y * WIDTH + x]);
+    assert!(center_error < full_error, "{center_error} >= {full_error}");
+
+    // an empty region restores the default crop
+    options["scaler-crop"] = Variant::new_dict();
+    device.configure(&options).unwrap();
+    capture(device);
+    assert_eq!(device.scaler_crop(), Some(default_crop));
+}
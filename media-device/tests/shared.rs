@@ -0,0 +1,116 @@
+#![cfg(feature = "video")]
+
+use std::sync::{Arc, Mutex};
+
+use media_core::{error::Error, frame::Frame, variant::Variant, video::PixelFormat, Result};
+use media_device::{Device, OutputDevice, SharedDevice};
+
+type Handler = Box<dyn Fn(Frame) -> Result<()> + Send + Sync>;
+
+// Delivers frames only when pushed through the shared handler slot
+#[derive(Default)]
+struct MockDevice {
+    handler: Arc<Mutex<Option<Handler>>>,
+    running: Arc<Mutex<bool>>,
+}
+
+impl Device for MockDevice {
+    fn name(&self) -> &str {
+        "mock"
+    }
+
+    fn id(&self) -> &str {
+        "mock"
+    }
+
+    fn start(&mut self) -> Result<()> {
+        *self.running.lock().unwrap() = true;
+        Ok(())
+    }
+
+    fn stop(&mut self) -> Result<()> {
+        *self.running.lock().unwrap() = false;
+        Ok(())
+    }
+
+    fn configure(&mut self, _options: &Variant) -> Result<()> {
+        Ok(())
+    }
+
+    fn control(&mut self, _action: &Variant) -> Result<()> {
+        Err(Error::NotImplemented)
+    }
+
+    fn running(&self) -> bool {
+        *self.running.lock().unwrap()
+    }
+
+    fn formats(&self) -> Result<Variant> {
+        Ok(Variant::new_array())
+    }
+}
+
+impl OutputDevice for MockDevice {
+    fn set_output_handler<F>(&mut self, handler: F) -> Result<()>
+    where
+        F: Fn(Frame) -> Result<()> + Send + Sync + 'static,
+    {
+        *self.handler.lock().unwrap() = Some(Box::new(handler));
+        Ok(())
+    }
+}
+
+#[test]
+fn test_shared_device() {
+    let device = MockDevice::default();
+    let handler = device.handler.clone();
+    let running = device.running.clone();
+    let push_frame = |pts: i64| {
+        if *running.lock().unwrap() {
+            let mut frame = Frame::video_creator().create(PixelFormat::NV12, 16, 16).unwrap();
+            frame.pts = Some(pts);
+            handler.lock().unwrap().as_ref().unwrap()(frame).unwrap();
+        }
+    };
+
+    // not implemented by the mock
+    assert!(matches!(device.properties(), Err(Error::Unsupported(_))));
+    assert!(matches!(device.effective_config(), Err(Error::Unsupported(_))));
+
+    let shared = SharedDevice::new(device).unwrap();
+    assert!(!shared.running());
+
+    let first_frames = Arc::new(Mutex::new(Vec::new()));
+    let second_frames = Arc::new(Mutex::new(Vec::new()));
+    let frames = first_frames.clone();
+    let first = shared
+        .subscribe(move |frame| {
+            frames.lock().unwrap().push(frame.pts.unwrap());
+            Ok(())
+        })
+        .unwrap();
+    assert!(shared.running());
+    let frames = second_frames.clone();
+    let second = shared
+        .subscribe(move |frame| {
+            frames.lock().unwrap().push(frame.pts.unwrap());
+            Ok(())
+        })
+        .unwrap();
+    assert_eq!(shared.subscriber_count(), 2);
+
+    push_frame(0);
+    push_frame(1);
+    assert_eq!(*first_frames.lock().unwrap(), [0, 1]);
+    assert_eq!(*second_frames.lock().unwrap(), [0, 1]);
+
+    shared.unsubscribe(first).unwrap();
+    assert!(shared.running());
+    push_frame(2);
+    assert_eq!(*first_frames.lock().unwrap(), [0, 1]);
+    assert_eq!(*second_frames.lock().unwrap(), [0, 1, 2]);
+
+    shared.unsubscribe(second).unwrap();
+    assert!(!shared.running());
+    assert!(matches!(shared.unsubscribe(second), Err(Error::NotFound(_))));
+}